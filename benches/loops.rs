@@ -0,0 +1,105 @@
+//! Measures hot wasm loops compiled with and without Cranelift's loop
+//! unrolling and induction-variable strength reduction.
+
+use criterion::*;
+use wasmtime::*;
+
+criterion_main!(benches);
+criterion_group!(benches, bench_loops);
+
+const MODULE: &str = r#"
+    (module
+        (memory (export "memory") 2)
+
+        ;; memcpy-style byte loop.
+        (func (export "copy") (param $dst i32) (param $src i32) (param $len i32)
+            (local $i i32)
+            (loop $l
+                (i32.store8
+                    (i32.add (local.get $dst) (local.get $i))
+                    (i32.load8_u (i32.add (local.get $src) (local.get $i))))
+                (local.set $i (i32.add (local.get $i) (i32.const 1)))
+                (br_if $l (i32.lt_u (local.get $i) (local.get $len)))))
+
+        ;; Sum of 32-bit words, indexed by a scaled induction variable.
+        (func (export "checksum") (param $ptr i32) (param $len i32) (result i32)
+            (local $i i32)
+            (local $sum i32)
+            (loop $l
+                (local.set $sum
+                    (i32.add
+                        (local.get $sum)
+                        (i32.load
+                            (i32.add
+                                (local.get $ptr)
+                                (i32.shl (local.get $i) (i32.const 2))))))
+                (local.set $i (i32.add (local.get $i) (i32.const 1)))
+                (br_if $l (i32.lt_u (local.get $i) (local.get $len))))
+            (local.get $sum))
+
+        ;; Fixed-size dot product with a constant trip count.
+        (func (export "dot16") (param $a i32) (param $b i32) (result i32)
+            (local $i i32)
+            (local $sum i32)
+            (loop $l
+                (local.set $sum
+                    (i32.add
+                        (local.get $sum)
+                        (i32.mul
+                            (i32.load (i32.add (local.get $a) (i32.shl (local.get $i) (i32.const 2))))
+                            (i32.load (i32.add (local.get $b) (i32.shl (local.get $i) (i32.const 2)))))))
+                (local.set $i (i32.add (local.get $i) (i32.const 1)))
+                (br_if $l (i32.lt_u (local.get $i) (i32.const 16))))
+            (local.get $sum))
+    )
+"#;
+
+const LEN: i32 = 16 * 1024;
+
+fn engine(unroll: bool) -> Engine {
+    let mut config = Config::new();
+    config.cranelift_opt_level(OptLevel::Speed);
+    // SAFETY: loop unrolling doesn't change the semantics of compiled code.
+    unsafe {
+        config.cranelift_flag_set(
+            "enable_loop_unrolling",
+            if unroll { "true" } else { "false" },
+        );
+    }
+    Engine::new(&config).unwrap()
+}
+
+fn bench_loops(c: &mut Criterion) {
+    let mut group = c.benchmark_group("loops");
+
+    for unroll in [false, true] {
+        let name = if unroll { "unrolled" } else { "baseline" };
+        let engine = engine(unroll);
+        let module = Module::new(&engine, MODULE).unwrap();
+        let mut store = Store::new(&engine, ());
+        let instance = Instance::new(&mut store, &module, &[]).unwrap();
+
+        let copy = instance
+            .get_typed_func::<(i32, i32, i32), ()>(&mut store, "copy")
+            .unwrap();
+        group.bench_function(BenchmarkId::new("copy", name), |b| {
+            b.iter(|| copy.call(&mut store, (LEN, 0, LEN)).unwrap())
+        });
+
+        let checksum = instance
+            .get_typed_func::<(i32, i32), i32>(&mut store, "checksum")
+            .unwrap();
+        group.bench_function(BenchmarkId::new("checksum", name), |b| {
+            b.iter(|| checksum.call(&mut store, (0, LEN / 4)).unwrap())
+        });
+
+        let dot16 = instance
+            .get_typed_func::<(i32, i32), i32>(&mut store, "dot16")
+            .unwrap();
+        group.bench_function(BenchmarkId::new("dot16", name), |b| {
+            b.iter(|| dot16.call(&mut store, (0, 64)).unwrap())
+        });
+    }
+
+    group.finish();
+}
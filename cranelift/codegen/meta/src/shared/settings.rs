@@ -67,6 +67,45 @@ pub(crate) fn define() -> SettingGroup {
         true,
     );
 
+    settings.add_bool(
+        "enable_loop_unrolling",
+        "Unroll small loops and strength-reduce their induction variables.",
+        r#"
+            This unrolls loops consisting of a single block which are counted by an induction
+            variable, and replaces address computations derived from induction variables with
+            induction variables of their own. Only effective when `opt_level` is `speed`.
+        "#,
+        false,
+    );
+
+    settings.add_bool(
+        "enable_slp_vectorization",
+        "Pack isomorphic scalar operations into vector operations.",
+        r#"
+            This replaces scalar stores to adjacent memory, along with the isomorphic
+            arithmetic and loads feeding them, with 128-bit vector operations when the
+            target supports them natively and doing so saves instructions. Only effective
+            when `opt_level` is `speed` or `speed_and_size`.
+        "#,
+        false,
+    );
+
+    settings.add_num(
+        "egraph_register_pressure_limit",
+        "Estimated register pressure at which loop-invariant code motion stops hoisting cheap values.",
+        r#"
+            Values that only depend on loop-invariant inputs are hoisted out of
+            loops, which keeps them live in a register across the whole loop.
+            The number of values hoisted out of a loop, per register class, is
+            used as an estimate of the register pressure this adds. Once it
+            reaches this limit, cheap values (such as simple arithmetic and
+            extensions) are instead computed next to their uses inside the
+            loop. Zero disables the limit. Only effective when `opt_level` is
+            `speed` or `speed_and_size`.
+        "#,
+        12,
+    );
+
     settings.add_bool(
         "enable_verifier",
         "Run the Cranelift IR verifier at strategic times during compilation.",
@@ -123,8 +162,15 @@ pub(crate) fn define() -> SettingGroup {
     settings.add_enum(
         "tls_model",
         "Defines the model used to perform TLS accesses.",
-        "",
-        vec!["none", "elf_gd", "macho", "coff"],
+        r#"
+            On ELF targets, `elf_gd` (general dynamic) works for any TLS symbol
+            but calls into the dynamic linker. `elf_ie` (initial exec) loads the
+            symbol's offset from the thread pointer from the GOT, and only works
+            for modules loaded at program startup. `elf_le` (local exec) encodes
+            that offset directly in the code, and only works for symbols defined
+            in the executable itself.
+        "#,
+        vec!["none", "elf_gd", "elf_ie", "elf_le", "macho", "coff"],
     );
 
     settings.add_enum(
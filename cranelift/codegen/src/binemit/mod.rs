@@ -50,6 +50,14 @@ pub enum Reloc {
     /// Elf x86_64 32 bit signed PC relative offset to two GOT entries for GD symbol.
     ElfX86_64TlsGd,
 
+    /// Elf x86_64 32 bit signed PC relative offset to the GOT entry holding the thread pointer
+    /// offset of an IE symbol. Equivalent to `R_X86_64_GOTTPOFF`.
+    ElfX86_64GotTpOff,
+
+    /// Elf x86_64 32 bit signed offset of an LE symbol from the thread pointer.
+    /// Equivalent to `R_X86_64_TPOFF32`.
+    ElfX86_64TpOff32,
+
     /// Mach-O x86_64 32 bit signed PC relative offset to a `__thread_vars` entry.
     MachOX86_64Tlv,
 
@@ -77,6 +85,22 @@ pub enum Reloc {
     /// This is equivalent to `R_AARCH64_TLSDESC_CALL` in the [aaelf64](https://github.com/ARM-software/abi-aa/blob/2bcab1e3b22d55170c563c3c7940134089176746/aaelf64/aaelf64.rst#57105thread-local-storage-descriptors)
     Aarch64TlsDescCall,
 
+    /// Aarch64 TLS IE GOT Page21
+    /// This is equivalent to `R_AARCH64_TLSIE_ADR_GOTTPREL_PAGE21` in the [aaelf64](https://github.com/ARM-software/abi-aa/blob/2bcab1e3b22d55170c563c3c7940134089176746/aaelf64/aaelf64.rst#initial-exec)
+    Aarch64TlsIeAdrGotTpRelPage21,
+
+    /// Aarch64 TLS IE GOT Ld64 Lo12
+    /// This is equivalent to `R_AARCH64_TLSIE_LD64_GOTTPREL_LO12_NC` in the [aaelf64](https://github.com/ARM-software/abi-aa/blob/2bcab1e3b22d55170c563c3c7940134089176746/aaelf64/aaelf64.rst#initial-exec)
+    Aarch64TlsIeLd64GotTpRelLo12Nc,
+
+    /// Aarch64 TLS LE Add Hi12
+    /// This is equivalent to `R_AARCH64_TLSLE_ADD_TPREL_HI12` in the [aaelf64](https://github.com/ARM-software/abi-aa/blob/2bcab1e3b22d55170c563c3c7940134089176746/aaelf64/aaelf64.rst#local-exec)
+    Aarch64TlsLeAddTpRelHi12,
+
+    /// Aarch64 TLS LE Add Lo12
+    /// This is equivalent to `R_AARCH64_TLSLE_ADD_TPREL_LO12_NC` in the [aaelf64](https://github.com/ARM-software/abi-aa/blob/2bcab1e3b22d55170c563c3c7940134089176746/aaelf64/aaelf64.rst#local-exec)
+    Aarch64TlsLeAddTpRelLo12Nc,
+
     /// AArch64 GOT Page
     /// Set the immediate value of an ADRP to bits 32:12 of X; check that –2^32 <= X < 2^32
     /// This is equivalent to `R_AARCH64_ADR_GOT_PAGE` (311) in the  [aaelf64](https://github.com/ARM-software/abi-aa/blob/2bcab1e3b22d55170c563c3c7940134089176746/aaelf64/aaelf64.rst#static-aarch64-relocations)
@@ -108,6 +132,30 @@ pub enum Reloc {
     /// <https://github.com/riscv-non-isa/riscv-elf-psabi-doc/blob/master/riscv-elf.adoc#global-dynamic>
     RiscvTlsGdHi20,
 
+    /// RISC-V TLS IE: High 20 bits of 32-bit PC-relative TLS IE GOT reference,
+    ///
+    /// This is the `R_RISCV_TLS_GOT_HI20` relocation from the RISC-V ELF psABI document.
+    /// <https://github.com/riscv-non-isa/riscv-elf-psabi-doc/blob/master/riscv-elf.adoc#initial-exec>
+    RiscvTlsIeHi20,
+
+    /// RISC-V TLS LE: High 20 bits of 32-bit offset from the thread pointer
+    ///
+    /// This is the `R_RISCV_TPREL_HI20` relocation from the RISC-V ELF psABI document.
+    /// <https://github.com/riscv-non-isa/riscv-elf-psabi-doc/blob/master/riscv-elf.adoc#local-exec>
+    RiscvTpRelHi20,
+
+    /// RISC-V TLS LE: Marker for the thread pointer add, allowing the linker to relax it
+    ///
+    /// This is the `R_RISCV_TPREL_ADD` relocation from the RISC-V ELF psABI document.
+    /// <https://github.com/riscv-non-isa/riscv-elf-psabi-doc/blob/master/riscv-elf.adoc#local-exec>
+    RiscvTpRelAdd,
+
+    /// RISC-V TLS LE: Low 12 bits of 32-bit offset from the thread pointer (I-Type instruction)
+    ///
+    /// This is the `R_RISCV_TPREL_LO12_I` relocation from the RISC-V ELF psABI document.
+    /// <https://github.com/riscv-non-isa/riscv-elf-psabi-doc/blob/master/riscv-elf.adoc#local-exec>
+    RiscvTpRelLo12I,
+
     /// Low 12 bits of a 32-bit PC-relative relocation (I-Type instruction)
     ///
     /// This is the `R_RISCV_PCREL_LO12_I` relocation from the RISC-V ELF psABI document.
@@ -157,10 +205,16 @@ impl fmt::Display for Reloc {
             Self::Arm32Call | Self::Arm64Call => write!(f, "Call"),
             Self::RiscvCallPlt => write!(f, "RiscvCallPlt"),
             Self::RiscvTlsGdHi20 => write!(f, "RiscvTlsGdHi20"),
+            Self::RiscvTlsIeHi20 => write!(f, "RiscvTlsIeHi20"),
+            Self::RiscvTpRelHi20 => write!(f, "RiscvTpRelHi20"),
+            Self::RiscvTpRelAdd => write!(f, "RiscvTpRelAdd"),
+            Self::RiscvTpRelLo12I => write!(f, "RiscvTpRelLo12I"),
             Self::RiscvGotHi20 => write!(f, "RiscvGotHi20"),
             Self::RiscvPCRelHi20 => write!(f, "RiscvPCRelHi20"),
             Self::RiscvPCRelLo12I => write!(f, "RiscvPCRelLo12I"),
             Self::ElfX86_64TlsGd => write!(f, "ElfX86_64TlsGd"),
+            Self::ElfX86_64GotTpOff => write!(f, "ElfX86_64GotTpOff"),
+            Self::ElfX86_64TpOff32 => write!(f, "ElfX86_64TpOff32"),
             Self::MachOX86_64Tlv => write!(f, "MachOX86_64Tlv"),
             Self::MachOAarch64TlsAdrPage21 => write!(f, "MachOAarch64TlsAdrPage21"),
             Self::MachOAarch64TlsAdrPageOff12 => write!(f, "MachOAarch64TlsAdrPageOff12"),
@@ -168,6 +222,10 @@ impl fmt::Display for Reloc {
             Self::Aarch64TlsDescLd64Lo12 => write!(f, "Aarch64TlsDescLd64Lo12"),
             Self::Aarch64TlsDescAddLo12 => write!(f, "Aarch64TlsDescAddLo12"),
             Self::Aarch64TlsDescCall => write!(f, "Aarch64TlsDescCall"),
+            Self::Aarch64TlsIeAdrGotTpRelPage21 => write!(f, "Aarch64TlsIeAdrGotTpRelPage21"),
+            Self::Aarch64TlsIeLd64GotTpRelLo12Nc => write!(f, "Aarch64TlsIeLd64GotTpRelLo12Nc"),
+            Self::Aarch64TlsLeAddTpRelHi12 => write!(f, "Aarch64TlsLeAddTpRelHi12"),
+            Self::Aarch64TlsLeAddTpRelLo12Nc => write!(f, "Aarch64TlsLeAddTpRelLo12Nc"),
             Self::Aarch64AdrGotPage21 => write!(f, "Aarch64AdrGotPage21"),
             Self::Aarch64Ld64GotLo12Nc => write!(f, "Aarch64AdrGotLo12Nc"),
             Self::Aarch64AdrPrelPgHi21 => write!(f, "Aarch64AdrPrelPgHi21"),
@@ -17,11 +17,14 @@ use crate::inline::{Inline, do_inlining};
 use crate::ir::Function;
 use crate::isa::TargetIsa;
 use crate::loop_analysis::LoopAnalysis;
+use crate::loop_unroll::do_loop_unroll;
 use crate::machinst::{CompiledCode, CompiledCodeStencil};
 use crate::nan_canonicalization::do_nan_canonicalization;
+use crate::profile::BlockProfile;
 use crate::remove_constant_phis::do_remove_constant_phis;
 use crate::result::{CodegenResult, CompileResult};
 use crate::settings::{FlagsOrIsa, OptLevel};
+use crate::slp_vectorize::do_slp_vectorize;
 use crate::trace;
 use crate::unreachable_code::eliminate_unreachable_code;
 use crate::verifier::{VerifierErrors, VerifierResult, verify_context};
@@ -49,6 +52,9 @@ pub struct Context {
     /// Loop analysis of `func`.
     pub loop_analysis: LoopAnalysis,
 
+    /// Execution profile of `func`, used to lay out its blocks.
+    pub profile: BlockProfile,
+
     /// Result of MachBackend compilation, if computed.
     pub(crate) compiled_code: Option<CompiledCode>,
 
@@ -78,6 +84,7 @@ impl Context {
             cfg: ControlFlowGraph::new(),
             domtree: DominatorTree::new(),
             loop_analysis: LoopAnalysis::new(),
+            profile: BlockProfile::new(),
             compiled_code: None,
             want_disasm: false,
             regalloc_ctx: regalloc2::Ctx::default(),
@@ -90,6 +97,7 @@ impl Context {
         self.cfg.clear();
         self.domtree.clear();
         self.loop_analysis.clear();
+        self.profile.clear();
         self.compiled_code = None;
         self.want_disasm = false;
     }
@@ -143,6 +151,7 @@ impl Context {
             result = isa.compile_function(
                 &self.func,
                 &self.domtree,
+                &self.profile,
                 &mut self.regalloc_ctx,
                 self.want_disasm,
                 ctrl_plane,
@@ -191,10 +200,20 @@ impl Context {
 
         self.func.dfg.resolve_all_aliases();
 
+        if opt_level == OptLevel::Speed && isa.flags().enable_loop_unrolling() {
+            self.loop_unroll(isa)?;
+        }
+
         if opt_level != OptLevel::None {
             self.egraph_pass(isa, ctrl_plane)?;
         }
 
+        // Runs after the egraph pass, whose GVN makes the addresses of
+        // adjacent accesses share one base value.
+        if opt_level != OptLevel::None && isa.flags().enable_slp_vectorization() {
+            self.slp_vectorize(isa)?;
+        }
+
         Ok(())
     }
 
@@ -357,6 +376,30 @@ impl Context {
         Ok(())
     }
 
+    /// Unroll small loops and strength-reduce their induction variables.
+    pub fn loop_unroll<'a, FOI>(&mut self, fisa: FOI) -> CodegenResult<()>
+    where
+        FOI: Into<FlagsOrIsa<'a>>,
+    {
+        self.compute_loop_analysis();
+        if do_loop_unroll(
+            &mut self.func,
+            &mut self.cfg,
+            &mut self.domtree,
+            &mut self.loop_analysis,
+        ) {
+            trace!("After loop unrolling:\n{}", self.func.display());
+        }
+        self.verify_if(fisa)
+    }
+
+    /// Pack isomorphic scalar operations into vector operations.
+    pub fn slp_vectorize(&mut self, isa: &dyn TargetIsa) -> CodegenResult<()> {
+        do_slp_vectorize(&mut self.func, isa);
+        trace!("After SLP vectorization:\n{}", self.func.display());
+        self.verify_if(isa)
+    }
+
     /// Run optimizations via the egraph infrastructure.
     pub fn egraph_pass<'a, FOI>(
         &mut self,
@@ -382,6 +425,7 @@ impl Context {
             &mut alias_analysis,
             ctrl_plane,
             &mut self.cfg,
+            fisa.flags.egraph_register_pressure_limit(),
         );
         pass.run();
         log::debug!("egraph stats: {:?}", pass.stats);
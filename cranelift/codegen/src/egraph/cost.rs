@@ -113,6 +113,13 @@ impl Cost {
         }
     }
 
+    /// Is this opcode cheap enough that recomputing it next to its uses
+    /// is preferable to keeping its result live across a loop when
+    /// registers are scarce?
+    pub(crate) fn is_cheap_op(op: Opcode) -> bool {
+        Cost::of_opcode(op) <= Cost::new(3)
+    }
+
     /// Compute the cost of the operation and its given operands.
     ///
     /// Caller is responsible for checking that the opcode came from an instruction
@@ -7,7 +7,7 @@ use crate::ctxhash::NullCtx;
 use crate::dominator_tree::DominatorTree;
 use crate::hash_map::Entry as HashEntry;
 use crate::inst_predicates::is_pure_for_egraph;
-use crate::ir::{Block, Function, Inst, Value, ValueDef};
+use crate::ir::{Block, Function, Inst, Type, Value, ValueDef};
 use crate::loop_analysis::{Loop, LoopAnalysis};
 use crate::scoped_hash_map::ScopedHashMap;
 use crate::trace;
@@ -55,6 +55,9 @@ pub(crate) struct Elaborator<'a> {
     /// in every block they are used (e.g., immediates or other
     /// "cheap-to-compute" ops).
     remat_values: &'a FxHashSet<Value>,
+    /// Estimated register pressure at which we stop hoisting cheap
+    /// values out of a loop; zero for no limit.
+    pressure_limit: u8,
     /// Explicitly-unrolled value elaboration stack.
     elab_stack: Vec<ElabStackEntry>,
     /// Results from the elab stack.
@@ -111,6 +114,17 @@ struct LoopStackEntry {
     hoist_block: Block,
     /// The depth in the scope map.
     scope_depth: u32,
+    /// The number of values hoisted out of this loop so far, indexed
+    /// by `pressure_class`. Each of them is live across the whole loop,
+    /// so this serves as an estimate of the register pressure that LICM
+    /// adds to the loop.
+    hoisted: [u32; 2],
+}
+
+/// The register class, as an index into `LoopStackEntry::hoisted`, that
+/// a value of type `ty` will most likely be allocated to.
+fn pressure_class(ty: Type) -> usize {
+    if ty.is_int() { 0 } else { 1 }
 }
 
 #[derive(Clone, Debug)]
@@ -143,6 +157,7 @@ impl<'a> Elaborator<'a> {
         domtree: &'a DominatorTree,
         loop_analysis: &'a LoopAnalysis,
         remat_values: &'a FxHashSet<Value>,
+        pressure_limit: u8,
         stats: &'a mut Stats,
         ctrl_plane: &'a mut ControlPlane,
     ) -> Self {
@@ -159,6 +174,7 @@ impl<'a> Elaborator<'a> {
             loop_stack: smallvec![],
             cur_block: Block::reserved_value(),
             remat_values,
+            pressure_limit,
             elab_stack: vec![],
             elab_result_stack: vec![],
             block_stack: vec![],
@@ -201,6 +217,7 @@ impl<'a> Elaborator<'a> {
                     // level.
                     hoist_block: idom,
                     scope_depth: (self.value_to_elaborated_value.depth() - 1) as u32,
+                    hoisted: [0; 2],
                 });
                 trace!(
                     " -> loop header, pushing; depth now {}",
@@ -409,6 +426,48 @@ impl<'a> Elaborator<'a> {
         self.elab_result_stack.pop().unwrap()
     }
 
+    /// Limit the loop level to which the pure `inst` is hoisted, given
+    /// that its args would allow hoisting it to `hoist_level`.
+    ///
+    /// A hoisted value is live across every loop it was hoisted out
+    /// of. When one of those loops has already reached the register
+    /// pressure limit, a cheap instruction is kept inside it instead,
+    /// next to its uses; it is then recomputed in each part of the
+    /// loop's domtree that uses it, much like a remat value.
+    fn pressure_limited_hoist_level(
+        func: &Function,
+        loop_stack: &[LoopStackEntry],
+        pressure_limit: u8,
+        inst: Inst,
+        hoist_level: usize,
+        stats: &mut Stats,
+    ) -> usize {
+        let limit = u32::from(pressure_limit);
+        if limit == 0
+            || hoist_level == loop_stack.len()
+            || !Cost::is_cheap_op(func.dfg.insts[inst].opcode())
+        {
+            return hoist_level;
+        }
+
+        let result = func.dfg.first_result(inst);
+        let class = pressure_class(func.dfg.value_type(result));
+        match loop_stack[hoist_level..]
+            .iter()
+            .rposition(|entry| entry.hoisted[class] >= limit)
+        {
+            Some(i) => {
+                trace!(
+                    " -> loop at level {} is at the pressure limit; not hoisting past it",
+                    hoist_level + i
+                );
+                stats.elaborate_pressure_sink += 1;
+                hoist_level + i + 1
+            }
+            None => hoist_level,
+        }
+    }
+
     /// Possibly rematerialize the instruction producing the value in
     /// `arg` and rewrite `arg` to refer to it, if needed. Returns
     /// `true` if a rewrite occurred.
@@ -581,6 +640,14 @@ impl<'a> Elaborator<'a> {
                         })
                         .max()
                         .unwrap_or(self.loop_stack.len().saturating_sub(1));
+                    let loop_hoist_level = Self::pressure_limited_hoist_level(
+                        &self.func,
+                        &self.loop_stack,
+                        self.pressure_limit,
+                        inst,
+                        loop_hoist_level,
+                        &mut self.stats,
+                    );
                     trace!(
                         " -> loop hoist level: {:?}; cur loop depth: {:?}, loop_stack: {:?}",
                         loop_hoist_level,
@@ -612,6 +679,17 @@ impl<'a> Elaborator<'a> {
                         // Does not depend on any args at current
                         // loop depth: hoist out of loop.
                         self.stats.elaborate_licm_hoist += 1;
+                        // Remat values are recomputed next to their uses
+                        // rather than kept live across the loop, so they
+                        // don't add to its register pressure.
+                        for &result in self.func.dfg.inst_results(inst) {
+                            if !self.remat_values.contains(&result) {
+                                let class = pressure_class(self.func.dfg.value_type(result));
+                                for entry in &mut self.loop_stack[loop_hoist_level..] {
+                                    entry.hoisted[class] += 1;
+                                }
+                            }
+                        }
                         let data = &self.loop_stack[loop_hoist_level];
                         // `data.hoist_block` should dominate `before`'s block.
                         let before_block = self.func.layout.inst_block(before).unwrap();
@@ -120,6 +120,9 @@ pub struct EgraphPass<'a> {
     /// Which Values do we want to rematerialize in each block where
     /// they're used?
     remat_values: FxHashSet<Value>,
+    /// Estimated register pressure at which elaboration stops hoisting
+    /// cheap values out of loops; zero for no limit.
+    pressure_limit: u8,
     /// Stats collected while we run this pass.
     pub(crate) stats: Stats,
 }
@@ -783,6 +786,7 @@ impl<'a> EgraphPass<'a> {
         alias_analysis: &'a mut AliasAnalysis<'a>,
         ctrl_plane: &'a mut ControlPlane,
         cfg: &'a mut ControlFlowGraph,
+        pressure_limit: u8,
     ) -> Self {
         Self {
             func,
@@ -794,6 +798,7 @@ impl<'a> EgraphPass<'a> {
             branch_to_trap_analysis: BranchToTrapAnalysis::default(),
             stats: Stats::default(),
             remat_values: FxHashSet::default(),
+            pressure_limit,
         }
     }
 
@@ -1244,6 +1249,7 @@ impl<'a> EgraphPass<'a> {
             &self.domtree,
             self.loop_analysis,
             &self.remat_values,
+            self.pressure_limit,
             &mut self.stats,
             self.ctrl_plane,
         );
@@ -1333,6 +1339,7 @@ pub(crate) struct Stats {
     pub(crate) elaborate_memoize_miss: u64,
     pub(crate) elaborate_remat: u64,
     pub(crate) elaborate_licm_hoist: u64,
+    pub(crate) elaborate_pressure_sink: u64,
     pub(crate) elaborate_func: u64,
     pub(crate) elaborate_func_pre_insts: u64,
     pub(crate) elaborate_func_post_insts: u64,
@@ -36,12 +36,21 @@ use cranelift_control::ControlPlane;
 impl Context {
     /// Compile the function, as in `compile`, but tries to reuse compiled artifacts from former
     /// compilations using the provided cache store.
+    ///
+    /// The profile isn't part of the cache key, so functions with a profile are always compiled
+    /// from scratch.
     pub fn compile_with_cache(
         &mut self,
         isa: &dyn TargetIsa,
         cache_store: &mut dyn CacheKvStore,
         ctrl_plane: &mut ControlPlane,
     ) -> CompileResult<'_, (&CompiledCode, bool)> {
+        if !self.profile.is_empty() {
+            return self
+                .compile(isa, ctrl_plane)
+                .map(|compiled_code| (compiled_code, false));
+        }
+
         let cache_key_hash = {
             let _tt = timing::try_incremental_cache();
 
@@ -3516,6 +3516,80 @@ impl MachInstEmit for Inst {
                 .emit(sink, emit_info, state);
             }
 
+            &Inst::ElfTlsIeGetAddr {
+                ref symbol,
+                rd,
+                tmp,
+            } => {
+                // Implement the initial-exec instruction sequence:
+                //   adrp tmp, :gottprel:tlsvar
+                //   ldr  tmp, [tmp, :gottprel_lo12:tlsvar]
+                //   mrs  rd, tpidr_el0
+                //   add  rd, rd, tmp
+
+                // adrp tmp, :gottprel:tlsvar
+                sink.add_reloc(Reloc::Aarch64TlsIeAdrGotTpRelPage21, &**symbol, 0);
+                Inst::Adrp { rd: tmp, off: 0 }.emit(sink, emit_info, state);
+
+                // ldr tmp, [tmp, :gottprel_lo12:tlsvar]
+                sink.add_reloc(Reloc::Aarch64TlsIeLd64GotTpRelLo12Nc, &**symbol, 0);
+                Inst::ULoad64 {
+                    rd: tmp,
+                    mem: AMode::reg(tmp.to_reg()),
+                    flags: MemFlagsData::trusted(),
+                }
+                .emit(sink, emit_info, state);
+
+                // mrs rd, tpidr_el0
+                sink.put4(0xd53bd040 | machreg_to_gpr(rd.to_reg()));
+
+                // add rd, rd, tmp
+                Inst::AluRRR {
+                    alu_op: ALUOp::Add,
+                    size: OperandSize::Size64,
+                    rd,
+                    rn: rd.to_reg(),
+                    rm: tmp.to_reg(),
+                }
+                .emit(sink, emit_info, state);
+            }
+
+            &Inst::ElfTlsLeGetAddr { ref symbol, rd } => {
+                // Implement the local-exec instruction sequence, which supports
+                // offsets of up to 16MiB from the thread pointer:
+                //   mrs rd, tpidr_el0
+                //   add rd, rd, :tprel_hi12:tlsvar, lsl #12
+                //   add rd, rd, :tprel_lo12_nc:tlsvar
+
+                // mrs rd, tpidr_el0
+                sink.put4(0xd53bd040 | machreg_to_gpr(rd.to_reg()));
+
+                // add rd, rd, :tprel_hi12:tlsvar, lsl #12
+                sink.add_reloc(Reloc::Aarch64TlsLeAddTpRelHi12, &**symbol, 0);
+                Inst::AluRRImm12 {
+                    alu_op: ALUOp::Add,
+                    size: OperandSize::Size64,
+                    rd,
+                    rn: rd.to_reg(),
+                    imm12: Imm12 {
+                        bits: 0,
+                        shift12: true,
+                    },
+                }
+                .emit(sink, emit_info, state);
+
+                // add rd, rd, :tprel_lo12_nc:tlsvar
+                sink.add_reloc(Reloc::Aarch64TlsLeAddTpRelLo12Nc, &**symbol, 0);
+                Inst::AluRRImm12 {
+                    alu_op: ALUOp::Add,
+                    size: OperandSize::Size64,
+                    rd,
+                    rn: rd.to_reg(),
+                    imm12: Imm12::ZERO,
+                }
+                .emit(sink, emit_info, state);
+            }
+
             &Inst::MachOTlsGetAddr { ref symbol, rd } => {
                 // Each thread local variable gets a descriptor, where the first xword of the descriptor is a pointer
                 // to a function that takes the descriptor address in x0, and after the function returns x0
@@ -906,6 +906,16 @@ fn aarch64_get_operands(inst: &mut Inst, collector: &mut impl OperandVisitor) {
             collector.reg_fixed_def(rd, regs::xreg(0));
             collector.reg_early_def(tmp);
         }
+        Inst::ElfTlsIeGetAddr { rd, tmp, .. } => {
+            // `tmp` holds the loaded offset while `rd` receives the thread
+            // pointer; as two defs of one instruction they never share a
+            // register.
+            collector.reg_def(rd);
+            collector.reg_def(tmp);
+        }
+        Inst::ElfTlsLeGetAddr { rd, .. } => {
+            collector.reg_def(rd);
+        }
         Inst::MachOTlsGetAddr { rd, .. } => {
             collector.reg_fixed_def(rd, regs::xreg(0));
             let mut clobbers =
@@ -2896,6 +2906,24 @@ impl Inst {
                 let tmp = pretty_print_reg(tmp.to_reg());
                 format!("elf_tls_get_addr {}, {}, {}", rd, tmp, symbol.display(None))
             }
+            &Inst::ElfTlsIeGetAddr {
+                ref symbol,
+                rd,
+                tmp,
+            } => {
+                let rd = pretty_print_reg(rd.to_reg());
+                let tmp = pretty_print_reg(tmp.to_reg());
+                format!(
+                    "elf_tls_ie_get_addr {}, {}, {}",
+                    rd,
+                    tmp,
+                    symbol.display(None)
+                )
+            }
+            &Inst::ElfTlsLeGetAddr { ref symbol, rd } => {
+                let rd = pretty_print_reg(rd.to_reg());
+                format!("elf_tls_le_get_addr {}, {}", rd, symbol.display(None))
+            }
             &Inst::MachOTlsGetAddr { ref symbol, rd } => {
                 let rd = pretty_print_reg(rd.to_reg());
                 format!("macho_tls_get_addr {}, {}", rd, symbol.display(None))
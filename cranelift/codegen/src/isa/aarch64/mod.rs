@@ -12,6 +12,7 @@ use crate::machinst::{
     CompiledCodeStencil, MachInst, MachTextSectionBuilder, Reg, SigSet, TextSectionBuilder, VCode,
     compile,
 };
+use crate::profile::BlockProfile;
 use crate::result::CodegenResult;
 use crate::settings as shared_settings;
 use alloc::string::String;
@@ -55,6 +56,7 @@ impl AArch64Backend {
         &self,
         func: &Function,
         domtree: &DominatorTree,
+        profile: &BlockProfile,
         regalloc_ctx: &mut regalloc2::Ctx,
         ctrl_plane: &mut ControlPlane,
     ) -> CodegenResult<VCode<inst::Inst>> {
@@ -64,6 +66,7 @@ impl AArch64Backend {
         compile::compile::<AArch64Backend>(
             func,
             domtree,
+            profile,
             regalloc_ctx,
             self,
             abi,
@@ -79,11 +82,12 @@ impl TargetIsa for AArch64Backend {
         &self,
         func: &Function,
         domtree: &DominatorTree,
+        profile: &BlockProfile,
         regalloc_ctx: &mut regalloc2::Ctx,
         want_disasm: bool,
         ctrl_plane: &mut ControlPlane,
     ) -> CodegenResult<CompiledCodeStencil> {
-        let vcode = self.compile_vcode(func, domtree, regalloc_ctx, ctrl_plane)?;
+        let vcode = self.compile_vcode(func, domtree, profile, regalloc_ctx, ctrl_plane)?;
 
         let emit_result = vcode.emit(&regalloc_ctx.output, want_disasm, &self.flags, ctrl_plane)?;
         let value_labels_ranges = emit_result.value_labels_ranges;
@@ -224,6 +228,10 @@ impl TargetIsa for AArch64Backend {
         true
     }
 
+    fn has_native_simd(&self, ty: Type) -> bool {
+        ty.bits() == 128
+    }
+
     fn has_blendv_lowering(&self, _: Type) -> bool {
         false
     }
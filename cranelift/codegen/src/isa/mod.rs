@@ -51,6 +51,7 @@ use crate::ir::{self, Function, Type};
 #[cfg(feature = "unwind")]
 use crate::isa::unwind::{UnwindInfoKind, systemv::RegisterMappingError};
 use crate::machinst::{CompiledCodeStencil, TextSectionBuilder};
+use crate::profile::BlockProfile;
 use crate::settings;
 use crate::settings::Configurable;
 use crate::settings::SetResult;
@@ -305,11 +306,12 @@ pub trait TargetIsa: fmt::Display + Send + Sync {
     /// Get the ISA-dependent maximum vector register size, in bytes.
     fn dynamic_vector_bytes(&self, dynamic_ty: ir::Type) -> u32;
 
-    /// Compile the given function.
+    /// Compile the given function, using `profile` to lay out its blocks.
     fn compile_function(
         &self,
         func: &Function,
         domtree: &DominatorTree,
+        profile: &BlockProfile,
         regalloc_ctx: &mut regalloc2::Ctx,
         want_disasm: bool,
         ctrl_plane: &mut ControlPlane,
@@ -392,6 +394,11 @@ pub trait TargetIsa: fmt::Display + Send + Sync {
     /// Returns whether this ISA has instructions for `ceil`, `floor`, etc.
     fn has_round(&self) -> bool;
 
+    /// Returns whether this ISA has native instructions for operations on the
+    /// 128-bit vector type `ty`, making it worthwhile to pack scalar
+    /// operations into them.
+    fn has_native_simd(&self, ty: Type) -> bool;
+
     /// Returns whether the CLIF `blendv` instruction is implemented for
     /// this ISA for the specified type.
     fn has_blendv_lowering(&self, ty: Type) -> bool;
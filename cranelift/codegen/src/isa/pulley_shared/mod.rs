@@ -13,6 +13,7 @@ use crate::{
     ir,
     isa::{self, IsaFlagsHashKey, OwnedTargetIsa, TargetIsa},
     machinst::{self, CompiledCodeStencil, MachInst, SigSet, VCode},
+    profile::BlockProfile,
     result::CodegenResult,
     settings::{self as shared_settings, Flags},
 };
@@ -122,6 +123,7 @@ where
         &self,
         func: &ir::Function,
         domtree: &DominatorTree,
+        profile: &BlockProfile,
         regalloc_ctx: &mut regalloc2::Ctx,
         ctrl_plane: &mut ControlPlane,
     ) -> CodegenResult<VCode<inst::InstAndKind<P>>> {
@@ -135,6 +137,7 @@ where
         machinst::compile::<Self>(
             func,
             domtree,
+            profile,
             regalloc_ctx,
             self,
             abi,
@@ -182,11 +185,12 @@ where
         &self,
         func: &ir::Function,
         domtree: &DominatorTree,
+        profile: &BlockProfile,
         regalloc_ctx: &mut regalloc2::Ctx,
         want_disasm: bool,
         ctrl_plane: &mut cranelift_control::ControlPlane,
     ) -> CodegenResult<CompiledCodeStencil> {
-        let vcode = self.compile_vcode(func, domtree, regalloc_ctx, ctrl_plane)?;
+        let vcode = self.compile_vcode(func, domtree, profile, regalloc_ctx, ctrl_plane)?;
 
         let want_disasm =
             want_disasm || (cfg!(feature = "trace-log") && log::log_enabled!(log::Level::Debug));
@@ -243,6 +247,11 @@ where
         true
     }
 
+    fn has_native_simd(&self, _ty: ir::Type) -> bool {
+        // The interpreter emulates vector opcodes lane by lane.
+        false
+    }
+
     fn has_blendv_lowering(&self, _ty: ir::Type) -> bool {
         false
     }
@@ -175,6 +175,8 @@ impl Inst {
             | Inst::LoadExtNameNear { .. }
             | Inst::LoadExtNameFar { .. }
             | Inst::ElfTlsGetAddr { .. }
+            | Inst::ElfTlsIeGetAddr { .. }
+            | Inst::ElfTlsLeGetAddr { .. }
             | Inst::LoadAddr { .. }
             | Inst::Mov { .. }
             | Inst::MovFromPReg { .. }
@@ -2158,6 +2160,86 @@ impl Inst {
                 .emit_uncompressed(sink, emit_info, state, start_off);
             }
 
+            &Inst::ElfTlsIeGetAddr { rd, ref name } => {
+                // The IE model loads the offset of the symbol from the thread
+                // pointer out of its GOT entry, which is addressed just like in
+                // `LoadExtNameGot`:
+                //
+                // label:
+                //    auipc rd,0                    # R_RISCV_TLS_GOT_HI20 (symbol)
+                //    ld    rd,0(rd)                # R_RISCV_PCREL_LO12_I (label)
+                //    add   rd,rd,tp
+                //
+                // https://github.com/riscv-non-isa/riscv-elf-psabi-doc/blob/master/riscv-elf.adoc#initial-exec
+
+                // Create the label that is going to be published to the final binary object.
+                let auipc_label = sink.get_label();
+                sink.bind_label(auipc_label, &mut state.ctrl_plane);
+
+                // Get the current PC.
+                sink.add_reloc(Reloc::RiscvTlsIeHi20, &**name, 0);
+                Inst::Auipc {
+                    rd,
+                    imm: Imm20::from_i32(0),
+                }
+                .emit_uncompressed(sink, emit_info, state, start_off);
+
+                // The `ld` here, points to the `auipc` label instead of directly to the symbol.
+                sink.add_reloc(Reloc::RiscvPCRelLo12I, &auipc_label, 0);
+                Inst::Load {
+                    rd,
+                    op: LoadOP::Ld,
+                    flags: MemFlagsData::trusted(),
+                    from: AMode::RegOffset(rd.to_reg(), 0),
+                }
+                .emit_uncompressed(sink, emit_info, state, start_off);
+
+                Inst::AluRRR {
+                    alu_op: AluOPRRR::Add,
+                    rd,
+                    rs1: rd.to_reg(),
+                    rs2: tp_reg(),
+                }
+                .emit_uncompressed(sink, emit_info, state, start_off);
+            }
+
+            &Inst::ElfTlsLeGetAddr { rd, ref name } => {
+                // The LE model adds the link-time offset of the symbol to the
+                // thread pointer. The `add` is annotated so that the linker can
+                // relax the sequence when the offset fits in 12 bits.
+                //
+                //    lui   rd,0                    # R_RISCV_TPREL_HI20 (symbol)
+                //    add   rd,rd,tp                # R_RISCV_TPREL_ADD (symbol)
+                //    addi  rd,rd,0                 # R_RISCV_TPREL_LO12_I (symbol)
+                //
+                // https://github.com/riscv-non-isa/riscv-elf-psabi-doc/blob/master/riscv-elf.adoc#local-exec
+
+                sink.add_reloc(Reloc::RiscvTpRelHi20, &**name, 0);
+                Inst::Lui {
+                    rd,
+                    imm: Imm20::from_i32(0),
+                }
+                .emit_uncompressed(sink, emit_info, state, start_off);
+
+                sink.add_reloc(Reloc::RiscvTpRelAdd, &**name, 0);
+                Inst::AluRRR {
+                    alu_op: AluOPRRR::Add,
+                    rd,
+                    rs1: rd.to_reg(),
+                    rs2: tp_reg(),
+                }
+                .emit_uncompressed(sink, emit_info, state, start_off);
+
+                sink.add_reloc(Reloc::RiscvTpRelLo12I, &**name, 0);
+                Inst::AluRRImm12 {
+                    alu_op: AluOPRRI::Addi,
+                    rd,
+                    rs: rd.to_reg(),
+                    imm12: Imm12::ZERO,
+                }
+                .emit_uncompressed(sink, emit_info, state, start_off);
+            }
+
             &Inst::TrapIf { cmp, trap_code } => {
                 let label_end = sink.get_label();
 
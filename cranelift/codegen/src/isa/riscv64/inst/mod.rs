@@ -392,7 +392,9 @@ fn riscv64_get_operands(inst: &mut Inst, collector: &mut impl OperandVisitor) {
         }
         Inst::LoadExtNameGot { rd, .. }
         | Inst::LoadExtNameNear { rd, .. }
-        | Inst::LoadExtNameFar { rd, .. } => {
+        | Inst::LoadExtNameFar { rd, .. }
+        | Inst::ElfTlsIeGetAddr { rd, .. }
+        | Inst::ElfTlsLeGetAddr { rd, .. } => {
             collector.reg_def(rd);
         }
         Inst::ElfTlsGetAddr { rd, .. } => {
@@ -1451,6 +1453,14 @@ impl Inst {
                 let rd = format_reg(rd.to_reg());
                 format!("elf_tls_get_addr {rd},{}", name.display(None))
             }
+            &Inst::ElfTlsIeGetAddr { rd, ref name } => {
+                let rd = format_reg(rd.to_reg());
+                format!("elf_tls_ie_get_addr {rd},{}", name.display(None))
+            }
+            &Inst::ElfTlsLeGetAddr { rd, ref name } => {
+                let rd = format_reg(rd.to_reg());
+                format!("elf_tls_le_get_addr {rd},{}", name.display(None))
+            }
             &MInst::LoadAddr { ref rd, ref mem } => {
                 let rs = mem.to_string();
                 let rd = format_reg(rd.to_reg());
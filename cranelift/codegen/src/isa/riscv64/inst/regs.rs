@@ -91,6 +91,12 @@ pub fn writable_link_reg() -> Writable<Reg> {
     Writable::from_reg(link_reg())
 }
 
+/// Get a reference to the thread pointer (x4).
+#[inline]
+pub fn tp_reg() -> Reg {
+    x_reg(4)
+}
+
 /// Get a reference to the frame pointer (x8).
 #[inline]
 pub fn fp_reg() -> Reg {
@@ -12,6 +12,7 @@ use crate::machinst::{
     CompiledCodeStencil, MachInst, MachTextSectionBuilder, Reg, SigSet, TextSectionBuilder, VCode,
     compile,
 };
+use crate::profile::BlockProfile;
 use crate::result::CodegenResult;
 use crate::settings::{self as shared_settings, Flags};
 use crate::{CodegenError, ir};
@@ -56,6 +57,7 @@ impl Riscv64Backend {
         &self,
         func: &Function,
         domtree: &DominatorTree,
+        profile: &BlockProfile,
         regalloc_ctx: &mut regalloc2::Ctx,
         ctrl_plane: &mut ControlPlane,
     ) -> CodegenResult<VCode<inst::Inst>> {
@@ -65,6 +67,7 @@ impl Riscv64Backend {
         compile::compile::<Riscv64Backend>(
             func,
             domtree,
+            profile,
             regalloc_ctx,
             self,
             abi,
@@ -80,11 +83,12 @@ impl TargetIsa for Riscv64Backend {
         &self,
         func: &Function,
         domtree: &DominatorTree,
+        profile: &BlockProfile,
         regalloc_ctx: &mut regalloc2::Ctx,
         want_disasm: bool,
         ctrl_plane: &mut ControlPlane,
     ) -> CodegenResult<CompiledCodeStencil> {
-        let vcode = self.compile_vcode(func, domtree, regalloc_ctx, ctrl_plane)?;
+        let vcode = self.compile_vcode(func, domtree, profile, regalloc_ctx, ctrl_plane)?;
 
         let want_disasm = want_disasm || log::log_enabled!(log::Level::Debug);
         let emit_result = vcode.emit(&regalloc_ctx.output, want_disasm, &self.flags, ctrl_plane)?;
@@ -218,6 +222,12 @@ impl TargetIsa for Riscv64Backend {
         true
     }
 
+    fn has_native_simd(&self, _: Type) -> bool {
+        // Vector lowerings depend on the configured vector length, which
+        // hasn't been tuned for packing scalar operations yet.
+        false
+    }
+
     fn has_blendv_lowering(&self, _: Type) -> bool {
         false
     }
@@ -10,6 +10,7 @@ use crate::machinst::{
     CompiledCode, CompiledCodeStencil, MachInst, MachTextSectionBuilder, Reg, SigSet,
     TextSectionBuilder, VCode, compile,
 };
+use crate::profile::BlockProfile;
 use crate::result::CodegenResult;
 use crate::settings as shared_settings;
 use alloc::string::String;
@@ -53,6 +54,7 @@ impl S390xBackend {
         &self,
         func: &Function,
         domtree: &DominatorTree,
+        profile: &BlockProfile,
         regalloc_ctx: &mut regalloc2::Ctx,
         ctrl_plane: &mut ControlPlane,
     ) -> CodegenResult<VCode<inst::Inst>> {
@@ -62,6 +64,7 @@ impl S390xBackend {
         compile::compile::<S390xBackend>(
             func,
             domtree,
+            profile,
             regalloc_ctx,
             self,
             abi,
@@ -77,12 +80,13 @@ impl TargetIsa for S390xBackend {
         &self,
         func: &Function,
         domtree: &DominatorTree,
+        profile: &BlockProfile,
         regalloc_ctx: &mut regalloc2::Ctx,
         want_disasm: bool,
         ctrl_plane: &mut ControlPlane,
     ) -> CodegenResult<CompiledCodeStencil> {
         let flags = self.flags();
-        let vcode = self.compile_vcode(func, domtree, regalloc_ctx, ctrl_plane)?;
+        let vcode = self.compile_vcode(func, domtree, profile, regalloc_ctx, ctrl_plane)?;
 
         let emit_result = vcode.emit(&regalloc_ctx.output, want_disasm, flags, ctrl_plane)?;
         let value_labels_ranges = emit_result.value_labels_ranges;
@@ -196,6 +200,12 @@ impl TargetIsa for S390xBackend {
         true
     }
 
+    fn has_native_simd(&self, _: Type) -> bool {
+        // Lane order in vector registers depends on the endianness of the
+        // function's vector accesses, so scalar operations aren't packed.
+        false
+    }
+
     fn has_blendv_lowering(&self, _: Type) -> bool {
         self.isa_flags.has_vxrs_ext3()
     }
@@ -1739,6 +1739,41 @@ pub(crate) fn emit(
             sink.put4(0); // offset
         }
 
+        Inst::ElfTlsIeGetAddr { symbol, dst } => {
+            let dst = dst.to_reg().to_reg().to_real_reg().unwrap().hw_enc();
+
+            // N.B.: The linker may relax this sequence to local-exec when the
+            // symbol turns out to be defined in the executable, so it must be
+            // exactly an `add` from the GOT entry.
+
+            // movq %fs:0, %dst
+            emit_load_elf_thread_pointer(sink, dst);
+
+            // addq gv@gottpoff(%rip), %dst
+            sink.put1(0x48 | ((dst >> 3) << 2)); // REX.W + REX.R
+            sink.put1(0x03); // ADD
+            sink.put1(0x05 | ((dst & 7) << 3)); // ModRM byte
+            emit_reloc(sink, Reloc::ElfX86_64GotTpOff, symbol, -4);
+            sink.put4(0); // offset
+        }
+
+        Inst::ElfTlsLeGetAddr { symbol, dst } => {
+            let dst = dst.to_reg().to_reg().to_real_reg().unwrap().hw_enc();
+
+            // movq %fs:0, %dst
+            emit_load_elf_thread_pointer(sink, dst);
+
+            // leaq gv@tpoff(%dst), %dst
+            sink.put1(0x48 | ((dst >> 3) << 2) | (dst >> 3)); // REX.W + REX.R + REX.B
+            sink.put1(0x8d); // LEA
+            sink.put1(0x80 | ((dst & 7) << 3) | (dst & 7)); // ModRM byte
+            if dst & 7 == 4 {
+                sink.put1(0x24); // SIB byte, needed for %rsp and %r12 bases
+            }
+            emit_reloc(sink, Reloc::ElfX86_64TpOff32, symbol, 0);
+            sink.put4(0); // offset
+        }
+
         Inst::MachOTlsGetAddr { symbol, dst } => {
             let dst = dst.to_reg().to_reg();
             debug_assert_eq!(dst, regs::rax());
@@ -1854,6 +1889,17 @@ pub(crate) fn emit(
     state.clear_post_insn();
 }
 
+/// Emit `movq %fs:0, %dst`, loading the ELF thread pointer, which the x86-64
+/// psABI stores at offset 0 of the thread control block.
+fn emit_load_elf_thread_pointer(sink: &mut MachBuffer<Inst>, dst: u8) {
+    sink.put1(0x64); // fs segment override
+    sink.put1(0x48 | ((dst >> 3) << 2)); // REX.W + REX.R
+    sink.put1(0x8b); // MOV
+    sink.put1(0x04 | ((dst & 7) << 3)); // ModRM byte
+    sink.put1(0x25); // SIB byte, absolute address
+    sink.put4(0); // address
+}
+
 /// Emit the common sequence used for both direct and indirect tail calls:
 ///
 /// * Copy the new frame's stack arguments over the top of our current frame.
@@ -33,6 +33,7 @@ fn test_x64_emit() {
     let r9 = regs::r9();
     let r10 = regs::r10();
     let r11 = regs::r11();
+    let r12 = regs::r12();
     let r14 = regs::r14();
     let r15 = regs::r15();
 
@@ -64,6 +65,7 @@ fn test_x64_emit() {
     let _w_r8 = Writable::<Reg>::from_reg(r8);
     let _w_r9 = Writable::<Reg>::from_reg(r9);
     let w_r11 = Writable::<Reg>::from_reg(r11);
+    let w_r12 = Writable::<Reg>::from_reg(r12);
     let _w_r14 = Writable::<Reg>::from_reg(r14);
     let _w_r15 = Writable::<Reg>::from_reg(r15);
 
@@ -322,6 +324,42 @@ fn test_x64_emit() {
         "%rax = elf_tls_get_addr User(userextname0)",
     ));
 
+    insns.push((
+        Inst::ElfTlsIeGetAddr {
+            symbol: ExternalName::User(UserExternalNameRef::new(0)),
+            dst: WritableGpr::from_writable_reg(w_rax).unwrap(),
+        },
+        "64488B04250000000048030500000000",
+        "%rax = elf_tls_ie_get_addr User(userextname0)",
+    ));
+
+    insns.push((
+        Inst::ElfTlsIeGetAddr {
+            symbol: ExternalName::User(UserExternalNameRef::new(0)),
+            dst: WritableGpr::from_writable_reg(w_r12).unwrap(),
+        },
+        "644C8B2425000000004C032500000000",
+        "%r12 = elf_tls_ie_get_addr User(userextname0)",
+    ));
+
+    insns.push((
+        Inst::ElfTlsLeGetAddr {
+            symbol: ExternalName::User(UserExternalNameRef::new(0)),
+            dst: WritableGpr::from_writable_reg(w_rax).unwrap(),
+        },
+        "64488B042500000000488D8000000000",
+        "%rax = elf_tls_le_get_addr User(userextname0)",
+    ));
+
+    insns.push((
+        Inst::ElfTlsLeGetAddr {
+            symbol: ExternalName::User(UserExternalNameRef::new(0)),
+            dst: WritableGpr::from_writable_reg(w_r12).unwrap(),
+        },
+        "644C8B2425000000004D8DA42400000000",
+        "%r12 = elf_tls_le_get_addr User(userextname0)",
+    ));
+
     insns.push((
         Inst::MachOTlsGetAddr {
             symbol: ExternalName::User(UserExternalNameRef::new(0)),
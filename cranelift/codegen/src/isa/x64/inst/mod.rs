@@ -104,6 +104,8 @@ impl Inst {
             | Inst::XmmUninitializedValue { .. }
             | Inst::GprUninitializedValue { .. }
             | Inst::ElfTlsGetAddr { .. }
+            | Inst::ElfTlsIeGetAddr { .. }
+            | Inst::ElfTlsLeGetAddr { .. }
             | Inst::MachOTlsGetAddr { .. }
             | Inst::CoffTlsGetAddr { .. }
             | Inst::Unwind { .. }
@@ -807,6 +809,16 @@ impl PrettyPrint for Inst {
                 format!("{dst} = elf_tls_get_addr {symbol:?}")
             }
 
+            Inst::ElfTlsIeGetAddr { symbol, dst } => {
+                let dst = pretty_print_reg(dst.to_reg().to_reg(), 8);
+                format!("{dst} = elf_tls_ie_get_addr {symbol:?}")
+            }
+
+            Inst::ElfTlsLeGetAddr { symbol, dst } => {
+                let dst = pretty_print_reg(dst.to_reg().to_reg(), 8);
+                format!("{dst} = elf_tls_le_get_addr {symbol:?}")
+            }
+
             Inst::MachOTlsGetAddr { symbol, dst } => {
                 let dst = pretty_print_reg(dst.to_reg().to_reg(), 8);
                 format!("{dst} = macho_tls_get_addr {symbol:?}")
@@ -1190,6 +1202,12 @@ fn x64_get_operands(inst: &mut Inst, collector: &mut impl OperandVisitor) {
             collector.reg_clobbers(clobbers);
         }
 
+        Inst::ElfTlsIeGetAddr { dst, .. } | Inst::ElfTlsLeGetAddr { dst, .. } => {
+            // The thread pointer is read through the fs segment register,
+            // which is not allocatable, so only `dst` is visible to regalloc.
+            collector.reg_def(dst);
+        }
+
         Inst::CoffTlsGetAddr { dst, tmp, .. } => {
             // We also use the gs register. But that register is not allocatable by the
             // register allocator, so we don't need to mark it as used here.
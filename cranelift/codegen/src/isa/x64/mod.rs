@@ -13,6 +13,7 @@ use crate::machinst::{
     CompiledCodeStencil, MachInst, MachTextSectionBuilder, Reg, SigSet, TextSectionBuilder, VCode,
     compile,
 };
+use crate::profile::BlockProfile;
 use crate::result::{CodegenError, CodegenResult};
 use crate::settings::{self as shared_settings, Flags};
 use crate::{Final, MachBufferFinalized};
@@ -61,6 +62,7 @@ impl X64Backend {
         &self,
         func: &Function,
         domtree: &DominatorTree,
+        profile: &BlockProfile,
         regalloc_ctx: &mut regalloc2::Ctx,
         ctrl_plane: &mut ControlPlane,
     ) -> CodegenResult<VCode<inst::Inst>> {
@@ -72,6 +74,7 @@ impl X64Backend {
         compile::compile::<Self>(
             func,
             domtree,
+            profile,
             regalloc_ctx,
             self,
             abi,
@@ -87,11 +90,12 @@ impl TargetIsa for X64Backend {
         &self,
         func: &Function,
         domtree: &DominatorTree,
+        profile: &BlockProfile,
         regalloc_ctx: &mut regalloc2::Ctx,
         want_disasm: bool,
         ctrl_plane: &mut ControlPlane,
     ) -> CodegenResult<CompiledCodeStencil> {
-        let vcode = self.compile_vcode(func, domtree, regalloc_ctx, ctrl_plane)?;
+        let vcode = self.compile_vcode(func, domtree, profile, regalloc_ctx, ctrl_plane)?;
 
         let emit_result = vcode.emit(&regalloc_ctx.output, want_disasm, &self.flags, ctrl_plane)?;
         let value_labels_ranges = emit_result.value_labels_ranges;
@@ -189,6 +193,12 @@ impl TargetIsa for X64Backend {
         self.x64_flags.has_sse41()
     }
 
+    fn has_native_simd(&self, ty: Type) -> bool {
+        // Many vector lowerings, such as `imul.i32x4`, need SSE 4.1 to avoid
+        // long emulation sequences.
+        ty.bits() == 128 && self.x64_flags.has_sse41()
+    }
+
     fn has_blendv_lowering(&self, ty: Type) -> bool {
         // The `blendvpd`, `blendvps`, and `pblendvb` instructions are all only
         // available from SSE 4.1 and onwards. Otherwise the i16x8 type has no
@@ -62,6 +62,7 @@ pub mod isa;
 pub mod loop_analysis;
 pub mod post_dominator_tree;
 pub mod print_errors;
+pub mod profile;
 pub mod settings;
 pub mod timing;
 pub mod traversals;
@@ -89,12 +90,14 @@ mod ctxhash;
 mod egraph;
 mod inst_predicates;
 mod isle_prelude;
+mod loop_unroll;
 mod nan_canonicalization;
 mod opts;
 mod ranges;
 mod remove_constant_phis;
 mod result;
 mod scoped_hash_map;
+mod slp_vectorize;
 mod take_and_replace;
 mod unreachable_code;
 mod value_label;
@@ -0,0 +1,641 @@
+//! Loop unrolling and induction-variable strength reduction.
+//!
+//! This pass handles the most common shape of hot loop: a loop made of a
+//! single block which branches back to itself, such as a byte-copy loop or a
+//! checksum over an array. It runs before the egraph pass, which cleans up the
+//! redundant arithmetic this pass introduces.
+//!
+//! * Induction variables are block parameters incremented by a constant on the
+//!   back edge. Address computations of the form `base + (iv << k)` or
+//!   `base + iv * m`, where `base` is defined outside of the loop, are replaced
+//!   by a new block parameter which is incremented by the scaled step instead.
+//!
+//! * Small loops whose exit test compares an induction variable with a
+//!   loop-invariant value are then unrolled by duplicating the block, so that
+//!   each copy branches to the next one and the last copy branches back to the
+//!   header. Every copy keeps its exit test, unless the trip count is a known
+//!   constant which is a multiple of the unroll factor, in which case only the
+//!   last copy needs one.
+
+use crate::FxHashMap;
+use crate::cursor::{Cursor, FuncCursor};
+use crate::dominator_tree::DominatorTree;
+use crate::flowgraph::ControlFlowGraph;
+use crate::ir::condcodes::IntCC;
+use crate::ir::immediates::Imm64;
+use crate::ir::{
+    Block, BlockArg, Function, Inst, InstBuilder, InstructionData, Opcode, Value, ValueDef,
+};
+use crate::loop_analysis::LoopAnalysis;
+use crate::timing;
+use alloc::vec::Vec;
+use smallvec::SmallVec;
+
+/// Loops with at most this many instructions, not counting the branch, are
+/// unrolled four times.
+const UNROLL_BY_4_MAX_INSTS: usize = 8;
+
+/// Loops with at most this many instructions, not counting the branch, are
+/// unrolled twice.
+const UNROLL_BY_2_MAX_INSTS: usize = 24;
+
+/// Maximum number of instructions unrolling may add to a single function.
+const MAX_ADDED_INSTS: usize = 1024;
+
+/// Maximum number of address computations strength-reduced in a single loop,
+/// each of which costs a block parameter.
+const MAX_REDUCED_PER_LOOP: usize = 4;
+
+/// Maximum number of iterations evaluated when looking for a constant trip
+/// count.
+const MAX_EVALUATED_TRIPS: u64 = 1 << 16;
+
+/// Unroll and strength-reduce the single-block loops of `func`.
+///
+/// Returns whether anything changed. Unrolling adds blocks and edges, so `cfg`,
+/// `domtree` and `loop_analysis` are recomputed after each loop is unrolled and
+/// are up to date on return.
+pub fn do_loop_unroll(
+    func: &mut Function,
+    cfg: &mut ControlFlowGraph,
+    domtree: &mut DominatorTree,
+    loop_analysis: &mut LoopAnalysis,
+) -> bool {
+    let _tt = timing::loop_unroll();
+
+    let headers: Vec<Block> = loop_analysis
+        .loops()
+        .map(|lp| loop_analysis.loop_header(lp))
+        .collect();
+
+    // Strength reduction rewrites the branches entering each loop, so do it
+    // for every loop before unrolling duplicates any of those branches. It
+    // only changes branch arguments, so the CFG stays valid.
+    let mut changed = false;
+    for &header in &headers {
+        if let Some(lp) = SimpleLoop::new(func, cfg, header) {
+            changed |= strength_reduce(func, cfg, &lp);
+        }
+    }
+    // Strength-reduced addresses are aliases now, which copies of the loop
+    // body would otherwise keep pointing at the original block's values.
+    if changed {
+        func.dfg.resolve_all_aliases();
+    }
+
+    let mut budget = MAX_ADDED_INSTS;
+    for header in headers {
+        if loop_analysis.is_loop_header(header).is_none() {
+            continue;
+        }
+        let Some(lp) = SimpleLoop::new(func, cfg, header) else {
+            continue;
+        };
+        if unroll(func, cfg, &lp, &mut budget) {
+            changed = true;
+            cfg.compute(func);
+            domtree.compute(func, cfg);
+            loop_analysis.compute(func, cfg, domtree);
+        }
+    }
+
+    if changed {
+        func.dfg.resolve_all_aliases();
+    }
+    changed
+}
+
+/// A loop consisting of a single block which ends in a `brif` back to itself.
+struct SimpleLoop {
+    header: Block,
+    /// The `brif` terminating `header`.
+    branch: Inst,
+    /// Index of the back edge in the destinations of `branch`.
+    back: usize,
+    /// The block `branch` exits the loop to.
+    exit: Block,
+}
+
+impl SimpleLoop {
+    fn new(func: &Function, cfg: &ControlFlowGraph, header: Block) -> Option<Self> {
+        if func.layout.entry_block() == Some(header) {
+            return None;
+        }
+        let branch = func.layout.last_inst(header)?;
+        let InstructionData::Brif { blocks, .. } = &func.dfg.insts[branch] else {
+            return None;
+        };
+        let targets = blocks.map(|call| call.block(&func.dfg.value_lists));
+        let back = match (targets[0] == header, targets[1] == header) {
+            (true, false) => 0,
+            (false, true) => 1,
+            _ => return None,
+        };
+
+        // Any other edge into the header would make it part of a larger loop.
+        let preds_in_loop = cfg.pred_iter(header).filter(|p| p.block == header).count();
+        if preds_in_loop != 1 {
+            return None;
+        }
+
+        Some(SimpleLoop {
+            header,
+            branch,
+            back,
+            exit: targets[1 - back],
+        })
+    }
+
+    /// Returns the arguments passed to the header on the back edge.
+    fn back_edge_args(&self, func: &Function) -> SmallVec<[BlockArg; 8]> {
+        func.dfg.insts[self.branch]
+            .branch_destination(&func.dfg.jump_tables, &func.dfg.exception_tables)[self.back]
+            .args(&func.dfg.value_lists)
+            .collect()
+    }
+
+    /// Returns the value passed for the `index`th header parameter on each
+    /// edge entering the loop, along with the branch of that edge and the
+    /// index of its destination.
+    ///
+    /// Returns `None` if any edge enters from a `br_table`, whose jump table
+    /// may be shared with other branches, or passes something other than a
+    /// plain value.
+    fn entry_args(
+        &self,
+        func: &Function,
+        cfg: &ControlFlowGraph,
+        index: usize,
+    ) -> Option<SmallVec<[(Inst, usize, Value); 2]>> {
+        let mut ret = SmallVec::new();
+        for pred in cfg.pred_iter(self.header) {
+            if pred.block == self.header {
+                continue;
+            }
+            if !matches!(
+                func.dfg.insts[pred.inst].opcode(),
+                Opcode::Jump | Opcode::Brif
+            ) {
+                return None;
+            }
+            let dests = func.dfg.insts[pred.inst]
+                .branch_destination(&func.dfg.jump_tables, &func.dfg.exception_tables);
+            for (i, dest) in dests.iter().enumerate() {
+                if dest.block(&func.dfg.value_lists) != self.header {
+                    continue;
+                }
+                let arg = dest.args(&func.dfg.value_lists).nth(index)?.as_value()?;
+                ret.push((pred.inst, i, arg));
+            }
+        }
+        Some(ret)
+    }
+
+    /// Returns whether `value` is defined by this loop.
+    fn defines(&self, func: &Function, value: Value) -> bool {
+        match func.dfg.value_def(value) {
+            ValueDef::Result(inst, _) => func.layout.inst_block(inst) == Some(self.header),
+            ValueDef::Param(block, _) => block == self.header,
+            ValueDef::Union(..) => false,
+        }
+    }
+}
+
+/// A header parameter which is incremented by a constant on every iteration.
+struct InductionVar {
+    /// Index of the parameter among the header's parameters.
+    index: usize,
+    param: Value,
+    /// The incremented value passed on the back edge.
+    next: Value,
+    step: i64,
+}
+
+fn induction_vars(func: &Function, lp: &SimpleLoop) -> Vec<InductionVar> {
+    let params = func.dfg.block_params(lp.header);
+    let mut ivs = Vec::new();
+    for (index, arg) in lp.back_edge_args(func).into_iter().enumerate() {
+        let Some(next) = arg.as_value() else { continue };
+        let ValueDef::Result(inst, _) = func.dfg.value_def(next) else {
+            continue;
+        };
+        if func.layout.inst_block(inst) != Some(lp.header) {
+            continue;
+        }
+        let InstructionData::Binary {
+            opcode: Opcode::Iadd,
+            args: [x, y],
+        } = func.dfg.insts[inst]
+        else {
+            continue;
+        };
+        let param = params[index];
+        let step = if x == param {
+            iconst(func, y)
+        } else if y == param {
+            iconst(func, x)
+        } else {
+            None
+        };
+        if let Some(step) = step {
+            ivs.push(InductionVar {
+                index,
+                param,
+                next,
+                step,
+            });
+        }
+    }
+    ivs
+}
+
+/// Returns the constant `value` is defined as, if any.
+fn iconst(func: &Function, value: Value) -> Option<i64> {
+    let ValueDef::Result(inst, _) = func.dfg.value_def(value) else {
+        return None;
+    };
+    match func.dfg.insts[inst] {
+        InstructionData::UnaryImm {
+            opcode: Opcode::Iconst,
+            imm,
+        } => Some(imm.bits()),
+        _ => None,
+    }
+}
+
+/// If `value` is an induction variable multiplied by a constant, returns the
+/// index of that induction variable in `ivs` and the multiplier.
+fn scaled_iv(
+    func: &Function,
+    lp: &SimpleLoop,
+    ivs: &[InductionVar],
+    value: Value,
+) -> Option<(usize, i64)> {
+    let iv = |v: Value| ivs.iter().position(|iv| iv.param == v);
+    if let Some(i) = iv(value) {
+        return Some((i, 1));
+    }
+    let ValueDef::Result(inst, _) = func.dfg.value_def(value) else {
+        return None;
+    };
+    if func.layout.inst_block(inst) != Some(lp.header) {
+        return None;
+    }
+    match func.dfg.insts[inst] {
+        InstructionData::Binary {
+            opcode: Opcode::Ishl,
+            args: [x, y],
+        } => {
+            let bits = i64::from(func.dfg.value_type(value).bits());
+            let shift = iconst(func, y)? & (bits - 1);
+            Some((iv(x)?, 1i64.wrapping_shl(shift as u32)))
+        }
+        InstructionData::Binary {
+            opcode: Opcode::Imul,
+            args: [x, y],
+        } => match (iv(x), iv(y)) {
+            (Some(i), _) => Some((i, iconst(func, y)?)),
+            (_, Some(i)) => Some((i, iconst(func, x)?)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Replace `base + iv * scale` computations in the loop with new induction
+/// variables.
+fn strength_reduce(func: &mut Function, cfg: &ControlFlowGraph, lp: &SimpleLoop) -> bool {
+    let ivs = induction_vars(func, lp);
+    if ivs.is_empty() {
+        return false;
+    }
+
+    let mut candidates = Vec::new();
+    for inst in func.layout.block_insts(lp.header) {
+        let InstructionData::Binary {
+            opcode: Opcode::Iadd,
+            args: [x, y],
+        } = func.dfg.insts[inst]
+        else {
+            continue;
+        };
+        let ty = func.dfg.value_type(func.dfg.first_result(inst));
+        if !ty.is_int() || ty.bits() > 64 {
+            continue;
+        }
+        for (base, scaled) in [(x, y), (y, x)] {
+            if lp.defines(func, base) || iconst(func, base).is_some() {
+                continue;
+            }
+            if let Some((iv, scale)) = scaled_iv(func, lp, &ivs, scaled)
+                && func.dfg.value_type(ivs[iv].param) == ty
+                && !ivs.iter().any(|v| v.next == func.dfg.first_result(inst))
+            {
+                candidates.push((inst, ty, base, iv, scale));
+                break;
+            }
+        }
+        if candidates.len() == MAX_REDUCED_PER_LOOP {
+            break;
+        }
+    }
+
+    let mut changed = false;
+    for (inst, ty, base, iv, scale) in candidates {
+        let Some(entries) = lp.entry_args(func, cfg, ivs[iv].index) else {
+            continue;
+        };
+        let reduced = func.dfg.append_block_param(lp.header, ty);
+
+        for (branch, dest, init) in entries {
+            let mut pos = FuncCursor::new(func).at_inst(branch);
+            pos.use_srcloc(branch);
+            let scaled = if scale == 1 {
+                init
+            } else {
+                let scale = pos
+                    .ins()
+                    .iconst(ty, Imm64::new(scale).zero_extend_from_width(ty.bits()));
+                pos.ins().imul(init, scale)
+            };
+            let start = pos.ins().iadd(base, scaled);
+            append_branch_arg(func, branch, dest, start);
+        }
+
+        let mut pos = FuncCursor::new(func).at_inst(lp.branch);
+        pos.use_srcloc(lp.branch);
+        let step = Imm64::new(ivs[iv].step.wrapping_mul(scale)).zero_extend_from_width(ty.bits());
+        let step = pos.ins().iconst(ty, step);
+        let next = pos.ins().iadd(reduced, step);
+        append_branch_arg(func, lp.branch, lp.back, next);
+
+        let old = func.dfg.first_result(inst);
+        func.dfg.clear_results(inst);
+        func.layout.remove_inst(inst);
+        func.dfg.change_to_alias(old, reduced);
+        changed = true;
+    }
+    changed
+}
+
+fn append_branch_arg(func: &mut Function, branch: Inst, dest: usize, arg: Value) {
+    let dfg = &mut func.dfg;
+    dfg.insts[branch].branch_destination_mut(&mut dfg.jump_tables, &mut dfg.exception_tables)[dest]
+        .append_argument(arg, &mut dfg.value_lists);
+}
+
+/// Unroll the loop by a factor chosen from its size, if it's counted by an
+/// induction variable.
+fn unroll(
+    func: &mut Function,
+    cfg: &ControlFlowGraph,
+    lp: &SimpleLoop,
+    budget: &mut usize,
+) -> bool {
+    let Some(test) = exit_test(func, lp) else {
+        return false;
+    };
+    let body: Vec<Inst> = func.layout.block_insts(lp.header).collect();
+    let factor = match body.len() - 1 {
+        n if n <= UNROLL_BY_4_MAX_INSTS => 4,
+        n if n <= UNROLL_BY_2_MAX_INSTS => 2,
+        _ => return false,
+    };
+    let added = body.len() * (factor - 1);
+    if added > *budget {
+        return false;
+    }
+
+    // Values defined in the loop which are used after it must be passed to the
+    // exit block instead, since each copy of the loop defines its own. That's
+    // only possible if the exit block is reached from the loop alone.
+    let mut escaping = Vec::new();
+    for block in func.layout.blocks() {
+        if block == lp.header {
+            continue;
+        }
+        for inst in func.layout.block_insts(block) {
+            for value in func.dfg.inst_values(inst) {
+                if lp.defines(func, value) && !escaping.contains(&value) {
+                    escaping.push(value);
+                }
+            }
+        }
+    }
+    if !escaping.is_empty() && cfg.pred_iter(lp.exit).count() != 1 {
+        return false;
+    }
+    *budget -= added;
+
+    let trip_count = constant_trip_count(func, cfg, lp, &test);
+    let keep_exits = trip_count.is_none_or(|n| n % factor as u64 != 0);
+
+    if !escaping.is_empty() {
+        let exit_params: FxHashMap<Value, Value> = escaping
+            .iter()
+            .map(|&v| {
+                let ty = func.dfg.value_type(v);
+                (v, func.dfg.append_block_param(lp.exit, ty))
+            })
+            .collect();
+        let mut cursor = FuncCursor::new(func);
+        while let Some(block) = cursor.next_block() {
+            if block == lp.header {
+                continue;
+            }
+            while let Some(inst) = cursor.next_inst() {
+                cursor
+                    .func
+                    .dfg
+                    .map_inst_values(inst, |v| *exit_params.get(&v).unwrap_or(&v));
+            }
+        }
+        for &value in &escaping {
+            append_branch_arg(func, lp.branch, 1 - lp.back, value);
+        }
+    }
+
+    let mut branches = SmallVec::<[Inst; 4]>::new();
+    branches.push(lp.branch);
+    let mut after = lp.header;
+    for _ in 1..factor {
+        let block = func.dfg.make_block();
+        func.layout.insert_block_after(block, after);
+
+        let mut map = FxHashMap::default();
+        for i in 0..func.dfg.num_block_params(lp.header) {
+            let param = func.dfg.block_params(lp.header)[i];
+            let ty = func.dfg.value_type(param);
+            map.insert(param, func.dfg.append_block_param(block, ty));
+        }
+        for &inst in &body {
+            let copy = func.dfg.clone_inst(inst);
+            func.dfg
+                .map_inst_values(copy, |v| *map.get(&v).unwrap_or(&v));
+            for (&old, &new) in func
+                .dfg
+                .inst_results(inst)
+                .iter()
+                .zip(func.dfg.inst_results(copy))
+            {
+                map.insert(old, new);
+            }
+            func.layout.append_inst(copy, block);
+            let srcloc = func.srcloc(inst);
+            func.set_srcloc(copy, srcloc);
+        }
+
+        // The previous copy now continues into this one, while this copy
+        // continues back to the header. The copied branch was cloned from the
+        // header's, which an earlier copy may already have retargeted.
+        let prev = *branches.last().unwrap();
+        let branch = func.layout.last_inst(block).unwrap();
+        let dfg = &mut func.dfg;
+        dfg.insts[prev].branch_destination_mut(&mut dfg.jump_tables, &mut dfg.exception_tables)
+            [lp.back]
+            .set_block(block, &mut dfg.value_lists);
+        dfg.insts[branch].branch_destination_mut(&mut dfg.jump_tables, &mut dfg.exception_tables)
+            [lp.back]
+            .set_block(lp.header, &mut dfg.value_lists);
+        branches.push(branch);
+        after = block;
+    }
+
+    if !keep_exits {
+        for &branch in &branches[..factor - 1] {
+            let dest = func.dfg.insts[branch]
+                .branch_destination(&func.dfg.jump_tables, &func.dfg.exception_tables)[lp.back];
+            let target = dest.block(&func.dfg.value_lists);
+            let args: SmallVec<[BlockArg; 8]> = dest.args(&func.dfg.value_lists).collect();
+            func.replace(branch).jump(target, &args);
+        }
+    }
+
+    true
+}
+
+/// The exit test of a counted loop: a comparison of an induction variable with
+/// a constant or a value defined outside of the loop.
+struct ExitTest {
+    iv: InductionVar,
+    /// Whether the incremented induction variable is compared, rather than
+    /// the header parameter.
+    compares_next: bool,
+    /// Whether the induction variable is the left-hand side of the comparison.
+    lhs: bool,
+    cc: IntCC,
+    bound: Value,
+}
+
+fn exit_test(func: &Function, lp: &SimpleLoop) -> Option<ExitTest> {
+    let InstructionData::Brif { arg: cond, .. } = func.dfg.insts[lp.branch] else {
+        unreachable!()
+    };
+    let ValueDef::Result(cmp, _) = func.dfg.value_def(cond) else {
+        return None;
+    };
+    let InstructionData::IntCompare {
+        opcode: Opcode::Icmp,
+        cond: cc,
+        args: [x, y],
+    } = func.dfg.insts[cmp]
+    else {
+        return None;
+    };
+
+    induction_vars(func, lp).into_iter().find_map(|iv| {
+        let (compares_next, lhs, bound) = if x == iv.param || x == iv.next {
+            (x == iv.next, true, y)
+        } else if y == iv.param || y == iv.next {
+            (y == iv.next, false, x)
+        } else {
+            return None;
+        };
+        if lp.defines(func, bound) && iconst(func, bound).is_none() {
+            return None;
+        }
+        Some(ExitTest {
+            iv,
+            compares_next,
+            lhs,
+            cc,
+            bound,
+        })
+    })
+}
+
+/// Returns the number of times the loop body runs, if it's a constant.
+///
+/// That's the case when the exit test compares with a constant and the
+/// induction variable has the same constant value on every edge entering the
+/// loop.
+fn constant_trip_count(
+    func: &Function,
+    cfg: &ControlFlowGraph,
+    lp: &SimpleLoop,
+    test: &ExitTest,
+) -> Option<u64> {
+    let bound = iconst(func, test.bound)? as u64;
+
+    let mut init = None;
+    for (_, _, arg) in lp.entry_args(func, cfg, test.iv.index)? {
+        let value = iconst(func, arg)?;
+        if init.is_some_and(|init| init != value) {
+            return None;
+        }
+        init = Some(value);
+    }
+    let mut current = init? as u64;
+
+    let bits = func.dfg.value_type(test.iv.param).bits();
+    for trips in 1..=MAX_EVALUATED_TRIPS {
+        let next = current.wrapping_add(test.iv.step as u64);
+        let compared = if test.compares_next { next } else { current };
+        let taken = if test.lhs {
+            eval_icmp(test.cc, bits, compared, bound)
+        } else {
+            eval_icmp(test.cc, bits, bound, compared)
+        };
+        if taken != (lp.back == 0) {
+            return Some(trips);
+        }
+        current = next;
+    }
+    None
+}
+
+/// Evaluates `icmp cc x, y` on integers of the given width.
+fn eval_icmp(cc: IntCC, bits: u32, x: u64, y: u64) -> bool {
+    let shift = 64 - bits;
+    let (ux, uy) = ((x << shift) >> shift, (y << shift) >> shift);
+    let (sx, sy) = (
+        ((x << shift) as i64) >> shift,
+        ((y << shift) as i64) >> shift,
+    );
+    match cc {
+        IntCC::Equal => ux == uy,
+        IntCC::NotEqual => ux != uy,
+        IntCC::SignedLessThan => sx < sy,
+        IntCC::SignedGreaterThanOrEqual => sx >= sy,
+        IntCC::SignedGreaterThan => sx > sy,
+        IntCC::SignedLessThanOrEqual => sx <= sy,
+        IntCC::UnsignedLessThan => ux < uy,
+        IntCC::UnsignedGreaterThanOrEqual => ux >= uy,
+        IntCC::UnsignedGreaterThan => ux > uy,
+        IntCC::UnsignedLessThanOrEqual => ux <= uy,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn icmp_respects_width() {
+        assert!(eval_icmp(IntCC::Equal, 8, 0x100, 0));
+        assert!(eval_icmp(IntCC::SignedLessThan, 8, 0xff, 0));
+        assert!(!eval_icmp(IntCC::UnsignedLessThan, 8, 0xff, 0));
+        assert!(eval_icmp(IntCC::UnsignedGreaterThan, 32, 0xffff_ffff, 1));
+        assert!(eval_icmp(IntCC::SignedLessThan, 64, u64::MAX, 0));
+    }
+}
@@ -59,11 +59,17 @@
 //! Furthermore, the [MachBuffer] machine-code sink performs final peephole-like
 //! branch editing that in practice elides empty blocks and simplifies some of
 //! the other redundancies that this scheme produces.
+//!
+//! When a [BlockProfile] is available, the DFS visits the hottest successor of
+//! each block last, so that the reverse postorder places it right after the
+//! block and the branch to it becomes a fall-through. Blocks the profile shows
+//! are rarely executed are marked cold, like blocks marked cold in the CLIF.
 
 use crate::dominator_tree::DominatorTree;
 use crate::entity::SecondaryMap;
 use crate::inst_predicates::visit_block_succs;
 use crate::ir::{Block, Function, Inst, Opcode};
+use crate::profile::BlockProfile;
 use crate::{FxHashMap, FxHashSet};
 use crate::{machinst::*, trace};
 
@@ -148,6 +154,7 @@ impl BlockLoweringOrder {
     pub fn new(
         f: &Function,
         domtree: &DominatorTree,
+        profile: &BlockProfile,
         ctrl_plane: &mut ControlPlane,
     ) -> BlockLoweringOrder {
         trace!("BlockLoweringOrder: function body {:?}", f);
@@ -197,11 +204,20 @@ impl BlockLoweringOrder {
         }
 
         // Step 2: walk the postorder from the domtree in reverse to produce our desired node
-        // lowering order, identifying critical edges to split along the way.
+        // lowering order, identifying critical edges to split along the way. With a profile, a
+        // postorder that puts hot successors right after their predecessors is used instead.
+
+        let profiled_postorder;
+        let postorder = if profile.is_empty() {
+            domtree.cfg_postorder()
+        } else {
+            profiled_postorder = profile_postorder(f, profile, &block_succs, &block_succ_range);
+            &profiled_postorder[..]
+        };
 
         let mut lowered_order = Vec::new();
         let mut blockindex_by_block = SecondaryMap::with_default(BlockIndex::invalid());
-        for &block in domtree.cfg_rpo() {
+        for &block in postorder.iter().rev() {
             let idx = BlockIndex::new(lowered_order.len());
             lowered_order.push(LoweredBlock::Orig { block });
             blockindex_by_block[block] = idx;
@@ -242,6 +258,16 @@ impl BlockLoweringOrder {
         // Step 3: build the successor tables given the lowering order. We can't perform this step
         // during the creation of `lowering_order`, as we need `lb_to_bindex` to be fully populated
         // first.
+        let cold_threshold = f
+            .layout
+            .entry_block()
+            .and_then(|entry| profile.cold_threshold(entry));
+        let is_cold = |block| {
+            f.is_effectively_cold(block)
+                || cold_threshold
+                    .is_some_and(|t| profile.block_count(block).is_some_and(|count| count < t))
+        };
+
         let mut lowered_succ_indices = Vec::new();
         let mut cold_blocks = FxHashSet::default();
         let mut indirect_branch_targets = FxHashSet::default();
@@ -257,7 +283,7 @@ impl BlockLoweringOrder {
                         lowered_succ_indices
                             .extend(block_succs[range].iter().map(|lb| lb_to_bindex[lb]));
 
-                        if f.is_effectively_cold(block) {
+                        if is_cold(block) {
                             cold_blocks.insert(bindex);
                         }
 
@@ -282,7 +308,7 @@ impl BlockLoweringOrder {
                         // Edges inherit indirect branch and cold block metadata from their
                         // successor.
 
-                        if f.is_effectively_cold(succ) {
+                        if is_cold(succ) {
                             cold_blocks.insert(bindex);
                         }
 
@@ -342,6 +368,47 @@ impl BlockLoweringOrder {
     }
 }
 
+/// Compute a postorder of the blocks reachable from `f`'s entry in which the
+/// hottest successor of each block, according to `profile`, is visited last,
+/// so that it comes right after the block in reverse postorder unless another
+/// path to it has to come first.
+fn profile_postorder(
+    f: &Function,
+    profile: &BlockProfile,
+    block_succs: &[LoweredBlock],
+    block_succ_range: &SecondaryMap<Block, core::ops::Range<usize>>,
+) -> Vec<Block> {
+    let mut postorder = Vec::new();
+    let mut visited = SecondaryMap::with_default(false);
+    let mut stack: Vec<(Block, bool)> = f
+        .layout
+        .entry_block()
+        .map(|b| (b, false))
+        .into_iter()
+        .collect();
+    while let Some((block, done)) = stack.pop() {
+        if done {
+            postorder.push(block);
+            continue;
+        }
+        if visited[block] {
+            continue;
+        }
+        visited[block] = true;
+        stack.push((block, true));
+
+        // Successors pushed last are visited first, so push the hottest first.
+        let mut succs: SmallVec<[Block; 4]> = block_succs[block_succ_range[block].clone()]
+            .iter()
+            .map(|lb| lb.orig_block().unwrap())
+            .filter(|&succ| !visited[succ])
+            .collect();
+        succs.sort_by_key(|&succ| core::cmp::Reverse(profile.edge_weight(block, succ)));
+        stack.extend(succs.into_iter().map(|succ| (succ, false)));
+    }
+    postorder
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -353,6 +420,14 @@ mod test {
     use crate::isa::CallConv;
 
     fn build_test_func(n_blocks: usize, edges: &[(usize, usize)]) -> BlockLoweringOrder {
+        build_profiled_test_func(n_blocks, edges, &[])
+    }
+
+    fn build_profiled_test_func(
+        n_blocks: usize,
+        edges: &[(usize, usize)],
+        counts: &[(usize, u64)],
+    ) -> BlockLoweringOrder {
         assert!(n_blocks > 0);
 
         let name = UserFuncName::testcase("test0");
@@ -395,7 +470,21 @@ mod test {
         cfg.compute(&func);
         let dom_tree = DominatorTree::with_function(&func, &cfg);
 
-        BlockLoweringOrder::new(&func, &dom_tree, &mut Default::default())
+        let mut profile = BlockProfile::new();
+        for &(block, count) in counts {
+            profile.set_block_count(blocks[block], count);
+        }
+
+        BlockLoweringOrder::new(&func, &dom_tree, &profile, &mut Default::default())
+    }
+
+    fn orig_blocks(order: &BlockLoweringOrder) -> Vec<u32> {
+        order
+            .lowered_order
+            .iter()
+            .filter_map(|lb| lb.orig_block())
+            .map(|block| block.as_u32())
+            .collect()
     }
 
     #[test]
@@ -483,4 +572,28 @@ mod test {
         assert!(order.lowered_order[8].in_edge().is_none());
         assert!(order.lowered_order[8].out_edge().is_none());
     }
+
+    #[test]
+    fn test_blockorder_profile() {
+        //       0
+        //      / \
+        //     1   2
+        //      \ /
+        //       3
+        let edges = [(0, 1), (0, 2), (1, 3), (2, 3)];
+
+        // Whichever of blocks 1 and 2 is hotter comes right after block 0.
+        let order = build_profiled_test_func(4, &edges, &[(0, 100), (1, 99), (2, 1)]);
+        assert_eq!(orig_blocks(&order), [0, 1, 2, 3]);
+        let order = build_profiled_test_func(4, &edges, &[(0, 100), (1, 1), (2, 99)]);
+        assert_eq!(orig_blocks(&order), [0, 2, 1, 3]);
+
+        // Rarely executed blocks are cold.
+        let order = build_profiled_test_func(4, &edges, &[(0, 10_000), (1, 9_999), (2, 1)]);
+        let cold: Vec<u32> = (0..order.lowered_order.len())
+            .filter(|&i| order.is_cold(BlockIndex::new(i)))
+            .map(|i| order.lowered_order[i].orig_block().unwrap().as_u32())
+            .collect();
+        assert_eq!(cold, [2]);
+    }
 }
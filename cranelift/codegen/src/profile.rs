@@ -0,0 +1,147 @@
+//! Execution profiles used to guide code layout.
+
+use crate::FxHashMap;
+use crate::entity::SecondaryMap;
+use crate::ir::Block;
+
+/// Blocks executed less than once per this many executions of the function's
+/// entry are considered cold.
+const COLD_RATIO: u64 = 1000;
+
+/// Execution counts for the blocks and control-flow edges of a function.
+///
+/// Embedders can fill this in from profiling data before compiling a function
+/// through a [`Context`](crate::Context). Blocks which the profile shows are
+/// rarely executed are treated like blocks marked cold and are moved to the
+/// end of the function, and the hottest successor of each block is placed
+/// right after it where possible, so that it's reached by falling through.
+///
+/// Counts are relative: only their ratios matter, so sample counts from a
+/// sampling profiler work as well as exact counts. Blocks without counts,
+/// such as those created by optimizations, are laid out as usual.
+#[derive(Clone, Debug, Default)]
+pub struct BlockProfile {
+    /// Counts given for blocks.
+    blocks: SecondaryMap<Block, Option<u64>>,
+    /// Counts given for edges between blocks.
+    edges: FxHashMap<(Block, Block), u64>,
+    /// Sum of the counts given for the edges into each block.
+    incoming: SecondaryMap<Block, Option<u64>>,
+}
+
+impl BlockProfile {
+    /// Create a new empty profile.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Clear all counts.
+    pub fn clear(&mut self) {
+        self.blocks.clear();
+        self.edges.clear();
+        self.incoming.clear();
+    }
+
+    /// Does this profile have no counts at all?
+    pub fn is_empty(&self) -> bool {
+        self.blocks.values().all(Option::is_none) && self.edges.is_empty()
+    }
+
+    /// Record that `block` was executed `count` times.
+    pub fn set_block_count(&mut self, block: Block, count: u64) {
+        self.blocks[block] = Some(count);
+    }
+
+    /// Record that control flowed from `from` to `to` `count` times.
+    pub fn set_edge_count(&mut self, from: Block, to: Block, count: u64) {
+        let old = self.edges.insert((from, to), count).unwrap_or(0);
+        let incoming = self.incoming[to].unwrap_or(0) - old;
+        self.incoming[to] = Some(incoming.saturating_add(count));
+    }
+
+    /// Get the execution count of `block`.
+    ///
+    /// This is the count given for the block itself if there is one, and
+    /// otherwise the sum of the counts given for edges into it.
+    pub fn block_count(&self, block: Block) -> Option<u64> {
+        self.blocks[block].or(self.incoming[block])
+    }
+
+    /// Get the count given for the edge from `from` to `to`.
+    pub fn edge_count(&self, from: Block, to: Block) -> Option<u64> {
+        self.edges.get(&(from, to)).copied()
+    }
+
+    /// Estimate how often control flows from `from` to `to`, falling back to
+    /// the count of `to` when there's no count for the edge itself.
+    pub(crate) fn edge_weight(&self, from: Block, to: Block) -> u64 {
+        self.edge_count(from, to)
+            .or_else(|| self.block_count(to))
+            .unwrap_or(0)
+    }
+
+    /// Get the count below which blocks are cold, given the function's entry
+    /// block.
+    ///
+    /// Returns `None` when nothing in the function is known to have executed.
+    pub(crate) fn cold_threshold(&self, entry: Block) -> Option<u64> {
+        let reference = self.block_count(entry).or_else(|| {
+            self.blocks
+                .keys()
+                .filter_map(|block| self.block_count(block))
+                .max()
+        })?;
+        Some(reference.div_ceil(COLD_RATIO)).filter(|&threshold| threshold > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts() {
+        let b0 = Block::from_u32(0);
+        let b1 = Block::from_u32(1);
+        let b2 = Block::from_u32(2);
+
+        let mut profile = BlockProfile::new();
+        assert!(profile.is_empty());
+
+        profile.set_block_count(b0, 10);
+        profile.set_edge_count(b0, b2, 3);
+        profile.set_edge_count(b1, b2, 4);
+        profile.set_edge_count(b1, b2, 5);
+        assert!(!profile.is_empty());
+        assert_eq!(profile.block_count(b0), Some(10));
+        assert_eq!(profile.block_count(b1), None);
+        assert_eq!(profile.block_count(b2), Some(8));
+        assert_eq!(profile.edge_weight(b0, b2), 3);
+        assert_eq!(profile.edge_weight(b0, b1), 0);
+
+        profile.set_block_count(b2, 1);
+        assert_eq!(profile.block_count(b2), Some(1));
+
+        profile.clear();
+        assert!(profile.is_empty());
+        assert_eq!(profile.block_count(b2), None);
+    }
+
+    #[test]
+    fn cold_threshold() {
+        let b0 = Block::from_u32(0);
+        let b1 = Block::from_u32(1);
+
+        let mut profile = BlockProfile::new();
+        assert_eq!(profile.cold_threshold(b0), None);
+
+        profile.set_block_count(b1, 5000);
+        assert_eq!(profile.cold_threshold(b0), Some(5));
+
+        profile.set_block_count(b0, 1500);
+        assert_eq!(profile.cold_threshold(b0), Some(2));
+
+        profile.set_block_count(b0, 0);
+        assert_eq!(profile.cold_threshold(b0), None);
+    }
+}
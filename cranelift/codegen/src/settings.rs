@@ -489,6 +489,7 @@ mod tests {
         let expected = r#"[shared]
 regalloc_algorithm = "backtracking"
 opt_level = "none"
+egraph_register_pressure_limit = 12
 tls_model = "none"
 stack_switch_model = "none"
 libcall_call_conv = "isa_default"
@@ -499,6 +500,8 @@ log2_min_function_alignment = 0
 regalloc_checker = false
 regalloc_verbose_logs = false
 enable_alias_analysis = true
+enable_loop_unrolling = false
+enable_slp_vectorization = false
 enable_verifier = true
 is_pic = false
 use_colocated_libcalls = false
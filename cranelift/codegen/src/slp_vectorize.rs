@@ -0,0 +1,615 @@
+//! Superword-level parallelism (SLP) vectorization.
+//!
+//! This pass looks for groups of scalar stores to adjacent memory which
+//! together fill a 128-bit vector, and for isomorphic computations feeding
+//! them: the same operation applied to each lane, bottoming out in loads from
+//! adjacent memory, constants, or a value shared by every lane. When the
+//! target has native instructions for the vector type, and the cost model
+//! predicts fewer instructions afterwards, the stores and the computation are
+//! replaced with their vector equivalents, inserted just before the last of
+//! the scalar stores. Scalar instructions left unused are removed; those which
+//! still have other uses are kept.
+//!
+//! When one of several scalar stores traps, the stores before it have already
+//! written memory, which a single vector store wouldn't do. So stores which
+//! can trap are only packed when the first of them traps exactly when the
+//! vector store would: they must be heap accesses, whose out-of-bounds
+//! addresses all lie past the heap's end, at offsets from one base address
+//! checked by one bounds check, and the store to the end of the vector must
+//! come first.
+
+use crate::FxHashMap;
+use crate::cursor::{Cursor, FuncCursor};
+use crate::inst_predicates::{is_constant_64bit, is_pure_for_egraph};
+use crate::ir::immediates::Offset32;
+use crate::ir::{
+    Block, Endianness, Function, Inst, InstBuilder, InstructionData, MemFlags, MemFlagsData,
+    Opcode, TrapCode, Type, Value, types,
+};
+use crate::isa::TargetIsa;
+use crate::timing;
+use alloc::vec::Vec;
+use cranelift_entity::SecondaryMap;
+use smallvec::SmallVec;
+
+/// Maximum depth of the computations packed into vector operations.
+const MAX_DEPTH: usize = 8;
+
+/// Maximum number of `iadd` instructions looked through when finding the base
+/// of an address.
+const MAX_ADDRESS_DEPTH: usize = 4;
+
+/// The scalar values or instructions of each lane.
+type Lanes<T> = SmallVec<[T; 16]>;
+
+/// Pack isomorphic scalar operations in `func` into vector operations.
+pub fn do_slp_vectorize(func: &mut Function, isa: &dyn TargetIsa) {
+    let _tt = timing::slp_vectorize();
+
+    let mut uses = SecondaryMap::<Value, u32>::new();
+    for block in func.layout.blocks() {
+        for inst in func.layout.block_insts(block) {
+            for value in func.dfg.inst_values(inst) {
+                uses[value] += 1;
+            }
+        }
+    }
+
+    let blocks: Vec<Block> = func.layout.blocks().collect();
+    for block in blocks {
+        for group in store_groups(func, isa, block) {
+            vectorize_group(func, isa, block, &group, &mut uses);
+        }
+    }
+}
+
+/// Splits `addr + offset` into a base value and a constant offset from it.
+fn address(func: &Function, addr: Value, offset: Offset32) -> (Value, i64) {
+    let mut base = addr;
+    let mut offset = i64::from(offset);
+    for _ in 0..MAX_ADDRESS_DEPTH {
+        let Some(inst) = func.dfg.value_def(base).inst() else {
+            break;
+        };
+        let InstructionData::Binary {
+            opcode: Opcode::Iadd,
+            args: [x, y],
+        } = func.dfg.insts[inst]
+        else {
+            break;
+        };
+        let constant = |v: Value| {
+            let inst = func.dfg.value_def(v).inst()?;
+            match func.dfg.insts[inst] {
+                InstructionData::UnaryImm {
+                    opcode: Opcode::Iconst,
+                    imm,
+                } => Some(
+                    imm.sign_extend_from_width(func.dfg.value_type(v).bits())
+                        .bits(),
+                ),
+                _ => None,
+            }
+        };
+        let (next, delta) = match (constant(x), constant(y)) {
+            (_, Some(c)) => (x, c),
+            (Some(c), _) => (y, c),
+            _ => break,
+        };
+        let Some(sum) = offset.checked_add(delta) else {
+            break;
+        };
+        base = next;
+        offset = sum;
+    }
+    (base, offset)
+}
+
+/// Returns whether `flags` allow a scalar access to be part of a vector one.
+fn packable_flags(func: &Function, flags: MemFlags) -> bool {
+    func.dfg.mem_flags[flags].explicit_endianness() != Some(Endianness::Big)
+}
+
+/// Returns whether a group of stores with `flags` traps exactly when a vector
+/// store covering them all would. `offsets` holds each store's offset from
+/// their shared base address and position in the block, in lane order.
+fn same_trap(func: &Function, flags: MemFlags, offsets: &[(i64, usize, Inst)]) -> bool {
+    match func.dfg.mem_flags[flags].trap_code() {
+        None => true,
+        // Every address from the base up to the heap's end is accessible, so
+        // an out-of-bounds access in the vector means the last lane is out of
+        // bounds. If that lane is stored first, nothing is written before the
+        // trap, just like the vector store.
+        Some(TrapCode::HEAP_OUT_OF_BOUNDS) => {
+            let (_, last, _) = offsets[offsets.len() - 1];
+            offsets[0].0 >= 0 && offsets.iter().all(|&(_, order, _)| order >= last)
+        }
+        Some(_) => false,
+    }
+}
+
+/// Returns groups of stores in `block`, in lane order, which together write
+/// a whole vector.
+fn store_groups(func: &Function, isa: &dyn TargetIsa, block: Block) -> Vec<Lanes<Inst>> {
+    let mut keys = Vec::new();
+    let mut stores = FxHashMap::<(Value, MemFlags, Type), Vec<(i64, usize, Inst)>>::default();
+    for (order, inst) in func.layout.block_insts(block).enumerate() {
+        let InstructionData::Store {
+            opcode: Opcode::Store,
+            args: [value, addr],
+            flags,
+            offset,
+        } = func.dfg.insts[inst]
+        else {
+            continue;
+        };
+        let ty = func.dfg.value_type(value);
+        if !packable_flags(func, flags)
+            || !matches!(
+                ty,
+                types::I8 | types::I16 | types::I32 | types::I64 | types::F32 | types::F64
+            )
+            || !isa.has_native_simd(vector_type(ty))
+        {
+            continue;
+        }
+        let (base, offset) = address(func, addr, offset);
+        let key = (base, flags, ty);
+        let offsets = stores.entry(key).or_default();
+        if offsets.is_empty() {
+            keys.push(key);
+        }
+        offsets.push((offset, order, inst));
+    }
+
+    let mut groups = Vec::new();
+    for key in keys {
+        let mut offsets = stores.remove(&key).unwrap();
+        offsets.sort_by_key(|&(offset, _, _)| offset);
+        let size = i64::from(key.2.bytes());
+        let lanes = lane_count(key.2);
+        let mut start = 0;
+        for i in 0..offsets.len() {
+            if i > start && offsets[i].0 != offsets[i - 1].0 + size {
+                start = i;
+            }
+            if i + 1 - start == lanes {
+                let run = &offsets[start..=i];
+                if same_trap(func, key.1, run) {
+                    groups.push(run.iter().map(|&(_, _, inst)| inst).collect());
+                }
+                start = i + 1;
+            }
+        }
+    }
+    groups
+}
+
+fn lane_count(ty: Type) -> usize {
+    (16 / ty.bytes()) as usize
+}
+
+fn vector_type(ty: Type) -> Type {
+    ty.by(16 / ty.bytes()).unwrap()
+}
+
+/// Returns whether `opcode` can be applied to vectors of `ty` lanes without
+/// being more expensive than the scalar instructions it replaces.
+fn packable_op(opcode: Opcode, ty: Type) -> bool {
+    match opcode {
+        Opcode::Iadd
+        | Opcode::Isub
+        | Opcode::Ineg
+        | Opcode::Band
+        | Opcode::Bor
+        | Opcode::Bxor
+        | Opcode::Bnot => ty.is_int(),
+        // No target has a cheap lane-wise multiply of bytes or 64-bit
+        // integers.
+        Opcode::Imul => matches!(ty, types::I16 | types::I32),
+        Opcode::Ishl | Opcode::Ushr => matches!(ty, types::I16 | types::I32 | types::I64),
+        Opcode::Sshr => matches!(ty, types::I16 | types::I32),
+        Opcode::Fadd
+        | Opcode::Fsub
+        | Opcode::Fmul
+        | Opcode::Fdiv
+        | Opcode::Fneg
+        | Opcode::Fabs
+        | Opcode::Sqrt => ty.is_float(),
+        _ => false,
+    }
+}
+
+/// A vector value in a packed computation.
+enum Node {
+    /// Loads from adjacent memory, in lane order.
+    Load(Lanes<Inst>),
+    /// The same unary or binary operation applied to each lane. A shift
+    /// amount shared by all lanes is kept as a scalar.
+    Op {
+        insts: Lanes<Inst>,
+        args: SmallVec<[usize; 2]>,
+        amount: Option<Value>,
+    },
+    /// A constant in each lane.
+    Const(Lanes<u64>),
+    /// The same value in every lane.
+    Splat(Value),
+    /// Unrelated values inserted into each lane.
+    Gather(Lanes<Value>),
+}
+
+/// Builds the packed computation for a group of stores and tallies its cost.
+struct Packer<'a> {
+    func: &'a Function,
+    block: Block,
+    uses: &'a SecondaryMap<Value, u32>,
+    nodes: Vec<Node>,
+    /// Number of instructions saved by packing, which is negative when
+    /// packing adds instructions.
+    benefit: isize,
+}
+
+impl Packer<'_> {
+    /// Returns the instructions defining `lanes` if they're all in the block
+    /// being vectorized.
+    fn defs(&self, lanes: &[Value]) -> Option<Lanes<Inst>> {
+        lanes
+            .iter()
+            .map(|&v| {
+                let inst = self.func.dfg.value_def(v).inst()?;
+                (self.func.layout.inst_block(inst) == Some(self.block)).then_some(inst)
+            })
+            .collect()
+    }
+
+    /// Returns how many of `insts` will be left unused once the packed
+    /// computation replaces them.
+    fn removable(&self, insts: &[Inst]) -> isize {
+        insts
+            .iter()
+            .filter(|&&inst| self.uses[self.func.dfg.first_result(inst)] == 1)
+            .count() as isize
+    }
+
+    fn pack(&mut self, lanes: &[Value], depth: usize) -> usize {
+        let node = self.pack_node(lanes, depth);
+        self.nodes.push(node);
+        self.nodes.len() - 1
+    }
+
+    fn pack_node(&mut self, lanes: &[Value], depth: usize) -> Node {
+        let ty = self.func.dfg.value_type(lanes[0]);
+        if lanes.iter().all(|&v| v == lanes[0]) {
+            self.benefit -= 1;
+            return Node::Splat(lanes[0]);
+        }
+        let constants: Option<Lanes<u64>> = lanes
+            .iter()
+            .map(|&v| is_constant_64bit(self.func, self.func.dfg.value_def(v).inst()?))
+            .collect();
+        if let Some(constants) = constants {
+            self.benefit -= 1;
+            return Node::Const(constants);
+        }
+
+        if depth < MAX_DEPTH
+            && let Some(insts) = self.defs(lanes)
+        {
+            if self.is_packable_load(&insts, ty) {
+                self.benefit += self.removable(&insts) - 1;
+                return Node::Load(insts);
+            }
+            if let Some(node) = self.pack_op(insts, ty, depth) {
+                return node;
+            }
+        }
+
+        self.benefit -= lanes.len() as isize;
+        Node::Gather(lanes.iter().copied().collect())
+    }
+
+    fn is_packable_load(&self, insts: &[Inst], ty: Type) -> bool {
+        let mut first = None;
+        for (i, &inst) in insts.iter().enumerate() {
+            let InstructionData::Load {
+                opcode: Opcode::Load,
+                arg,
+                flags,
+                offset,
+            } = self.func.dfg.insts[inst]
+            else {
+                return false;
+            };
+            let (base, offset) = address(self.func, arg, offset);
+            match first {
+                None => {
+                    if !packable_flags(self.func, flags) {
+                        return false;
+                    }
+                    first = Some((base, flags, offset));
+                }
+                Some((first_base, first_flags, first_offset)) => {
+                    let expected = first_offset + i as i64 * i64::from(ty.bytes());
+                    if base != first_base || flags != first_flags || offset != expected {
+                        return false;
+                    }
+                }
+            }
+        }
+        true
+    }
+
+    fn pack_op(&mut self, insts: Lanes<Inst>, ty: Type, depth: usize) -> Option<Node> {
+        let opcode = self.func.dfg.insts[insts[0]].opcode();
+        if !packable_op(opcode, ty)
+            || insts
+                .iter()
+                .any(|&inst| self.func.dfg.insts[inst].opcode() != opcode)
+        {
+            return None;
+        }
+
+        let dfg = &self.func.dfg;
+        let arg = |n: usize| -> Lanes<Value> {
+            insts.iter().map(|&inst| dfg.inst_args(inst)[n]).collect()
+        };
+        let (args, amount): (SmallVec<[Lanes<Value>; 2]>, Option<Value>) = match dfg.insts[insts[0]]
+        {
+            InstructionData::Unary { .. } => (SmallVec::from_iter([arg(0)]), None),
+            InstructionData::Binary { .. }
+                if matches!(opcode, Opcode::Ishl | Opcode::Ushr | Opcode::Sshr) =>
+            {
+                let amounts = arg(1);
+                if amounts.iter().any(|&v| v != amounts[0]) {
+                    return None;
+                }
+                (SmallVec::from_iter([arg(0)]), Some(amounts[0]))
+            }
+            InstructionData::Binary { .. } => (SmallVec::from_iter([arg(0), arg(1)]), None),
+            _ => return None,
+        };
+
+        self.benefit += self.removable(&insts) - 1;
+        let args = args
+            .iter()
+            .map(|lanes: &Lanes<Value>| self.pack(lanes, depth + 1))
+            .collect();
+        Some(Node::Op {
+            insts,
+            args,
+            amount,
+        })
+    }
+
+    /// Returns the loads in the packed computation.
+    fn loads(&self) -> impl Iterator<Item = Inst> + '_ {
+        self.nodes.iter().flat_map(|node| {
+            match node {
+                Node::Load(insts) => insts.as_slice(),
+                _ => &[],
+            }
+            .iter()
+            .copied()
+        })
+    }
+}
+
+/// Returns `data` without the `aligned` flag, which promises alignment to the
+/// size of the access and so doesn't carry over to a wider one.
+fn unaligned(data: MemFlagsData) -> MemFlagsData {
+    let mut ret = MemFlagsData::new()
+        .with_trap_code(data.trap_code())
+        .with_alias_region(data.alias_region());
+    if data.readonly() {
+        ret = ret.with_readonly();
+    }
+    if data.can_move() {
+        ret = ret.with_can_move();
+    }
+    if let Some(endianness) = data.explicit_endianness() {
+        ret = ret.with_endianness(endianness);
+    }
+    ret
+}
+
+/// Replace the scalar stores in `group` and the computation feeding them with
+/// vector operations, if that's both correct and profitable.
+fn vectorize_group(
+    func: &mut Function,
+    isa: &dyn TargetIsa,
+    block: Block,
+    group: &[Inst],
+    uses: &mut SecondaryMap<Value, u32>,
+) {
+    let stored: Lanes<Value> = group
+        .iter()
+        .map(|&inst| func.dfg.inst_args(inst)[0])
+        .collect();
+    let ty = vector_type(func.dfg.value_type(stored[0]));
+    debug_assert!(isa.has_native_simd(ty));
+
+    let mut packer = Packer {
+        func,
+        block,
+        uses,
+        nodes: Vec::new(),
+        benefit: group.len() as isize - 1,
+    };
+    let root = packer.pack(&stored, 0);
+    if packer.benefit <= 0 {
+        return;
+    }
+
+    // The vector computation happens where the last scalar store was, so the
+    // scalar stores are delayed and the loads feeding them are too. That's
+    // only allowed if nothing in between touches memory, traps, or otherwise
+    // has side effects, and if the loads all happen before the first store.
+    let position: FxHashMap<Inst, usize> = func
+        .layout
+        .block_insts(block)
+        .enumerate()
+        .map(|(i, inst)| (inst, i))
+        .collect();
+    let first_store = group.iter().map(|inst| position[inst]).min().unwrap();
+    let last_store = *group.iter().max_by_key(|&inst| position[inst]).unwrap();
+    let mut start = first_store;
+    for load in packer.loads() {
+        if position[&load] > first_store {
+            return;
+        }
+        start = start.min(position[&load]);
+    }
+    let loads: Vec<Inst> = packer.loads().collect();
+    for inst in func.layout.block_insts(block) {
+        let i = position[&inst];
+        if i <= start
+            || i >= position[&last_store]
+            || group.contains(&inst)
+            || loads.contains(&inst)
+        {
+            continue;
+        }
+        let opcode = func.dfg.insts[inst].opcode();
+        if opcode.can_load()
+            || opcode.can_store()
+            || opcode.can_trap()
+            || opcode.is_call()
+            || opcode.other_side_effects()
+        {
+            return;
+        }
+    }
+
+    let nodes = packer.nodes;
+    let Some(store_flags) = vector_flags(func, group[0]) else {
+        return;
+    };
+    let mut load_flags = FxHashMap::default();
+    for node in &nodes {
+        if let Node::Load(insts) = node {
+            let Some(flags) = vector_flags(func, insts[0]) else {
+                return;
+            };
+            load_flags.insert(insts[0], flags);
+        }
+    }
+
+    // Emit the vector computation before the last store, then replace the
+    // scalar stores with a vector one.
+    let mut pos = FuncCursor::new(func).at_inst(last_store);
+    pos.use_srcloc(last_store);
+    let mut values = Vec::with_capacity(nodes.len());
+    for node in &nodes {
+        let value = match node {
+            Node::Load(insts) => {
+                let (addr, offset) = match pos.func.dfg.insts[insts[0]] {
+                    InstructionData::Load { arg, offset, .. } => (arg, offset),
+                    _ => unreachable!(),
+                };
+                let flags = pos.func.dfg.mem_flags[load_flags[&insts[0]]];
+                pos.ins().load(ty, flags, addr, offset)
+            }
+            Node::Op {
+                insts,
+                args,
+                amount,
+            } => {
+                let opcode = pos.func.dfg.insts[insts[0]].opcode();
+                let (inst, dfg) = match (args.as_slice(), amount) {
+                    ([x], None) => pos.ins().Unary(opcode, ty, values[*x]),
+                    ([x], Some(amount)) => pos.ins().Binary(opcode, ty, values[*x], *amount),
+                    ([x, y], None) => pos.ins().Binary(opcode, ty, values[*x], values[*y]),
+                    _ => unreachable!(),
+                };
+                dfg.first_result(inst)
+            }
+            Node::Const(lanes) => {
+                let lane_bytes = ty.lane_type().bytes() as usize;
+                let bytes: Vec<u8> = lanes
+                    .iter()
+                    .flat_map(|lane| lane.to_le_bytes().into_iter().take(lane_bytes))
+                    .collect();
+                let constant = pos.func.dfg.constants.insert(bytes.into());
+                pos.ins().vconst(ty, constant)
+            }
+            Node::Splat(value) => pos.ins().splat(ty, *value),
+            Node::Gather(lanes) => {
+                let mut vector = pos.ins().scalar_to_vector(ty, lanes[0]);
+                for (i, &lane) in lanes.iter().enumerate().skip(1) {
+                    vector = pos.ins().insertlane(vector, lane, i as u8);
+                }
+                vector
+            }
+        };
+        for arg in pos
+            .func
+            .dfg
+            .inst_values(pos.func.dfg.value_def(value).unwrap_inst())
+        {
+            uses[arg] += 1;
+        }
+        values.push(value);
+    }
+
+    let (addr, offset) = match pos.func.dfg.insts[group[0]] {
+        InstructionData::Store { args, offset, .. } => (args[1], offset),
+        _ => unreachable!(),
+    };
+    let flags = pos.func.dfg.mem_flags[store_flags];
+    let store = pos.ins().store(flags, values[root], addr, offset);
+    for arg in pos.func.dfg.inst_values(store) {
+        uses[arg] += 1;
+    }
+
+    // Remove the scalar instructions which were only used by the stores, and
+    // the pure instructions, such as address computations, which were only
+    // used by those. Nodes are built after their arguments, so popping them
+    // visits each scalar instruction after its users.
+    let mut dead: Vec<Inst> = nodes
+        .iter()
+        .flat_map(|node| match node {
+            Node::Load(insts) | Node::Op { insts, .. } => insts.as_slice(),
+            _ => &[],
+        })
+        .copied()
+        .collect();
+    for &inst in group {
+        remove_inst(func, inst, uses, &mut dead);
+    }
+    while let Some(inst) = dead.pop() {
+        if func.layout.inst_block(inst).is_some() && uses[func.dfg.first_result(inst)] == 0 {
+            remove_inst(func, inst, uses, &mut dead);
+        }
+    }
+}
+
+/// Returns the flags for a vector access standing in for the scalar access
+/// `inst`, or `None` if the function has too many distinct flags already.
+fn vector_flags(func: &mut Function, inst: Inst) -> Option<MemFlags> {
+    let flags = match func.dfg.insts[inst] {
+        InstructionData::Load { flags, .. } | InstructionData::Store { flags, .. } => flags,
+        _ => unreachable!(),
+    };
+    let data = unaligned(func.dfg.mem_flags[flags]);
+    func.dfg.mem_flags.insert(data).ok()
+}
+
+/// Removes `inst`, adding the pure instructions left unused to `dead`.
+fn remove_inst(
+    func: &mut Function,
+    inst: Inst,
+    uses: &mut SecondaryMap<Value, u32>,
+    dead: &mut Vec<Inst>,
+) {
+    for arg in func.dfg.inst_values(inst) {
+        uses[arg] -= 1;
+        if uses[arg] == 0
+            && let Some(def) = func.dfg.value_def(arg).inst()
+            && is_pure_for_egraph(func, def)
+        {
+            dead.push(def);
+        }
+    }
+    func.layout.remove_inst(inst);
+}
@@ -107,6 +107,7 @@ fn run_test(func_store: &FunctionStore, func: &Function, details: &Details) -> a
                     let func_name = &format!("%{func_name}");
                     match Interpreter::new(state).call_by_name(func_name, &args) {
                         Ok(ControlFlow::Return(results)) => Ok(results.to_vec()),
+                        Ok(ControlFlow::Throw(tag, _)) => Err(format!("uncaught exception: {tag}")),
                         Ok(e) => {
                             panic!("Unexpected returned control flow: {e:?}")
                         }
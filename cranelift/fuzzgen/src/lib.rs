@@ -185,6 +185,8 @@ where
         //   aarch64: https://github.com/bytecodealliance/wasmtime/issues/2735
         let bool_settings = [
             "enable_alias_analysis",
+            "enable_loop_unrolling",
+            "enable_slp_vectorization",
             "unwind_info",
             "preserve_frame_pointers",
             "enable_heap_access_spectre_mitigation",
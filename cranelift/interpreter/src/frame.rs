@@ -1,7 +1,7 @@
 //! Implements a call frame (activation record) for the Cranelift interpreter.
 
 use cranelift_codegen::data_value::DataValue;
-use cranelift_codegen::ir::{Function, Value as ValueRef, types};
+use cranelift_codegen::ir::{Function, Inst, Value as ValueRef, types};
 use cranelift_entity::EntityRef;
 use log::trace;
 
@@ -16,6 +16,11 @@ pub struct Frame<'a> {
     /// The current mapping of SSA value-references to their actual values. For efficiency, each SSA value is used as an
     /// index into the Vec, meaning some slots may be unused.
     registers: Entries,
+    /// The offset of this frame's stack slots in the interpreter's stack memory.
+    stack_offset: usize,
+    /// The call or `stack_switch` instruction this frame is waiting on, if it is not the innermost
+    /// frame of the running stack.
+    suspended_at: Option<Inst>,
 }
 
 impl<'a> Frame<'a> {
@@ -27,6 +32,16 @@ impl<'a> Frame<'a> {
         Self {
             function,
             registers: vec![None; num_slots],
+            stack_offset: 0,
+            suspended_at: None,
+        }
+    }
+
+    /// Place this frame's stack slots at `stack_offset` in the interpreter's stack memory.
+    pub fn with_stack_offset(self, stack_offset: usize) -> Self {
+        Self {
+            stack_offset,
+            ..self
         }
     }
 
@@ -105,6 +120,21 @@ impl<'a> Frame<'a> {
     pub fn function(&self) -> &'a Function {
         self.function
     }
+
+    /// Accessor for the offset of this frame's stack slots in the interpreter's stack memory.
+    pub fn stack_offset(&self) -> usize {
+        self.stack_offset
+    }
+
+    /// Record that this frame is waiting on `inst`, a call or `stack_switch`, to complete.
+    pub fn suspend_at(&mut self, inst: Inst) {
+        self.suspended_at = Some(inst);
+    }
+
+    /// Take the instruction this frame was suspended at, so that execution can continue after it.
+    pub fn resume(&mut self) -> Option<Inst> {
+        self.suspended_at.take()
+    }
 }
 
 #[cfg(test)]
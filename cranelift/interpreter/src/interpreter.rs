@@ -6,13 +6,13 @@ use crate::address::{Address, AddressFunctionEntry, AddressRegion, AddressSize};
 use crate::environment::{FuncIndex, FunctionStore};
 use crate::frame::Frame;
 use crate::instruction::DfgInstructionContext;
-use crate::state::{InterpreterFunctionRef, MemoryError, State};
-use crate::step::{ControlFlow, CraneliftTrap, StepError, step};
+use crate::state::{InterpreterFunctionRef, MemoryError, State, THROW_HOSTCALL_NAME};
+use crate::step::{ControlFlow, CraneliftTrap, StepError, collect_block_args, step};
 use crate::value::{DataValueExt, ValueError};
 use cranelift_codegen::data_value::DataValue;
 use cranelift_codegen::ir::{
-    ArgumentPurpose, Block, Endianness, ExternalName, FuncRef, Function, GlobalValue,
-    GlobalValueData, LibCall, MemFlagsData, StackSlot, Type,
+    ArgumentPurpose, Block, BlockCall, Endianness, ExceptionTableItem, ExceptionTag, ExternalName,
+    FuncRef, Function, GlobalValue, GlobalValueData, Inst, LibCall, MemFlagsData, StackSlot, Type,
 };
 use log::trace;
 use smallvec::SmallVec;
@@ -26,11 +26,22 @@ use thiserror::Error;
 pub struct Interpreter<'a> {
     state: InterpreterState<'a>,
     fuel: Option<u64>,
+    /// The frames of every stack created by `stack_switch`, indexed by the id saved in their
+    /// contexts; the stack the interpreter was called on has id 0. An entry is `None` while its
+    /// stack is running or once it has been resumed, since contexts are one-shot.
+    stacks: Vec<Option<Vec<Frame<'a>>>>,
+    /// The id of the running stack.
+    current_stack: usize,
 }
 
 impl<'a> Interpreter<'a> {
     pub fn new(state: InterpreterState<'a>) -> Self {
-        Self { state, fuel: None }
+        Self {
+            state,
+            fuel: None,
+            stacks: vec![None],
+            current_stack: 0,
+        }
     }
 
     /// The `fuel` mechanism sets a number of instructions that
@@ -73,6 +84,13 @@ impl<'a> Interpreter<'a> {
         function: &'a Function,
         arguments: &[DataValue],
     ) -> Result<ControlFlow<'a>, InterpreterError> {
+        let first_inst = self.enter(function, arguments);
+        self.run(first_inst)
+    }
+
+    /// Push a frame for `function` with its parameters set to `arguments`, returning the first
+    /// instruction to execute in it.
+    fn enter(&mut self, function: &'a Function, arguments: &[DataValue]) -> Option<Inst> {
         trace!("Call: {}({:?})", function.name, arguments);
         let first_block = function
             .layout
@@ -84,66 +102,63 @@ impl<'a> Interpreter<'a> {
         self.state
             .current_frame_mut()
             .set_all(parameters, arguments.to_vec());
-
-        self.block(first_block)
+        function.layout.first_inst(first_block)
     }
 
-    /// Interpret a [Block] in a [Function]. This drives the interpretation over sequences of
-    /// instructions, which may continue in other blocks, until the function returns.
-    fn block(&mut self, block: Block) -> Result<ControlFlow<'a>, InterpreterError> {
-        trace!("Block: {block}");
-        let function = self.state.current_frame_mut().function();
-        let layout = &function.layout;
-        let mut maybe_inst = layout.first_inst(block);
+    /// Interpret instructions, starting at `inst` in the current frame, until the function the
+    /// interpreter was called with returns. Calls push a frame, suspending the caller at the call
+    /// instruction, rather than recursing; this keeps all of a stack's state in its frames, so
+    /// that `stack_switch` can set it aside and resume it later.
+    fn run(&mut self, mut maybe_inst: Option<Inst>) -> Result<ControlFlow<'a>, InterpreterError> {
         while let Some(inst) = maybe_inst {
             if self.consume_fuel() == FuelResult::Stop {
                 return Err(InterpreterError::FuelExhausted);
             }
 
+            let function = self.state.current_frame().function();
+            let layout = &function.layout;
             let inst_context = DfgInstructionContext::new(inst, &function.dfg);
-            match step(&mut self.state, inst_context)? {
+            maybe_inst = match step(&mut self.state, inst_context)? {
                 ControlFlow::Assign(values) => {
                     self.state
                         .current_frame_mut()
                         .set_all(function.dfg.inst_results(inst), values.to_vec());
-                    maybe_inst = layout.next_inst(inst)
+                    layout.next_inst(inst)
                 }
-                ControlFlow::Continue => maybe_inst = layout.next_inst(inst),
+                ControlFlow::Continue => layout.next_inst(inst),
                 ControlFlow::ContinueAt(block, block_arguments) => {
                     trace!("Block: {block}");
                     self.state
                         .current_frame_mut()
                         .set_all(function.dfg.block_params(block), block_arguments.to_vec());
-                    maybe_inst = layout.first_inst(block)
+                    layout.first_inst(block)
                 }
                 ControlFlow::Call(called_function, arguments) => {
-                    match self.call(called_function, &arguments)? {
-                        ControlFlow::Return(rets) => {
-                            self.state
-                                .current_frame_mut()
-                                .set_all(function.dfg.inst_results(inst), rets.to_vec());
-                            maybe_inst = layout.next_inst(inst)
-                        }
-                        ControlFlow::Trap(trap) => return Ok(ControlFlow::Trap(trap)),
-                        cf => {
-                            panic!("invalid control flow after call: {cf:?}")
-                        }
-                    }
+                    self.state.current_frame_mut().suspend_at(inst);
+                    self.enter(called_function, &arguments)
                 }
                 ControlFlow::ReturnCall(callee, args) => {
                     self.state.pop_frame();
-
-                    return match self.call(callee, &args)? {
-                        ControlFlow::Return(rets) => Ok(ControlFlow::Return(rets)),
-                        ControlFlow::Trap(trap) => Ok(ControlFlow::Trap(trap)),
-                        cf => {
-                            panic!("invalid control flow after return_call: {cf:?}")
-                        }
-                    };
+                    self.enter(callee, &args)
                 }
                 ControlFlow::Return(returned_values) => {
                     self.state.pop_frame();
-                    return Ok(ControlFlow::Return(returned_values));
+                    if self.state.frame_stack.is_empty() {
+                        return self.exit_stack(ControlFlow::Return(returned_values));
+                    }
+                    self.resume_after_call(&returned_values)
+                }
+                ControlFlow::Throw(tag, payloads) => match self.throw(inst, tag, &payloads) {
+                    handler @ Some(_) => handler,
+                    None => return self.exit_stack(ControlFlow::Throw(tag, payloads)),
+                },
+                ControlFlow::StackSwitch {
+                    store_context,
+                    load_context,
+                    payload,
+                } => {
+                    self.state.current_frame_mut().suspend_at(inst);
+                    self.stack_switch(store_context, load_context, payload)?
                 }
                 ControlFlow::Trap(trap) => return Ok(ControlFlow::Trap(trap)),
             }
@@ -151,6 +166,204 @@ impl<'a> Interpreter<'a> {
         Err(InterpreterError::Unreachable)
     }
 
+    /// Continue in the current frame after the call it was suspended at returned `rets`.
+    fn resume_after_call(&mut self, rets: &[DataValue]) -> Option<Inst> {
+        let function = self.state.current_frame().function();
+        let inst = self
+            .state
+            .current_frame_mut()
+            .resume()
+            .expect("caller to be suspended at a call");
+        match function.dfg.insts[inst].exception_table() {
+            // A `try_call` resumes at its normal-return block, which receives the returned values
+            // through its block arguments.
+            Some(table) => {
+                let normal_return = *function.dfg.exception_tables[table].normal_return();
+                let (block, block_arguments) = self.resolve_block_call(normal_return, rets);
+                trace!("Block: {block}");
+                self.state
+                    .current_frame_mut()
+                    .set_all(function.dfg.block_params(block), block_arguments.to_vec());
+                function.layout.first_inst(block)
+            }
+            None => {
+                self.state
+                    .current_frame_mut()
+                    .set_all(function.dfg.inst_results(inst), rets.to_vec());
+                function.layout.next_inst(inst)
+            }
+        }
+    }
+
+    /// Unwind an exception with the given `tag` and `payloads`, thrown by `inst`, through the
+    /// frames of the current stack. Returns the first instruction of the handler that catches it,
+    /// if any; otherwise every frame of the stack has been popped.
+    fn throw(&mut self, inst: Inst, tag: ExceptionTag, payloads: &[DataValue]) -> Option<Inst> {
+        let mut inst = inst;
+        loop {
+            if let Some(handler) = self.unwind(inst, tag, payloads) {
+                let layout = &self.state.current_frame().function().layout;
+                return layout.first_inst(handler);
+            }
+            inst = self.state.frame_stack.last_mut()?.resume()?;
+        }
+    }
+
+    /// Handle the last frame of the current stack finishing with `control_flow`. Only the stack
+    /// the interpreter was called on can finish: there is nothing to return to from a stack that
+    /// `stack_switch` started.
+    fn exit_stack(
+        &mut self,
+        control_flow: ControlFlow<'a>,
+    ) -> Result<ControlFlow<'a>, InterpreterError> {
+        match self.current_stack {
+            0 => Ok(control_flow),
+            _ => Err(InterpreterError::StackFunctionExited),
+        }
+    }
+
+    /// Suspend the current stack and resume the one whose context is stored at `load_context`,
+    /// passing it `payload`. Returns the instruction to continue at on the resumed stack.
+    ///
+    /// A context is a single address-sized value. Suspending a stack saves a function-region
+    /// address with a [AddressFunctionEntry::SuspendedStack] entry that identifies it. A new
+    /// stack is created by storing the address of a function, as produced by `func_addr`, in a
+    /// context: switching to it calls that function on a new stack, passing the payload as its
+    /// only argument. That function must not return; it switches away instead.
+    fn stack_switch(
+        &mut self,
+        store_context: Address,
+        load_context: Address,
+        payload: DataValue,
+    ) -> Result<Option<Inst>, InterpreterError> {
+        let ty = payload.ty();
+        let size = AddressSize::try_from(ty).map_err(StepError::from)?;
+        let mem_flags = MemFlagsData::trusted();
+        let target = self
+            .state
+            .checked_load(load_context, ty, mem_flags)
+            .map_err(StepError::from)?;
+
+        // All of the target context is read before the current one is written, since both may be
+        // the same memory.
+        enum Target<'f> {
+            Resume(usize),
+            Start(&'f Function),
+        }
+        let address = Address::try_from(target.clone()).map_err(StepError::from)?;
+        let invalid = || InterpreterError::InvalidStackContext(target.clone());
+        let target = match (address.region, AddressFunctionEntry::from(address.entry)) {
+            (AddressRegion::Function, AddressFunctionEntry::SuspendedStack) => {
+                let id = address.offset as usize;
+                match self.stacks.get(id) {
+                    Some(Some(_)) => Target::Resume(id),
+                    _ => return Err(invalid()),
+                }
+            }
+            (AddressRegion::Function, AddressFunctionEntry::UserFunction) => {
+                match self.state.get_function_from_address(address) {
+                    Some(InterpreterFunctionRef::Function(function))
+                        if function.signature.params.len() == 1 =>
+                    {
+                        Target::Start(function)
+                    }
+                    _ => return Err(invalid()),
+                }
+            }
+            _ => return Err(invalid()),
+        };
+
+        let context = Address::from_parts(
+            size,
+            AddressRegion::Function,
+            AddressFunctionEntry::SuspendedStack as u64,
+            self.current_stack as u64,
+        )
+        .and_then(DataValue::try_from)
+        .map_err(StepError::from)?;
+        self.state
+            .checked_store(store_context, context, mem_flags)
+            .map_err(StepError::from)?;
+
+        match target {
+            Target::Resume(id) => {
+                let frames = self.stacks[id].take().unwrap();
+                let suspended = self.state.switch_frames(frames);
+                self.stacks[self.current_stack] = Some(suspended);
+                self.current_stack = id;
+                trace!("Stack switch: resume stack {id}");
+
+                // The target stack continues after its own `stack_switch`, which returns the
+                // payload.
+                let frame = self.state.current_frame_mut();
+                let function = frame.function();
+                let inst = frame
+                    .resume()
+                    .expect("stack to be suspended at a stack_switch");
+                frame.set_all(function.dfg.inst_results(inst), vec![payload]);
+                Ok(function.layout.next_inst(inst))
+            }
+            Target::Start(function) => {
+                let suspended = self.state.switch_frames(vec![]);
+                self.stacks[self.current_stack] = Some(suspended);
+                self.current_stack = self.stacks.len();
+                self.stacks.push(None);
+                trace!("Stack switch: start stack {}", self.current_stack);
+                Ok(self.enter(function, &[payload]))
+            }
+        }
+    }
+
+    /// Handle an exception with the given `tag` and `payloads` thrown by the callee of `inst`. If
+    /// `inst` is a `try_call` with a matching handler, the handler block's parameters are assigned
+    /// and the block is returned; otherwise the current frame is popped and the exception must
+    /// continue to unwind into the caller.
+    fn unwind(&mut self, inst: Inst, tag: ExceptionTag, payloads: &[DataValue]) -> Option<Block> {
+        let function = self.state.current_frame().function();
+        let handler = function.dfg.insts[inst]
+            .exception_table()
+            .and_then(|table| {
+                // Context items only carry information for a runtime's tag matching, which the
+                // interpreter does not need: tags are matched by identity.
+                function.dfg.exception_tables[table]
+                    .items()
+                    .find_map(|item| match item {
+                        ExceptionTableItem::Tag(t, handler) if t == tag => Some(handler),
+                        ExceptionTableItem::Default(handler) => Some(handler),
+                        ExceptionTableItem::Tag(..) | ExceptionTableItem::Context(_) => None,
+                    })
+            });
+
+        match handler {
+            Some(handler) => {
+                let (block, block_arguments) = self.resolve_block_call(handler, payloads);
+                trace!("Unwind: {tag} caught at {block}");
+                self.state
+                    .current_frame_mut()
+                    .set_all(function.dfg.block_params(block), block_arguments.to_vec());
+                Some(block)
+            }
+            None => {
+                trace!("Unwind: {tag} not caught in {}", function.name);
+                self.state.pop_frame();
+                None
+            }
+        }
+    }
+
+    /// Resolve the destination of a `try_call` successor: its block and arguments, taking the
+    /// call's return values or exception payloads from `results`.
+    fn resolve_block_call(
+        &self,
+        block_call: BlockCall,
+        results: &[DataValue],
+    ) -> (Block, SmallVec<[DataValue; 1]>) {
+        let function = self.state.current_frame().function();
+        let pool = &function.dfg.value_lists;
+        let args = collect_block_args(self.state.current_frame(), block_call.args(pool), results);
+        (block_call.block(pool), args)
+    }
+
     fn consume_fuel(&mut self) -> FuelResult {
         match self.fuel {
             Some(0) => FuelResult::Stop,
@@ -189,6 +402,10 @@ pub enum InterpreterError {
     ValueError(#[from] ValueError),
     #[error("fuel exhausted")]
     FuelExhausted,
+    #[error("invalid stack context: {0}")]
+    InvalidStackContext(DataValue),
+    #[error("the function started on a new stack by `stack_switch` exited")]
+    StackFunctionExited,
 }
 
 pub type LibCallValues = SmallVec<[DataValue; 1]>;
@@ -235,6 +452,28 @@ impl<'a> InterpreterState<'a> {
         self.libcall_handler = handler;
         self
     }
+
+    /// Make `frames` the running stack, returning the frames of the stack that was running.
+    fn switch_frames(&mut self, frames: Vec<Frame<'a>>) -> Vec<Frame<'a>> {
+        self.frame_offset = frames.last().map_or(0, |frame| frame.stack_offset());
+        std::mem::replace(&mut self.frame_stack, frames)
+    }
+
+    /// Compute the address of a function in the function store, as `func_addr` would.
+    fn interpreted_function_address(
+        &self,
+        size: AddressSize,
+        function: &Function,
+    ) -> Result<Address, MemoryError> {
+        // TODO: This is not optimal since we are looking up by string name
+        let index = self.functions.index_of(&function.name.to_string()).unwrap();
+        Address::from_parts(
+            size,
+            AddressRegion::Function,
+            AddressFunctionEntry::UserFunction as u64,
+            index.as_u32() as u64,
+        )
+    }
 }
 
 impl<'a> State<'a> for InterpreterState<'a> {
@@ -251,25 +490,27 @@ impl<'a> State<'a> for InterpreterState<'a> {
     }
 
     fn push_frame(&mut self, function: &'a Function) {
-        if let Some(frame) = self.frame_stack.iter().last() {
-            self.frame_offset += frame.function().fixed_stack_size() as usize;
-        }
-
-        // Grow the stack by the space necessary for this frame
+        // Grow the stack by the space necessary for this frame. Frames of other stacks may have
+        // been pushed since the current frame, so this frame starts at the end of the stack.
+        self.frame_offset = self.stack.len();
         self.stack
             .extend(iter::repeat(0).take(function.fixed_stack_size() as usize));
 
-        self.frame_stack.push(Frame::new(function));
+        self.frame_stack
+            .push(Frame::new(function).with_stack_offset(self.frame_offset));
     }
     fn pop_frame(&mut self) {
         if let Some(frame) = self.frame_stack.pop() {
-            // Shorten the stack after exiting the frame
-            self.stack
-                .truncate(self.stack.len() - frame.function().fixed_stack_size() as usize);
+            // Shorten the stack after exiting the frame, unless a frame of another stack was
+            // pushed after it
+            let frame_end = frame.stack_offset() + frame.function().fixed_stack_size() as usize;
+            if frame_end == self.stack.len() {
+                self.stack.truncate(frame.stack_offset());
+            }
 
             // Reset frame_offset to the start of this function
             if let Some(frame) = self.frame_stack.iter().last() {
-                self.frame_offset -= frame.function().fixed_stack_size() as usize;
+                self.frame_offset = frame.stack_offset();
             }
         }
     }
@@ -406,6 +647,10 @@ impl<'a> State<'a> for InterpreterState<'a> {
                 (AddressFunctionEntry::UserFunction, index.as_u32())
             }
 
+            ExternalName::TestCase(testname) if testname.raw() == THROW_HOSTCALL_NAME => {
+                (AddressFunctionEntry::Throw, 0)
+            }
+
             ExternalName::TestCase(testname) => {
                 // TODO: This is not optimal since we are looking up by string name
                 let index = self.functions.index_of(&testname.to_string()).unwrap();
@@ -444,9 +689,43 @@ impl<'a> State<'a> for InterpreterState<'a> {
                 .get(index as usize)
                 .copied()
                 .map(InterpreterFunctionRef::from),
+
+            AddressFunctionEntry::Throw => Some(InterpreterFunctionRef::Throw),
+
+            // Contexts saved by `stack_switch` can only be switched to, not called.
+            AddressFunctionEntry::SuspendedStack => None,
+        }
+    }
+
+    fn current_function_address(&self, size: AddressSize) -> Result<Address, MemoryError> {
+        self.interpreted_function_address(size, self.get_current_function())
+    }
+
+    fn return_address(&self, size: AddressSize) -> Result<Option<Address>, MemoryError> {
+        let num_frames = self.frame_stack.len();
+        match num_frames {
+            0 | 1 => Ok(None),
+            _ => {
+                let caller = self.frame_stack[num_frames - 2].function();
+                self.interpreted_function_address(size, caller).map(Some)
+            }
         }
     }
 
+    fn frame_pointer(&self, size: AddressSize) -> Result<Address, MemoryError> {
+        Address::from_parts(size, AddressRegion::Stack, 0, self.frame_offset as u64)
+    }
+
+    fn stack_pointer(&self, size: AddressSize) -> Result<Address, MemoryError> {
+        let frame_size = self.get_current_function().fixed_stack_size() as u64;
+        Address::from_parts(
+            size,
+            AddressRegion::Stack,
+            0,
+            self.frame_offset as u64 + frame_size,
+        )
+    }
+
     /// Non-Recursively resolves a global value until its address is found
     fn resolve_global_value(&self, gv: GlobalValue) -> Result<DataValue, MemoryError> {
         // Resolving a Global Value is a "pointer" chasing operation that lends itself to
@@ -1079,4 +1358,111 @@ mod tests {
         // Ensure that the correct trap was propagated.
         assert_eq!(trap, ControlFlow::Trap(CraneliftTrap::HeapMisaligned));
     }
+
+    // An exception without a matching handler escapes the outermost frame; the filetest
+    // infrastructure has no way to check for this.
+    #[test]
+    fn uncaught_exception() {
+        let code = "
+        function %thrower() tail {
+            sig0 = (i64, i64, i64, i32, i64, i64)
+            fn0 = %__cranelift_throw(i64, i64, i64, i32, i64, i64)
+
+        block0:
+            v0 = iconst.i64 0
+            v1 = iconst.i32 2
+            v2 = iconst.i64 42
+            call fn0(v0, v0, v0, v1, v2, v0)
+            return
+        }
+
+        function %test() -> i64 {
+            sig0 = () tail
+            fn0 = %thrower() tail
+
+        block0:
+            try_call fn0(), sig0, block1, [ tag1: block2(exn0) ]
+
+        block1:
+            v0 = iconst.i64 0
+            return v0
+
+        block2(v1: i64):
+            return v1
+        }";
+
+        let mut env = FunctionStore::default();
+        let funcs = parse_functions(code).unwrap();
+        for func in &funcs {
+            env.add(func.name.to_string(), func);
+        }
+
+        let state = InterpreterState::default().with_function_store(env);
+        let result = Interpreter::new(state).call_by_name("%test", &[]).unwrap();
+
+        assert_eq!(
+            result,
+            ControlFlow::Throw(
+                ExceptionTag::from_u32(2),
+                smallvec![DataValue::I64(42), DataValue::I64(0)]
+            )
+        );
+    }
+
+    // A function started on a new stack has nothing to return to, and a context must hold either
+    // a suspended stack or a function to start; the filetest infrastructure cannot check for
+    // errors.
+    #[test]
+    fn stack_switch_errors() {
+        let code = "
+        function %start(i64) tail {
+        block0(v0: i64):
+            return
+        }
+
+        function %returns() -> i64 {
+            ss0 = explicit_slot 8
+            fn0 = %start(i64) tail
+
+        block0:
+            v0 = func_addr.i64 fn0
+            v1 = stack_addr.i64 ss0
+            store v0, v1
+            v2 = stack_switch v1, v1, v1
+            return v2
+        }
+
+        function %uninitialized() -> i64 {
+            ss0 = explicit_slot 8
+            ss1 = explicit_slot 8
+
+        block0:
+            v0 = stack_addr.i64 ss0
+            v1 = stack_addr.i64 ss1
+            v2 = iconst.i64 0
+            v3 = stack_switch v0, v1, v2
+            return v3
+        }";
+
+        let mut env = FunctionStore::default();
+        let funcs = parse_functions(code).unwrap();
+        for func in &funcs {
+            env.add(func.name.to_string(), func);
+        }
+
+        let state = InterpreterState::default().with_function_store(env.clone());
+        let result = Interpreter::new(state).call_by_name("%returns", &[]);
+        match result {
+            Err(InterpreterError::StackFunctionExited) => {}
+            _ => panic!("Expected Err(StackFunctionExited), but got {result:?}"),
+        }
+
+        // `ss1` is zeroed, which is a stack address rather than a context.
+        let state = InterpreterState::default().with_function_store(env);
+        let result = Interpreter::new(state).call_by_name("%uninitialized", &[]);
+        match result {
+            Err(InterpreterError::InvalidStackContext(_)) => {}
+            _ => panic!("Expected Err(InvalidStackContext), but got {result:?}"),
+        }
+    }
 }
@@ -5,8 +5,8 @@ use crate::frame::Frame;
 use crate::interpreter::LibCallHandler;
 use cranelift_codegen::data_value::DataValue;
 use cranelift_codegen::ir::{
-    ExternalName, FuncRef, Function, GlobalValue, LibCall, MemFlagsData, Signature, StackSlot,
-    Type, Value, types,
+    AbiParam, ExternalName, FuncRef, Function, GlobalValue, LibCall, MemFlagsData, Signature,
+    StackSlot, Type, Value, types,
 };
 use cranelift_codegen::isa::CallConv;
 use smallvec::SmallVec;
@@ -76,6 +76,18 @@ pub trait State<'a> {
     /// Retrieve a reference to a [Function] given its address.
     fn get_function_from_address(&self, address: Address) -> Option<InterpreterFunctionRef<'a>>;
 
+    /// Compute the address of the currently executing [Function]. The interpreter has no notion
+    /// of instruction addresses, so this is also used for code addresses within the function,
+    /// e.g. exception handlers.
+    fn current_function_address(&self, size: AddressSize) -> Result<Address, MemoryError>;
+    /// Compute the address of the [Function] that the current frame will return to, if it was
+    /// called from another interpreted function.
+    fn return_address(&self, size: AddressSize) -> Result<Option<Address>, MemoryError>;
+    /// Compute the address of the start of the current frame's stack space.
+    fn frame_pointer(&self, size: AddressSize) -> Result<Address, MemoryError>;
+    /// Compute the address of the end of the current frame's stack space.
+    fn stack_pointer(&self, size: AddressSize) -> Result<Address, MemoryError>;
+
     /// Given a global value, compute the final value for that global value, applying all operations
     /// in intermediate global values.
     fn resolve_global_value(&self, gv: GlobalValue) -> Result<DataValue, MemoryError>;
@@ -86,9 +98,15 @@ pub trait State<'a> {
     fn set_pinned_reg(&mut self, v: DataValue);
 }
 
+/// The name of the hostcall which throws an exception. `cranelift-filetests` provides it to
+/// compiled code; the interpreter implements it by unwinding its own frames.
+pub const THROW_HOSTCALL_NAME: &[u8] = b"__cranelift_throw";
+
 pub enum InterpreterFunctionRef<'a> {
     Function(&'a Function),
     LibCall(LibCall),
+    /// The [THROW_HOSTCALL_NAME] hostcall.
+    Throw,
 }
 
 impl<'a> InterpreterFunctionRef<'a> {
@@ -98,6 +116,23 @@ impl<'a> InterpreterFunctionRef<'a> {
             // CallConv here is sort of irrelevant, since we don't use it for anything
             // FIXME handle non-64bit systems
             InterpreterFunctionRef::LibCall(lc) => lc.signature(CallConv::SystemV, types::I64),
+            // Matches the hostcall in `cranelift-filetests`: the entry frame pointer, exit frame
+            // pointer and exit PC (all ignored here), the exception tag and two payload values.
+            InterpreterFunctionRef::Throw => {
+                let mut sig = Signature::new(CallConv::SystemV);
+                sig.params.extend(
+                    [
+                        types::I64,
+                        types::I64,
+                        types::I64,
+                        types::I32,
+                        types::I64,
+                        types::I64,
+                    ]
+                    .map(AbiParam::new),
+                );
+                sig
+            }
         }
     }
 }
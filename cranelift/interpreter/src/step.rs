@@ -3,13 +3,13 @@
 use crate::address::{Address, AddressSize};
 use crate::frame::Frame;
 use crate::instruction::InstructionContext;
-use crate::state::{InterpreterFunctionRef, MemoryError, State};
+use crate::state::{InterpreterFunctionRef, MemoryError, State, THROW_HOSTCALL_NAME};
 use crate::value::{DataValueExt, ValueConversionKind, ValueError, ValueResult};
 use cranelift_codegen::data_value::DataValue;
 use cranelift_codegen::ir::condcodes::{FloatCC, IntCC};
 use cranelift_codegen::ir::{
-    AbiParam, AtomicRmwOp, Block, BlockArg, BlockCall, Endianness, ExternalName, FuncRef, Function,
-    InstructionData, Opcode, TrapCode, Type, Value as ValueRef, types,
+    AbiParam, AtomicRmwOp, Block, BlockArg, BlockCall, Endianness, ExceptionTag, ExternalName,
+    FuncRef, Function, InstructionData, Opcode, TrapCode, Type, Value as ValueRef, types,
 };
 use log::trace;
 use smallvec::{SmallVec, smallvec};
@@ -43,15 +43,17 @@ fn sum_unsigned(head: DataValue, tail: SmallVec<[DataValue; 1]>) -> ValueResult<
     acc.into_int_unsigned()
 }
 
-/// Collect a list of block arguments.
-fn collect_block_args(
+/// Collect a list of block arguments. The return values or exception payloads of a `try_call`
+/// are taken from `results`.
+pub(crate) fn collect_block_args(
     frame: &Frame,
     args: impl Iterator<Item = BlockArg>,
+    results: &[DataValue],
 ) -> SmallVec<[DataValue; 1]> {
     args.into_iter()
         .map(|n| match n {
             BlockArg::Value(n) => frame.get(n).clone(),
-            _ => panic!("exceptions not supported"),
+            BlockArg::TryCallRet(i) | BlockArg::TryCallExn(i) => results[i as usize].clone(),
         })
         .collect()
 }
@@ -263,6 +265,7 @@ where
         let branch_args = collect_block_args(
             state.current_frame(),
             block.args(&state.get_current_function().dfg.value_lists),
+            &[],
         );
         Ok(ControlFlow::ContinueAt(
             block.block(&state.get_current_function().dfg.value_lists),
@@ -309,6 +312,10 @@ where
 
             Ok(match func_ref {
                 InterpreterFunctionRef::Function(func) => make_ctrl_flow(func, args),
+                InterpreterFunctionRef::Throw => {
+                    let tag = ExceptionTag::from_u32(args[3].clone().into_int_unsigned()? as u32);
+                    ControlFlow::Throw(tag, SmallVec::from(&args[4..]))
+                }
                 InterpreterFunctionRef::LibCall(libcall) => {
                     debug_assert!(
                         !matches!(
@@ -336,6 +343,52 @@ where
             })
         };
 
+    // Resolves the callee of a direct call.
+    let call_target = |func_ref: FuncRef| -> Result<InterpreterFunctionRef<'a>, StepError> {
+        let ext_data = state
+            .get_current_function()
+            .dfg
+            .ext_funcs
+            .get(func_ref)
+            .ok_or(StepError::UnknownFunction(func_ref))?;
+
+        Ok(match ext_data.name {
+            ExternalName::TestCase(ref name) if name.raw() == THROW_HOSTCALL_NAME => {
+                InterpreterFunctionRef::Throw
+            }
+            // These functions should be registered in the regular function store
+            ExternalName::User(_) | ExternalName::TestCase(_) => {
+                let function = state
+                    .get_function(func_ref)
+                    .ok_or(StepError::UnknownFunction(func_ref))?;
+                InterpreterFunctionRef::Function(function)
+            }
+            ExternalName::LibCall(libcall) => InterpreterFunctionRef::LibCall(libcall),
+            ExternalName::KnownSymbol(_) => unimplemented!(),
+        })
+    };
+
+    // Resolves the callee of an indirect call from its address.
+    let indirect_call_target =
+        |callee: DataValue| -> Result<InterpreterFunctionRef<'a>, StepError> {
+            let addr_dv = DataValue::I64(callee.into_int_unsigned()? as i64);
+            let addr = Address::try_from(addr_dv.clone()).map_err(StepError::MemoryError)?;
+
+            state
+                .get_function_from_address(addr)
+                .ok_or_else(|| StepError::MemoryError(MemoryError::InvalidAddress(addr_dv)))
+        };
+
+    // Computes an address with the given `addr_ty` from the interpreter state.
+    let assign_address = |addr_ty: Type,
+                          addr: fn(&dyn State<'a>, AddressSize) -> Result<Address, MemoryError>|
+     -> ControlFlow<'a> {
+        assign_or_memtrap(
+            AddressSize::try_from(addr_ty)
+                .and_then(|addr_size| DataValue::try_from(addr(&*state, addr_size)?)),
+        )
+    };
+
     // Interpret a Cranelift instruction.
     Ok(match inst.opcode() {
         Opcode::Jump => {
@@ -393,25 +446,8 @@ where
                 unreachable!()
             };
 
-            let curr_func = state.get_current_function();
-            let ext_data = curr_func
-                .dfg
-                .ext_funcs
-                .get(func_ref)
-                .ok_or(StepError::UnknownFunction(func_ref))?;
-
             let args = args();
-            let func = match ext_data.name {
-                // These functions should be registered in the regular function store
-                ExternalName::User(_) | ExternalName::TestCase(_) => {
-                    let function = state
-                        .get_function(func_ref)
-                        .ok_or(StepError::UnknownFunction(func_ref))?;
-                    InterpreterFunctionRef::Function(function)
-                }
-                ExternalName::LibCall(libcall) => InterpreterFunctionRef::LibCall(libcall),
-                ExternalName::KnownSymbol(_) => unimplemented!(),
-            };
+            let func = call_target(func_ref)?;
 
             let make_control_flow = match inst.opcode() {
                 Opcode::Call => ControlFlow::Call,
@@ -422,15 +458,8 @@ where
             call_func(func, args, make_control_flow)?
         }
         Opcode::CallIndirect | Opcode::ReturnCallIndirect => {
-            let args = args();
-            let addr_dv = DataValue::I64(arg(0).into_int_unsigned()? as i64);
-            let addr = Address::try_from(addr_dv.clone()).map_err(StepError::MemoryError)?;
-
-            let func = state
-                .get_function_from_address(addr)
-                .ok_or_else(|| StepError::MemoryError(MemoryError::InvalidAddress(addr_dv)))?;
-
-            let call_args: SmallVec<[DataValue; 1]> = SmallVec::from(&args[1..]);
+            let func = indirect_call_target(arg(0))?;
+            let call_args = args_range(1..)?;
 
             let make_control_flow = match inst.opcode() {
                 Opcode::CallIndirect => ControlFlow::Call,
@@ -440,6 +469,38 @@ where
 
             call_func(func, call_args, make_control_flow)?
         }
+        Opcode::TryCall | Opcode::TryCallIndirect => {
+            let (func, call_args, exception) = match inst {
+                InstructionData::TryCall {
+                    func_ref,
+                    exception,
+                    ..
+                } => (call_target(func_ref)?, args(), exception),
+                InstructionData::TryCallIndirect { exception, .. } => {
+                    (indirect_call_target(arg(0))?, args_range(1..)?, exception)
+                }
+                _ => unreachable!(),
+            };
+
+            // Calls into interpreted functions continue at the normal-return block, or unwind to
+            // one of the handlers, once the callee finishes; see `Interpreter::run`.
+            match call_func(func, call_args, ControlFlow::Call)? {
+                // Libcalls have already returned their results.
+                ControlFlow::Assign(rets) => {
+                    let dfg = &state.get_current_function().dfg;
+                    let normal_return = dfg.exception_tables[exception].normal_return();
+                    ControlFlow::ContinueAt(
+                        normal_return.block(&dfg.value_lists),
+                        collect_block_args(
+                            state.current_frame(),
+                            normal_return.args(&dfg.value_lists),
+                            &rets,
+                        ),
+                    )
+                }
+                cf => cf,
+            }
+        }
         Opcode::FuncAddr => {
             let func_ref = if let InstructionData::FuncAddr { func_ref, .. } = inst {
                 func_ref
@@ -1294,22 +1355,109 @@ where
         Opcode::ExtractVector => {
             unimplemented!("ExtractVector not supported");
         }
-        Opcode::GetFramePointer => unimplemented!("GetFramePointer"),
-        Opcode::GetStackPointer => unimplemented!("GetStackPointer"),
-        Opcode::GetReturnAddress => unimplemented!("GetReturnAddress"),
-        Opcode::X86Pshufb => unimplemented!("X86Pshufb"),
-        Opcode::Blendv => unimplemented!("Blendv"),
-        Opcode::X86Pmulhrsw => unimplemented!("X86Pmulhrsw"),
-        Opcode::X86Pmaddubsw => unimplemented!("X86Pmaddubsw"),
-        Opcode::X86Cvtt2dq => unimplemented!("X86Cvtt2dq"),
-        Opcode::StackSwitch => unimplemented!("StackSwitch"),
-
-        Opcode::TryCall => unimplemented!("TryCall"),
-        Opcode::TryCallIndirect => unimplemented!("TryCallIndirect"),
-
-        Opcode::GetExceptionHandlerAddress => unimplemented!("GetExceptionHandlerAddress"),
-
-        Opcode::SequencePoint => unimplemented!("SequencePoint"),
+        Opcode::GetFramePointer => assign_address(ctrl_ty, |state, size| state.frame_pointer(size)),
+        Opcode::GetStackPointer => assign_address(ctrl_ty, |state, size| state.stack_pointer(size)),
+        Opcode::GetReturnAddress => {
+            assign_or_memtrap(AddressSize::try_from(ctrl_ty).and_then(|addr_size| {
+                match state.return_address(addr_size)? {
+                    Some(addr) => DataValue::try_from(addr),
+                    // The outermost frame returns to the host, which has no interpreter address.
+                    None => Ok(match addr_size {
+                        AddressSize::_32 => DataValue::I32(0),
+                        AddressSize::_64 => DataValue::I64(0),
+                    }),
+                }
+            }))
+        }
+        Opcode::GetExceptionHandlerAddress => {
+            assign_address(ctrl_ty, |state, size| state.current_function_address(size))
+        }
+        Opcode::X86Pshufb => {
+            let x = DataValueExt::into_array(&arg(0))?;
+            let s = DataValueExt::into_array(&arg(1))?;
+            let mut new = [0u8; 16];
+            for i in 0..new.len() {
+                if s[i] & 0x80 == 0 {
+                    new[i] = x[(s[i] & 0x0f) as usize];
+                } // else leave as 0
+            }
+            assign(DataValueExt::vector(new, types::I8X16)?)
+        }
+        Opcode::Blendv => {
+            let c = extractlanes(&arg(0), ctrl_ty.as_int())?;
+            let x = extractlanes(&arg(1), ctrl_ty)?;
+            let y = extractlanes(&arg(2), ctrl_ty)?;
+            let new_vec = c
+                .into_iter()
+                .zip(x)
+                .zip(y)
+                .map(|((c, x), y)| Ok(if c.into_int_signed()? < 0 { x } else { y }))
+                .collect::<ValueResult<SimdVec<_>>>()?;
+            assign(vectorizelanes(&new_vec, ctrl_ty)?)
+        }
+        Opcode::X86Pmulhrsw => {
+            // This is `sqmul_round_sat` without the saturation: `MIN * MIN` wraps around to `MIN`.
+            let lane_type = ctrl_ty.lane_type();
+            let arg0 = extractlanes(&arg(0), ctrl_ty)?;
+            let arg1 = extractlanes(&arg(1), ctrl_ty)?;
+            let new_vec = arg0
+                .into_iter()
+                .zip(arg1)
+                .map(|(x, y)| {
+                    let x = x.into_int_signed()?;
+                    let y = y.into_int_signed()?;
+                    let z = (x * y + (1 << (lane_type.bits() - 2))) >> (lane_type.bits() - 1);
+                    DataValueExt::int(z, lane_type)
+                })
+                .collect::<ValueResult<SimdVec<_>>>()?;
+            assign(vectorizelanes(&new_vec, ctrl_ty)?)
+        }
+        Opcode::X86Pmaddubsw => {
+            // Signed bytes of `x` are multiplied with unsigned bytes of `y`, and adjacent products
+            // are summed with signed saturation.
+            let x = DataValueExt::into_array(&arg(0))?;
+            let y = DataValueExt::into_array(&arg(1))?;
+            let new_vec = x
+                .chunks(2)
+                .zip(y.chunks(2))
+                .map(|(x, y)| {
+                    let sum = i32::from(x[0] as i8) * i32::from(y[0])
+                        + i32::from(x[1] as i8) * i32::from(y[1]);
+                    let sum = sum.clamp(i16::MIN.into(), i16::MAX.into());
+                    DataValueExt::int(sum.into(), types::I16)
+                })
+                .collect::<ValueResult<SimdVec<_>>>()?;
+            assign(vectorizelanes(&new_vec, types::I16X8)?)
+        }
+        Opcode::X86Cvtt2dq => {
+            // Both `cvttps2dq` and `cvttpd2dq` produce 32-bit integers, with `i32::MIN` for NaN
+            // and out-of-range lanes; wider result lanes hold the sign-extended value.
+            let in_ty = inst_context.type_of(inst_context.args()[0]).unwrap();
+            let cvt = |x: DataValue| -> ValueResult<DataValue> {
+                let x = x.into_float()?.trunc();
+                let x = if x >= f64::from(i32::MIN) && x <= f64::from(i32::MAX) {
+                    x as i32
+                } else {
+                    i32::MIN
+                };
+                DataValueExt::int(x.into(), ctrl_ty.lane_type())
+            };
+            let x = extractlanes(&arg(0), in_ty)?;
+            assign(vectorizelanes(
+                &x.into_iter()
+                    .map(cvt)
+                    .collect::<ValueResult<SimdVec<DataValue>>>()?,
+                ctrl_ty,
+            )?)
+        }
+        // Stacks are interpreter frame stacks, so the switch itself is performed by the
+        // `Interpreter`; see `Interpreter::stack_switch` for the layout of the contexts.
+        Opcode::StackSwitch => ControlFlow::StackSwitch {
+            store_context: Address::try_from(arg(0))?,
+            load_context: Address::try_from(arg(1))?,
+            payload: arg(2),
+        },
+        Opcode::SequencePoint => ControlFlow::Continue,
     })
 }
 
@@ -1346,6 +1494,16 @@ pub enum ControlFlow<'a> {
     ReturnCall(&'a Function, SmallVec<[DataValue; 1]>),
     /// Return from the current function with the given parameters, e.g.: `return [v1, v2]`.
     Return(SmallVec<[DataValue; 1]>),
+    /// Throw an exception with the given tag and payload values, unwinding frames until a
+    /// `try_call` with a matching handler is found.
+    Throw(ExceptionTag, SmallVec<[DataValue; 1]>),
+    /// Suspend the current stack, saving its context at `store_context`, and resume the stack
+    /// whose context is stored at `load_context`, passing it `payload`.
+    StackSwitch {
+        store_context: Address,
+        load_context: Address,
+        payload: DataValue,
+    },
     /// Stop with a program-generated trap; note that these are distinct from errors that may occur
     /// during interpretation.
     Trap(CraneliftTrap),
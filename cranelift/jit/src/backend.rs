@@ -20,7 +20,8 @@ use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::ffi::CString;
 use std::io::Write;
-use target_lexicon::PointerWidth;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use target_lexicon::{Architecture, PointerWidth};
 
 const WRITABLE_DATA_ALIGNMENT: u64 = 0x8;
 const READONLY_DATA_ALIGNMENT: u64 = 0x1;
@@ -32,6 +33,7 @@ pub struct JITBuilder {
     lookup_symbols: Vec<Box<dyn Fn(&str) -> Option<*const u8> + Send>>,
     libcall_names: Box<dyn Fn(ir::LibCall) -> String + Send + Sync>,
     memory: Option<Box<dyn JITMemoryProvider + Send>>,
+    hotswap_enabled: bool,
 }
 
 impl JITBuilder {
@@ -96,6 +98,7 @@ impl JITBuilder {
             lookup_symbols,
             libcall_names,
             memory: None,
+            hotswap_enabled: false,
         }
     }
 
@@ -154,6 +157,41 @@ impl JITBuilder {
         self.memory = Some(provider);
         self
     }
+
+    /// Enable or disable hotswap support for the module.
+    ///
+    /// With hotswap support, every function is called through a small stub
+    /// that jumps to the function's current body, which allows redefining the
+    /// function with [`JITModule::prepare_for_function_redefine`] while
+    /// existing callers pick up the new body. This is supported on x86_64,
+    /// aarch64 and riscv64.
+    pub fn hotswap(&mut self, enabled: bool) -> &mut Self {
+        self.hotswap_enabled = enabled;
+        self
+    }
+}
+
+/// The indirection used to call a function when hotswap support is enabled.
+#[derive(Clone)]
+struct HotswapSlot {
+    /// Pointer-sized writable memory holding the address of the function's
+    /// current body.
+    slot: CompiledBlob,
+    /// Code which jumps to the address stored in `slot`. This is the address
+    /// of the function as seen by callers.
+    stub: CompiledBlob,
+}
+
+impl HotswapSlot {
+    /// Publish `body` as the function's current body.
+    ///
+    /// # Safety
+    ///
+    /// `body` must be null or point to finalized code.
+    unsafe fn set(&self, body: *const u8) {
+        let slot = unsafe { &*self.slot.ptr().cast::<AtomicUsize>() };
+        slot.store(body.addr(), Ordering::Release);
+    }
 }
 
 /// A wrapper that impls Send for the contents.
@@ -181,6 +219,11 @@ pub struct JITModule {
     code_ranges: BTreeMap<usize, (usize, FuncId)>,
     functions_to_finalize: Vec<FuncId>,
     data_objects_to_finalize: Vec<DataId>,
+    hotswap_enabled: bool,
+    hotswap_slots: SecondaryMap<FuncId, Option<HotswapSlot>>,
+    /// Bodies of redefined functions which may still be running and haven't
+    /// been freed yet.
+    retired_functions: SecondaryMap<FuncId, Vec<CompiledBlob>>,
 }
 
 impl JITModule {
@@ -224,6 +267,9 @@ impl JITModule {
             ModuleRelocTarget::User { .. } => {
                 let (name, linkage) = if ModuleDeclarations::is_function(name) {
                     let func_id = FuncId::from_name(name);
+                    if let Some(hotswap) = &self.hotswap_slots[func_id] {
+                        return hotswap.stub.ptr();
+                    }
                     match &self.compiled_functions[func_id] {
                         Some(compiled) => return compiled.ptr(),
                         None => {
@@ -269,23 +315,31 @@ impl JITModule {
 
     /// Returns the address of a finalized function.
     ///
-    /// The pointer remains valid until either [`JITModule::free_memory`] is called or in the future
-    /// some way of deallocating this individual function is used.
+    /// With hotswap support enabled this is the address of the function's
+    /// stub, which always calls the latest finalized definition of the
+    /// function.
+    ///
+    /// The pointer remains valid until either [`JITModule::free_memory`] or
+    /// [`JITModule::free_function`] is called.
     pub fn get_finalized_function(&self, func_id: FuncId) -> *const u8 {
         let info = &self.compiled_functions[func_id];
         assert!(
             !self.functions_to_finalize.iter().any(|x| *x == func_id),
             "function not yet finalized"
         );
-        info.as_ref()
-            .expect("function must be compiled before it can be finalized")
-            .ptr()
+        let compiled = info
+            .as_ref()
+            .expect("function must be compiled before it can be finalized");
+        match &self.hotswap_slots[func_id] {
+            Some(hotswap) => hotswap.stub.ptr(),
+            None => compiled.ptr(),
+        }
     }
 
     /// Returns the address and size of a finalized data object.
     ///
-    /// The pointer remains valid until either [`JITModule::free_memory`] is called or in the future
-    /// some way of deallocating this individual data object is used.
+    /// The pointer remains valid until either [`JITModule::free_memory`] or
+    /// [`JITModule::free_data`] is called.
     pub fn get_finalized_data(&self, data_id: DataId) -> (*const u8, usize) {
         let info = &self.compiled_data_objects[data_id];
         assert!(
@@ -325,7 +379,8 @@ impl JITModule {
     ///
     /// Returns ModuleError in case of allocation or syscall failure
     pub fn finalize_definitions(&mut self) -> ModuleResult<()> {
-        for func in std::mem::take(&mut self.functions_to_finalize) {
+        let functions = std::mem::take(&mut self.functions_to_finalize);
+        for &func in &functions {
             let decl = self.declarations.get_function_decl(func);
             assert!(decl.linkage.is_definable());
             let func = self.compiled_functions[func]
@@ -351,9 +406,183 @@ impl JITModule {
         };
         self.memory.finalize(branch_protection)?;
 
+        // Route callers of hotswappable functions to the new bodies only now
+        // that they're executable.
+        for func in functions {
+            if let Some(hotswap) = &self.hotswap_slots[func] {
+                let body = self.compiled_functions[func].as_ref().unwrap().ptr();
+                unsafe { hotswap.set(body) };
+            }
+        }
+
         Ok(())
     }
 
+    /// Allow a single function to be redefined, with callers picking up the
+    /// new definition once it has been finalized.
+    ///
+    /// The current body of the function is kept around, as it may still be
+    /// running, until it's freed with [`JITModule::free_old_function_bodies`].
+    ///
+    /// This requires hotswap support to be enabled with
+    /// [`JITBuilder::hotswap`] and the function to be defined and finalized.
+    pub fn prepare_for_function_redefine(&mut self, func_id: FuncId) -> ModuleResult<()> {
+        assert!(self.hotswap_enabled, "Hotswap support is not enabled");
+        let decl = self.declarations.get_function_decl(func_id);
+        if !decl.linkage.is_definable() {
+            return Err(ModuleError::InvalidImportDefinition(
+                decl.linkage_name(func_id).into_owned(),
+            ));
+        }
+
+        if self.compiled_functions[func_id].is_none() {
+            return Err(ModuleError::Backend(anyhow::anyhow!(
+                "Tried to redefine not yet defined function {}",
+                decl.linkage_name(func_id),
+            )));
+        }
+
+        if self.functions_to_finalize.contains(&func_id) {
+            return Err(ModuleError::Backend(anyhow::anyhow!(
+                "Tried to redefine not yet finalized function {}",
+                decl.linkage_name(func_id),
+            )));
+        }
+
+        let old = self.compiled_functions[func_id].take().unwrap();
+        self.retired_functions[func_id].push(old);
+
+        Ok(())
+    }
+
+    /// Free the bodies of a function which have been replaced by
+    /// redefining it.
+    ///
+    /// # Safety
+    ///
+    /// None of the old bodies may be executing, which includes having been
+    /// entered through the function's stub before the latest definition was
+    /// finalized.
+    pub unsafe fn free_old_function_bodies(&mut self, func_id: FuncId) {
+        for blob in std::mem::take(&mut self.retired_functions[func_id]) {
+            self.code_ranges.remove(&blob.ptr().addr());
+            unsafe { blob.free(&mut *self.memory) };
+        }
+    }
+
+    /// Free all memory of a function, leaving it undefined. The function may be
+    /// defined again afterwards.
+    ///
+    /// With hotswap support the function's stub is kept, so that callers pick
+    /// up a later definition, but calling it before then will crash.
+    ///
+    /// # Safety
+    ///
+    /// The function may not be executing or be called afterwards, including
+    /// through pointers obtained from [`JITModule::get_finalized_function`] or
+    /// by other functions, until it has been defined and finalized again.
+    pub unsafe fn free_function(&mut self, func_id: FuncId) {
+        unsafe { self.free_old_function_bodies(func_id) };
+        self.functions_to_finalize.retain(|&id| id != func_id);
+        if let Some(hotswap) = &self.hotswap_slots[func_id] {
+            unsafe { hotswap.set(std::ptr::null()) };
+        }
+        if let Some(blob) = self.compiled_functions[func_id].take() {
+            self.code_ranges.remove(&blob.ptr().addr());
+            unsafe { blob.free(&mut *self.memory) };
+        }
+    }
+
+    /// Free the memory of a data object, leaving it undefined. The data object
+    /// may be defined again afterwards.
+    ///
+    /// # Safety
+    ///
+    /// The data object may not be accessed afterwards, including through
+    /// pointers obtained from [`JITModule::get_finalized_data`] or by
+    /// functions referring to it.
+    pub unsafe fn free_data(&mut self, data_id: DataId) {
+        self.data_objects_to_finalize.retain(|&id| id != data_id);
+        if let Some(blob) = self.compiled_data_objects[data_id].take() {
+            unsafe { blob.free(&mut *self.memory) };
+        }
+    }
+
+    /// Create the stub through which a function is called when hotswap
+    /// support is enabled, if it doesn't exist yet.
+    fn define_hotswap_stub(&mut self, id: FuncId) -> ModuleResult<()> {
+        if !self.hotswap_enabled || self.hotswap_slots[id].is_some() {
+            return Ok(());
+        }
+
+        let slot = CompiledBlob::new_zeroed(
+            &mut *self.memory,
+            8,
+            8,
+            vec![],
+            #[cfg(feature = "wasmtime-unwinder")]
+            None,
+            JITMemoryKind::Writable,
+        )?;
+        let code = self.hotswap_stub_code(slot.ptr())?;
+        let align = (self.isa.function_alignment().minimum as u64).max(8);
+        let stub = CompiledBlob::new(
+            &mut *self.memory,
+            &code,
+            align,
+            vec![],
+            #[cfg(feature = "wasmtime-unwinder")]
+            None,
+            JITMemoryKind::Executable,
+        )?;
+        self.hotswap_slots[id] = Some(HotswapSlot { slot, stub });
+        Ok(())
+    }
+
+    /// Machine code which jumps to the address stored at `slot`, clobbering
+    /// only registers which are free to use at function entry.
+    fn hotswap_stub_code(&self, slot: *const u8) -> ModuleResult<Vec<u8>> {
+        let slot = (slot.addr() as u64).to_le_bytes();
+        let mut code = Vec::new();
+        match self.isa.triple().architecture {
+            Architecture::X86_64 => {
+                // movabs r11, slot
+                code.extend([0x49, 0xbb]);
+                code.extend(slot);
+                // jmp qword ptr [r11]
+                code.extend([0x41, 0xff, 0x23]);
+            }
+            Architecture::Aarch64(_) => {
+                for inst in [
+                    0xd503245f_u32, // bti c
+                    0x58000070,     // ldr x16, #12
+                    0xf9400210,     // ldr x16, [x16]
+                    0xd61f0200,     // br x16
+                ] {
+                    code.extend(inst.to_le_bytes());
+                }
+                code.extend(slot);
+            }
+            Architecture::Riscv64(_) => {
+                for inst in [
+                    0x00000297_u32, // auipc t0, 0
+                    0x0102b283,     // ld t0, 16(t0)
+                    0x0002b283,     // ld t0, 0(t0)
+                    0x00028067,     // jr t0
+                ] {
+                    code.extend(inst.to_le_bytes());
+                }
+                code.extend(slot);
+            }
+            arch => {
+                return Err(ModuleError::Backend(anyhow::anyhow!(
+                    "hotswap is not supported on {arch}"
+                )));
+            }
+        }
+        Ok(code)
+    }
+
     /// Create a new `JITModule`.
     pub fn new(builder: JITBuilder) -> Self {
         assert!(
@@ -376,6 +605,9 @@ impl JITModule {
             code_ranges: BTreeMap::new(),
             functions_to_finalize: Vec::new(),
             data_objects_to_finalize: Vec::new(),
+            hotswap_enabled: builder.hotswap_enabled,
+            hotswap_slots: SecondaryMap::new(),
+            retired_functions: SecondaryMap::new(),
         }
     }
 
@@ -394,10 +626,11 @@ impl JITModule {
 
         // Get the ExceptionTable. The "parse" here simply reads two
         // u32s for lengths and constructs borrowed slices, so it's
-        // cheap.
+        // cheap. The PC may be in an old body of a redefined function.
         let data = self.compiled_functions[func]
-            .as_ref()
-            .unwrap()
+            .iter()
+            .chain(&self.retired_functions[func])
+            .find(|blob| blob.ptr().addr() == start)?
             .wasmtime_exception_data()?;
         let exception_table = wasmtime_unwinder::ExceptionTable::parse(data).ok()?;
         Some((start, exception_table))
@@ -515,6 +748,7 @@ impl Module for JITModule {
         let range_end = range_start + size;
         self.code_ranges.insert(range_start, (range_end, id));
 
+        self.define_hotswap_stub(id)?;
         self.functions_to_finalize.push(id);
 
         Ok(())
@@ -557,6 +791,7 @@ impl Module for JITModule {
         let (ptr, size) = (blob.ptr(), blob.size());
         self.record_function_for_perf(ptr, size, &decl.linkage_name(id));
 
+        self.define_hotswap_stub(id)?;
         self.functions_to_finalize.push(id);
 
         Ok(())
@@ -22,6 +22,7 @@ pub(crate) struct CompiledBlob {
     size: usize,
     relocs: Vec<ModuleReloc>,
     veneer_count: usize,
+    kind: JITMemoryKind,
     #[cfg(feature = "wasmtime-unwinder")]
     wasmtime_exception_data: Option<Vec<u8>>,
 }
@@ -59,6 +60,7 @@ impl CompiledBlob {
             size: data.len(),
             relocs,
             veneer_count,
+            kind,
             #[cfg(feature = "wasmtime-unwinder")]
             wasmtime_exception_data,
         })
@@ -83,6 +85,7 @@ impl CompiledBlob {
             size,
             relocs,
             veneer_count: 0,
+            kind,
             #[cfg(feature = "wasmtime-unwinder")]
             wasmtime_exception_data,
         })
@@ -96,6 +99,17 @@ impl CompiledBlob {
         self.size
     }
 
+    /// Returns this blob's memory, including any veneers, to `memory`.
+    ///
+    /// # Safety
+    ///
+    /// The blob must have been allocated from `memory` and must not be used by
+    /// any code that may still run.
+    pub(crate) unsafe fn free(self, memory: &mut dyn JITMemoryProvider) {
+        let size = self.size + self.veneer_count * VENEER_SIZE;
+        unsafe { memory.deallocate(self.ptr, size, self.kind) };
+    }
+
     #[cfg(feature = "wasmtime-unwinder")]
     pub(crate) fn wasmtime_exception_data(&self) -> Option<&[u8]> {
         self.wasmtime_exception_data.as_deref()
@@ -18,6 +18,11 @@ struct Segment {
     position: usize,
     target_prot: region::Protection,
     finalized: bool,
+    /// The number of allocations in this segment that haven't been freed.
+    live: usize,
+    /// Whether all allocations in this finalized segment have been freed, so
+    /// that it can be reused for new allocations.
+    free: bool,
 }
 
 impl Segment {
@@ -31,6 +36,8 @@ impl Segment {
             target_prot,
             position: 0,
             finalized: false,
+            live: 0,
+            free: false,
         };
         // Set segment to read-write for initialization. The target permissions
         // will be applied in `finalize`.
@@ -64,6 +71,28 @@ impl Segment {
         self.finalized = true;
     }
 
+    /// Give the memory of this segment back to the OS and mark it as free for
+    /// reuse. The address range itself stays reserved.
+    fn release(&mut self) {
+        unsafe {
+            region::protect(self.ptr, self.len, region::Protection::NONE)
+                .expect("unable to change memory protection for jit memory segment");
+            #[cfg(unix)]
+            libc::madvise(self.ptr.cast(), self.len, libc::MADV_DONTNEED);
+        }
+        self.position = 0;
+        self.finalized = false;
+        self.free = true;
+    }
+
+    /// Reuse a segment previously freed by `release`.
+    fn reuse(&mut self, target_prot: region::Protection) {
+        debug_assert!(self.free);
+        self.target_prot = target_prot;
+        self.free = false;
+        self.set_rw();
+    }
+
     // Note: We do pointer arithmetic on `ptr` passed to `Segment::new` here.
     // This assumes that `ptr` is valid for `len` bytes, or will result in UB.
     fn allocate(&mut self, size: usize, align: usize) -> *mut u8 {
@@ -71,11 +100,16 @@ impl Segment {
         self.position = align_up(self.position, align);
         let ptr = unsafe { self.ptr.add(self.position) };
         self.position += size;
+        self.live += 1;
         ptr
     }
 
     fn has_space_for(&self, size: usize, align: usize) -> bool {
-        !self.finalized && align_up(self.position, align) + size <= self.len
+        !self.finalized && !self.free && align_up(self.position, align) + size <= self.len
+    }
+
+    fn contains(&self, ptr: *mut u8) -> bool {
+        self.ptr <= ptr && ptr < self.ptr.wrapping_add(self.len)
     }
 }
 
@@ -146,7 +180,7 @@ impl ArenaMemoryProvider {
 
         // Can we resize the last segment?
         if let Some(segment) = self.segments.iter_mut().last() {
-            if segment.target_prot == protection && !segment.finalized {
+            if segment.target_prot == protection && !segment.finalized && !segment.free {
                 let additional_size = align_up(size, region::page::size());
 
                 // If our reserved arena can fit the additional size, extend the
@@ -161,17 +195,26 @@ impl ArenaMemoryProvider {
         }
 
         // Allocate new segment for given size and alignment.
-        self.allocate_segment(size, protection)?;
-        let i = self.segments.len() - 1;
+        let i = self.allocate_segment(size, protection)?;
         Ok(self.segments[i].allocate(size, align))
     }
 
+    /// Returns the index of a segment with room for `size` bytes, either one
+    /// that was freed earlier or a new one.
     fn allocate_segment(
         &mut self,
         size: usize,
         target_prot: region::Protection,
-    ) -> Result<(), io::Error> {
+    ) -> Result<usize, io::Error> {
         let size = align_up(size, region::page::size());
+        if let Some(i) = self
+            .segments
+            .iter()
+            .position(|seg| seg.free && seg.len >= size)
+        {
+            self.segments[i].reuse(target_prot);
+            return Ok(i);
+        }
         let ptr = unsafe { self.ptr.add(self.position) };
         if self.position + size > self.size {
             return Err(io::Error::new(
@@ -181,7 +224,26 @@ impl ArenaMemoryProvider {
         }
         self.position += size;
         self.segments.push(Segment::new(ptr, size, target_prot));
-        Ok(())
+        Ok(self.segments.len() - 1)
+    }
+
+    /// Frees a single allocation. Once all allocations in a finalized segment
+    /// have been freed, the segment's memory is released and the segment is
+    /// reused for later allocations.
+    pub(crate) unsafe fn deallocate(&mut self, ptr: *mut u8) {
+        let segment = self
+            .segments
+            .iter_mut()
+            .find(|seg| !seg.free && seg.contains(ptr))
+            .expect("freed pointer was not allocated by this arena");
+        segment.live -= 1;
+        if segment.live == 0 {
+            if segment.finalized {
+                segment.release();
+            } else {
+                segment.position = 0;
+            }
+        }
     }
 
     pub(crate) fn finalize(&mut self, branch_protection: BranchProtection) {
@@ -237,6 +299,10 @@ impl JITMemoryProvider for ArenaMemoryProvider {
         self.free_memory();
     }
 
+    unsafe fn deallocate(&mut self, ptr: *mut u8, _size: usize, _kind: JITMemoryKind) {
+        self.deallocate(ptr);
+    }
+
     fn finalize(&mut self, branch_protection: BranchProtection) -> ModuleResult<()> {
         self.finalize(branch_protection);
         Ok(())
@@ -288,6 +354,29 @@ mod tests {
             .unwrap_err();
     }
 
+    #[test]
+    fn reuse_freed_segment() {
+        let mut arena = ArenaMemoryProvider::new_with_size(1 << 20).unwrap();
+
+        let a = arena.allocate(100, 16, JITMemoryKind::Executable).unwrap();
+        let b = arena.allocate(100, 16, JITMemoryKind::Executable).unwrap();
+        arena.finalize(BranchProtection::None);
+
+        // The segment is only released once both allocations are freed.
+        unsafe { arena.deallocate(a) };
+        assert!(!arena.segments[0].free);
+        unsafe { arena.deallocate(b) };
+        assert!(arena.segments[0].free);
+
+        // The freed segment is reused, even for a different kind of memory.
+        let c = arena.allocate(100, 16, JITMemoryKind::Writable).unwrap();
+        assert_eq!(c, a);
+        assert_eq!(arena.segments.len(), 1);
+        arena.finalize(BranchProtection::None);
+        unsafe { c.write_volatile(42) };
+        unsafe { arena.free_memory() };
+    }
+
     #[test]
     fn test_is_send() {
         fn assert_is_send<T: Send>() {}
@@ -17,6 +17,7 @@ pub enum BranchProtection {
 }
 
 /// The kind of memory allocation requested by a [`JITMemoryProvider`].
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum JITMemoryKind {
     /// Allocate memory that will be executable once finalized.
     Executable,
@@ -31,6 +32,21 @@ pub trait JITMemoryProvider {
     /// Allocate memory
     fn allocate(&mut self, size: usize, align: u64, kind: JITMemoryKind) -> io::Result<*mut u8>;
 
+    /// Free a single allocation once it's no longer in use.
+    ///
+    /// Providers are free to only give memory back once all allocations
+    /// sharing its pages have been freed, or to never reuse it at all, which
+    /// is what the default implementation does.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must have been returned by [`JITMemoryProvider::allocate`] for
+    /// the same `size` and `kind`, must not have been freed already, and
+    /// nothing may access the memory afterwards.
+    unsafe fn deallocate(&mut self, ptr: *mut u8, size: usize, kind: JITMemoryKind) {
+        let _ = (ptr, size, kind);
+    }
+
     /// Free the memory region.
     unsafe fn free_memory(&mut self);
     /// Finalize the memory region and apply memory protections.
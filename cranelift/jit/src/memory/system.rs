@@ -12,6 +12,8 @@ struct PtrLen {
     map: Option<MmapMut>,
     ptr: *mut u8,
     len: usize,
+    /// The number of allocations in this region that haven't been freed.
+    live: usize,
 }
 
 impl PtrLen {
@@ -21,6 +23,7 @@ impl PtrLen {
             map: None,
             ptr: ptr::null_mut(),
             len: 0,
+            live: 0,
         }
     }
 
@@ -35,6 +38,7 @@ impl PtrLen {
                 ptr: mmap.as_mut_ptr(),
                 map: Some(mmap),
                 len: alloc_size,
+                live: 0,
             }
         })
     }
@@ -80,6 +84,7 @@ impl Memory {
             // TODO: Ensure overflow is not possible.
             let ptr = unsafe { self.current.ptr.add(self.position) };
             self.position += size;
+            self.current.live += 1;
             return Ok(ptr);
         }
 
@@ -88,10 +93,39 @@ impl Memory {
         // TODO: Allocate more at a time.
         self.current = PtrLen::with_size(size)?;
         self.position = size;
+        self.current.live = 1;
 
         Ok(self.current.ptr)
     }
 
+    /// Free an allocation made by `allocate`.
+    ///
+    /// Regions are unmapped once all allocations in them have been freed,
+    /// except for the region currently being allocated from, which is reused
+    /// from the start instead.
+    pub(crate) unsafe fn deallocate(&mut self, ptr: *mut u8) {
+        let contains = |alloc: &PtrLen| {
+            alloc.map.is_some() && alloc.ptr <= ptr && ptr < alloc.ptr.wrapping_add(alloc.len)
+        };
+        if contains(&self.current) {
+            self.current.live -= 1;
+            if self.current.live == 0 {
+                self.position = 0;
+            }
+            return;
+        }
+        let alloc = self
+            .allocations
+            .iter_mut()
+            .find(|alloc| contains(alloc))
+            .expect("freed pointer was not allocated by this memory");
+        alloc.live -= 1;
+        if alloc.live == 0 {
+            // Keep the entry, which `already_protected` counts, but unmap it.
+            *alloc = PtrLen::new();
+        }
+    }
+
     /// Set all memory allocated in this `Memory` up to now as readable and executable.
     pub(crate) fn set_readable_and_executable(
         &mut self,
@@ -182,6 +216,14 @@ impl JITMemoryProvider for SystemMemoryProvider {
         self.writable.free_memory();
     }
 
+    unsafe fn deallocate(&mut self, ptr: *mut u8, _size: usize, kind: JITMemoryKind) {
+        match kind {
+            JITMemoryKind::Executable => self.code.deallocate(ptr),
+            JITMemoryKind::Writable => self.writable.deallocate(ptr),
+            JITMemoryKind::ReadOnly => self.readonly.deallocate(ptr),
+        }
+    }
+
     fn finalize(&mut self, branch_protection: BranchProtection) -> ModuleResult<()> {
         self.readonly.set_readonly()?;
         self.code.set_readable_and_executable(branch_protection)
@@ -219,3 +219,147 @@ fn empty_data_object() {
     data.define(Box::new([]));
     module.define_data(data_id, &data).unwrap();
 }
+
+fn define_const_function(module: &mut JITModule, func_id: FuncId, value: i64) {
+    let mut ctx = module.make_context();
+    ctx.func.signature = module
+        .declarations()
+        .get_function_decl(func_id)
+        .signature
+        .clone();
+    ctx.func.name = UserFuncName::user(0, func_id.as_u32());
+    let mut func_ctx = FunctionBuilderContext::new();
+    {
+        let mut bcx: FunctionBuilder = FunctionBuilder::new(&mut ctx.func, &mut func_ctx);
+        let block = bcx.create_block();
+        bcx.switch_to_block(block);
+        let value = bcx.ins().iconst(types::I64, value);
+        bcx.ins().return_(&[value]);
+    }
+    module.define_function(func_id, &mut ctx).unwrap();
+}
+
+#[test]
+fn redefine_function() {
+    let Some(isa) = isa() else {
+        return;
+    };
+    let mut builder = JITBuilder::with_isa(isa, default_libcall_names());
+    builder.hotswap(true);
+    let mut module = JITModule::new(builder);
+
+    let mut sig = module.make_signature();
+    sig.returns.push(AbiParam::new(types::I64));
+    let callee = module
+        .declare_function("callee", Linkage::Local, &sig)
+        .unwrap();
+    let caller = module
+        .declare_function("caller", Linkage::Local, &sig)
+        .unwrap();
+
+    define_const_function(&mut module, callee, 1);
+
+    let mut ctx = module.make_context();
+    ctx.func.signature = sig.clone();
+    ctx.func.name = UserFuncName::user(0, caller.as_u32());
+    let mut func_ctx = FunctionBuilderContext::new();
+    {
+        let mut bcx: FunctionBuilder = FunctionBuilder::new(&mut ctx.func, &mut func_ctx);
+        let block = bcx.create_block();
+        bcx.switch_to_block(block);
+        let local_callee = module.declare_func_in_func(callee, &mut bcx.func);
+        let call = bcx.ins().call(local_callee, &[]);
+        let result = bcx.inst_results(call)[0];
+        bcx.ins().return_(&[result]);
+    }
+    module.define_function(caller, &mut ctx).unwrap();
+    module.finalize_definitions().unwrap();
+
+    let call_caller = unsafe {
+        std::mem::transmute::<*const u8, extern "C" fn() -> i64>(
+            module.get_finalized_function(caller),
+        )
+    };
+    let call_callee = unsafe {
+        std::mem::transmute::<*const u8, extern "C" fn() -> i64>(
+            module.get_finalized_function(callee),
+        )
+    };
+    assert_eq!(call_caller(), 1);
+    assert_eq!(call_callee(), 1);
+
+    // Defining the function again requires preparing for it first.
+    let mut ctx = module.make_context();
+    ctx.func.signature = sig.clone();
+    module.define_function(callee, &mut ctx).err().unwrap();
+
+    module.prepare_for_function_redefine(callee).unwrap();
+    define_const_function(&mut module, callee, 2);
+    module.finalize_definitions().unwrap();
+
+    // Both the existing caller and old function pointers see the new body.
+    assert_eq!(call_caller(), 2);
+    assert_eq!(call_callee(), 2);
+
+    unsafe { module.free_old_function_bodies(callee) };
+    assert_eq!(call_caller(), 2);
+
+    // A freed function can be defined again as well.
+    unsafe { module.free_function(callee) };
+    define_const_function(&mut module, callee, 3);
+    module.finalize_definitions().unwrap();
+    assert_eq!(call_caller(), 3);
+}
+
+#[test]
+fn redefine_requires_finalized_definition() {
+    let Some(isa) = isa() else {
+        return;
+    };
+    let mut builder = JITBuilder::with_isa(isa, default_libcall_names());
+    builder.hotswap(true);
+    let mut module = JITModule::new(builder);
+
+    let mut sig = module.make_signature();
+    sig.returns.push(AbiParam::new(types::I64));
+    let func_id = module
+        .declare_function("function", Linkage::Local, &sig)
+        .unwrap();
+
+    module.prepare_for_function_redefine(func_id).err().unwrap();
+    define_const_function(&mut module, func_id, 1);
+    module.prepare_for_function_redefine(func_id).err().unwrap();
+    module.finalize_definitions().unwrap();
+    module.prepare_for_function_redefine(func_id).unwrap();
+}
+
+#[test]
+fn free_data_object() {
+    let Some(isa) = isa() else {
+        return;
+    };
+    let mut module = JITModule::new(JITBuilder::with_isa(isa, default_libcall_names()));
+
+    let data_id = module
+        .declare_data("data", Linkage::Export, true, false)
+        .unwrap();
+
+    let mut data = DataDescription::new();
+    data.define(Box::new([1, 2, 3, 4]));
+    module.define_data(data_id, &data).unwrap();
+    module.finalize_definitions().unwrap();
+    let (ptr, size) = module.get_finalized_data(data_id);
+    assert_eq!(
+        unsafe { std::slice::from_raw_parts(ptr, size) },
+        [1, 2, 3, 4]
+    );
+
+    // Freeing a data object allows defining it again.
+    unsafe { module.free_data(data_id) };
+    let mut data = DataDescription::new();
+    data.define(Box::new([5, 6]));
+    module.define_data(data_id, &data).unwrap();
+    module.finalize_definitions().unwrap();
+    let (ptr, size) = module.get_finalized_data(data_id);
+    assert_eq!(unsafe { std::slice::from_raw_parts(ptr, size) }, [5, 6]);
+}
@@ -0,0 +1,613 @@
+//! DWARF debug info for functions defined in a [`Module`].
+//!
+//! A frontend creates a [`DebugContext`] next to its module, allocates source
+//! locations for its AST nodes with [`DebugContext::source_loc`] and passes
+//! them to `FunctionBuilder::set_srcloc`, and labels the values holding its
+//! variables with `FunctionBuilder::set_val_label`. After defining a function
+//! in the module it describes the function with
+//! [`DebugContext::define_function`], which reads the source locations, value
+//! label ranges and unwind info from the compiled code. Finally
+//! [`DebugContext::finish`] produces the `.debug_info`, `.debug_line`,
+//! `.debug_frame` and related sections, with relocations against the
+//! module's functions, for the backend to emit.
+
+use crate::{FuncId, HashMap, Linkage, Module, ModuleError, ModuleResult};
+use cranelift_codegen::gimli::write::{
+    Address, AttributeValue, CieId, DwarfUnit, EndianVec, Expression, FileId, FrameTable,
+    LineProgram, LineString, Location, LocationList, Range, RangeList, Sections, UnitEntryId,
+    Writer,
+};
+use cranelift_codegen::gimli::{self, Encoding, Format, LineEncoding, Register, RunTimeEndian};
+use cranelift_codegen::ir::{self, Endianness, ValueLabel};
+use cranelift_codegen::isa::TargetIsa;
+use cranelift_codegen::isa::unwind::UnwindInfo;
+use cranelift_codegen::{Context, LabelValueLoc};
+use std::string::String;
+use std::vec::Vec;
+
+/// A source file referenced by debug info.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct DebugFileId(FileId);
+
+/// A type described in debug info.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct DebugTypeId(UnitEntryId);
+
+/// A position in the source code of the frontend's language.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SourceLocation {
+    /// The file containing the position.
+    pub file: DebugFileId,
+    /// The 1-based line number, or 0 if unknown.
+    pub line: u64,
+    /// The 1-based column number, or 0 if unknown.
+    pub column: u64,
+}
+
+/// How the value of a base type is encoded.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BaseTypeEncoding {
+    /// A boolean.
+    Boolean,
+    /// A signed integer.
+    Signed,
+    /// An unsigned integer.
+    Unsigned,
+    /// A signed character.
+    SignedChar,
+    /// An unsigned character.
+    UnsignedChar,
+    /// An IEEE 754 floating point number.
+    Float,
+    /// A machine address.
+    Address,
+}
+
+/// A field of a struct type.
+#[derive(Clone, Debug)]
+pub struct StructField {
+    /// The name of the field.
+    pub name: String,
+    /// The type of the field.
+    pub ty: DebugTypeId,
+    /// The offset of the field from the start of the struct in bytes.
+    pub offset: u64,
+}
+
+/// A variable or parameter of a function.
+#[derive(Clone, Debug)]
+pub struct VariableDebugInfo {
+    /// The name of the variable.
+    pub name: String,
+    /// The type of the variable.
+    pub ty: DebugTypeId,
+    /// The label of the values holding the variable, as passed to
+    /// `FunctionBuilder::set_val_label`.
+    pub label: ValueLabel,
+    /// Whether this is a parameter of the function.
+    pub is_parameter: bool,
+    /// Where the variable is declared.
+    pub location: Option<SourceLocation>,
+}
+
+/// Debug info of a function, beyond what is recorded in its compiled code.
+#[derive(Clone, Debug)]
+pub struct FunctionDebugInfo {
+    /// The name of the function in the source language.
+    pub name: String,
+    /// Where the function is declared.
+    pub location: Option<SourceLocation>,
+    /// The return type, or `None` if the function doesn't return a value.
+    pub return_type: Option<DebugTypeId>,
+    /// The variables and parameters of the function.
+    pub variables: Vec<VariableDebugInfo>,
+}
+
+/// A DWARF section produced by [`DebugContext::finish`].
+#[derive(Clone, Debug)]
+pub struct DebugSection {
+    /// The name of the section, e.g. `.debug_info`.
+    pub name: &'static str,
+    /// The contents of the section.
+    pub data: Vec<u8>,
+    /// Relocations to apply to the contents.
+    pub relocs: Vec<DebugReloc>,
+}
+
+/// An absolute relocation in a [`DebugSection`].
+#[derive(Clone, Debug)]
+pub struct DebugReloc {
+    /// The offset of the relocated value in the section.
+    pub offset: u32,
+    /// The size of the relocated value in bytes.
+    pub size: u8,
+    /// What the value refers to.
+    pub target: DebugRelocTarget,
+    /// The value to add to the address of the target.
+    pub addend: i64,
+}
+
+/// The target of a [`DebugReloc`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DebugRelocTarget {
+    /// The start of a function.
+    Func(FuncId),
+    /// The start of another DWARF section, given by its name.
+    Section(&'static str),
+}
+
+/// Collects the debug info of a module's functions into DWARF sections.
+pub struct DebugContext {
+    dwarf: DwarfUnit,
+    endian: RunTimeEndian,
+    frame_table: FrameTable,
+    cie_id: Option<CieId>,
+    /// Source locations handed out by `source_loc`, indexed by the bits of
+    /// the corresponding `ir::SourceLoc`.
+    locations: Vec<SourceLocation>,
+    location_ids: HashMap<SourceLocation, ir::SourceLoc>,
+    unit_ranges: Vec<Range>,
+}
+
+impl DebugContext {
+    /// Create a context for a single compilation unit.
+    ///
+    /// `producer` names the compiler, `comp_dir` is the directory relative
+    /// paths are resolved against, and `name` is the path of the primary
+    /// source file of the unit.
+    pub fn new(isa: &dyn TargetIsa, producer: &str, comp_dir: &str, name: &str) -> Self {
+        let encoding = Encoding {
+            format: Format::Dwarf32,
+            version: 4,
+            address_size: isa.pointer_bytes(),
+        };
+        let endian = match isa.endianness() {
+            Endianness::Little => RunTimeEndian::Little,
+            Endianness::Big => RunTimeEndian::Big,
+        };
+
+        let mut dwarf = DwarfUnit::new(encoding);
+        let comp_dir_id = dwarf.strings.add(comp_dir);
+        let name_id = dwarf.strings.add(name);
+        dwarf.unit.line_program = LineProgram::new(
+            encoding,
+            LineEncoding::default(),
+            LineString::StringRef(comp_dir_id),
+            None,
+            LineString::StringRef(name_id),
+            None,
+        );
+
+        let root = dwarf.unit.root();
+        let producer_id = dwarf.strings.add(producer);
+        let root = dwarf.unit.get_mut(root);
+        root.set(
+            gimli::DW_AT_producer,
+            AttributeValue::StringRef(producer_id),
+        );
+        root.set(gimli::DW_AT_name, AttributeValue::StringRef(name_id));
+        root.set(
+            gimli::DW_AT_comp_dir,
+            AttributeValue::StringRef(comp_dir_id),
+        );
+        root.set(gimli::DW_AT_stmt_list, AttributeValue::LineProgramRef);
+
+        Self {
+            dwarf,
+            endian,
+            frame_table: FrameTable::default(),
+            cie_id: None,
+            locations: Vec::new(),
+            location_ids: HashMap::new(),
+            unit_ranges: Vec::new(),
+        }
+    }
+
+    /// Set the source language of the unit, e.g. `gimli::DW_LANG_C11`.
+    pub fn set_language(&mut self, language: gimli::DwLang) {
+        let root = self.dwarf.unit.root();
+        self.dwarf
+            .unit
+            .get_mut(root)
+            .set(gimli::DW_AT_language, AttributeValue::Language(language));
+    }
+
+    /// Add a source file. Relative paths are relative to the compilation
+    /// directory.
+    pub fn add_file(&mut self, path: &str) -> DebugFileId {
+        let line_program = &mut self.dwarf.unit.line_program;
+        let (dir, file) = match path.rfind('/') {
+            Some(i) => (
+                line_program.add_directory(LineString::String(path[..i].into())),
+                &path[i + 1..],
+            ),
+            None => (line_program.default_directory(), path),
+        };
+        DebugFileId(line_program.add_file(LineString::String(file.into()), dir, None))
+    }
+
+    /// Get the `ir::SourceLoc` to attach to instructions generated for
+    /// `location`.
+    pub fn source_loc(&mut self, location: SourceLocation) -> ir::SourceLoc {
+        *self.location_ids.entry(location).or_insert_with(|| {
+            let srcloc = ir::SourceLoc::new(u32::try_from(self.locations.len()).unwrap());
+            assert!(!srcloc.is_default(), "too many source locations");
+            self.locations.push(location);
+            srcloc
+        })
+    }
+
+    /// Describe a base type such as an integer or a float.
+    pub fn add_base_type(
+        &mut self,
+        name: &str,
+        encoding: BaseTypeEncoding,
+        byte_size: u8,
+    ) -> DebugTypeId {
+        let encoding = match encoding {
+            BaseTypeEncoding::Boolean => gimli::DW_ATE_boolean,
+            BaseTypeEncoding::Signed => gimli::DW_ATE_signed,
+            BaseTypeEncoding::Unsigned => gimli::DW_ATE_unsigned,
+            BaseTypeEncoding::SignedChar => gimli::DW_ATE_signed_char,
+            BaseTypeEncoding::UnsignedChar => gimli::DW_ATE_unsigned_char,
+            BaseTypeEncoding::Float => gimli::DW_ATE_float,
+            BaseTypeEncoding::Address => gimli::DW_ATE_address,
+        };
+        let id = self.add_type_entry(gimli::DW_TAG_base_type, Some(name));
+        let entry = self.dwarf.unit.get_mut(id);
+        entry.set(gimli::DW_AT_encoding, AttributeValue::Encoding(encoding));
+        entry.set(gimli::DW_AT_byte_size, AttributeValue::Data1(byte_size));
+        DebugTypeId(id)
+    }
+
+    /// Describe a pointer to `pointee`, or an untyped pointer if `None`.
+    pub fn add_pointer_type(&mut self, pointee: Option<DebugTypeId>) -> DebugTypeId {
+        let byte_size = self.dwarf.unit.encoding().address_size;
+        let id = self.add_type_entry(gimli::DW_TAG_pointer_type, None);
+        let entry = self.dwarf.unit.get_mut(id);
+        entry.set(gimli::DW_AT_byte_size, AttributeValue::Data1(byte_size));
+        if let Some(pointee) = pointee {
+            entry.set(gimli::DW_AT_type, AttributeValue::UnitRef(pointee.0));
+        }
+        DebugTypeId(id)
+    }
+
+    /// Describe a struct type with the given fields.
+    pub fn add_struct_type(
+        &mut self,
+        name: &str,
+        byte_size: u64,
+        fields: &[StructField],
+    ) -> DebugTypeId {
+        let id = self.add_type_entry(gimli::DW_TAG_structure_type, Some(name));
+        self.dwarf
+            .unit
+            .get_mut(id)
+            .set(gimli::DW_AT_byte_size, AttributeValue::Udata(byte_size));
+        for field in fields {
+            let name = self.dwarf.strings.add(field.name.as_str());
+            let field_id = self.dwarf.unit.add(id, gimli::DW_TAG_member);
+            let entry = self.dwarf.unit.get_mut(field_id);
+            entry.set(gimli::DW_AT_name, AttributeValue::StringRef(name));
+            entry.set(gimli::DW_AT_type, AttributeValue::UnitRef(field.ty.0));
+            entry.set(
+                gimli::DW_AT_data_member_location,
+                AttributeValue::Udata(field.offset),
+            );
+        }
+        DebugTypeId(id)
+    }
+
+    fn add_type_entry(&mut self, tag: gimli::DwTag, name: Option<&str>) -> UnitEntryId {
+        let root = self.dwarf.unit.root();
+        let id = self.dwarf.unit.add(root, tag);
+        if let Some(name) = name {
+            let name = self.dwarf.strings.add(name);
+            self.dwarf
+                .unit
+                .get_mut(id)
+                .set(gimli::DW_AT_name, AttributeValue::StringRef(name));
+        }
+        id
+    }
+
+    /// Describe a function which has just been defined in `module` from
+    /// `ctx`.
+    ///
+    /// This must be called before `ctx` is cleared or reused. Variables are
+    /// only given locations if `ctx.func.dfg.collect_debug_info()` was called
+    /// before building the function.
+    pub fn define_function<M: Module + ?Sized>(
+        &mut self,
+        module: &M,
+        func_id: FuncId,
+        ctx: &Context,
+        info: &FunctionDebugInfo,
+    ) -> ModuleResult<()> {
+        let isa = module.isa();
+        let compiled = ctx.compiled_code().ok_or_else(|| {
+            ModuleError::Backend(anyhow::anyhow!(
+                "function {func_id} must be compiled before describing it"
+            ))
+        })?;
+        let code_len = compiled.code_buffer().len() as u64;
+
+        // Functions are referred to by their `FuncId` in `Address::Symbol`.
+        let symbol = func_id.as_u32() as usize;
+        let low_pc = Address::Symbol { symbol, addend: 0 };
+        self.unit_ranges.push(Range::StartLength {
+            begin: low_pc,
+            length: code_len,
+        });
+
+        self.add_line_rows(symbol, compiled.buffer.get_srclocs_sorted(), code_len);
+
+        if let Some(UnwindInfo::SystemV(unwind)) = compiled.create_unwind_info(isa)? {
+            if self.cie_id.is_none() {
+                self.cie_id = isa
+                    .create_systemv_cie()
+                    .map(|cie| self.frame_table.add_cie(cie));
+            }
+            if let Some(cie_id) = self.cie_id {
+                self.frame_table.add_fde(cie_id, unwind.to_fde(low_pc));
+            }
+        }
+
+        let decl = module.declarations().get_function_decl(func_id);
+        let linkage_name = decl.linkage_name(func_id);
+        let external = matches!(decl.linkage, Linkage::Export | Linkage::Preemptible);
+
+        let root = self.dwarf.unit.root();
+        let die_id = self.dwarf.unit.add(root, gimli::DW_TAG_subprogram);
+        let name = self.dwarf.strings.add(info.name.as_str());
+        let linkage_name = (linkage_name != info.name.as_str())
+            .then(|| self.dwarf.strings.add(linkage_name.as_bytes()));
+        let die = self.dwarf.unit.get_mut(die_id);
+        die.set(gimli::DW_AT_name, AttributeValue::StringRef(name));
+        if let Some(linkage_name) = linkage_name {
+            die.set(
+                gimli::DW_AT_linkage_name,
+                AttributeValue::StringRef(linkage_name),
+            );
+        }
+        if external {
+            die.set(gimli::DW_AT_external, AttributeValue::Flag(true));
+        }
+        die.set(gimli::DW_AT_low_pc, AttributeValue::Address(low_pc));
+        die.set(gimli::DW_AT_high_pc, AttributeValue::Udata(code_len));
+        let mut frame_base = Expression::new();
+        frame_base.op(gimli::DW_OP_call_frame_cfa);
+        die.set(gimli::DW_AT_frame_base, AttributeValue::Exprloc(frame_base));
+        if let Some(ty) = info.return_type {
+            die.set(gimli::DW_AT_type, AttributeValue::UnitRef(ty.0));
+        }
+        self.set_decl_location(die_id, info.location);
+
+        for var in &info.variables {
+            let tag = if var.is_parameter {
+                gimli::DW_TAG_formal_parameter
+            } else {
+                gimli::DW_TAG_variable
+            };
+            let var_id = self.dwarf.unit.add(die_id, tag);
+            let name = self.dwarf.strings.add(var.name.as_str());
+            let entry = self.dwarf.unit.get_mut(var_id);
+            entry.set(gimli::DW_AT_name, AttributeValue::StringRef(name));
+            entry.set(gimli::DW_AT_type, AttributeValue::UnitRef(var.ty.0));
+            self.set_decl_location(var_id, var.location);
+
+            let Some(ranges) = compiled.value_labels_ranges.get(&var.label) else {
+                // The variable is optimized out.
+                continue;
+            };
+            let mut locations = Vec::new();
+            for range in ranges {
+                let mut expr = Expression::new();
+                match range.loc {
+                    LabelValueLoc::Reg(reg) => {
+                        let reg = isa.map_regalloc_reg_to_dwarf(reg).map_err(|e| {
+                            ModuleError::Backend(anyhow::anyhow!(
+                                "no DWARF register for {reg:?}: {e}"
+                            ))
+                        })?;
+                        expr.op_reg(Register(reg));
+                    }
+                    LabelValueLoc::CFAOffset(offset) => expr.op_fbreg(offset),
+                }
+                locations.push(Location::StartEnd {
+                    begin: Address::Symbol {
+                        symbol,
+                        addend: i64::from(range.start),
+                    },
+                    end: Address::Symbol {
+                        symbol,
+                        addend: i64::from(range.end),
+                    },
+                    data: expr,
+                });
+            }
+            let locations = self.dwarf.unit.locations.add(LocationList(locations));
+            self.dwarf.unit.get_mut(var_id).set(
+                gimli::DW_AT_location,
+                AttributeValue::LocationListRef(locations),
+            );
+        }
+
+        Ok(())
+    }
+
+    fn set_decl_location(&mut self, id: UnitEntryId, location: Option<SourceLocation>) {
+        let Some(location) = location else {
+            return;
+        };
+        let entry = self.dwarf.unit.get_mut(id);
+        entry.set(
+            gimli::DW_AT_decl_file,
+            AttributeValue::FileIndex(Some(location.file.0)),
+        );
+        entry.set(gimli::DW_AT_decl_line, AttributeValue::Udata(location.line));
+        if location.column != 0 {
+            entry.set(
+                gimli::DW_AT_decl_column,
+                AttributeValue::Udata(location.column),
+            );
+        }
+    }
+
+    /// Add a line program sequence for the function with the given symbol
+    /// number. Code without a source location is attributed to line 0.
+    fn add_line_rows(
+        &mut self,
+        symbol: usize,
+        srclocs: &[cranelift_codegen::MachSrcLoc<cranelift_codegen::Final>],
+        code_len: u64,
+    ) {
+        let line_program = &mut self.dwarf.unit.line_program;
+        line_program.begin_sequence(Some(Address::Symbol { symbol, addend: 0 }));
+
+        let mut end = 0;
+        for srcloc in srclocs {
+            let Some(location) = self.locations.get(srcloc.loc.bits() as usize) else {
+                continue;
+            };
+            if srcloc.start > end {
+                line_program.row().address_offset = u64::from(end);
+                line_program.row().line = 0;
+                line_program.row().column = 0;
+                line_program.generate_row();
+            }
+            line_program.row().address_offset = u64::from(srcloc.start);
+            line_program.row().file = location.file.0;
+            line_program.row().line = location.line;
+            line_program.row().column = location.column;
+            line_program.row().is_statement = true;
+            line_program.generate_row();
+            end = srcloc.end;
+        }
+
+        line_program.end_sequence(code_len);
+    }
+
+    /// Produce the DWARF sections for all functions described so far.
+    ///
+    /// Empty sections are left out.
+    pub fn finish(mut self) -> ModuleResult<Vec<DebugSection>> {
+        let root = self.dwarf.unit.root();
+        let ranges = self
+            .dwarf
+            .unit
+            .ranges
+            .add(RangeList(std::mem::take(&mut self.unit_ranges)));
+        let root = self.dwarf.unit.get_mut(root);
+        root.set(
+            gimli::DW_AT_low_pc,
+            AttributeValue::Address(Address::Constant(0)),
+        );
+        root.set(gimli::DW_AT_ranges, AttributeValue::RangeListRef(ranges));
+
+        let write_err = |e: gimli::write::Error| {
+            ModuleError::Backend(anyhow::anyhow!("failed to write DWARF: {e}"))
+        };
+        let mut sections = Sections::new(RelocWriter {
+            writer: EndianVec::new(self.endian),
+            relocs: Vec::new(),
+        });
+        self.dwarf.write(&mut sections).map_err(write_err)?;
+        if self.cie_id.is_some() {
+            self.frame_table
+                .write_debug_frame(&mut sections.debug_frame)
+                .map_err(write_err)?;
+        }
+
+        let mut result = Vec::new();
+        sections
+            .for_each_mut(|id, section| -> gimli::write::Result<()> {
+                let data = section.writer.take();
+                if data.is_empty() {
+                    return Ok(());
+                }
+                result.push(DebugSection {
+                    name: id.name(),
+                    data,
+                    relocs: std::mem::take(&mut section.relocs),
+                });
+                Ok(())
+            })
+            .map_err(write_err)?;
+        Ok(result)
+    }
+}
+
+/// A gimli writer which records relocations for addresses and offsets into
+/// other sections.
+#[derive(Clone)]
+struct RelocWriter {
+    writer: EndianVec<RunTimeEndian>,
+    relocs: Vec<DebugReloc>,
+}
+
+impl Writer for RelocWriter {
+    type Endian = RunTimeEndian;
+
+    fn endian(&self) -> Self::Endian {
+        self.writer.endian()
+    }
+
+    fn len(&self) -> usize {
+        self.writer.len()
+    }
+
+    fn write(&mut self, bytes: &[u8]) -> gimli::write::Result<()> {
+        self.writer.write(bytes)
+    }
+
+    fn write_at(&mut self, offset: usize, bytes: &[u8]) -> gimli::write::Result<()> {
+        self.writer.write_at(offset, bytes)
+    }
+
+    fn write_address(&mut self, address: Address, size: u8) -> gimli::write::Result<()> {
+        match address {
+            Address::Constant(val) => self.write_udata(val, size),
+            Address::Symbol { symbol, addend } => {
+                self.relocs.push(DebugReloc {
+                    offset: self.len() as u32,
+                    size,
+                    target: DebugRelocTarget::Func(FuncId::from_u32(symbol as u32)),
+                    addend,
+                });
+                self.write_udata(addend as u64, size)
+            }
+        }
+    }
+
+    fn write_offset(
+        &mut self,
+        val: usize,
+        section: gimli::SectionId,
+        size: u8,
+    ) -> gimli::write::Result<()> {
+        self.relocs.push(DebugReloc {
+            offset: self.len() as u32,
+            size,
+            target: DebugRelocTarget::Section(section.name()),
+            addend: val as i64,
+        });
+        self.write_udata(val as u64, size)
+    }
+
+    fn write_offset_at(
+        &mut self,
+        offset: usize,
+        val: usize,
+        section: gimli::SectionId,
+        size: u8,
+    ) -> gimli::write::Result<()> {
+        self.relocs.push(DebugReloc {
+            offset: offset as u32,
+            size,
+            target: DebugRelocTarget::Section(section.name()),
+            addend: val as i64,
+        });
+        self.write_udata_at(offset, val as u64, size)
+    }
+}
@@ -21,10 +21,17 @@ use std::string::String;
 use cranelift_codegen::ir;
 
 mod data_context;
+#[cfg(feature = "debug")]
+mod debug;
 mod module;
 mod traps;
 
 pub use crate::data_context::{DataDescription, Init};
+#[cfg(feature = "debug")]
+pub use crate::debug::{
+    BaseTypeEncoding, DebugContext, DebugFileId, DebugReloc, DebugRelocTarget, DebugSection,
+    DebugTypeId, FunctionDebugInfo, SourceLocation, StructField, VariableDebugInfo,
+};
 pub use crate::module::{
     DataDeclaration, DataId, FuncId, FuncOrDataId, FunctionDeclaration, Linkage, Module,
     ModuleDeclarations, ModuleError, ModuleReloc, ModuleRelocTarget, ModuleResult,
@@ -674,6 +674,21 @@ impl ObjectModule {
         Ok(())
     }
 
+    /// Add the DWARF sections produced by a [`DebugContext`] describing the
+    /// functions of this module to the object.
+    ///
+    /// This is supported for ELF and COFF objects.
+    ///
+    /// [`DebugContext`]: cranelift_module::DebugContext
+    #[cfg(feature = "debug")]
+    pub fn add_debug_sections(
+        &mut self,
+        sections: &[cranelift_module::DebugSection],
+    ) -> ModuleResult<()> {
+        crate::debug::add_debug_sections(&mut self.object, &self.functions, sections)
+            .map_err(ModuleError::Backend)
+    }
+
     /// Finalize all relocations and output an object.
     pub fn finish(mut self) -> ObjectProduct {
         if cfg!(debug_assertions) {
@@ -899,6 +914,26 @@ impl ObjectModule {
                     r_type: object::elf::R_X86_64_TLSGD,
                 }
             }
+            Reloc::ElfX86_64GotTpOff => {
+                assert_eq!(
+                    self.object.format(),
+                    object::BinaryFormat::Elf,
+                    "ElfX86_64GotTpOff is not supported for this file format"
+                );
+                RelocationFlags::Elf {
+                    r_type: object::elf::R_X86_64_GOTTPOFF,
+                }
+            }
+            Reloc::ElfX86_64TpOff32 => {
+                assert_eq!(
+                    self.object.format(),
+                    object::BinaryFormat::Elf,
+                    "ElfX86_64TpOff32 is not supported for this file format"
+                );
+                RelocationFlags::Elf {
+                    r_type: object::elf::R_X86_64_TPOFF32,
+                }
+            }
             Reloc::MachOX86_64Tlv => {
                 assert_eq!(
                     self.object.format(),
@@ -975,6 +1010,46 @@ impl ObjectModule {
                     r_type: object::elf::R_AARCH64_TLSDESC_CALL,
                 }
             }
+            Reloc::Aarch64TlsIeAdrGotTpRelPage21 => {
+                assert_eq!(
+                    self.object.format(),
+                    object::BinaryFormat::Elf,
+                    "Aarch64TlsIeAdrGotTpRelPage21 is not supported for this file format"
+                );
+                RelocationFlags::Elf {
+                    r_type: object::elf::R_AARCH64_TLSIE_ADR_GOTTPREL_PAGE21,
+                }
+            }
+            Reloc::Aarch64TlsIeLd64GotTpRelLo12Nc => {
+                assert_eq!(
+                    self.object.format(),
+                    object::BinaryFormat::Elf,
+                    "Aarch64TlsIeLd64GotTpRelLo12Nc is not supported for this file format"
+                );
+                RelocationFlags::Elf {
+                    r_type: object::elf::R_AARCH64_TLSIE_LD64_GOTTPREL_LO12_NC,
+                }
+            }
+            Reloc::Aarch64TlsLeAddTpRelHi12 => {
+                assert_eq!(
+                    self.object.format(),
+                    object::BinaryFormat::Elf,
+                    "Aarch64TlsLeAddTpRelHi12 is not supported for this file format"
+                );
+                RelocationFlags::Elf {
+                    r_type: object::elf::R_AARCH64_TLSLE_ADD_TPREL_HI12,
+                }
+            }
+            Reloc::Aarch64TlsLeAddTpRelLo12Nc => {
+                assert_eq!(
+                    self.object.format(),
+                    object::BinaryFormat::Elf,
+                    "Aarch64TlsLeAddTpRelLo12Nc is not supported for this file format"
+                );
+                RelocationFlags::Elf {
+                    r_type: object::elf::R_AARCH64_TLSLE_ADD_TPREL_LO12_NC,
+                }
+            }
 
             Reloc::Aarch64AdrGotPage21 => match self.object.format() {
                 object::BinaryFormat::Elf => RelocationFlags::Elf {
@@ -1070,6 +1145,46 @@ impl ObjectModule {
                     r_type: object::elf::R_RISCV_TLS_GD_HI20,
                 }
             }
+            Reloc::RiscvTlsIeHi20 => {
+                assert_eq!(
+                    self.object.format(),
+                    object::BinaryFormat::Elf,
+                    "RiscvTlsIeHi20 is not supported for this file format"
+                );
+                RelocationFlags::Elf {
+                    r_type: object::elf::R_RISCV_TLS_GOT_HI20,
+                }
+            }
+            Reloc::RiscvTpRelHi20 => {
+                assert_eq!(
+                    self.object.format(),
+                    object::BinaryFormat::Elf,
+                    "RiscvTpRelHi20 is not supported for this file format"
+                );
+                RelocationFlags::Elf {
+                    r_type: object::elf::R_RISCV_TPREL_HI20,
+                }
+            }
+            Reloc::RiscvTpRelAdd => {
+                assert_eq!(
+                    self.object.format(),
+                    object::BinaryFormat::Elf,
+                    "RiscvTpRelAdd is not supported for this file format"
+                );
+                RelocationFlags::Elf {
+                    r_type: object::elf::R_RISCV_TPREL_ADD,
+                }
+            }
+            Reloc::RiscvTpRelLo12I => {
+                assert_eq!(
+                    self.object.format(),
+                    object::BinaryFormat::Elf,
+                    "RiscvTpRelLo12I is not supported for this file format"
+                );
+                RelocationFlags::Elf {
+                    r_type: object::elf::R_RISCV_TPREL_LO12_I,
+                }
+            }
             Reloc::RiscvPCRelLo12I => {
                 assert_eq!(
                     self.object.format(),
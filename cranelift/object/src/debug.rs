@@ -0,0 +1,80 @@
+//! DWARF debug section emission for [`ObjectModule`].
+//!
+//! The sections themselves are produced by `cranelift_module::DebugContext`;
+//! this module adds them to the object and translates their relocations into
+//! relocations against function and section symbols.
+//!
+//! [`ObjectModule`]: crate::ObjectModule
+
+use anyhow::{Result, anyhow};
+use cranelift_codegen::entity::SecondaryMap;
+use cranelift_module::{DebugRelocTarget, DebugSection, FuncId};
+use object::write::{Object, Relocation, StandardSegment, SymbolId};
+use object::{BinaryFormat, RelocationEncoding, RelocationFlags, RelocationKind, SectionKind};
+use std::collections::HashMap;
+
+pub(crate) fn add_debug_sections(
+    object: &mut Object<'static>,
+    functions: &SecondaryMap<FuncId, Option<(SymbolId, bool)>>,
+    sections: &[DebugSection],
+) -> Result<()> {
+    let format = object.format();
+    if format == BinaryFormat::MachO {
+        // Mach-O keeps DWARF in `__DWARF` sections without relocations
+        // between them, which is out of scope here.
+        return Err(anyhow!("Mach-O debug info emission is not yet supported"));
+    }
+
+    let mut section_ids = HashMap::new();
+    for section in sections {
+        let segment = object.segment_name(StandardSegment::Debug).to_vec();
+        let id = object.add_section(
+            segment,
+            section.name.as_bytes().to_vec(),
+            SectionKind::Debug,
+        );
+        object.append_section_data(id, &section.data, 1);
+        section_ids.insert(section.name, id);
+    }
+
+    for section in sections {
+        let section_id = section_ids[section.name];
+        for reloc in &section.relocs {
+            let (symbol, kind) = match reloc.target {
+                DebugRelocTarget::Func(func_id) => {
+                    let (symbol, _) = functions[func_id]
+                        .ok_or_else(|| anyhow!("debug info refers to undeclared {func_id}"))?;
+                    (symbol, RelocationKind::Absolute)
+                }
+                DebugRelocTarget::Section(name) => {
+                    let target = *section_ids
+                        .get(name)
+                        .ok_or_else(|| anyhow!("debug info refers to missing section {name}"))?;
+                    // COFF expresses offsets into other sections with
+                    // section-relative relocations.
+                    let kind = if format == BinaryFormat::Coff {
+                        RelocationKind::SectionOffset
+                    } else {
+                        RelocationKind::Absolute
+                    };
+                    (object.section_symbol(target), kind)
+                }
+            };
+            object.add_relocation(
+                section_id,
+                Relocation {
+                    offset: u64::from(reloc.offset),
+                    symbol,
+                    addend: reloc.addend,
+                    flags: RelocationFlags::Generic {
+                        kind,
+                        encoding: RelocationEncoding::Generic,
+                        size: reloc.size * 8,
+                    },
+                },
+            )?;
+        }
+    }
+
+    Ok(())
+}
@@ -5,6 +5,8 @@
 #![deny(missing_docs)]
 
 mod backend;
+#[cfg(feature = "debug")]
+mod debug;
 #[cfg(feature = "unwind")]
 mod unwind;
 
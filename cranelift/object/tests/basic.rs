@@ -563,3 +563,158 @@ mod eh_frame {
         );
     }
 }
+
+// ---------- DWARF debug info emission tests ----------
+
+#[cfg(feature = "debug")]
+mod debug_info {
+    use super::*;
+    use cranelift_codegen::gimli;
+    use object::Object as _;
+    use object::ObjectSection as _;
+
+    /// Define `add1(x) = x + 1` with the body at line 3 of `add.src`, and
+    /// describe it in `debug`.
+    fn define_add1(module: &mut ObjectModule, debug: &mut DebugContext) -> FuncId {
+        let file = debug.add_file("src/add.src");
+        let decl_loc = SourceLocation {
+            file,
+            line: 2,
+            column: 1,
+        };
+        let body_loc = debug.source_loc(SourceLocation {
+            file,
+            line: 3,
+            column: 5,
+        });
+        let int = debug.add_base_type("int", BaseTypeEncoding::Signed, 8);
+
+        let sig = Signature {
+            params: vec![AbiParam::new(types::I64)],
+            returns: vec![AbiParam::new(types::I64)],
+            call_conv: module.isa().default_call_conv(),
+        };
+        let func_id = module
+            .declare_function("add1", Linkage::Export, &sig)
+            .unwrap();
+        let mut ctx = Context::new();
+        ctx.func = Function::with_name_signature(UserFuncName::user(0, func_id.as_u32()), sig);
+        ctx.func.dfg.collect_debug_info();
+        let mut func_ctx = FunctionBuilderContext::new();
+        {
+            let mut bcx = FunctionBuilder::new(&mut ctx.func, &mut func_ctx);
+            let block = bcx.create_block();
+            bcx.append_block_params_for_function_params(block);
+            bcx.switch_to_block(block);
+            bcx.set_srcloc(body_loc);
+            let x = bcx.block_params(block)[0];
+            bcx.set_val_label(x, ValueLabel::new(0));
+            let result = bcx.ins().iadd_imm(x, 1);
+            bcx.ins().return_(&[result]);
+            bcx.seal_all_blocks();
+            bcx.finalize(module.target_config());
+        }
+        module.define_function(func_id, &mut ctx).unwrap();
+
+        debug
+            .define_function(
+                &*module,
+                func_id,
+                &ctx,
+                &FunctionDebugInfo {
+                    name: "add1".to_string(),
+                    location: Some(decl_loc),
+                    return_type: Some(int),
+                    variables: vec![VariableDebugInfo {
+                        name: "x".to_string(),
+                        ty: int,
+                        label: ValueLabel::new(0),
+                        is_parameter: true,
+                        location: Some(decl_loc),
+                    }],
+                },
+            )
+            .unwrap();
+        func_id
+    }
+
+    fn module_for(triple: &str) -> ObjectModule {
+        let isa = cranelift_codegen::isa::lookup_by_name(triple)
+            .unwrap()
+            .finish(settings::Flags::new(settings::builder()))
+            .unwrap();
+        ObjectModule::new(ObjectBuilder::new(isa, "test", default_libcall_names()).unwrap())
+    }
+
+    #[test]
+    fn emits_debug_sections() {
+        let mut module = module_for("x86_64-unknown-linux-gnu");
+        let mut debug = DebugContext::new(module.isa(), "test", "/tmp", "add.src");
+        define_add1(&mut module, &mut debug);
+        let sections = debug.finish().unwrap();
+        module.add_debug_sections(&sections).unwrap();
+
+        let bytes = module.finish().emit().expect("emit object file");
+        let file = object::File::parse(&*bytes).expect("parse emitted object");
+        for name in [
+            ".debug_info",
+            ".debug_abbrev",
+            ".debug_line",
+            ".debug_frame",
+        ] {
+            let section = file
+                .section_by_name(name)
+                .unwrap_or_else(|| panic!("{name} section is present"));
+            assert!(!section.data().unwrap().is_empty(), "{name} is empty");
+        }
+
+        let strings = file.section_by_name(".debug_str").unwrap();
+        let strings = strings.data().unwrap();
+        for name in [&b"add1\0"[..], b"x\0", b"int\0"] {
+            assert!(
+                strings.windows(name.len()).any(|s| s == name),
+                "{:?} is missing from .debug_str",
+                std::str::from_utf8(name).unwrap()
+            );
+        }
+
+        // The code address of the function is relocated.
+        let info = file.section_by_name(".debug_info").unwrap();
+        assert!(info.relocations().count() > 0);
+    }
+
+    #[test]
+    fn line_program_has_source_lines() {
+        let mut module = module_for("x86_64-unknown-linux-gnu");
+        let mut debug = DebugContext::new(module.isa(), "test", "/tmp", "add.src");
+        define_add1(&mut module, &mut debug);
+        let sections = debug.finish().unwrap();
+
+        let section = |id: gimli::SectionId| {
+            sections
+                .iter()
+                .find(|s| s.name == id.name())
+                .map(|s| &s.data[..])
+                .unwrap_or(&[])
+        };
+        let line = gimli::DebugLine::new(section(gimli::SectionId::DebugLine), gimli::LittleEndian);
+        let program = line
+            .program(gimli::DebugLineOffset(0), 8, None, None)
+            .unwrap();
+        let mut rows = program.rows();
+        let mut lines = vec![];
+        while let Some((_, row)) = rows.next_row().unwrap() {
+            lines.push(row.line().map_or(0, |l| l.get()));
+        }
+        assert!(lines.contains(&3), "no row for line 3 in {lines:?}");
+    }
+
+    #[test]
+    fn mach_o_is_rejected() {
+        let mut module = module_for("aarch64-apple-darwin");
+        let mut debug = DebugContext::new(module.isa(), "test", "/tmp", "add.src");
+        define_add1(&mut module, &mut debug);
+        let sections = debug.finish().unwrap();
+        module.add_debug_sections(&sections).unwrap_err();
+    }
+}
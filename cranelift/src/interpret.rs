@@ -127,6 +127,7 @@ impl FileInterpreter {
                     match Interpreter::new(state).call_by_name(func_name, args) {
                         Ok(ControlFlow::Return(results)) => Ok(results.to_vec()),
                         Ok(ControlFlow::Trap(trap)) => Err(trap.to_string()),
+                        Ok(ControlFlow::Throw(tag, _)) => Err(format!("uncaught exception: {tag}")),
                         Ok(_) => panic!("Unexpected returned control flow--this is likely a bug."),
                         Err(t) => Err(t.to_string()),
                     }
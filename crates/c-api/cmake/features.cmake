@@ -45,6 +45,7 @@ feature(async ON)
 feature(cranelift ON)
 feature(winch ON)
 feature(debug-builtins ON)
+feature(debug ON)
 feature(pooling-allocator ON)
 feature(component-model ON)
 feature(component-model-async ON)
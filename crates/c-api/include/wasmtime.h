@@ -201,6 +201,8 @@
 #include <wasmtime/async.h>
 #include <wasmtime/component.h>
 #include <wasmtime/config.h>
+#include <wasmtime/coredump.h>
+#include <wasmtime/debug.h>
 #include <wasmtime/engine.h>
 #include <wasmtime/eqref.h>
 #include <wasmtime/error.h>
@@ -2,6 +2,7 @@
 #define WASMTIME_COMPONENT_H
 
 #include <wasmtime/component/component.h>
+#include <wasmtime/component/concurrent.h>
 #include <wasmtime/component/func.h>
 #include <wasmtime/component/instance.h>
 #include <wasmtime/component/linker.h>
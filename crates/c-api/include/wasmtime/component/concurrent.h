@@ -0,0 +1,294 @@
+/// \file wasmtime/component/concurrent.h
+
+#ifndef WASMTIME_COMPONENT_CONCURRENT_H
+#define WASMTIME_COMPONENT_CONCURRENT_H
+
+#include <wasmtime/conf.h>
+
+#ifdef WASMTIME_FEATURE_COMPONENT_MODEL_ASYNC
+
+#include <stdint.h>
+#include <wasm.h>
+#include <wasmtime/component/val.h>
+#include <wasmtime/error.h>
+#include <wasmtime/store.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/// \brief Represents the readable end of a component model `future<T>`.
+///
+/// Values of this type are produced by component functions returning a
+/// `future` and may be passed to component functions taking a `future`. They
+/// may be of any payload type and are type-checked when they're passed to a
+/// component. Futures created or read by the host with
+/// `wasmtime_component_future_new` and `wasmtime_component_future_read` are
+/// limited to payloads of a primitive type or `string`, whose values are
+/// represented as #wasmtime_component_val_t, and futures without a payload.
+///
+/// This handle is owned by the store it came from. The in-store future must
+/// be disposed of either by passing it to a component, reading it with
+/// `wasmtime_component_future_read`, or closing it with
+/// `wasmtime_component_future_close`. Separately the host-side memory for
+/// this handle must be deallocated with `wasmtime_component_future_delete`.
+typedef struct wasmtime_component_future wasmtime_component_future_t;
+
+/// \brief The host-owned writable end of a future created with
+/// `wasmtime_component_future_new`.
+typedef struct wasmtime_component_future_writer
+    wasmtime_component_future_writer_t;
+
+/// \brief The host-owned reader of a future consumed with
+/// `wasmtime_component_future_read`.
+typedef struct wasmtime_component_future_reader
+    wasmtime_component_future_reader_t;
+
+/// \brief Represents the readable end of a component model `stream<T>`.
+///
+/// This is similar to #wasmtime_component_future_t except that it represents
+/// a `stream`. Streams created or read by the host with
+/// `wasmtime_component_stream_new` and `wasmtime_component_stream_read` have
+/// the same payload limitations as futures.
+typedef struct wasmtime_component_stream wasmtime_component_stream_t;
+
+/// \brief The host-owned writable end of a stream created with
+/// `wasmtime_component_stream_new`.
+typedef struct wasmtime_component_stream_writer
+    wasmtime_component_stream_writer_t;
+
+/// \brief The host-owned reader of a stream consumed with
+/// `wasmtime_component_stream_read`.
+typedef struct wasmtime_component_stream_reader
+    wasmtime_component_stream_reader_t;
+
+/// \brief Represents a component model `error-context`.
+///
+/// There are currently no operations on error contexts other than passing
+/// them between component functions.
+typedef struct wasmtime_component_error_context
+    wasmtime_component_error_context_t;
+
+/// \brief Creates a new host-owned handle to the same future as `future`.
+///
+/// Note that this does not create a new future, and the in-store future only
+/// needs to be disposed of once.
+///
+/// The returned pointer must be deallocated with
+/// `wasmtime_component_future_delete`.
+WASM_API_EXTERN wasmtime_component_future_t *
+wasmtime_component_future_clone(const wasmtime_component_future_t *future);
+
+/// \brief Closes the future in the store, signaling to its writer that the
+/// value will not be read.
+///
+/// Returns an error if the future has already been closed or was already
+/// transferred elsewhere.
+WASM_API_EXTERN wasmtime_error_t *
+wasmtime_component_future_close(wasmtime_context_t *context,
+                                wasmtime_component_future_t *future);
+
+/// \brief Deallocates the host-side memory of `future`.
+///
+/// This does not close the future in the store.
+WASM_API_EXTERN void
+wasmtime_component_future_delete(wasmtime_component_future_t *future);
+
+/// \brief Creates a new `future<T>` whose value is written by the host.
+///
+/// The payload type `T` is given by `payload`, which must point to one of
+/// #WASMTIME_COMPONENT_BOOL through #WASMTIME_COMPONENT_STRING, or which is
+/// `NULL` for a `future` without a payload.
+///
+/// On success `future_ret` is filled in with the readable end, which can be
+/// passed to a component, and `writer_ret` is filled in with the writable
+/// end. Both must be deallocated with their respective `*_delete` functions.
+///
+/// Returns an error if concurrency support is not enabled in the store's
+/// engine or if the payload kind isn't supported.
+WASM_API_EXTERN wasmtime_error_t *
+wasmtime_component_future_new(wasmtime_context_t *context,
+                              const wasmtime_component_valkind_t *payload,
+                              wasmtime_component_future_t **future_ret,
+                              wasmtime_component_future_writer_t **writer_ret);
+
+/// \brief Resolves the future created alongside `writer` with `value`.
+///
+/// The `value` must have the kind the future was created with, or be `NULL`
+/// if the future has no payload. It is copied, so ownership of `value`
+/// remains with the caller.
+///
+/// On success `closed_ret` is set to whether the reading end of the future
+/// was already closed, in which case nobody will observe the value. Returns
+/// an error if `value` doesn't match the payload type or if the future was
+/// already written to.
+WASM_API_EXTERN wasmtime_error_t *wasmtime_component_future_writer_write(
+    const wasmtime_component_future_writer_t *writer,
+    const wasmtime_component_val_t *value, bool *closed_ret);
+
+/// \brief Deallocates `writer`, closing the writable end of the future.
+///
+/// If `wasmtime_component_future_writer_write` was not called then reading
+/// the future fails with an error once it's closed, just like a component
+/// which drops the writable end of a future without writing to it.
+WASM_API_EXTERN void wasmtime_component_future_writer_delete(
+    wasmtime_component_future_writer_t *writer);
+
+/// \brief Starts reading the value of `future`.
+///
+/// The `payload` is the expected payload type of the future, in the same
+/// format as for `wasmtime_component_future_new`, and an error is returned if
+/// it doesn't match the actual payload type.
+///
+/// This takes ownership of the in-store future, so `future` must not be
+/// passed to a component or closed afterwards. On success `reader_ret` is
+/// filled in with a reader from which the value can be taken with
+/// `wasmtime_component_future_reader_read`.
+///
+/// Note that the value is transferred while the store is executing, for
+/// example during `wasmtime_component_func_call_async`.
+WASM_API_EXTERN wasmtime_error_t *
+wasmtime_component_future_read(wasmtime_context_t *context,
+                               const wasmtime_component_future_t *future,
+                               const wasmtime_component_valkind_t *payload,
+                               wasmtime_component_future_reader_t **reader_ret);
+
+/// \brief Takes the value of the future being read by `reader`, if it has
+/// resolved.
+///
+/// Returns `true` if the future resolved, in which case its value is moved
+/// into `value_ret`. The caller is then responsible for calling
+/// `wasmtime_component_val_delete` on `value_ret`. For futures without a
+/// payload `value_ret` is left untouched and may be `NULL`.
+///
+/// Returns `false` if the future hasn't resolved yet or if its value was
+/// already taken.
+WASM_API_EXTERN bool wasmtime_component_future_reader_read(
+    const wasmtime_component_future_reader_t *reader,
+    wasmtime_component_val_t *value_ret);
+
+/// \brief Deallocates `reader`, discarding the value if it wasn't taken.
+WASM_API_EXTERN void wasmtime_component_future_reader_delete(
+    wasmtime_component_future_reader_t *reader);
+
+/// \brief Creates a new host-owned handle to the same stream as `stream`.
+///
+/// See `wasmtime_component_future_clone` for more information.
+WASM_API_EXTERN wasmtime_component_stream_t *
+wasmtime_component_stream_clone(const wasmtime_component_stream_t *stream);
+
+/// \brief Closes the stream in the store, signaling to its writer that no more
+/// items will be read.
+///
+/// Returns an error if the stream has already been closed or was already
+/// transferred elsewhere.
+WASM_API_EXTERN wasmtime_error_t *
+wasmtime_component_stream_close(wasmtime_context_t *context,
+                                wasmtime_component_stream_t *stream);
+
+/// \brief Deallocates the host-side memory of `stream`.
+///
+/// This does not close the stream in the store.
+WASM_API_EXTERN void
+wasmtime_component_stream_delete(wasmtime_component_stream_t *stream);
+
+/// \brief Creates a new `stream<T>` whose items are written by the host.
+///
+/// The payload type `T` is given by `payload` in the same format as for
+/// `wasmtime_component_future_new`.
+///
+/// On success `stream_ret` is filled in with the readable end, which can be
+/// passed to a component, and `writer_ret` is filled in with the writable
+/// end. Both must be deallocated with their respective `*_delete` functions.
+///
+/// Returns an error if concurrency support is not enabled in the store's
+/// engine or if the payload kind isn't supported.
+WASM_API_EXTERN wasmtime_error_t *
+wasmtime_component_stream_new(wasmtime_context_t *context,
+                              const wasmtime_component_valkind_t *payload,
+                              wasmtime_component_stream_t **stream_ret,
+                              wasmtime_component_stream_writer_t **writer_ret);
+
+/// \brief Writes the `len` values in `items` to the stream created alongside
+/// `writer`.
+///
+/// The values must all have the kind the stream was created with. For
+/// streams without a payload `items` may be `NULL` to write `len` items.
+/// Values are copied, so ownership of `items` remains with the caller.
+///
+/// The items are buffered on the host until the reader reads them. On
+/// success `closed_ret` is set to whether the reading end of the stream was
+/// closed, in which case the items are discarded. Returns an error, without
+/// writing any items, if any of `items` doesn't match the payload type.
+WASM_API_EXTERN wasmtime_error_t *wasmtime_component_stream_writer_write(
+    const wasmtime_component_stream_writer_t *writer,
+    const wasmtime_component_val_t *items, size_t len, bool *closed_ret);
+
+/// \brief Deallocates `writer`, ending the stream.
+///
+/// The reader will still receive all items written before this call.
+WASM_API_EXTERN void wasmtime_component_stream_writer_delete(
+    wasmtime_component_stream_writer_t *writer);
+
+/// \brief Starts reading the items of `stream`.
+///
+/// The `payload` is the expected payload type of the stream, in the same
+/// format as for `wasmtime_component_future_new`, and an error is returned if
+/// it doesn't match the actual payload type.
+///
+/// This takes ownership of the in-store stream, so `stream` must not be
+/// passed to a component or closed afterwards. On success `reader_ret` is
+/// filled in with a reader from which received items can be taken with
+/// `wasmtime_component_stream_reader_read`.
+///
+/// Note that items are transferred while the store is executing, for example
+/// during `wasmtime_component_func_call_async`.
+WASM_API_EXTERN wasmtime_error_t *
+wasmtime_component_stream_read(wasmtime_context_t *context,
+                               const wasmtime_component_stream_t *stream,
+                               const wasmtime_component_valkind_t *payload,
+                               wasmtime_component_stream_reader_t **reader_ret);
+
+/// \brief Moves up to `len` received items into `items`, returning how many
+/// items were received.
+///
+/// The caller is responsible for calling `wasmtime_component_val_delete` on
+/// each of the returned items. For streams without a payload `items` is left
+/// untouched and may be `NULL`.
+///
+/// This never blocks and returns 0 if no items are currently available. Use
+/// `wasmtime_component_stream_reader_done` to distinguish this from the end
+/// of the stream.
+WASM_API_EXTERN size_t wasmtime_component_stream_reader_read(
+    const wasmtime_component_stream_reader_t *reader,
+    wasmtime_component_val_t *items, size_t len);
+
+/// \brief Returns whether the writer has ended the stream and all of its
+/// items have been read.
+WASM_API_EXTERN bool wasmtime_component_stream_reader_done(
+    const wasmtime_component_stream_reader_t *reader);
+
+/// \brief Deallocates `reader`, closing the stream for the writer.
+WASM_API_EXTERN void wasmtime_component_stream_reader_delete(
+    wasmtime_component_stream_reader_t *reader);
+
+/// \brief Creates a new host-owned handle to the same error context as
+/// `error_context`.
+///
+/// The returned pointer must be deallocated with
+/// `wasmtime_component_error_context_delete`.
+WASM_API_EXTERN wasmtime_component_error_context_t *
+wasmtime_component_error_context_clone(
+    const wasmtime_component_error_context_t *error_context);
+
+/// \brief Deallocates the host-side memory of `error_context`.
+WASM_API_EXTERN void wasmtime_component_error_context_delete(
+    wasmtime_component_error_context_t *error_context);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif
+
+#endif // WASMTIME_FEATURE_COMPONENT_MODEL_ASYNC
+
+#endif // WASMTIME_COMPONENT_CONCURRENT_H
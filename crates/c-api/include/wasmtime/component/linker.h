@@ -202,6 +202,22 @@ wasmtime_component_linker_instance_add_resource(
     wasmtime_component_resource_destructor_t destructor, void *data,
     void (*finalizer)(void *));
 
+/**
+ * \brief Defines a new resource type whose resources carry host data.
+ *
+ * This is similar to #wasmtime_component_linker_instance_add_resource except
+ * that resources of this type are expected to be created with
+ * #wasmtime_component_resource_host_new_with_data. When a guest drops an owned
+ * resource of this type its data is released by running the finalizer
+ * provided when it was created.
+ *
+ * \return on success `NULL`, otherwise an error
+ */
+WASM_API_EXTERN wasmtime_error_t *
+wasmtime_component_linker_instance_add_resource_with_data(
+    wasmtime_component_linker_instance_t *linker_instance, const char *name,
+    size_t name_len, const wasmtime_component_resource_type_t *resource);
+
 /**
  * \brief Deletes a #wasmtime_component_linker_instance_t
  *
@@ -143,6 +143,20 @@ public:
 
     return std::monostate();
   }
+
+  /// \brief Defines a new resource in this linker whose resources are
+  /// created as `Resource<T>`, destroying their data when dropped by a guest.
+  Result<std::monostate> add_resource_with_data(std::string_view name,
+                                                const ResourceType &ty) {
+    auto *error = wasmtime_component_linker_instance_add_resource_with_data(
+        ptr.get(), name.data(), name.length(), ty.capi());
+
+    if (error != nullptr) {
+      return Error(error);
+    }
+
+    return std::monostate();
+  }
 };
 
 /**
@@ -197,6 +197,63 @@ wasmtime_error_t *wasmtime_component_resource_host_to_any(
     wasmtime_context_t *ctx, const wasmtime_component_resource_host_t *resource,
     wasmtime_component_resource_any_t **ret);
 
+/// \brief Creates a new owned host-defined resource backed by host data.
+///
+/// This allocates a fresh "rep" for a resource of type `ty` and associates
+/// `data` with it in the store. The data can later be retrieved with
+/// `wasmtime_component_resource_host_data` and is released by running
+/// `finalizer`, if provided, when the resource is dropped. Resources passed to
+/// a guest are dropped by the destructor installed with
+/// `wasmtime_component_linker_instance_add_resource_with_data`, and resources
+/// kept on the host are dropped with
+/// `wasmtime_component_resource_host_drop_data`. Any remaining data is
+/// released when the store is deleted.
+///
+/// Reps allocated by this function are never reused within a store, so a
+/// stale handle can never refer to a different resource.
+///
+/// If no error is returned then the pointer written to `ret` must be
+/// deallocated with `wasmtime_component_resource_host_delete`.
+WASM_API_EXTERN
+wasmtime_error_t *wasmtime_component_resource_host_new_with_data(
+    wasmtime_context_t *ctx, uint32_t ty, void *data, void (*finalizer)(void *),
+    wasmtime_component_resource_host_t **ret);
+
+/// \brief Looks up the host data associated with `resource`.
+///
+/// This works for both `own` and `borrow` handles, for example those lifted
+/// from a guest with `wasmtime_component_resource_any_to_host`. An error is
+/// returned if the resource wasn't created with
+/// `wasmtime_component_resource_host_new_with_data`, if it was already
+/// dropped, or if its type doesn't match the type it was created with.
+///
+/// Note that borrows received as arguments of a host function are only valid
+/// for the duration of that call and must not be retained afterwards.
+WASM_API_EXTERN
+wasmtime_error_t *wasmtime_component_resource_host_data(
+    wasmtime_context_t *ctx, const wasmtime_component_resource_host_t *resource,
+    void **data);
+
+/// \brief Creates a `borrow` handle to the same resource as `resource`.
+///
+/// This is used to pass a host resource as a `borrow<T>` to a guest. The
+/// pointer returned from this function must be deallocated with
+/// `wasmtime_component_resource_host_delete`.
+WASM_API_EXTERN
+wasmtime_component_resource_host_t *wasmtime_component_resource_host_borrow(
+    const wasmtime_component_resource_host_t *resource);
+
+/// \brief Drops an owned host resource created with
+/// `wasmtime_component_resource_host_new_with_data`, running its finalizer.
+///
+/// This is used for resources which are still owned by the host. Resources
+/// whose ownership was transferred to a guest are instead dropped by the
+/// guest. Returns an error if `resource` is a `borrow` or was already dropped.
+WASM_API_EXTERN
+wasmtime_error_t *wasmtime_component_resource_host_drop_data(
+    wasmtime_context_t *ctx,
+    const wasmtime_component_resource_host_t *resource);
+
 /// \brief Discriminant used in #wasmtime_component_val_t::kind
 typedef uint8_t wasmtime_component_valkind_t;
 
@@ -269,10 +326,24 @@ typedef uint8_t wasmtime_component_valkind_t;
 /// \brief Value of #wasmtime_component_valkind_t meaning that
 /// #wasmtime_component_val_t is a map
 #define WASMTIME_COMPONENT_MAP 22
+/// \brief Value of #wasmtime_component_valkind_t meaning that
+/// #wasmtime_component_val_t is a future
+#define WASMTIME_COMPONENT_FUTURE 24
+/// \brief Value of #wasmtime_component_valkind_t meaning that
+/// #wasmtime_component_val_t is a stream
+#define WASMTIME_COMPONENT_STREAM 25
+/// \brief Value of #wasmtime_component_valkind_t meaning that
+/// #wasmtime_component_val_t is an error-context
+#define WASMTIME_COMPONENT_ERROR_CONTEXT 26
 
 struct wasmtime_component_val;
 struct wasmtime_component_valrecord_entry;
 struct wasmtime_component_valmap_entry;
+#ifdef WASMTIME_FEATURE_COMPONENT_MODEL_ASYNC
+struct wasmtime_component_future;
+struct wasmtime_component_stream;
+struct wasmtime_component_error_context;
+#endif // WASMTIME_FEATURE_COMPONENT_MODEL_ASYNC
 
 #define DECLARE_VEC(name, type)                                                \
   /** \brief A vec of a type */                                                \
@@ -376,6 +447,17 @@ typedef union {
   /// Field used if #wasmtime_component_val_t::kind is
   /// #WASMTIME_COMPONENT_RESOURCE
   wasmtime_component_resource_any_t *resource;
+#ifdef WASMTIME_FEATURE_COMPONENT_MODEL_ASYNC
+  /// Field used if #wasmtime_component_val_t::kind is
+  /// #WASMTIME_COMPONENT_FUTURE
+  struct wasmtime_component_future *future;
+  /// Field used if #wasmtime_component_val_t::kind is
+  /// #WASMTIME_COMPONENT_STREAM
+  struct wasmtime_component_stream *stream;
+  /// Field used if #wasmtime_component_val_t::kind is
+  /// #WASMTIME_COMPONENT_ERROR_CONTEXT
+  struct wasmtime_component_error_context *error_context;
+#endif // WASMTIME_FEATURE_COMPONENT_MODEL_ASYNC
 } wasmtime_component_valunion_t;
 
 /// \brief Represents possible runtime values which a component function can
@@ -535,6 +535,95 @@ class ResourceHost {
       return Error(err);
     return ResourceAny(out);
   }
+
+  /// \brief Returns a `borrow` handle to the same resource.
+  ResourceHost borrow() const {
+    return ResourceHost(wasmtime_component_resource_host_borrow(capi()));
+  }
+
+  /// \brief Returns the host data of this resource, which must have been
+  /// created as a `Resource<T>`.
+  ///
+  /// Fails if this resource has no data or was already dropped. Note that the
+  /// type `T` is not checked beyond the resource's type identifier, so each
+  /// type identifier should only ever be used with a single `T`.
+  template <typename T> Result<T *> data(Store::Context cx) const {
+    void *data = nullptr;
+    wasmtime_error_t *err =
+        wasmtime_component_resource_host_data(cx.capi(), capi(), &data);
+    if (err)
+      return Error(err);
+    return static_cast<T *>(data);
+  }
+};
+
+/// Class representing an owned host-defined resource whose data is a `T`
+/// owned by the store.
+///
+/// Resources of this type should be defined in a linker with
+/// `LinkerInstance::add_resource_with_data`. The `T` is destroyed when the
+/// resource is dropped, either by a guest which it was passed to, or by
+/// calling `drop` on the host, or otherwise when the store is deleted.
+template <typename T> class Resource {
+  ResourceHost host_;
+
+  explicit Resource(ResourceHost host) : host_(std::move(host)) {}
+
+  static void finalize(void *data) {
+    std::unique_ptr<T> ptr(static_cast<T *>(data));
+  }
+
+public:
+  /// \brief Creates a new resource of type `ty` which owns `value`.
+  static Result<Resource> create(Store::Context cx, uint32_t ty,
+                                 std::unique_ptr<T> value) {
+    wasmtime_component_resource_host_t *out;
+    wasmtime_error_t *err = wasmtime_component_resource_host_new_with_data(
+        cx.capi(), ty, value.get(), finalize, &out);
+    if (err)
+      return Error(err);
+    value.release();
+    return Resource(ResourceHost(out));
+  }
+
+  /// \brief Recovers a typed resource from an owned `ResourceHost`, for
+  /// example one lifted from a guest.
+  ///
+  /// Fails if `host` is a borrow, has no data, or was already dropped.
+  static Result<Resource> from_host(Store::Context cx, ResourceHost host) {
+    if (!host.owned())
+      return Error("expected an owned resource, found a borrow");
+    auto data = host.data<T>(cx);
+    if (!data)
+      return data.err();
+    return Resource(std::move(host));
+  }
+
+  /// \brief Returns the underlying untyped resource.
+  const ResourceHost &host() const { return host_; }
+
+  /// \brief Returns a `borrow` handle to this resource.
+  ResourceHost borrow() const { return host_.borrow(); }
+
+  /// \brief Returns the data of this resource, failing if it was dropped.
+  Result<T *> get(Store::Context cx) const { return host_.data<T>(cx); }
+
+  /// \brief Converts this resource into a generic resource-any, for example
+  /// to pass it to a guest.
+  Result<ResourceAny> to_any(Store::Context cx) const {
+    return host_.to_any(cx);
+  }
+
+  /// \brief Drops this resource on the host, destroying its data.
+  ///
+  /// This must only be used for resources which were not passed to a guest.
+  Result<std::monostate> drop(Store::Context cx) const {
+    wasmtime_error_t *err =
+        wasmtime_component_resource_host_drop_data(cx.capi(), host_.capi());
+    if (err)
+      return Error(err);
+    return std::monostate();
+  }
 };
 
 inline Result<ResourceHost> ResourceAny::to_host(Store::Context cx) const {
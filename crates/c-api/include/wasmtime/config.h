@@ -940,6 +940,36 @@ WASMTIME_CONFIG_PROP(void, wasm_component_model_async_stackful, bool)
 
 #endif // WASMTIME_FEATURE_COMPONENT_MODEL_ASYNC
 
+#ifdef WASMTIME_FEATURE_DEBUG
+
+/**
+ * \brief Configures whether guest debugging instrumentation is enabled.
+ *
+ * This must be enabled to use breakpoints, single-stepping, frame inspection
+ * or debug handlers, see `wasmtime/debug.h`.
+ *
+ * For more information see the Rust documentation at
+ * https://docs.wasmtime.dev/api/wasmtime/struct.Config.html#method.guest_debug.
+ */
+WASMTIME_CONFIG_PROP(void, guest_debug, bool)
+
+#endif // WASMTIME_FEATURE_DEBUG
+
+#ifdef WASMTIME_FEATURE_COREDUMP
+
+/**
+ * \brief Configures whether a core dump is captured when WebAssembly traps.
+ *
+ * Captured core dumps can be serialized with
+ * `wasmtime_trap_coredump_serialize`, see `wasmtime/coredump.h`.
+ *
+ * For more information see the Rust documentation at
+ * https://docs.wasmtime.dev/api/wasmtime/struct.Config.html#method.coredump_on_trap.
+ */
+WASMTIME_CONFIG_PROP(void, coredump_on_trap, bool)
+
+#endif // WASMTIME_FEATURE_COREDUMP
+
 #ifdef __cplusplus
 } // extern "C"
 #endif
@@ -306,6 +306,25 @@ class Config {
     wasmtime_config_max_wasm_stack_set(ptr.get(), stack);
   }
 
+#ifdef WASMTIME_FEATURE_DEBUG
+  /// \brief Configures whether guest debugging instrumentation is enabled.
+  ///
+  /// https://docs.wasmtime.dev/api/wasmtime/struct.Config.html#method.guest_debug
+  void guest_debug(bool enable) {
+    wasmtime_config_guest_debug_set(ptr.get(), enable);
+  }
+#endif // WASMTIME_FEATURE_DEBUG
+
+#ifdef WASMTIME_FEATURE_COREDUMP
+  /// \brief Configures whether a core dump is captured when WebAssembly
+  /// traps.
+  ///
+  /// https://docs.wasmtime.dev/api/wasmtime/struct.Config.html#method.coredump_on_trap
+  void coredump_on_trap(bool enable) {
+    wasmtime_config_coredump_on_trap_set(ptr.get(), enable);
+  }
+#endif // WASMTIME_FEATURE_COREDUMP
+
 #ifdef WASMTIME_FEATURE_THREADS
   /// \brief Configures whether the WebAssembly threads proposal is enabled
   ///
@@ -0,0 +1,91 @@
+/**
+ * \file wasmtime/coredump.h
+ *
+ * Wasmtime APIs for inspecting and serializing core dumps captured when
+ * WebAssembly traps.
+ */
+
+#ifndef WASMTIME_COREDUMP_H
+#define WASMTIME_COREDUMP_H
+
+#include <wasmtime/conf.h>
+
+#ifdef WASMTIME_FEATURE_COREDUMP
+
+#include <wasm.h>
+#include <wasmtime/error.h>
+#include <wasmtime/store.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/**
+ * \brief Serializes the core dump attached to `trap`, if any.
+ *
+ * \param trap the trap to serialize the core dump of
+ * \param store the store that the trap originated from
+ * \param name the name of the core dump, typically the name of the module
+ * \param name_len the byte length of `name`
+ * \param out where to store the serialized core dump
+ *
+ * Core dumps are only attached to traps when
+ * `wasmtime_config_coredump_on_trap_set` is enabled. The serialized core
+ * dump follows the tool-conventions core dump format and is stored in `out`,
+ * which is owned by the caller and must be deallocated with
+ * #wasm_byte_vec_delete.
+ *
+ * Returns `false` and leaves `out` untouched if `trap` has no core dump or if
+ * `name` is not valid UTF-8.
+ */
+WASM_API_EXTERN bool wasmtime_trap_coredump_serialize(const wasm_trap_t *trap,
+                                                      wasmtime_context_t *store,
+                                                      const char *name,
+                                                      size_t name_len,
+                                                      wasm_byte_vec_t *out);
+
+/**
+ * \brief Serializes the core dump attached to `error`, if any.
+ *
+ * This is the same as #wasmtime_trap_coredump_serialize except that it
+ * operates on errors, such as those returned by #wasmtime_func_call.
+ */
+WASM_API_EXTERN bool wasmtime_error_coredump_serialize(
+    const wasmtime_error_t *error, wasmtime_context_t *store, const char *name,
+    size_t name_len, wasm_byte_vec_t *out);
+
+/**
+ * \brief Returns the stack frames of the core dump attached to `trap`, if
+ * any.
+ *
+ * \param trap the trap to inspect the core dump of
+ * \param out where to store the frames of the core dump, starting with the
+ *        innermost frame
+ *
+ * The frames can be inspected with the `wasm_frame_*` and `wasmtime_frame_*`
+ * functions, and are only valid for as long as `trap` is. The vector is
+ * owned by the caller and must be deallocated with #wasm_frame_vec_delete.
+ *
+ * Returns `false` and leaves `out` untouched if `trap` has no core dump.
+ */
+WASM_API_EXTERN bool wasmtime_trap_coredump_frames(const wasm_trap_t *trap,
+                                                   wasm_frame_vec_t *out);
+
+/**
+ * \brief Returns the stack frames of the core dump attached to `error`, if
+ * any.
+ *
+ * This is the same as #wasmtime_trap_coredump_frames except that it operates
+ * on errors, such as those returned by #wasmtime_func_call.
+ */
+WASM_API_EXTERN bool
+wasmtime_error_coredump_frames(const wasmtime_error_t *error,
+                               wasm_frame_vec_t *out);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif
+
+#endif // WASMTIME_FEATURE_COREDUMP
+
+#endif // WASMTIME_COREDUMP_H
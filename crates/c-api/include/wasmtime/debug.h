@@ -0,0 +1,274 @@
+/**
+ * \file wasmtime/debug.h
+ *
+ * \brief Wasmtime APIs for debugging WebAssembly guests.
+ *
+ * These APIs require guest debugging to be enabled in the engine's
+ * configuration with `wasmtime_config_guest_debug_set`. With it enabled a
+ * store can have breakpoints set in its modules, can be single-stepped, and
+ * can have its WebAssembly frames and their locals and operand stacks
+ * inspected from within a debug handler.
+ *
+ * For more information see the Rust documentation at
+ * https://docs.wasmtime.dev/api/wasmtime/struct.Store.html#method.set_debug_handler.
+ */
+
+#ifndef WASMTIME_DEBUG_H
+#define WASMTIME_DEBUG_H
+
+#include <wasmtime/conf.h>
+
+#ifdef WASMTIME_FEATURE_DEBUG
+
+#include <wasm.h>
+#include <wasmtime/async.h>
+#include <wasmtime/error.h>
+#include <wasmtime/instance.h>
+#include <wasmtime/module.h>
+#include <wasmtime/store.h>
+#include <wasmtime/trap.h>
+#include <wasmtime/val.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/**
+ * \brief Discriminant of a #wasmtime_debug_event_t.
+ *
+ * See #wasmtime_debug_event_kind_enum for possible values.
+ */
+typedef uint8_t wasmtime_debug_event_kind_t;
+
+/**
+ * \brief Kinds of events reported to a #wasmtime_debug_handler_t.
+ */
+enum wasmtime_debug_event_kind_enum {
+  /// A host function called by the guest returned an error. The error's
+  /// description is available in the event's `message`.
+  WASMTIME_DEBUG_EVENT_HOSTCALL_ERROR,
+  /// The guest threw an exception.
+  WASMTIME_DEBUG_EVENT_EXCEPTION,
+  /// The guest trapped. The trap code is available in the event's
+  /// `trap_code`.
+  WASMTIME_DEBUG_EVENT_TRAP,
+  /// The guest hit a breakpoint or completed a single step.
+  WASMTIME_DEBUG_EVENT_BREAKPOINT,
+  /// The guest reached an epoch yield point.
+  WASMTIME_DEBUG_EVENT_EPOCH_YIELD,
+};
+
+/**
+ * \brief An event reported to a #wasmtime_debug_handler_t.
+ */
+typedef struct wasmtime_debug_event {
+  /// The kind of event that happened.
+  wasmtime_debug_event_kind_t kind;
+  /// The trap code for #WASMTIME_DEBUG_EVENT_TRAP events.
+  wasmtime_trap_code_t trap_code;
+  /// A description of the error for #WASMTIME_DEBUG_EVENT_HOSTCALL_ERROR
+  /// events, which is only valid for the duration of the handler.
+  const char *message;
+  /// The byte length of `message`.
+  size_t message_len;
+} wasmtime_debug_event_t;
+
+/**
+ * \brief Callback invoked when a debug event happens in a store.
+ *
+ * The first argument is the `data` pointer provided to
+ * #wasmtime_store_set_debug_handler. The store context may be used to
+ * inspect the guest's frames with #wasmtime_context_debug_exit_frame and to
+ * edit breakpoints. Execution of the guest resumes once the callback returns.
+ */
+typedef void (*wasmtime_debug_handler_t)(void *data,
+                                         wasmtime_context_t *context,
+                                         const wasmtime_debug_event_t *event);
+
+/**
+ * \brief Installs a debug handler in `store`.
+ *
+ * \param store the store to install the handler in
+ * \param callback the callback invoked for each debug event
+ * \param data user-provided data passed to `callback`
+ * \param finalizer optional finalizer for `data`, run when the handler is
+ *        replaced, cleared, or the store is deleted
+ *
+ * Guest debugging must be enabled in the store's engine, otherwise this
+ * function aborts the process. Note that installing a debug handler
+ * requires WebAssembly to be invoked through the asynchronous entrypoints
+ * such as #wasmtime_func_call_async.
+ */
+WASM_API_EXTERN void wasmtime_store_set_debug_handler(
+    wasmtime_store_t *store, wasmtime_debug_handler_t callback, void *data,
+    void (*finalizer)(void *));
+
+/**
+ * \brief Removes the debug handler, if any, from `store`.
+ */
+WASM_API_EXTERN void
+wasmtime_store_clear_debug_handler(wasmtime_store_t *store);
+
+/**
+ * \brief Adds a breakpoint at `pc` in `module`.
+ *
+ * \param context the store to add the breakpoint in
+ * \param module the module to add the breakpoint to, which must have been
+ *        instantiated in this store
+ * \param pc the byte offset of an instruction within the module's binary
+ *
+ * When execution reaches the breakpoint the store's debug handler is invoked
+ * with a #WASMTIME_DEBUG_EVENT_BREAKPOINT event. Returns an error if guest
+ * debugging is not enabled or if the breakpoint couldn't be added.
+ */
+WASM_API_EXTERN wasmtime_error_t *
+wasmtime_context_add_breakpoint(wasmtime_context_t *context,
+                                const wasmtime_module_t *module, uint32_t pc);
+
+/**
+ * \brief Removes a breakpoint previously added with
+ * #wasmtime_context_add_breakpoint.
+ */
+WASM_API_EXTERN wasmtime_error_t *
+wasmtime_context_remove_breakpoint(wasmtime_context_t *context,
+                                   const wasmtime_module_t *module,
+                                   uint32_t pc);
+
+/**
+ * \brief Configures whether the store is single-stepped.
+ *
+ * When enabled the debug handler is invoked with a
+ * #WASMTIME_DEBUG_EVENT_BREAKPOINT event before every instruction. Returns an
+ * error if guest debugging is not enabled.
+ */
+WASM_API_EXTERN wasmtime_error_t *
+wasmtime_context_single_step(wasmtime_context_t *context, bool enabled);
+
+/**
+ * \brief A handle to a WebAssembly or host frame on the stack of a store.
+ *
+ * Frame handles are only valid while the store is paused in the frame, for
+ * example during a debug handler, which can be checked with
+ * #wasmtime_debug_frame_is_valid. Frames are owned by the caller and must be
+ * deallocated with #wasmtime_debug_frame_delete.
+ */
+typedef struct wasmtime_debug_frame wasmtime_debug_frame_t;
+
+/**
+ * \brief Deallocates a frame handle.
+ */
+WASM_API_EXTERN void wasmtime_debug_frame_delete(wasmtime_debug_frame_t *frame);
+
+/**
+ * \brief Returns the innermost frame of an activation of WebAssembly on the
+ * store's stack.
+ *
+ * \param context the store to inspect
+ * \param activation the index of the activation, where 0 is the most recent
+ *        entry into WebAssembly
+ *
+ * Returns `NULL` if there is no such activation.
+ */
+WASM_API_EXTERN wasmtime_debug_frame_t *
+wasmtime_context_debug_exit_frame(wasmtime_context_t *context,
+                                  size_t activation);
+
+/**
+ * \brief Returns whether `frame` still refers to a frame on the stack.
+ *
+ * All other frame functions return an error when the frame is no longer
+ * valid.
+ */
+WASM_API_EXTERN bool
+wasmtime_debug_frame_is_valid(wasmtime_context_t *context,
+                              const wasmtime_debug_frame_t *frame);
+
+/**
+ * \brief Returns the caller of `frame`.
+ *
+ * On success `parent` is filled in with a new frame handle, or `NULL` if
+ * `frame` is the outermost frame of its activation.
+ */
+WASM_API_EXTERN wasmtime_error_t *
+wasmtime_debug_frame_parent(wasmtime_context_t *context,
+                            const wasmtime_debug_frame_t *frame,
+                            wasmtime_debug_frame_t **parent);
+
+/**
+ * \brief Returns the instance that `frame` is executing in.
+ */
+WASM_API_EXTERN wasmtime_error_t *
+wasmtime_debug_frame_instance(wasmtime_context_t *context,
+                              const wasmtime_debug_frame_t *frame,
+                              wasmtime_instance_t *instance);
+
+/**
+ * \brief Returns the module that `frame` is executing in.
+ *
+ * On success `module` is filled in with a new module handle owned by the
+ * caller, or `NULL` if the frame isn't executing a core module.
+ */
+WASM_API_EXTERN wasmtime_error_t *
+wasmtime_debug_frame_module(wasmtime_context_t *context,
+                            const wasmtime_debug_frame_t *frame,
+                            wasmtime_module_t **module);
+
+/**
+ * \brief Returns the function and program counter that `frame` is executing.
+ *
+ * On success `is_wasm` is set to whether the frame is a WebAssembly frame.
+ * If it is then `func_index` is filled in with the index of the function
+ * among the module's defined functions, excluding imports, and `pc` is filled
+ * in with the byte offset of the current instruction within the module's
+ * binary.
+ */
+WASM_API_EXTERN wasmtime_error_t *wasmtime_debug_frame_func_index_and_pc(
+    wasmtime_context_t *context, const wasmtime_debug_frame_t *frame,
+    bool *is_wasm, uint32_t *func_index, uint32_t *pc);
+
+/**
+ * \brief Returns the number of locals, including parameters, in `frame`.
+ */
+WASM_API_EXTERN wasmtime_error_t *
+wasmtime_debug_frame_num_locals(wasmtime_context_t *context,
+                                const wasmtime_debug_frame_t *frame,
+                                uint32_t *num);
+
+/**
+ * \brief Returns the number of values on the operand stack of `frame`.
+ */
+WASM_API_EXTERN wasmtime_error_t *
+wasmtime_debug_frame_num_stacks(wasmtime_context_t *context,
+                                const wasmtime_debug_frame_t *frame,
+                                uint32_t *num);
+
+/**
+ * \brief Reads the local at `index` in `frame`.
+ *
+ * On success `val` is filled in with the value of the local, which is owned
+ * by the caller and must be deallocated with #wasmtime_val_unroot. Returns an
+ * error if `index` is out of bounds.
+ */
+WASM_API_EXTERN wasmtime_error_t *
+wasmtime_debug_frame_local(wasmtime_context_t *context,
+                           const wasmtime_debug_frame_t *frame, uint32_t index,
+                           wasmtime_val_t *val);
+
+/**
+ * \brief Reads the operand stack value at `index` in `frame`, where 0 is the
+ * bottom of the stack.
+ *
+ * See #wasmtime_debug_frame_local for more information.
+ */
+WASM_API_EXTERN wasmtime_error_t *
+wasmtime_debug_frame_stack(wasmtime_context_t *context,
+                           const wasmtime_debug_frame_t *frame, uint32_t index,
+                           wasmtime_val_t *val);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif
+
+#endif // WASMTIME_FEATURE_DEBUG
+
+#endif // WASMTIME_DEBUG_H
@@ -8,6 +8,7 @@
 #define WASMTIME_ENGINE_H
 
 #include <wasm.h>
+#include <wasmtime/conf.h>
 
 #ifdef __cplusplus
 extern "C" {
@@ -43,6 +44,107 @@ wasmtime_engine_increment_epoch(const wasm_engine_t *engine);
  */
 WASM_API_EXTERN bool wasmtime_engine_is_pulley(const wasm_engine_t *engine);
 
+#ifdef WASMTIME_FEATURE_POOLING_ALLOCATOR
+
+/**
+ * \brief Runtime metrics of an engine's pooling allocator.
+ *
+ * Metrics are read live from the engine whenever an accessor is called, so a
+ * single handle can be kept around and polled.
+ *
+ * For more information see the Rust documentation at
+ * https://docs.wasmtime.dev/api/wasmtime/struct.PoolingAllocatorMetrics.html.
+ */
+typedef struct wasmtime_pooling_allocator_metrics
+    wasmtime_pooling_allocator_metrics_t;
+
+/**
+ * \brief Returns the metrics of `engine`'s pooling allocator.
+ *
+ * Returns `NULL` if the engine wasn't configured with
+ * #wasmtime_pooling_allocation_strategy_set. Otherwise the returned value is
+ * owned by the caller and must be deallocated with
+ * #wasmtime_pooling_allocator_metrics_delete.
+ */
+WASM_API_EXTERN wasmtime_pooling_allocator_metrics_t *
+wasmtime_engine_pooling_allocator_metrics(const wasm_engine_t *engine);
+
+/**
+ * \brief Deallocates metrics returned by
+ * #wasmtime_engine_pooling_allocator_metrics.
+ */
+WASM_API_EXTERN void wasmtime_pooling_allocator_metrics_delete(
+    wasmtime_pooling_allocator_metrics_t *metrics);
+
+/// \brief Returns the number of core instances currently allocated.
+WASM_API_EXTERN uint64_t wasmtime_pooling_allocator_metrics_core_instances(
+    const wasmtime_pooling_allocator_metrics_t *metrics);
+
+/// \brief Returns the number of component instances currently allocated.
+WASM_API_EXTERN uint64_t
+wasmtime_pooling_allocator_metrics_component_instances(
+    const wasmtime_pooling_allocator_metrics_t *metrics);
+
+/// \brief Returns the number of linear memories currently allocated.
+WASM_API_EXTERN size_t wasmtime_pooling_allocator_metrics_memories(
+    const wasmtime_pooling_allocator_metrics_t *metrics);
+
+/// \brief Returns the number of tables currently allocated.
+WASM_API_EXTERN size_t wasmtime_pooling_allocator_metrics_tables(
+    const wasmtime_pooling_allocator_metrics_t *metrics);
+
+#ifdef WASMTIME_FEATURE_ASYNC
+/// \brief Returns the number of async stacks currently allocated.
+WASM_API_EXTERN size_t wasmtime_pooling_allocator_metrics_stacks(
+    const wasmtime_pooling_allocator_metrics_t *metrics);
+#endif // WASMTIME_FEATURE_ASYNC
+
+#ifdef WASMTIME_FEATURE_GC
+/// \brief Returns the number of GC heaps currently allocated.
+WASM_API_EXTERN size_t wasmtime_pooling_allocator_metrics_gc_heaps(
+    const wasmtime_pooling_allocator_metrics_t *metrics);
+#endif // WASMTIME_FEATURE_GC
+
+/// \brief Returns the number of previously used, but currently unused, linear
+/// memory slots.
+WASM_API_EXTERN uint32_t
+wasmtime_pooling_allocator_metrics_unused_warm_memories(
+    const wasmtime_pooling_allocator_metrics_t *metrics);
+
+/// \brief Returns the number of bytes kept resident in unused linear memory
+/// slots.
+WASM_API_EXTERN size_t
+wasmtime_pooling_allocator_metrics_unused_memory_bytes_resident(
+    const wasmtime_pooling_allocator_metrics_t *metrics);
+
+/// \brief Returns the number of previously used, but currently unused, table
+/// slots.
+WASM_API_EXTERN uint32_t wasmtime_pooling_allocator_metrics_unused_warm_tables(
+    const wasmtime_pooling_allocator_metrics_t *metrics);
+
+/// \brief Returns the number of bytes kept resident in unused table slots.
+WASM_API_EXTERN size_t
+wasmtime_pooling_allocator_metrics_unused_table_bytes_resident(
+    const wasmtime_pooling_allocator_metrics_t *metrics);
+
+#ifdef WASMTIME_FEATURE_ASYNC
+/// \brief Returns the number of previously used, but currently unused, async
+/// stack slots.
+WASM_API_EXTERN uint32_t wasmtime_pooling_allocator_metrics_unused_warm_stacks(
+    const wasmtime_pooling_allocator_metrics_t *metrics);
+
+/// \brief Returns the number of bytes kept resident in unused async stack
+/// slots.
+///
+/// Returns `false` if this isn't tracked, for example when async stack
+/// zeroing is disabled, and otherwise stores the number in `bytes`.
+WASM_API_EXTERN bool
+wasmtime_pooling_allocator_metrics_unused_stack_bytes_resident(
+    const wasmtime_pooling_allocator_metrics_t *metrics, size_t *bytes);
+#endif // WASMTIME_FEATURE_ASYNC
+
+#endif // WASMTIME_FEATURE_POOLING_ALLOCATOR
+
 #ifdef __cplusplus
 } // extern "C"
 #endif
@@ -114,6 +114,49 @@ WASM_API_EXTERN void wasmtime_store_limiter(wasmtime_store_t *store,
                                             int64_t instances, int64_t tables,
                                             int64_t memories);
 
+/**
+ * \brief Callback signature for #wasmtime_store_resource_limiter.
+ *
+ * \param env the user data passed to #wasmtime_store_resource_limiter
+ * \param current the current size of the memory in bytes, or the current
+ * number of elements in the table
+ * \param desired the desired size after growth, in the same units as
+ * `current`
+ * \param maximum the maximum size of the memory or table, or a negative
+ * value if it is unbounded
+ * \param allow set to whether the growth is allowed
+ *
+ * If an error is returned then the growth operation traps with that error
+ * rather than failing, and `allow` is ignored. Otherwise when `allow` is set
+ * to `false` the growth fails, for example `memory.grow` returns -1.
+ */
+typedef wasmtime_error_t *(*wasmtime_resource_limiter_growing_t)(
+    void *env, size_t current, size_t desired, int64_t maximum, bool *allow);
+
+/**
+ * \brief Configures a callback-based resource limiter for a store.
+ *
+ * \param store store where the limiter should be installed.
+ * \param memory_growing invoked whenever a linear memory, or the GC heap, is
+ * requested to grow.
+ * \param table_growing invoked whenever a table is requested to grow.
+ * \param data user data passed to both callbacks.
+ * \param finalizer optional finalizer for `data`, run when the limiter is
+ * replaced or the store is deleted.
+ *
+ * This is an alternative to #wasmtime_store_limiter for dynamically deciding
+ * whether memories and tables can grow, and replaces any previously
+ * configured limiter. The number of instances, tables and memories in the
+ * store use their default limits of 10,000.
+ *
+ * For more information see the Rust documentation at
+ * https://docs.wasmtime.dev/api/wasmtime/trait.ResourceLimiter.html.
+ */
+WASM_API_EXTERN void wasmtime_store_resource_limiter(
+    wasmtime_store_t *store, wasmtime_resource_limiter_growing_t memory_growing,
+    wasmtime_resource_limiter_growing_t table_growing, void *data,
+    void (*finalizer)(void *));
+
 /**
  * \brief Deletes a store.
  */
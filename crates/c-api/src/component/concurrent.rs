@@ -0,0 +1,687 @@
+use crate::{
+    WASMTIME_COMPONENT_BOOL, WASMTIME_COMPONENT_CHAR, WASMTIME_COMPONENT_F32,
+    WASMTIME_COMPONENT_F64, WASMTIME_COMPONENT_S8, WASMTIME_COMPONENT_S16, WASMTIME_COMPONENT_S32,
+    WASMTIME_COMPONENT_S64, WASMTIME_COMPONENT_STRING, WASMTIME_COMPONENT_U8,
+    WASMTIME_COMPONENT_U16, WASMTIME_COMPONENT_U32, WASMTIME_COMPONENT_U64,
+    WasmtimeStoreContextMut, handle_result, wasm_name_t, wasmtime_component_val_t,
+    wasmtime_component_valkind_t, wasmtime_error_t,
+};
+use std::collections::VecDeque;
+use std::mem::MaybeUninit;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use wasmtime::component::{
+    ComponentType, Destination, ErrorContextAny, FutureAny, FutureConsumer, FutureProducer,
+    FutureReader, Lift, Lower, Source, StreamAny, StreamConsumer, StreamProducer, StreamReader,
+    StreamResult, VecBuffer,
+};
+use wasmtime::{Error, Result, StoreContextMut, bail, format_err};
+
+/// Maximum number of items buffered on the host for a stream read by the
+/// embedder before backpressure is applied to the writer.
+const READ_BUFFER_CAPACITY: usize = 64 * 1024;
+
+#[repr(C)]
+#[derive(Clone)]
+pub struct wasmtime_component_future_t {
+    pub(crate) future: FutureAny,
+}
+
+#[repr(C)]
+#[derive(Clone)]
+pub struct wasmtime_component_stream_t {
+    pub(crate) stream: StreamAny,
+}
+
+#[repr(C)]
+#[derive(Clone)]
+pub struct wasmtime_component_error_context_t {
+    pub(crate) error_context: ErrorContextAny,
+}
+
+/// A payload type of the futures and streams whose ends are owned by the
+/// embedder.
+///
+/// The store only transfers statically typed payloads, so items are
+/// converted from and to `wasmtime_component_val_t` as they're written and
+/// read by the embedder.
+trait Payload: ComponentType + Lower + Lift + Send + Sync + Sized + 'static {
+    /// Converts an item written by the embedder, which is `None` if no value
+    /// was provided for it.
+    fn from_val(val: Option<&wasmtime_component_val_t>) -> Result<Self>;
+
+    /// Converts an item to be read by the embedder, returning `None` for
+    /// items without a value.
+    fn into_val(self) -> Option<wasmtime_component_val_t>;
+}
+
+fn mismatch(expected: &str) -> Error {
+    format_err!("expected a `{expected}` value to be written")
+}
+
+impl Payload for () {
+    fn from_val(val: Option<&wasmtime_component_val_t>) -> Result<Self> {
+        match val {
+            None => Ok(()),
+            Some(_) => bail!("cannot write values without a payload type"),
+        }
+    }
+
+    fn into_val(self) -> Option<wasmtime_component_val_t> {
+        None
+    }
+}
+
+macro_rules! scalar_payloads {
+    ($($ty:ty => $variant:ident $name:tt,)*) => {$(
+        impl Payload for $ty {
+            fn from_val(val: Option<&wasmtime_component_val_t>) -> Result<Self> {
+                match val {
+                    Some(wasmtime_component_val_t::$variant(x)) => Ok(*x),
+                    _ => Err(mismatch($name)),
+                }
+            }
+
+            fn into_val(self) -> Option<wasmtime_component_val_t> {
+                Some(wasmtime_component_val_t::$variant(self))
+            }
+        }
+    )*};
+}
+
+scalar_payloads! {
+    bool => Bool "bool",
+    i8 => S8 "s8",
+    u8 => U8 "u8",
+    i16 => S16 "s16",
+    u16 => U16 "u16",
+    i32 => S32 "s32",
+    u32 => U32 "u32",
+    i64 => S64 "s64",
+    u64 => U64 "u64",
+    f32 => F32 "f32",
+    f64 => F64 "f64",
+}
+
+impl Payload for char {
+    fn from_val(val: Option<&wasmtime_component_val_t>) -> Result<Self> {
+        match val {
+            Some(wasmtime_component_val_t::Char(x)) => {
+                char::from_u32(*x).ok_or_else(|| format_err!("invalid `char` value {x:#x}"))
+            }
+            _ => Err(mismatch("char")),
+        }
+    }
+
+    fn into_val(self) -> Option<wasmtime_component_val_t> {
+        Some(wasmtime_component_val_t::Char(u32::from(self)))
+    }
+}
+
+impl Payload for String {
+    fn from_val(val: Option<&wasmtime_component_val_t>) -> Result<Self> {
+        match val {
+            Some(wasmtime_component_val_t::String(x)) => Ok(str::from_utf8(x.as_slice())?.into()),
+            _ => Err(mismatch("string")),
+        }
+    }
+
+    fn into_val(self) -> Option<wasmtime_component_val_t> {
+        Some(wasmtime_component_val_t::String(wasm_name_t::from_name(
+            self,
+        )))
+    }
+}
+
+/// Invokes `$f::<T>(...)` with the `Payload` type `T` described by
+/// `$payload`, which is `None` for futures and streams without a payload.
+macro_rules! with_payload {
+    ($payload:expr, $f:ident($($arg:expr),*)) => {
+        match $payload {
+            None => $f::<()>($($arg),*),
+            Some(WASMTIME_COMPONENT_BOOL) => $f::<bool>($($arg),*),
+            Some(WASMTIME_COMPONENT_S8) => $f::<i8>($($arg),*),
+            Some(WASMTIME_COMPONENT_U8) => $f::<u8>($($arg),*),
+            Some(WASMTIME_COMPONENT_S16) => $f::<i16>($($arg),*),
+            Some(WASMTIME_COMPONENT_U16) => $f::<u16>($($arg),*),
+            Some(WASMTIME_COMPONENT_S32) => $f::<i32>($($arg),*),
+            Some(WASMTIME_COMPONENT_U32) => $f::<u32>($($arg),*),
+            Some(WASMTIME_COMPONENT_S64) => $f::<i64>($($arg),*),
+            Some(WASMTIME_COMPONENT_U64) => $f::<u64>($($arg),*),
+            Some(WASMTIME_COMPONENT_F32) => $f::<f32>($($arg),*),
+            Some(WASMTIME_COMPONENT_F64) => $f::<f64>($($arg),*),
+            Some(WASMTIME_COMPONENT_CHAR) => $f::<char>($($arg),*),
+            Some(WASMTIME_COMPONENT_STRING) => $f::<String>($($arg),*),
+            Some(kind) => Err(format_err!(
+                "unsupported payload kind {kind} for a future or stream owned by the host"
+            )),
+        }
+    };
+}
+
+/// State shared between one end of a stream or future owned by the embedder
+/// and the producer/consumer registered with the store.
+struct Pipe<T> {
+    buf: VecDeque<T>,
+    /// Whether the writing end is done producing items.
+    closed: bool,
+    /// Whether the reading end is no longer interested in items.
+    dropped: bool,
+    waker: Option<Waker>,
+}
+
+impl<T> Default for Pipe<T> {
+    fn default() -> Self {
+        Pipe {
+            buf: VecDeque::new(),
+            closed: false,
+            dropped: false,
+            waker: None,
+        }
+    }
+}
+
+type SharedPipe<T> = Arc<Mutex<Pipe<T>>>;
+
+fn wake<T>(pipe: &mut Pipe<T>) {
+    if let Some(waker) = pipe.waker.take() {
+        waker.wake();
+    }
+}
+
+/// The end of a `Pipe` owned by the embedder, with its payload type erased.
+trait PipeEnd: Send {
+    /// Buffers `len` items for the reading end, converted from `items` if
+    /// values were provided, and then closes the writing end if `close` is
+    /// set.
+    ///
+    /// Returns whether the reading end is still open; if it isn't then the
+    /// items are discarded.
+    fn write(
+        &self,
+        items: Option<&[wasmtime_component_val_t]>,
+        len: usize,
+        close: bool,
+    ) -> Result<bool>;
+
+    /// Takes up to `len` buffered items.
+    fn read(&self, len: usize) -> Vec<Option<wasmtime_component_val_t>>;
+
+    /// Returns whether the writing end is closed and all items were read.
+    fn done(&self) -> bool;
+
+    /// Closes the writing end.
+    fn close_write(&self);
+
+    /// Closes the reading end.
+    fn close_read(&self);
+}
+
+impl<T: Payload> PipeEnd for SharedPipe<T> {
+    fn write(
+        &self,
+        items: Option<&[wasmtime_component_val_t]>,
+        len: usize,
+        close: bool,
+    ) -> Result<bool> {
+        let items = match items {
+            Some(items) => items
+                .iter()
+                .map(|item| T::from_val(Some(item)))
+                .collect::<Result<Vec<_>>>()?,
+            None => (0..len).map(|_| T::from_val(None)).collect::<Result<_>>()?,
+        };
+        let mut pipe = self.lock().unwrap();
+        if pipe.closed {
+            bail!("the writing end is already closed");
+        }
+        pipe.closed = close;
+        if pipe.dropped {
+            return Ok(false);
+        }
+        pipe.buf.extend(items);
+        wake(&mut pipe);
+        Ok(true)
+    }
+
+    fn read(&self, len: usize) -> Vec<Option<wasmtime_component_val_t>> {
+        let mut pipe = self.lock().unwrap();
+        let count = len.min(pipe.buf.len());
+        let items = pipe.buf.drain(..count).map(T::into_val).collect();
+        wake(&mut pipe);
+        items
+    }
+
+    fn done(&self) -> bool {
+        let pipe = self.lock().unwrap();
+        pipe.closed && pipe.buf.is_empty()
+    }
+
+    fn close_write(&self) {
+        let mut pipe = self.lock().unwrap();
+        pipe.closed = true;
+        wake(&mut pipe);
+    }
+
+    fn close_read(&self) {
+        let mut pipe = self.lock().unwrap();
+        pipe.dropped = true;
+        wake(&mut pipe);
+    }
+}
+
+struct PipeProducer<T>(SharedPipe<T>);
+
+impl<D: 'static, T: Payload> StreamProducer<D> for PipeProducer<T> {
+    type Item = T;
+    type Buffer = VecBuffer<T>;
+
+    fn poll_produce<'a>(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        store: StoreContextMut<'a, D>,
+        mut destination: Destination<'a, Self::Item, Self::Buffer>,
+        finish: bool,
+    ) -> Poll<Result<StreamResult>> {
+        let mut pipe = self.0.lock().unwrap();
+        if !pipe.buf.is_empty() {
+            // Zero-length reads only want to know whether items are ready, so
+            // don't hand out any items in that case.
+            if destination.remaining(store) != Some(0) {
+                let items = pipe.buf.drain(..).collect::<Vec<_>>();
+                destination.set_buffer(items.into());
+            }
+            return Poll::Ready(Ok(StreamResult::Completed));
+        }
+        if pipe.closed {
+            return Poll::Ready(Ok(StreamResult::Dropped));
+        }
+        if finish {
+            return Poll::Ready(Ok(StreamResult::Cancelled));
+        }
+        pipe.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+impl<T> Drop for PipeProducer<T> {
+    fn drop(&mut self) {
+        self.0.lock().unwrap().dropped = true;
+    }
+}
+
+struct PipeConsumer<T>(SharedPipe<T>);
+
+impl<D: 'static, T: Payload> StreamConsumer<D> for PipeConsumer<T> {
+    type Item = T;
+
+    fn poll_consume(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        mut store: StoreContextMut<D>,
+        mut source: Source<'_, Self::Item>,
+        finish: bool,
+    ) -> Poll<Result<StreamResult>> {
+        let mut pipe = self.0.lock().unwrap();
+        if pipe.dropped {
+            return Poll::Ready(Ok(StreamResult::Dropped));
+        }
+        let capacity = READ_BUFFER_CAPACITY.saturating_sub(pipe.buf.len());
+        if capacity == 0 {
+            if finish {
+                return Poll::Ready(Ok(StreamResult::Cancelled));
+            }
+            pipe.waker = Some(cx.waker().clone());
+            return Poll::Pending;
+        }
+        let mut items = Vec::with_capacity(capacity);
+        source.read(&mut store, &mut items)?;
+        pipe.buf.extend(items);
+        Poll::Ready(Ok(StreamResult::Completed))
+    }
+}
+
+impl<T> Drop for PipeConsumer<T> {
+    fn drop(&mut self) {
+        self.0.lock().unwrap().closed = true;
+    }
+}
+
+struct PromiseProducer<T>(SharedPipe<T>);
+
+impl<D: 'static, T: Payload> FutureProducer<D> for PromiseProducer<T> {
+    type Item = T;
+
+    fn poll_produce(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        _store: StoreContextMut<D>,
+        finish: bool,
+    ) -> Poll<Result<Option<T>>> {
+        let mut pipe = self.0.lock().unwrap();
+        if let Some(item) = pipe.buf.pop_front() {
+            return Poll::Ready(Ok(Some(item)));
+        }
+        if finish {
+            return Poll::Ready(Ok(None));
+        }
+        // Like a guest dropping the writable end of a future it hasn't
+        // written to, this is an error for whoever is waiting on the value.
+        if pipe.closed {
+            return Poll::Ready(Err(format_err!(
+                "future write end was dropped without writing a value"
+            )));
+        }
+        pipe.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+impl<T> Drop for PromiseProducer<T> {
+    fn drop(&mut self) {
+        self.0.lock().unwrap().dropped = true;
+    }
+}
+
+struct PromiseConsumer<T>(SharedPipe<T>);
+
+impl<D: 'static, T: Payload> FutureConsumer<D> for PromiseConsumer<T> {
+    type Item = T;
+
+    fn poll_consume(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        mut store: StoreContextMut<D>,
+        mut source: Source<'_, Self::Item>,
+        _finish: bool,
+    ) -> Poll<Result<()>> {
+        let mut item = None;
+        source.read(&mut store, &mut item)?;
+        if let Some(item) = item {
+            let mut pipe = self.0.lock().unwrap();
+            pipe.buf.push_back(item);
+            pipe.closed = true;
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+fn new_future<T: Payload>(
+    store: &mut WasmtimeStoreContextMut<'_>,
+) -> Result<(FutureAny, Box<dyn PipeEnd>)> {
+    let pipe = SharedPipe::<T>::default();
+    let reader = FutureReader::new(&mut *store, PromiseProducer(pipe.clone()))?;
+    let future = FutureAny::try_from_future_reader(&mut *store, reader)?;
+    Ok((future, Box::new(pipe)))
+}
+
+fn read_future<T: Payload>(
+    store: &mut WasmtimeStoreContextMut<'_>,
+    future: FutureAny,
+) -> Result<Box<dyn PipeEnd>> {
+    let pipe = SharedPipe::<T>::default();
+    future
+        .try_into_future_reader::<T>()?
+        .pipe(&mut *store, PromiseConsumer(pipe.clone()))?;
+    Ok(Box::new(pipe))
+}
+
+fn new_stream<T: Payload>(
+    store: &mut WasmtimeStoreContextMut<'_>,
+) -> Result<(StreamAny, Box<dyn PipeEnd>)> {
+    let pipe = SharedPipe::<T>::default();
+    let reader = StreamReader::new(&mut *store, PipeProducer(pipe.clone()))?;
+    let stream = StreamAny::try_from_stream_reader(&mut *store, reader)?;
+    Ok((stream, Box::new(pipe)))
+}
+
+fn read_stream<T: Payload>(
+    store: &mut WasmtimeStoreContextMut<'_>,
+    stream: StreamAny,
+) -> Result<Box<dyn PipeEnd>> {
+    let pipe = SharedPipe::<T>::default();
+    stream
+        .try_into_stream_reader::<T>()?
+        .pipe(&mut *store, PipeConsumer(pipe.clone()))?;
+    Ok(Box::new(pipe))
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wasmtime_component_future_clone(
+    future: &wasmtime_component_future_t,
+) -> Box<wasmtime_component_future_t> {
+    Box::new(future.clone())
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wasmtime_component_future_close(
+    store: WasmtimeStoreContextMut<'_>,
+    future: &mut wasmtime_component_future_t,
+) -> Option<Box<wasmtime_error_t>> {
+    handle_result(future.future.close(store), |()| ())
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wasmtime_component_future_delete(
+    _future: Option<Box<wasmtime_component_future_t>>,
+) {
+}
+
+pub struct wasmtime_component_future_writer_t {
+    pipe: Box<dyn PipeEnd>,
+}
+
+impl Drop for wasmtime_component_future_writer_t {
+    fn drop(&mut self) {
+        self.pipe.close_write();
+    }
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wasmtime_component_future_new(
+    mut store: WasmtimeStoreContextMut<'_>,
+    payload: Option<&wasmtime_component_valkind_t>,
+    future_ret: &mut MaybeUninit<Box<wasmtime_component_future_t>>,
+    writer_ret: &mut MaybeUninit<Box<wasmtime_component_future_writer_t>>,
+) -> Option<Box<wasmtime_error_t>> {
+    let result = with_payload!(payload.copied(), new_future(&mut store));
+    handle_result(result, |(future, pipe)| {
+        future_ret.write(Box::new(wasmtime_component_future_t { future }));
+        writer_ret.write(Box::new(wasmtime_component_future_writer_t { pipe }));
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wasmtime_component_future_writer_write(
+    writer: &wasmtime_component_future_writer_t,
+    value: Option<&wasmtime_component_val_t>,
+    closed_ret: &mut bool,
+) -> Option<Box<wasmtime_error_t>> {
+    let result = writer.pipe.write(value.map(std::slice::from_ref), 1, true);
+    handle_result(result, |open| *closed_ret = !open)
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wasmtime_component_future_writer_delete(
+    _writer: Option<Box<wasmtime_component_future_writer_t>>,
+) {
+}
+
+pub struct wasmtime_component_future_reader_t {
+    pipe: Box<dyn PipeEnd>,
+}
+
+impl Drop for wasmtime_component_future_reader_t {
+    fn drop(&mut self) {
+        self.pipe.close_read();
+    }
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wasmtime_component_future_read(
+    mut store: WasmtimeStoreContextMut<'_>,
+    future: &wasmtime_component_future_t,
+    payload: Option<&wasmtime_component_valkind_t>,
+    reader_ret: &mut MaybeUninit<Box<wasmtime_component_future_reader_t>>,
+) -> Option<Box<wasmtime_error_t>> {
+    let future = future.future.clone();
+    let result = with_payload!(payload.copied(), read_future(&mut store, future));
+    handle_result(result, |pipe| {
+        reader_ret.write(Box::new(wasmtime_component_future_reader_t { pipe }));
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wasmtime_component_future_reader_read(
+    reader: &wasmtime_component_future_reader_t,
+    value_ret: Option<&mut MaybeUninit<wasmtime_component_val_t>>,
+) -> bool {
+    match reader.pipe.read(1).pop() {
+        Some(value) => {
+            if let (Some(value), Some(value_ret)) = (value, value_ret) {
+                value_ret.write(value);
+            }
+            true
+        }
+        None => false,
+    }
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wasmtime_component_future_reader_delete(
+    _reader: Option<Box<wasmtime_component_future_reader_t>>,
+) {
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wasmtime_component_stream_clone(
+    stream: &wasmtime_component_stream_t,
+) -> Box<wasmtime_component_stream_t> {
+    Box::new(stream.clone())
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wasmtime_component_stream_close(
+    store: WasmtimeStoreContextMut<'_>,
+    stream: &mut wasmtime_component_stream_t,
+) -> Option<Box<wasmtime_error_t>> {
+    handle_result(stream.stream.close(store), |()| ())
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wasmtime_component_stream_delete(
+    _stream: Option<Box<wasmtime_component_stream_t>>,
+) {
+}
+
+pub struct wasmtime_component_stream_writer_t {
+    pipe: Box<dyn PipeEnd>,
+}
+
+impl Drop for wasmtime_component_stream_writer_t {
+    fn drop(&mut self) {
+        self.pipe.close_write();
+    }
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wasmtime_component_stream_new(
+    mut store: WasmtimeStoreContextMut<'_>,
+    payload: Option<&wasmtime_component_valkind_t>,
+    stream_ret: &mut MaybeUninit<Box<wasmtime_component_stream_t>>,
+    writer_ret: &mut MaybeUninit<Box<wasmtime_component_stream_writer_t>>,
+) -> Option<Box<wasmtime_error_t>> {
+    let result = with_payload!(payload.copied(), new_stream(&mut store));
+    handle_result(result, |(stream, pipe)| {
+        stream_ret.write(Box::new(wasmtime_component_stream_t { stream }));
+        writer_ret.write(Box::new(wasmtime_component_stream_writer_t { pipe }));
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn wasmtime_component_stream_writer_write(
+    writer: &wasmtime_component_stream_writer_t,
+    items: *const wasmtime_component_val_t,
+    len: usize,
+    closed_ret: &mut bool,
+) -> Option<Box<wasmtime_error_t>> {
+    let items = (!items.is_null()).then(|| unsafe { crate::slice_from_raw_parts(items, len) });
+    let result = writer.pipe.write(items, len, false);
+    handle_result(result, |open| *closed_ret = !open)
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wasmtime_component_stream_writer_delete(
+    _writer: Option<Box<wasmtime_component_stream_writer_t>>,
+) {
+}
+
+pub struct wasmtime_component_stream_reader_t {
+    pipe: Box<dyn PipeEnd>,
+}
+
+impl Drop for wasmtime_component_stream_reader_t {
+    fn drop(&mut self) {
+        self.pipe.close_read();
+    }
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wasmtime_component_stream_read(
+    mut store: WasmtimeStoreContextMut<'_>,
+    stream: &wasmtime_component_stream_t,
+    payload: Option<&wasmtime_component_valkind_t>,
+    reader_ret: &mut MaybeUninit<Box<wasmtime_component_stream_reader_t>>,
+) -> Option<Box<wasmtime_error_t>> {
+    let stream = stream.stream.clone();
+    let result = with_payload!(payload.copied(), read_stream(&mut store, stream));
+    handle_result(result, |pipe| {
+        reader_ret.write(Box::new(wasmtime_component_stream_reader_t { pipe }));
+    })
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn wasmtime_component_stream_reader_read(
+    reader: &wasmtime_component_stream_reader_t,
+    items: *mut MaybeUninit<wasmtime_component_val_t>,
+    len: usize,
+) -> usize {
+    let received = reader.pipe.read(len);
+    let count = received.len();
+    if !items.is_null() {
+        let items = unsafe { crate::slice_from_raw_parts_mut(items, len) };
+        for (dst, src) in items.iter_mut().zip(received) {
+            if let Some(src) = src {
+                dst.write(src);
+            }
+        }
+    }
+    count
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wasmtime_component_stream_reader_done(
+    reader: &wasmtime_component_stream_reader_t,
+) -> bool {
+    reader.pipe.done()
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wasmtime_component_stream_reader_delete(
+    _reader: Option<Box<wasmtime_component_stream_reader_t>>,
+) {
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wasmtime_component_error_context_clone(
+    error_context: &wasmtime_component_error_context_t,
+) -> Box<wasmtime_component_error_context_t> {
+    Box::new(error_context.clone())
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wasmtime_component_error_context_delete(
+    _error_context: Option<Box<wasmtime_component_error_context_t>>,
+) {
+}
@@ -1,13 +1,19 @@
 mod component;
+#[cfg(feature = "component-model-async")]
+mod concurrent;
 mod func;
 mod instance;
 mod linker;
+mod resource;
 mod types;
 mod val;
 
 pub use component::*;
+#[cfg(feature = "component-model-async")]
+pub use concurrent::*;
 pub use func::*;
 pub use instance::*;
 pub use linker::*;
+pub use resource::*;
 pub use types::*;
 pub use val::*;
@@ -0,0 +1,133 @@
+use crate::{
+    ForeignData, WasmtimeStoreContextMut, handle_result, wasmtime_component_linker_instance_t,
+    wasmtime_component_resource_host_t, wasmtime_component_resource_type_t, wasmtime_error_t,
+};
+use std::collections::HashMap;
+use std::ffi::c_void;
+use std::mem::MaybeUninit;
+use wasmtime::component::ResourceDynamic;
+use wasmtime::{Result, bail, format_err};
+
+/// Per-store table of host data associated with resources created through
+/// `wasmtime_component_resource_host_new_with_data`.
+///
+/// Reps are allocated from a counter and never reused, so a stale handle,
+/// such as a borrow which outlived its call, can never alias a newer
+/// resource.
+#[derive(Default)]
+pub struct HostResourceTable {
+    next_rep: u32,
+    entries: HashMap<u32, HostResourceEntry>,
+}
+
+struct HostResourceEntry {
+    ty: u32,
+    data: ForeignData,
+}
+
+impl HostResourceTable {
+    fn push(&mut self, ty: u32, data: ForeignData) -> Result<u32> {
+        let rep = self.next_rep;
+        self.next_rep = rep
+            .checked_add(1)
+            .ok_or_else(|| format_err!("host resource reps exhausted"))?;
+        self.entries.insert(rep, HostResourceEntry { ty, data });
+        Ok(rep)
+    }
+
+    fn get(&self, resource: &ResourceDynamic) -> Result<*mut c_void> {
+        let rep = resource.rep();
+        let Some(entry) = self.entries.get(&rep) else {
+            bail!("host resource {rep} has no associated data or was already dropped");
+        };
+        if entry.ty != resource.ty() {
+            bail!(
+                "host resource {rep} has type {} but was used as type {}",
+                entry.ty,
+                resource.ty()
+            );
+        }
+        Ok(entry.data.data)
+    }
+
+    fn remove(&mut self, rep: u32) -> Result<()> {
+        match self.entries.remove(&rep) {
+            // The `ForeignData` finalizer runs as the entry is dropped here.
+            Some(_) => Ok(()),
+            None => bail!("host resource {rep} has no associated data or was already dropped"),
+        }
+    }
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wasmtime_component_resource_host_new_with_data(
+    mut store: WasmtimeStoreContextMut<'_>,
+    ty: u32,
+    data: *mut c_void,
+    finalizer: Option<extern "C" fn(*mut c_void)>,
+    ret: &mut MaybeUninit<Box<wasmtime_component_resource_host_t>>,
+) -> Option<Box<wasmtime_error_t>> {
+    let data = ForeignData { data, finalizer };
+    let result = store.data_mut().host_resources.push(ty, data);
+    handle_result(result, |rep| {
+        ret.write(Box::new(wasmtime_component_resource_host_t {
+            resource: ResourceDynamic::new_own(rep, ty),
+        }));
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wasmtime_component_resource_host_data(
+    store: WasmtimeStoreContextMut<'_>,
+    resource: &wasmtime_component_resource_host_t,
+    data: &mut *mut c_void,
+) -> Option<Box<wasmtime_error_t>> {
+    let result = store.data().host_resources.get(&resource.resource);
+    handle_result(result, |d| *data = d)
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wasmtime_component_resource_host_borrow(
+    resource: &wasmtime_component_resource_host_t,
+) -> Box<wasmtime_component_resource_host_t> {
+    Box::new(wasmtime_component_resource_host_t {
+        resource: ResourceDynamic::new_borrow(resource.resource.rep(), resource.resource.ty()),
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wasmtime_component_resource_host_drop_data(
+    mut store: WasmtimeStoreContextMut<'_>,
+    resource: &wasmtime_component_resource_host_t,
+) -> Option<Box<wasmtime_error_t>> {
+    let result = (|| {
+        if !resource.resource.owned() {
+            bail!("cannot drop the data of a borrowed host resource");
+        }
+        let table = &mut store.data_mut().host_resources;
+        table.get(&resource.resource)?;
+        table.remove(resource.resource.rep())
+    })();
+    handle_result(result, |()| ())
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn wasmtime_component_linker_instance_add_resource_with_data(
+    linker_instance: &mut wasmtime_component_linker_instance_t,
+    name: *const u8,
+    name_len: usize,
+    ty: &wasmtime_component_resource_type_t,
+) -> Option<Box<wasmtime_error_t>> {
+    let name = unsafe { crate::slice_from_raw_parts(name, name_len) };
+    let Ok(name) = std::str::from_utf8(name) else {
+        return crate::bad_utf8();
+    };
+
+    let result = linker_instance
+        .linker_instance
+        .resource(name, ty.ty, |mut ctx, rep| {
+            ctx.data_mut().host_resources.remove(rep)
+        });
+
+    handle_result(result, |_| ())
+}
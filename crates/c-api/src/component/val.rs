@@ -248,6 +248,21 @@ impl From<&Result<Option<Box<Val>>, Option<Box<Val>>>> for wasmtime_component_va
     }
 }
 
+pub type wasmtime_component_valkind_t = u8;
+pub const WASMTIME_COMPONENT_BOOL: wasmtime_component_valkind_t = 0;
+pub const WASMTIME_COMPONENT_S8: wasmtime_component_valkind_t = 1;
+pub const WASMTIME_COMPONENT_U8: wasmtime_component_valkind_t = 2;
+pub const WASMTIME_COMPONENT_S16: wasmtime_component_valkind_t = 3;
+pub const WASMTIME_COMPONENT_U16: wasmtime_component_valkind_t = 4;
+pub const WASMTIME_COMPONENT_S32: wasmtime_component_valkind_t = 5;
+pub const WASMTIME_COMPONENT_U32: wasmtime_component_valkind_t = 6;
+pub const WASMTIME_COMPONENT_S64: wasmtime_component_valkind_t = 7;
+pub const WASMTIME_COMPONENT_U64: wasmtime_component_valkind_t = 8;
+pub const WASMTIME_COMPONENT_F32: wasmtime_component_valkind_t = 9;
+pub const WASMTIME_COMPONENT_F64: wasmtime_component_valkind_t = 10;
+pub const WASMTIME_COMPONENT_CHAR: wasmtime_component_valkind_t = 11;
+pub const WASMTIME_COMPONENT_STRING: wasmtime_component_valkind_t = 12;
+
 #[repr(C, u8)]
 #[derive(Clone)]
 pub enum wasmtime_component_val_t {
@@ -275,6 +290,12 @@ pub enum wasmtime_component_val_t {
     Resource(Box<wasmtime_component_resource_any_t>),
     Map(wasmtime_component_valmap_t),
     FixedLengthList(wasmtime_component_vallist_t),
+    #[cfg(feature = "component-model-async")]
+    Future(Box<super::wasmtime_component_future_t>),
+    #[cfg(feature = "component-model-async")]
+    Stream(Box<super::wasmtime_component_stream_t>),
+    #[cfg(feature = "component-model-async")]
+    ErrorContext(Box<super::wasmtime_component_error_context_t>),
 }
 
 // Safety: the C API async contract (documented in async.h) guarantees that
@@ -323,6 +344,12 @@ impl From<&wasmtime_component_val_t> for Val {
             wasmtime_component_val_t::Map(x) => Val::Map(x.into()),
             wasmtime_component_val_t::Resource(x) => Val::Resource(x.resource),
             wasmtime_component_val_t::FixedLengthList(x) => Val::FixedLengthList(x.into()),
+            #[cfg(feature = "component-model-async")]
+            wasmtime_component_val_t::Future(x) => Val::Future(x.future.clone()),
+            #[cfg(feature = "component-model-async")]
+            wasmtime_component_val_t::Stream(x) => Val::Stream(x.stream.clone()),
+            #[cfg(feature = "component-model-async")]
+            wasmtime_component_val_t::ErrorContext(x) => Val::ErrorContext(x.error_context.clone()),
         }
     }
 }
@@ -362,9 +389,28 @@ impl From<&Val> for wasmtime_component_val_t {
                     resource: *resource_any,
                 }))
             }
-            Val::Future(_) => todo!(),
-            Val::Stream(_) => todo!(),
-            Val::ErrorContext(_) => todo!(),
+            #[cfg(feature = "component-model-async")]
+            Val::Future(future) => {
+                wasmtime_component_val_t::Future(Box::new(super::wasmtime_component_future_t {
+                    future: future.clone(),
+                }))
+            }
+            #[cfg(feature = "component-model-async")]
+            Val::Stream(stream) => {
+                wasmtime_component_val_t::Stream(Box::new(super::wasmtime_component_stream_t {
+                    stream: stream.clone(),
+                }))
+            }
+            #[cfg(feature = "component-model-async")]
+            Val::ErrorContext(error_context) => wasmtime_component_val_t::ErrorContext(Box::new(
+                super::wasmtime_component_error_context_t {
+                    error_context: error_context.clone(),
+                },
+            )),
+            #[cfg(not(feature = "component-model-async"))]
+            Val::Future(_) | Val::Stream(_) | Val::ErrorContext(_) => {
+                unreachable!("component-model-async support is disabled")
+            }
             Val::FixedLengthList(ty) => wasmtime_component_val_t::FixedLengthList(
                 wasmtime_component_vallist_t::from(ty.as_ref()),
             ),
@@ -446,7 +492,7 @@ pub extern "C" fn wasmtime_component_resource_any_delete(
 
 #[repr(C)]
 pub struct wasmtime_component_resource_host_t {
-    resource: ResourceDynamic,
+    pub(crate) resource: ResourceDynamic,
 }
 
 impl wasmtime_component_resource_host_t {
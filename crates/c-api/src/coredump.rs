@@ -0,0 +1,74 @@
+use crate::{
+    WasmtimeStoreContextMut, wasm_byte_vec_t, wasm_config_t, wasm_frame_vec_t, wasm_trap_t,
+    wasmtime_error_t,
+};
+use wasmtime::{Error, WasmCoreDump};
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wasmtime_config_coredump_on_trap_set(c: &mut wasm_config_t, enable: bool) {
+    c.config.coredump_on_trap(enable);
+}
+
+fn serialize(
+    error: &Error,
+    store: WasmtimeStoreContextMut<'_>,
+    name: *const u8,
+    name_len: usize,
+    out: &mut wasm_byte_vec_t,
+) -> bool {
+    let Some(dump) = error.downcast_ref::<WasmCoreDump>() else {
+        return false;
+    };
+    let name = unsafe { crate::slice_from_raw_parts(name, name_len) };
+    let Ok(name) = std::str::from_utf8(name) else {
+        return false;
+    };
+    out.set_buffer(dump.serialize(store, name));
+    true
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn wasmtime_trap_coredump_serialize(
+    trap: &wasm_trap_t,
+    store: WasmtimeStoreContextMut<'_>,
+    name: *const u8,
+    name_len: usize,
+    out: &mut wasm_byte_vec_t,
+) -> bool {
+    serialize(&trap.error, store, name, name_len, out)
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn wasmtime_error_coredump_serialize(
+    error: &wasmtime_error_t,
+    store: WasmtimeStoreContextMut<'_>,
+    name: *const u8,
+    name_len: usize,
+    out: &mut wasm_byte_vec_t,
+) -> bool {
+    serialize(&error.error, store, name, name_len, out)
+}
+
+fn frames<'a>(error: &'a Error, out: &mut wasm_frame_vec_t<'a>) -> bool {
+    let Some(dump) = error.downcast_ref::<WasmCoreDump>() else {
+        return false;
+    };
+    crate::trap::frame_vec(dump.frames(), out);
+    true
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wasmtime_trap_coredump_frames<'a>(
+    trap: &'a wasm_trap_t,
+    out: &mut wasm_frame_vec_t<'a>,
+) -> bool {
+    frames(&trap.error, out)
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wasmtime_error_coredump_frames<'a>(
+    error: &'a wasmtime_error_t,
+    out: &mut wasm_frame_vec_t<'a>,
+) -> bool {
+    frames(&error.error, out)
+}
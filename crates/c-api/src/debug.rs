@@ -0,0 +1,281 @@
+use crate::{
+    ForeignData, WasmtimeStoreContextMut, WasmtimeStoreData, handle_result, wasm_config_t,
+    wasmtime_error_t, wasmtime_module_t, wasmtime_store_t, wasmtime_val_t,
+};
+use std::ffi::c_void;
+use std::mem::MaybeUninit;
+use std::sync::Arc;
+use wasmtime::{
+    AsContextMut, DebugEvent, DebugHandler, FrameHandle, Instance, ModulePC, Result,
+    StoreContextMut, Val, ensure,
+};
+
+#[cfg(feature = "gc")]
+use wasmtime::RootScope;
+
+pub type wasmtime_debug_event_kind_t = u8;
+pub const WASMTIME_DEBUG_EVENT_HOSTCALL_ERROR: wasmtime_debug_event_kind_t = 0;
+pub const WASMTIME_DEBUG_EVENT_EXCEPTION: wasmtime_debug_event_kind_t = 1;
+pub const WASMTIME_DEBUG_EVENT_TRAP: wasmtime_debug_event_kind_t = 2;
+pub const WASMTIME_DEBUG_EVENT_BREAKPOINT: wasmtime_debug_event_kind_t = 3;
+pub const WASMTIME_DEBUG_EVENT_EPOCH_YIELD: wasmtime_debug_event_kind_t = 4;
+
+#[repr(C)]
+pub struct wasmtime_debug_event_t {
+    kind: wasmtime_debug_event_kind_t,
+    trap_code: u8,
+    message: *const u8,
+    message_len: usize,
+}
+
+pub type wasmtime_debug_handler_t =
+    extern "C" fn(*mut c_void, WasmtimeStoreContextMut<'_>, &wasmtime_debug_event_t);
+
+#[derive(Clone)]
+struct CDebugHandler {
+    callback: wasmtime_debug_handler_t,
+    foreign: Arc<ForeignData>,
+}
+
+impl DebugHandler for CDebugHandler {
+    type Data = WasmtimeStoreData;
+
+    fn handle(
+        &self,
+        store: StoreContextMut<'_, WasmtimeStoreData>,
+        event: DebugEvent<'_>,
+    ) -> impl Future<Output = ()> + Send {
+        let message = match &event {
+            DebugEvent::HostcallError(error) => format!("{error:?}"),
+            _ => String::new(),
+        };
+        let (kind, trap_code) = match event {
+            DebugEvent::HostcallError(_) => (WASMTIME_DEBUG_EVENT_HOSTCALL_ERROR, 0),
+            DebugEvent::Exception(_) => (WASMTIME_DEBUG_EVENT_EXCEPTION, 0),
+            DebugEvent::Trap(trap) => (WASMTIME_DEBUG_EVENT_TRAP, trap as u8),
+            DebugEvent::Breakpoint => (WASMTIME_DEBUG_EVENT_BREAKPOINT, 0),
+            DebugEvent::EpochYield => (WASMTIME_DEBUG_EVENT_EPOCH_YIELD, 0),
+        };
+        let event = wasmtime_debug_event_t {
+            kind,
+            trap_code,
+            message: message.as_ptr(),
+            message_len: message.len(),
+        };
+        (self.callback)(self.foreign.data, store, &event);
+        async {}
+    }
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wasmtime_config_guest_debug_set(c: &mut wasm_config_t, enable: bool) {
+    c.config.guest_debug(enable);
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wasmtime_store_set_debug_handler(
+    store: &mut wasmtime_store_t,
+    callback: wasmtime_debug_handler_t,
+    data: *mut c_void,
+    finalizer: Option<extern "C" fn(*mut c_void)>,
+) {
+    store.store.set_debug_handler(CDebugHandler {
+        callback,
+        foreign: Arc::new(ForeignData { data, finalizer }),
+    });
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wasmtime_store_clear_debug_handler(store: &mut wasmtime_store_t) {
+    store.store.clear_debug_handler();
+}
+
+fn edit_breakpoints(
+    store: WasmtimeStoreContextMut<'_>,
+    edit: impl FnOnce(&mut wasmtime::BreakpointEdit<'_>) -> Result<()>,
+) -> Option<Box<wasmtime_error_t>> {
+    let result = match store.edit_breakpoints() {
+        Some(mut breakpoints) => edit(&mut breakpoints),
+        None => Err(wasmtime::format_err!("guest debugging is not enabled")),
+    };
+    handle_result(result, |()| ())
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wasmtime_context_add_breakpoint(
+    store: WasmtimeStoreContextMut<'_>,
+    module: &wasmtime_module_t,
+    pc: u32,
+) -> Option<Box<wasmtime_error_t>> {
+    edit_breakpoints(store, |edit| {
+        edit.add_breakpoint(&module.module, ModulePC::new(pc))
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wasmtime_context_remove_breakpoint(
+    store: WasmtimeStoreContextMut<'_>,
+    module: &wasmtime_module_t,
+    pc: u32,
+) -> Option<Box<wasmtime_error_t>> {
+    edit_breakpoints(store, |edit| {
+        edit.remove_breakpoint(&module.module, ModulePC::new(pc))
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wasmtime_context_single_step(
+    store: WasmtimeStoreContextMut<'_>,
+    enabled: bool,
+) -> Option<Box<wasmtime_error_t>> {
+    edit_breakpoints(store, |edit| edit.single_step(enabled))
+}
+
+pub struct wasmtime_debug_frame_t {
+    frame: FrameHandle,
+}
+
+wasmtime_c_api_macros::declare_own!(wasmtime_debug_frame_t);
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wasmtime_context_debug_exit_frame(
+    mut store: WasmtimeStoreContextMut<'_>,
+    activation: usize,
+) -> Option<Box<wasmtime_debug_frame_t>> {
+    let frame = store.debug_exit_frames().nth(activation)?;
+    Some(Box::new(wasmtime_debug_frame_t { frame }))
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wasmtime_debug_frame_is_valid(
+    store: WasmtimeStoreContextMut<'_>,
+    frame: &wasmtime_debug_frame_t,
+) -> bool {
+    frame.frame.is_valid(store)
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wasmtime_debug_frame_parent(
+    store: WasmtimeStoreContextMut<'_>,
+    frame: &wasmtime_debug_frame_t,
+    parent: &mut *mut wasmtime_debug_frame_t,
+) -> Option<Box<wasmtime_error_t>> {
+    handle_result(frame.frame.parent(store), |frame| {
+        *parent = match frame {
+            Some(frame) => Box::into_raw(Box::new(wasmtime_debug_frame_t { frame })),
+            None => std::ptr::null_mut(),
+        };
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wasmtime_debug_frame_instance(
+    store: WasmtimeStoreContextMut<'_>,
+    frame: &wasmtime_debug_frame_t,
+    instance: &mut MaybeUninit<Instance>,
+) -> Option<Box<wasmtime_error_t>> {
+    handle_result(frame.frame.instance(store), |i| {
+        instance.write(i);
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wasmtime_debug_frame_module(
+    store: WasmtimeStoreContextMut<'_>,
+    frame: &wasmtime_debug_frame_t,
+    module: &mut *mut wasmtime_module_t,
+) -> Option<Box<wasmtime_error_t>> {
+    handle_result(frame.frame.module(store), |m| {
+        *module = match m {
+            Some(m) => Box::into_raw(Box::new(wasmtime_module_t { module: m.clone() })),
+            None => std::ptr::null_mut(),
+        };
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wasmtime_debug_frame_func_index_and_pc(
+    store: WasmtimeStoreContextMut<'_>,
+    frame: &wasmtime_debug_frame_t,
+    is_wasm: &mut bool,
+    func_index: &mut u32,
+    pc: &mut u32,
+) -> Option<Box<wasmtime_error_t>> {
+    handle_result(frame.frame.wasm_function_index_and_pc(store), |result| {
+        *is_wasm = result.is_some();
+        if let Some((func, module_pc)) = result {
+            *func_index = func.as_u32();
+            *pc = module_pc.raw();
+        }
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wasmtime_debug_frame_num_locals(
+    store: WasmtimeStoreContextMut<'_>,
+    frame: &wasmtime_debug_frame_t,
+    num: &mut u32,
+) -> Option<Box<wasmtime_error_t>> {
+    handle_result(frame.frame.num_locals(store), |n| *num = n)
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wasmtime_debug_frame_num_stacks(
+    store: WasmtimeStoreContextMut<'_>,
+    frame: &wasmtime_debug_frame_t,
+    num: &mut u32,
+) -> Option<Box<wasmtime_error_t>> {
+    handle_result(frame.frame.num_stacks(store), |n| *num = n)
+}
+
+fn frame_val(
+    store: WasmtimeStoreContextMut<'_>,
+    get: impl FnOnce(WasmtimeStoreContextMut<'_>) -> Result<Val>,
+    val: &mut MaybeUninit<wasmtime_val_t>,
+) -> Option<Box<wasmtime_error_t>> {
+    let mut store = store;
+
+    #[cfg(feature = "gc")]
+    let mut store = RootScope::new(&mut store);
+
+    let result = get(store.as_context_mut());
+    handle_result(result, |v| {
+        val.write(wasmtime_val_t::from_val(&mut store, v));
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wasmtime_debug_frame_local(
+    store: WasmtimeStoreContextMut<'_>,
+    frame: &wasmtime_debug_frame_t,
+    index: u32,
+    val: &mut MaybeUninit<wasmtime_val_t>,
+) -> Option<Box<wasmtime_error_t>> {
+    frame_val(
+        store,
+        |mut store| {
+            let num = frame.frame.num_locals(&mut store)?;
+            ensure!(index < num, "local index {index} is out of bounds");
+            frame.frame.local(store, index)
+        },
+        val,
+    )
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wasmtime_debug_frame_stack(
+    store: WasmtimeStoreContextMut<'_>,
+    frame: &wasmtime_debug_frame_t,
+    index: u32,
+    val: &mut MaybeUninit<wasmtime_val_t>,
+) -> Option<Box<wasmtime_error_t>> {
+    frame_val(
+        store,
+        |mut store| {
+            let num = frame.frame.num_stacks(&mut store)?;
+            ensure!(index < num, "operand stack index {index} is out of bounds");
+            frame.frame.stack(store, index)
+        },
+        val,
+    )
+}
@@ -52,3 +52,123 @@ pub extern "C" fn wasmtime_engine_increment_epoch(engine: &wasm_engine_t) {
 pub extern "C" fn wasmtime_engine_is_pulley(engine: &wasm_engine_t) -> bool {
     engine.engine.is_pulley()
 }
+
+#[cfg(feature = "pooling-allocator")]
+pub struct wasmtime_pooling_allocator_metrics_t {
+    metrics: wasmtime::PoolingAllocatorMetrics,
+}
+
+#[cfg(feature = "pooling-allocator")]
+wasmtime_c_api_macros::declare_own!(wasmtime_pooling_allocator_metrics_t);
+
+#[unsafe(no_mangle)]
+#[cfg(feature = "pooling-allocator")]
+pub extern "C" fn wasmtime_engine_pooling_allocator_metrics(
+    engine: &wasm_engine_t,
+) -> Option<Box<wasmtime_pooling_allocator_metrics_t>> {
+    let metrics = engine.engine.pooling_allocator_metrics()?;
+    Some(Box::new(wasmtime_pooling_allocator_metrics_t { metrics }))
+}
+
+#[unsafe(no_mangle)]
+#[cfg(feature = "pooling-allocator")]
+pub extern "C" fn wasmtime_pooling_allocator_metrics_core_instances(
+    m: &wasmtime_pooling_allocator_metrics_t,
+) -> u64 {
+    m.metrics.core_instances()
+}
+
+#[unsafe(no_mangle)]
+#[cfg(feature = "pooling-allocator")]
+pub extern "C" fn wasmtime_pooling_allocator_metrics_component_instances(
+    m: &wasmtime_pooling_allocator_metrics_t,
+) -> u64 {
+    m.metrics.component_instances()
+}
+
+#[unsafe(no_mangle)]
+#[cfg(feature = "pooling-allocator")]
+pub extern "C" fn wasmtime_pooling_allocator_metrics_memories(
+    m: &wasmtime_pooling_allocator_metrics_t,
+) -> usize {
+    m.metrics.memories()
+}
+
+#[unsafe(no_mangle)]
+#[cfg(feature = "pooling-allocator")]
+pub extern "C" fn wasmtime_pooling_allocator_metrics_tables(
+    m: &wasmtime_pooling_allocator_metrics_t,
+) -> usize {
+    m.metrics.tables()
+}
+
+#[unsafe(no_mangle)]
+#[cfg(all(feature = "pooling-allocator", feature = "async"))]
+pub extern "C" fn wasmtime_pooling_allocator_metrics_stacks(
+    m: &wasmtime_pooling_allocator_metrics_t,
+) -> usize {
+    m.metrics.stacks()
+}
+
+#[unsafe(no_mangle)]
+#[cfg(all(feature = "pooling-allocator", feature = "gc"))]
+pub extern "C" fn wasmtime_pooling_allocator_metrics_gc_heaps(
+    m: &wasmtime_pooling_allocator_metrics_t,
+) -> usize {
+    m.metrics.gc_heaps()
+}
+
+#[unsafe(no_mangle)]
+#[cfg(feature = "pooling-allocator")]
+pub extern "C" fn wasmtime_pooling_allocator_metrics_unused_warm_memories(
+    m: &wasmtime_pooling_allocator_metrics_t,
+) -> u32 {
+    m.metrics.unused_warm_memories()
+}
+
+#[unsafe(no_mangle)]
+#[cfg(feature = "pooling-allocator")]
+pub extern "C" fn wasmtime_pooling_allocator_metrics_unused_memory_bytes_resident(
+    m: &wasmtime_pooling_allocator_metrics_t,
+) -> usize {
+    m.metrics.unused_memory_bytes_resident()
+}
+
+#[unsafe(no_mangle)]
+#[cfg(feature = "pooling-allocator")]
+pub extern "C" fn wasmtime_pooling_allocator_metrics_unused_warm_tables(
+    m: &wasmtime_pooling_allocator_metrics_t,
+) -> u32 {
+    m.metrics.unused_warm_tables()
+}
+
+#[unsafe(no_mangle)]
+#[cfg(feature = "pooling-allocator")]
+pub extern "C" fn wasmtime_pooling_allocator_metrics_unused_table_bytes_resident(
+    m: &wasmtime_pooling_allocator_metrics_t,
+) -> usize {
+    m.metrics.unused_table_bytes_resident()
+}
+
+#[unsafe(no_mangle)]
+#[cfg(all(feature = "pooling-allocator", feature = "async"))]
+pub extern "C" fn wasmtime_pooling_allocator_metrics_unused_warm_stacks(
+    m: &wasmtime_pooling_allocator_metrics_t,
+) -> u32 {
+    m.metrics.unused_warm_stacks()
+}
+
+#[unsafe(no_mangle)]
+#[cfg(all(feature = "pooling-allocator", feature = "async"))]
+pub extern "C" fn wasmtime_pooling_allocator_metrics_unused_stack_bytes_resident(
+    m: &wasmtime_pooling_allocator_metrics_t,
+    bytes: &mut usize,
+) -> bool {
+    match m.metrics.unused_stack_bytes_resident() {
+        Some(b) => {
+            *bytes = b;
+            true
+        }
+        None => false,
+    }
+}
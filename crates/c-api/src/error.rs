@@ -3,7 +3,7 @@ use wasmtime::{Error, Result, format_err};
 
 #[repr(C)]
 pub struct wasmtime_error_t {
-    error: Error,
+    pub(crate) error: Error,
 }
 
 wasmtime_c_api_macros::declare_own!(wasmtime_error_t);
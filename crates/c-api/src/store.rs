@@ -3,8 +3,8 @@ use std::cell::UnsafeCell;
 use std::ffi::c_void;
 use std::sync::Arc;
 use wasmtime::{
-    AsContext, AsContextMut, Caller, Store, StoreContext, StoreContextMut, StoreLimits,
-    StoreLimitsBuilder, UpdateDeadline, Val,
+    AsContext, AsContextMut, Caller, ResourceLimiter, Result, Store, StoreContext, StoreContextMut,
+    StoreLimits, StoreLimitsBuilder, UpdateDeadline, Val,
 };
 
 // Store-related type aliases for `wasm.h` APIs. Not for use with `wasmtime.h`
@@ -97,6 +97,13 @@ pub struct WasmtimeStoreData {
 
     /// Limits for the store.
     pub store_limits: StoreLimits,
+
+    /// Callback-based limiter for the store, if configured.
+    resource_limiter: Option<CResourceLimiter>,
+
+    /// Host data of component resources created by the embedder.
+    #[cfg(feature = "component-model")]
+    pub(crate) host_resources: crate::HostResourceTable,
 }
 
 #[cfg(all(feature = "component-model", feature = "wasi"))]
@@ -138,6 +145,9 @@ pub extern "C" fn wasmtime_store_new(
                 hostcall_val_storage: Vec::new(),
                 wasm_val_storage: Vec::new(),
                 store_limits: StoreLimits::default(),
+                resource_limiter: None,
+                #[cfg(feature = "component-model")]
+                host_resources: Default::default(),
             },
         ),
     })
@@ -218,6 +228,70 @@ pub extern "C" fn wasmtime_store_limiter(
     store.store.limiter(|data| &mut data.store_limits);
 }
 
+pub type wasmtime_resource_limiter_growing_t =
+    extern "C" fn(*mut c_void, usize, usize, i64, &mut bool) -> Option<Box<wasmtime_error_t>>;
+
+struct CResourceLimiter {
+    memory_growing: wasmtime_resource_limiter_growing_t,
+    table_growing: wasmtime_resource_limiter_growing_t,
+    foreign: ForeignData,
+}
+
+impl CResourceLimiter {
+    fn growing(
+        &self,
+        callback: wasmtime_resource_limiter_growing_t,
+        current: usize,
+        desired: usize,
+        maximum: Option<usize>,
+    ) -> Result<bool> {
+        let maximum = maximum.map(|m| m as i64).unwrap_or(-1);
+        let mut allow = false;
+        match callback(self.foreign.data, current, desired, maximum, &mut allow) {
+            Some(err) => Err((*err).into()),
+            None => Ok(allow),
+        }
+    }
+}
+
+impl ResourceLimiter for CResourceLimiter {
+    fn memory_growing(
+        &mut self,
+        current: usize,
+        desired: usize,
+        maximum: Option<usize>,
+    ) -> Result<bool> {
+        self.growing(self.memory_growing, current, desired, maximum)
+    }
+
+    fn table_growing(
+        &mut self,
+        current: usize,
+        desired: usize,
+        maximum: Option<usize>,
+    ) -> Result<bool> {
+        self.growing(self.table_growing, current, desired, maximum)
+    }
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wasmtime_store_resource_limiter(
+    store: &mut wasmtime_store_t,
+    memory_growing: wasmtime_resource_limiter_growing_t,
+    table_growing: wasmtime_resource_limiter_growing_t,
+    data: *mut c_void,
+    finalizer: Option<extern "C" fn(*mut c_void)>,
+) {
+    store.store.data_mut().resource_limiter = Some(CResourceLimiter {
+        memory_growing,
+        table_growing,
+        foreign: ForeignData { data, finalizer },
+    });
+    store
+        .store
+        .limiter(|data| data.resource_limiter.as_mut().unwrap());
+}
+
 #[unsafe(no_mangle)]
 pub extern "C" fn wasmtime_context_get_data(store: WasmtimeStoreContext<'_>) -> *mut c_void {
     store.data().foreign.data
@@ -1,6 +1,6 @@
 use crate::{wasm_frame_vec_t, wasm_instance_t, wasm_name_t, wasm_store_t};
 use std::sync::OnceLock;
-use wasmtime::{Error, Trap, WasmBacktrace, format_err};
+use wasmtime::{Error, FrameInfo, Trap, WasmBacktrace, format_err};
 
 // Help ensure the Rust enum matches the C one.  If any of these assertions
 // fail, please update both this code and `trap.h` to sync them with
@@ -86,7 +86,7 @@ impl wasm_trap_t {
 #[repr(C)]
 #[derive(Clone)]
 pub struct wasm_frame_t<'a> {
-    trace: &'a WasmBacktrace,
+    frames: &'a [FrameInfo],
     idx: usize,
     func_name: OnceLock<Option<wasm_name_t>>,
     module_name: OnceLock<Option<wasm_name_t>>,
@@ -145,7 +145,7 @@ pub extern "C" fn wasm_trap_origin(raw: &wasm_trap_t) -> Option<Box<wasm_frame_t
     };
     if trace.frames().len() > 0 {
         Some(Box::new(wasm_frame_t {
-            trace,
+            frames: trace.frames(),
             idx: 0,
             func_name: OnceLock::new(),
             module_name: OnceLock::new(),
@@ -165,10 +165,14 @@ pub(crate) fn error_trace<'a>(error: &'a Error, out: &mut wasm_frame_vec_t<'a>)
         Some(trap) => trap,
         None => return out.set_buffer(Vec::new()),
     };
-    let vec = (0..trace.frames().len())
+    frame_vec(trace.frames(), out)
+}
+
+pub(crate) fn frame_vec<'a>(frames: &'a [FrameInfo], out: &mut wasm_frame_vec_t<'a>) {
+    let vec = (0..frames.len())
         .map(|idx| {
             Some(Box::new(wasm_frame_t {
-                trace,
+                frames,
                 idx,
                 func_name: OnceLock::new(),
                 module_name: OnceLock::new(),
@@ -190,7 +194,7 @@ pub extern "C" fn wasmtime_trap_code(raw: &wasm_trap_t, code: &mut u8) -> bool {
 
 #[unsafe(no_mangle)]
 pub extern "C" fn wasm_frame_func_index(frame: &wasm_frame_t<'_>) -> u32 {
-    frame.trace.frames()[frame.idx].func_index()
+    frame.frames[frame.idx].func_index()
 }
 
 #[unsafe(no_mangle)]
@@ -200,7 +204,7 @@ pub extern "C" fn wasmtime_frame_func_name<'a>(
     frame
         .func_name
         .get_or_init(|| {
-            frame.trace.frames()[frame.idx]
+            frame.frames[frame.idx]
                 .func_name()
                 .map(|s| wasm_name_t::from(s.to_string().into_bytes()))
         })
@@ -214,7 +218,7 @@ pub extern "C" fn wasmtime_frame_module_name<'a>(
     frame
         .module_name
         .get_or_init(|| {
-            frame.trace.frames()[frame.idx]
+            frame.frames[frame.idx]
                 .module()
                 .name()
                 .map(|s| wasm_name_t::from(s.to_string().into_bytes()))
@@ -224,9 +228,7 @@ pub extern "C" fn wasmtime_frame_module_name<'a>(
 
 #[unsafe(no_mangle)]
 pub extern "C" fn wasm_frame_func_offset(frame: &wasm_frame_t<'_>) -> usize {
-    frame.trace.frames()[frame.idx]
-        .func_offset()
-        .unwrap_or(usize::MAX)
+    frame.frames[frame.idx].func_offset().unwrap_or(usize::MAX)
 }
 
 #[unsafe(no_mangle)]
@@ -236,7 +238,7 @@ pub extern "C" fn wasm_frame_instance(_arg1: *const wasm_frame_t<'_>) -> *mut wa
 
 #[unsafe(no_mangle)]
 pub extern "C" fn wasm_frame_module_offset(frame: &wasm_frame_t<'_>) -> usize {
-    frame.trace.frames()[frame.idx]
+    frame.frames[frame.idx]
         .module_offset()
         .unwrap_or(usize::MAX)
 }
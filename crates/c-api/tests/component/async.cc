@@ -182,4 +182,367 @@ TEST(component_async, host_func_async) {
   EXPECT_EQ(results[0].get_u32(), 69);
 }
 
+TEST(component_async, streams_and_futures) {
+  Config config;
+  config.concurrency_support(true);
+  Engine engine(std::move(config));
+  Store store(engine);
+  auto context = store.context();
+
+  const wasmtime_component_valkind_t u8 = WASMTIME_COMPONENT_U8;
+  wasmtime_component_stream_t *stream = nullptr;
+  wasmtime_component_stream_writer_t *writer = nullptr;
+  auto *err =
+      wasmtime_component_stream_new(context.capi(), &u8, &stream, &writer);
+  ASSERT_EQ(err, nullptr);
+  wasmtime_component_val_t items[3];
+  for (uint8_t i = 0; i < 3; i++) {
+    items[i].kind = WASMTIME_COMPONENT_U8;
+    items[i].of.u8 = i + 1;
+  }
+  bool closed = true;
+  err = wasmtime_component_stream_writer_write(writer, items, 3, &closed);
+  EXPECT_EQ(err, nullptr);
+  EXPECT_FALSE(closed);
+
+  // Items must match the payload type of the stream.
+  items[0].kind = WASMTIME_COMPONENT_U32;
+  items[0].of.u32 = 1;
+  err = wasmtime_component_stream_writer_write(writer, items, 1, &closed);
+  EXPECT_NE(err, nullptr);
+  wasmtime_error_delete(err);
+
+  // Streams round-trip through component values.
+  wasmtime_component_val_t val;
+  val.kind = WASMTIME_COMPONENT_STREAM;
+  val.of.stream = wasmtime_component_stream_clone(stream);
+  wasmtime_component_val_t copy;
+  wasmtime_component_val_clone(&val, &copy);
+  EXPECT_EQ(copy.kind, WASMTIME_COMPONENT_STREAM);
+  wasmtime_component_val_delete(&copy);
+  wasmtime_component_val_delete(&val);
+
+  err = wasmtime_component_stream_close(context.capi(), stream);
+  EXPECT_EQ(err, nullptr);
+  err = wasmtime_component_stream_close(context.capi(), stream);
+  EXPECT_NE(err, nullptr);
+  wasmtime_error_delete(err);
+  wasmtime_component_stream_writer_delete(writer);
+  wasmtime_component_stream_delete(stream);
+
+  wasmtime_component_future_t *future = nullptr;
+  wasmtime_component_future_writer_t *future_writer = nullptr;
+  err = wasmtime_component_future_new(context.capi(), nullptr, &future,
+                                      &future_writer);
+  ASSERT_EQ(err, nullptr);
+  err = wasmtime_component_future_close(context.capi(), future);
+  EXPECT_EQ(err, nullptr);
+  err = wasmtime_component_future_writer_write(future_writer, nullptr, &closed);
+  EXPECT_EQ(err, nullptr);
+  EXPECT_TRUE(closed);
+  err = wasmtime_component_future_writer_write(future_writer, nullptr, &closed);
+  EXPECT_NE(err, nullptr);
+  wasmtime_error_delete(err);
+  wasmtime_component_future_writer_delete(future_writer);
+  wasmtime_component_future_delete(future);
+
+  // Only primitive and string payloads are supported.
+  const wasmtime_component_valkind_t list = WASMTIME_COMPONENT_LIST;
+  err = wasmtime_component_future_new(context.capi(), &list, &future,
+                                      &future_writer);
+  EXPECT_NE(err, nullptr);
+  wasmtime_error_delete(err);
+}
+
+// A component which sums a `future<u32>` and a `stream<u32>` from the host,
+// and then sends a `stream<u32>` and a `future<string>` back to the host
+// through its `sink` import.
+static constexpr auto transmit_component_text = std::string_view{
+    R"END(
+(component
+    (type $fu32 (future u32))
+    (type $su32 (stream u32))
+    (type $fstring (future string))
+    (import "sink" (func $sink (param "s" $su32) (param "f" $fstring)))
+
+    (core module $libc
+        (memory (export "memory") 1)
+        (data (i32.const 64) "hello")
+    )
+    (core instance $libc (instantiate $libc))
+
+    (core func $task-return (canon task.return (result u32)))
+    (core func $future-read (canon future.read $fu32 (memory $libc "memory")))
+    (core func $future-drop-readable (canon future.drop-readable $fu32))
+    (core func $stream-read (canon stream.read $su32 (memory $libc "memory")))
+    (core func $stream-drop-readable (canon stream.drop-readable $su32))
+    (core func $stream-new (canon stream.new $su32))
+    (core func $stream-write (canon stream.write $su32 (memory $libc "memory")))
+    (core func $stream-drop-writable (canon stream.drop-writable $su32))
+    (core func $future-new (canon future.new $fstring))
+    (core func $future-write (canon future.write $fstring (memory $libc "memory")))
+    (core func $future-drop-writable (canon future.drop-writable $fstring))
+    (core func $sink (canon lower (func $sink)))
+
+    (core module $m
+        (import "libc" "memory" (memory 1))
+        (import "" "task.return" (func $task-return (param i32)))
+        (import "" "future.read" (func $future-read (param i32 i32) (result i32)))
+        (import "" "future.drop-readable" (func $future-drop-readable (param i32)))
+        (import "" "stream.read" (func $stream-read (param i32 i32 i32) (result i32)))
+        (import "" "stream.drop-readable" (func $stream-drop-readable (param i32)))
+        (import "" "stream.new" (func $stream-new (result i64)))
+        (import "" "stream.write" (func $stream-write (param i32 i32 i32) (result i32)))
+        (import "" "stream.drop-writable" (func $stream-drop-writable (param i32)))
+        (import "" "future.new" (func $future-new (result i64)))
+        (import "" "future.write" (func $future-write (param i32 i32) (result i32)))
+        (import "" "future.drop-writable" (func $future-drop-writable (param i32)))
+        (import "" "sink" (func $sink (param i32 i32)))
+
+        (func (export "run") (param $f i32) (param $s i32)
+            (local $sum i32) (local $r i32) (local $n i32) (local $p i32)
+            (local $stream i64) (local $future i64)
+
+            (if (call $future-read (local.get $f) (i32.const 0))
+                (then unreachable))
+            (call $future-drop-readable (local.get $f))
+            (local.set $sum (i32.load (i32.const 0)))
+
+            ;; Read until the host ends the stream.
+            (loop $read
+                (local.set $r (call $stream-read (local.get $s) (i32.const 16) (i32.const 4)))
+                (local.set $n (i32.shr_u (local.get $r) (i32.const 4)))
+                (local.set $p (i32.const 16))
+                (block $done
+                    (loop $add
+                        (br_if $done (i32.eqz (local.get $n)))
+                        (local.set $sum (i32.add (local.get $sum) (i32.load (local.get $p))))
+                        (local.set $p (i32.add (local.get $p) (i32.const 4)))
+                        (local.set $n (i32.sub (local.get $n) (i32.const 1)))
+                        (br $add)))
+                (br_if $read (i32.eqz (i32.and (local.get $r) (i32.const 0xf)))))
+            (call $stream-drop-readable (local.get $s))
+
+            (local.set $stream (call $stream-new))
+            (local.set $future (call $future-new))
+            (call $sink (i32.wrap_i64 (local.get $stream)) (i32.wrap_i64 (local.get $future)))
+
+            (i32.store (i32.const 32) (local.get $sum))
+            (i32.store (i32.const 36) (i32.const 7))
+            (if (i32.ne
+                    (call $stream-write
+                        (i32.wrap_i64 (i64.shr_u (local.get $stream) (i64.const 32)))
+                        (i32.const 32) (i32.const 2))
+                    (i32.const 0x20))
+                (then unreachable))
+            (call $stream-drop-writable (i32.wrap_i64 (i64.shr_u (local.get $stream) (i64.const 32))))
+
+            (i32.store (i32.const 48) (i32.const 64))
+            (i32.store (i32.const 52) (i32.const 5))
+            (if (call $future-write
+                    (i32.wrap_i64 (i64.shr_u (local.get $future) (i64.const 32)))
+                    (i32.const 48))
+                (then unreachable))
+            (call $future-drop-writable (i32.wrap_i64 (i64.shr_u (local.get $future) (i64.const 32))))
+
+            (call $task-return (local.get $sum))
+        )
+    )
+    (core instance $i (instantiate $m
+        (with "libc" (instance $libc))
+        (with "" (instance
+            (export "task.return" (func $task-return))
+            (export "future.read" (func $future-read))
+            (export "future.drop-readable" (func $future-drop-readable))
+            (export "stream.read" (func $stream-read))
+            (export "stream.drop-readable" (func $stream-drop-readable))
+            (export "stream.new" (func $stream-new))
+            (export "stream.write" (func $stream-write))
+            (export "stream.drop-writable" (func $stream-drop-writable))
+            (export "future.new" (func $future-new))
+            (export "future.write" (func $future-write))
+            (export "future.drop-writable" (func $future-drop-writable))
+            (export "sink" (func $sink))
+        ))
+    ))
+    (func (export "run") async (param "f" $fu32) (param "s" $su32) (result u32)
+        (canon lift (core func $i "run") async))
+)
+    )END",
+};
+
+struct TransmitSink {
+  wasmtime_component_stream_reader_t *stream = nullptr;
+  wasmtime_component_future_reader_t *future = nullptr;
+};
+
+// Instantiates `transmit_component_text` with a `sink` import which starts
+// reading what it's given into `sink`, and then calls its `run` export with
+// `f` and `s`.
+static wasmtime_error_t *call_transmit(Engine &engine, Store::Context context,
+                                       wasmtime_component_future_t *f,
+                                       wasmtime_component_stream_t *s,
+                                       TransmitSink *sink, uint32_t *result) {
+  auto component = Component::compile(engine, transmit_component_text).unwrap();
+  auto run = *component.export_index(nullptr, "run");
+
+  Linker linker(engine);
+  auto *root = wasmtime_component_linker_root(linker.capi());
+  auto *err = wasmtime_component_linker_instance_add_func(
+      root, "sink", 4,
+      [](void *env, wasmtime_context_t *context,
+         const wasmtime_component_func_type_t *, wasmtime_component_val_t *args,
+         size_t, wasmtime_component_val_t *, size_t) -> wasmtime_error_t * {
+        auto *sink = static_cast<TransmitSink *>(env);
+        const wasmtime_component_valkind_t u32 = WASMTIME_COMPONENT_U32;
+        const wasmtime_component_valkind_t string = WASMTIME_COMPONENT_STRING;
+        auto *err = wasmtime_component_stream_read(context, args[0].of.stream,
+                                                   &u32, &sink->stream);
+        if (err != nullptr)
+          return err;
+        return wasmtime_component_future_read(context, args[1].of.future,
+                                              &string, &sink->future);
+      },
+      sink, nullptr);
+  EXPECT_EQ(err, nullptr);
+  wasmtime_component_linker_instance_delete(root);
+
+  auto instance = linker.instantiate(context, component).unwrap();
+  auto func = *instance.get_func(context, run);
+
+  wasmtime_component_val_t args[2];
+  args[0].kind = WASMTIME_COMPONENT_FUTURE;
+  args[0].of.future = f;
+  args[1].kind = WASMTIME_COMPONENT_STREAM;
+  args[1].of.stream = s;
+  wasmtime_component_val_t results[1];
+  results[0].kind = WASMTIME_COMPONENT_BOOL;
+  results[0].of.boolean = false;
+
+  wasmtime_error_t *error = nullptr;
+  auto *future = wasmtime_component_func_call_async(
+      func.capi(), context.capi(), args, 2, results, 1, &error);
+  while (!wasmtime_call_future_poll(future)) {
+  }
+  wasmtime_call_future_delete(future);
+  wasmtime_component_val_delete(&args[0]);
+  wasmtime_component_val_delete(&args[1]);
+  if (error == nullptr) {
+    EXPECT_EQ(results[0].kind, WASMTIME_COMPONENT_U32);
+    *result = results[0].of.u32;
+  }
+  return error;
+}
+
+static Engine transmit_engine() {
+  Config config;
+  config.concurrency_support(true);
+  config.wasm_component_model_async(true);
+  config.wasm_component_model_more_async_builtins(true);
+  config.wasm_component_model_async_stackful(true);
+  return Engine(std::move(config));
+}
+
+TEST(component_async, read_and_write_streams_and_futures) {
+  Engine engine = transmit_engine();
+  Store store(engine);
+  auto context = store.context();
+
+  const wasmtime_component_valkind_t u32 = WASMTIME_COMPONENT_U32;
+  wasmtime_component_future_t *future = nullptr;
+  wasmtime_component_future_writer_t *future_writer = nullptr;
+  auto *err = wasmtime_component_future_new(context.capi(), &u32, &future,
+                                            &future_writer);
+  ASSERT_EQ(err, nullptr);
+  wasmtime_component_val_t value;
+  value.kind = WASMTIME_COMPONENT_U32;
+  value.of.u32 = 10;
+  bool closed = true;
+  err = wasmtime_component_future_writer_write(future_writer, &value, &closed);
+  EXPECT_EQ(err, nullptr);
+  EXPECT_FALSE(closed);
+  wasmtime_component_future_writer_delete(future_writer);
+
+  wasmtime_component_stream_t *stream = nullptr;
+  wasmtime_component_stream_writer_t *stream_writer = nullptr;
+  err = wasmtime_component_stream_new(context.capi(), &u32, &stream,
+                                      &stream_writer);
+  ASSERT_EQ(err, nullptr);
+  wasmtime_component_val_t items[3];
+  for (uint32_t i = 0; i < 3; i++) {
+    items[i].kind = WASMTIME_COMPONENT_U32;
+    items[i].of.u32 = i + 1;
+  }
+  err = wasmtime_component_stream_writer_write(stream_writer, items, 3,
+                                               &closed);
+  EXPECT_EQ(err, nullptr);
+  EXPECT_FALSE(closed);
+  wasmtime_component_stream_writer_delete(stream_writer);
+
+  TransmitSink sink;
+  uint32_t result = 0;
+  err = call_transmit(engine, context, future, stream, &sink, &result);
+  ASSERT_EQ(err, nullptr);
+  EXPECT_EQ(result, 16);
+
+  ASSERT_NE(sink.stream, nullptr);
+  wasmtime_component_val_t received[4];
+  EXPECT_EQ(wasmtime_component_stream_reader_read(sink.stream, received, 4),
+            2);
+  EXPECT_EQ(received[0].kind, WASMTIME_COMPONENT_U32);
+  EXPECT_EQ(received[0].of.u32, 16);
+  EXPECT_EQ(received[1].kind, WASMTIME_COMPONENT_U32);
+  EXPECT_EQ(received[1].of.u32, 7);
+  wasmtime_component_val_delete(&received[0]);
+  wasmtime_component_val_delete(&received[1]);
+  EXPECT_TRUE(wasmtime_component_stream_reader_done(sink.stream));
+  wasmtime_component_stream_reader_delete(sink.stream);
+
+  ASSERT_NE(sink.future, nullptr);
+  EXPECT_TRUE(wasmtime_component_future_reader_read(sink.future, &value));
+  EXPECT_EQ(value.kind, WASMTIME_COMPONENT_STRING);
+  EXPECT_EQ(std::string_view(value.of.string.data, value.of.string.size),
+            "hello");
+  wasmtime_component_val_delete(&value);
+  EXPECT_FALSE(wasmtime_component_future_reader_read(sink.future, &value));
+  wasmtime_component_future_reader_delete(sink.future);
+}
+
+TEST(component_async, drop_future_writer) {
+  Engine engine = transmit_engine();
+  Store store(engine);
+  auto context = store.context();
+
+  // Dropping the writer without writing a value closes the future, which
+  // fails the component's read instead of leaving it blocked forever.
+  const wasmtime_component_valkind_t u32 = WASMTIME_COMPONENT_U32;
+  wasmtime_component_future_t *future = nullptr;
+  wasmtime_component_future_writer_t *future_writer = nullptr;
+  auto *err = wasmtime_component_future_new(context.capi(), &u32, &future,
+                                            &future_writer);
+  ASSERT_EQ(err, nullptr);
+  wasmtime_component_future_writer_delete(future_writer);
+
+  wasmtime_component_stream_t *stream = nullptr;
+  wasmtime_component_stream_writer_t *stream_writer = nullptr;
+  err = wasmtime_component_stream_new(context.capi(), &u32, &stream,
+                                      &stream_writer);
+  ASSERT_EQ(err, nullptr);
+  wasmtime_component_stream_writer_delete(stream_writer);
+
+  TransmitSink sink;
+  uint32_t result = 0;
+  err = call_transmit(engine, context, future, stream, &sink, &result);
+  ASSERT_NE(err, nullptr);
+  wasm_byte_vec_t message;
+  wasmtime_error_message(err, &message);
+  EXPECT_NE(std::string_view(message.data, message.size)
+                .find("dropped without writing a value"),
+            std::string_view::npos);
+  wasm_byte_vec_delete(&message);
+  wasmtime_error_delete(err);
+  EXPECT_EQ(sink.stream, nullptr);
+  EXPECT_EQ(sink.future, nullptr);
+}
+
 #endif // WASMTIME_FEATURE_COMPONENT_MODEL_ASYNC
@@ -931,6 +931,115 @@ TEST(component, value_host_resource) {
   check(ctx.context, res, 43);
 }
 
+TEST(component, value_host_resource_with_data) {
+  static const uint32_t RESOURCE_TYPE = 101;
+
+  struct Data {
+    uint32_t value = 0;
+    bool *dropped = nullptr;
+    ~Data() {
+      if (dropped)
+        *dropped = true;
+    }
+  };
+
+  const auto make_data = [](uint32_t value, bool *dropped) {
+    auto data = std::make_unique<Data>();
+    data->value = value;
+    data->dropped = dropped;
+    return data;
+  };
+
+  Engine engine;
+  Linker linker(engine);
+  {
+    LinkerInstance i = linker.root();
+
+    i.add_resource_with_data("r", ResourceType(RESOURCE_TYPE)).unwrap();
+
+    i.add_func(
+         "get",
+         +[](Store::Context cx, const FuncType &_ty, Span<const Val> args,
+             Span<Val> rets) -> Result<std::monostate> {
+           ResourceHost r = args[0].get_resource().to_host(cx).unwrap();
+           EXPECT_FALSE(r.owned());
+           rets[0] = Val(r.data<Data>(cx).unwrap()->value);
+           return std::monostate();
+         })
+        .unwrap();
+  }
+
+  auto ctx = Context::New(engine,
+                          R"(
+(component
+  (import "r" (type $r (sub resource)))
+  (import "get" (func $get (param "a" (borrow $r)) (result u32)))
+  (core func $get (canon lower (func $get)))
+  (core func $drop (canon resource.drop $r))
+
+  (core module $m
+    (import "" "get" (func $get (param i32) (result i32)))
+    (import "" "drop" (func $drop (param i32)))
+
+    (func (export "call") (param i32) (result i32)
+      (local $v i32)
+      local.get 0
+      call $get
+      local.set $v
+      local.get 0
+      call $drop
+      local.get $v)
+  )
+
+  (core instance $m (instantiate $m
+    (with "" (instance
+      (export "get" (func $get))
+      (export "drop" (func $drop))
+    ))
+  ))
+
+  (func (export "call") (param "a" (own $r)) (result u32)
+    (canon lift (core func $m "call")))
+)
+)",
+                          linker);
+
+  // A resource passed to the guest is destroyed when the guest drops it.
+  bool dropped = false;
+  auto r = Resource<Data>::create(ctx.context, RESOURCE_TYPE,
+                                  make_data(7, &dropped))
+               .unwrap();
+  EXPECT_EQ(r.get(ctx.context).unwrap()->value, 7);
+
+  auto arg = Val(r.to_any(ctx.context).unwrap());
+  auto res = Val(false);
+  ctx.func.call(ctx.context, Span<const Val>(&arg, 1), Span<Val>(&res, 1))
+      .unwrap();
+  ctx.func.post_return(ctx.context).unwrap();
+  EXPECT_EQ(res.get_u32(), 7);
+  EXPECT_TRUE(dropped);
+  EXPECT_FALSE(r.get(ctx.context));
+  EXPECT_FALSE(r.drop(ctx.context));
+
+  // A resource kept on the host is destroyed with `drop`.
+  bool dropped2 = false;
+  auto r2 = Resource<Data>::create(ctx.context, RESOURCE_TYPE,
+                                   make_data(8, &dropped2))
+                .unwrap();
+  EXPECT_EQ(r2.borrow().data<Data>(ctx.context).unwrap()->value, 8);
+  EXPECT_FALSE(r2.borrow().owned());
+  EXPECT_FALSE(Resource<Data>::from_host(ctx.context, r2.borrow()));
+  EXPECT_TRUE(Resource<Data>::from_host(ctx.context, r2.host()));
+
+  // Handles with a mismatched type are rejected.
+  ResourceHost forged(true, r2.host().rep(), RESOURCE_TYPE + 1);
+  EXPECT_FALSE(forged.data<Data>(ctx.context));
+
+  r2.drop(ctx.context).unwrap();
+  EXPECT_TRUE(dropped2);
+  EXPECT_FALSE(r2.borrow().data<Data>(ctx.context));
+}
+
 TEST(component, value_guest_resource) {
   Engine engine;
   Linker linker(engine);
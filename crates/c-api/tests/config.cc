@@ -139,3 +139,35 @@ TEST(Config, MemoryCreator) {
     }
   }
 }
+
+#ifdef WASMTIME_FEATURE_POOLING_ALLOCATOR
+TEST(PoolAllocationConfig, Metrics) {
+  Engine plain;
+  EXPECT_EQ(wasmtime_engine_pooling_allocator_metrics(plain.capi()), nullptr);
+
+  Config config;
+  PoolAllocationConfig pooling_config;
+  pooling_config.total_memories(1);
+  pooling_config.total_tables(1);
+  pooling_config.total_core_instances(1);
+  config.pooling_allocation_strategy(pooling_config);
+  Engine engine(std::move(config));
+  auto *metrics = wasmtime_engine_pooling_allocator_metrics(engine.capi());
+  ASSERT_NE(metrics, nullptr);
+  EXPECT_EQ(wasmtime_pooling_allocator_metrics_core_instances(metrics), 0);
+
+  {
+    Store store(engine);
+    Module m = Module::compile(engine, "(module (memory 1))").unwrap();
+    Instance i = Instance::create(store, m, {}).unwrap();
+    EXPECT_EQ(wasmtime_pooling_allocator_metrics_core_instances(metrics), 1);
+    EXPECT_EQ(wasmtime_pooling_allocator_metrics_memories(metrics), 1);
+  }
+
+  EXPECT_EQ(wasmtime_pooling_allocator_metrics_core_instances(metrics), 0);
+  EXPECT_EQ(wasmtime_pooling_allocator_metrics_memories(metrics), 0);
+  EXPECT_EQ(wasmtime_pooling_allocator_metrics_unused_warm_memories(metrics),
+            1);
+  wasmtime_pooling_allocator_metrics_delete(metrics);
+}
+#endif // WASMTIME_FEATURE_POOLING_ALLOCATOR
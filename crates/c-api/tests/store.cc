@@ -5,7 +5,9 @@
 #include <wasmtime/error.hh>
 #include <wasmtime/func.hh>
 #include <wasmtime/instance.hh>
+#include <wasmtime/memory.hh>
 #include <wasmtime/module.hh>
+#include <wasmtime/table.hh>
 
 using namespace wasmtime;
 
@@ -81,3 +83,38 @@ TEST(Store, EpochDeadlineCallback) {
   EXPECT_TRUE(result.err().message().find("error from callback") !=
               std::string::npos);
 }
+
+static wasmtime_error_t *limit_memory(void *env, size_t current,
+                                      size_t desired, int64_t maximum,
+                                      bool *allow) {
+  *allow = desired <= *static_cast<size_t *>(env);
+  return nullptr;
+}
+
+static wasmtime_error_t *deny_table(void *env, size_t current, size_t desired,
+                                    int64_t maximum, bool *allow) {
+  return Error("table growth denied").capi_release();
+}
+
+TEST(Store, ResourceLimiter) {
+  Engine engine;
+  Store store(engine);
+  size_t limit = 2 << 16;
+  wasmtime_store_resource_limiter(store.capi(), limit_memory, deny_table,
+                                  &limit, nullptr);
+
+  Memory m = unwrap(Memory::create(store, MemoryType(1)));
+  EXPECT_EQ(unwrap(m.grow(store, 1)), 1);
+  EXPECT_FALSE(m.grow(store, 1));
+
+  limit = 4 << 16;
+  EXPECT_EQ(unwrap(m.grow(store, 1)), 2);
+
+  Val null = std::optional<Func>();
+  Table t =
+      unwrap(Table::create(store, TableType(ValType::funcref(), 0), null));
+  auto result = t.grow(store, 1, null);
+  EXPECT_FALSE(result);
+  EXPECT_NE(result.err().message().find("table growth denied"),
+            std::string::npos);
+}
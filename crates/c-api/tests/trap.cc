@@ -1,8 +1,11 @@
 #include <wasmtime/trap.hh>
 
 #include <gtest/gtest.h>
+#include <wasmtime.h>
 #include <wasmtime.hh>
 
+#include <vector>
+
 using namespace wasmtime;
 
 TEST(Trap, Smoke) {
@@ -71,3 +74,167 @@ TEST(Trap, Codes) {
   TEST_CODE(DISABLED_OPCODE);
 #undef TEST_CODE
 }
+
+#ifdef WASMTIME_FEATURE_COREDUMP
+TEST(Trap, Coredump) {
+  Config config;
+  config.coredump_on_trap(true);
+  Engine engine(std::move(config));
+  Module m =
+      Module::compile(engine, "(module (func (export \"\") unreachable))")
+          .unwrap();
+  Store store(engine);
+  Instance i = Instance::create(store, m, {}).unwrap();
+  auto func = std::get<Func>(*i.get(store, ""));
+  auto trap = std::get<Trap>(func.call(store, {}).err().data);
+
+  wasm_byte_vec_t dump;
+  ASSERT_TRUE(wasmtime_trap_coredump_serialize(
+      trap.capi(), store.context().capi(), "m", 1, &dump));
+  ASSERT_GE(dump.size, 4);
+  EXPECT_EQ(dump.data[0], 0);
+  EXPECT_EQ(dump.data[1], 'a');
+  EXPECT_EQ(dump.data[2], 's');
+  EXPECT_EQ(dump.data[3], 'm');
+  wasm_byte_vec_delete(&dump);
+
+  wasm_frame_vec_t frames;
+  ASSERT_TRUE(wasmtime_trap_coredump_frames(trap.capi(), &frames));
+  ASSERT_EQ(frames.size, 1);
+  EXPECT_EQ(wasm_frame_func_index(frames.data[0]), 0);
+  EXPECT_EQ(wasm_frame_func_offset(frames.data[0]), 1);
+  EXPECT_EQ(wasm_frame_module_offset(frames.data[0]), 29);
+  wasm_frame_vec_delete(&frames);
+
+  Trap other("foo");
+  EXPECT_FALSE(wasmtime_trap_coredump_serialize(
+      other.capi(), store.context().capi(), "m", 1, &dump));
+  EXPECT_FALSE(wasmtime_trap_coredump_frames(other.capi(), &frames));
+}
+#endif // WASMTIME_FEATURE_COREDUMP
+
+#ifdef WASMTIME_FEATURE_DEBUG
+TEST(Trap, BreakpointsRequireGuestDebug) {
+  Engine engine;
+  Module m = Module::compile(engine, "(module (func (export \"\")))").unwrap();
+  Store store(engine);
+  wasmtime_error_t *error =
+      wasmtime_context_add_breakpoint(store.context().capi(), m.capi(), 0);
+  ASSERT_NE(error, nullptr);
+  Error err(error);
+  EXPECT_NE(err.message().find("guest debugging is not enabled"),
+            std::string::npos);
+  EXPECT_EQ(wasmtime_context_debug_exit_frame(store.context().capi(), 0),
+            nullptr);
+}
+
+namespace {
+
+struct BreakpointState {
+  size_t hits = 0;
+  wasmtime_debug_event_kind_t kind = 0;
+  bool is_wasm = false;
+  uint32_t func_index = 0;
+  uint32_t pc = 0;
+  std::vector<int32_t> locals;
+  std::vector<int32_t> stack;
+  bool has_parent = true;
+};
+
+void check_ok(wasmtime_error_t *error) {
+  if (error != nullptr) {
+    ADD_FAILURE() << Error(error).message();
+  }
+}
+
+void on_debug_event(void *data, wasmtime_context_t *context,
+                    const wasmtime_debug_event_t *event) {
+  auto *state = static_cast<BreakpointState *>(data);
+  state->hits++;
+  state->kind = event->kind;
+
+  wasmtime_debug_frame_t *frame =
+      wasmtime_context_debug_exit_frame(context, 0);
+  EXPECT_NE(frame, nullptr);
+  if (frame == nullptr) {
+    return;
+  }
+  EXPECT_TRUE(wasmtime_debug_frame_is_valid(context, frame));
+  check_ok(wasmtime_debug_frame_func_index_and_pc(
+      context, frame, &state->is_wasm, &state->func_index, &state->pc));
+
+  uint32_t num = 0;
+  check_ok(wasmtime_debug_frame_num_locals(context, frame, &num));
+  for (uint32_t i = 0; i < num; i++) {
+    wasmtime_val_t val;
+    check_ok(wasmtime_debug_frame_local(context, frame, i, &val));
+    EXPECT_EQ(val.kind, WASMTIME_I32);
+    state->locals.push_back(val.of.i32);
+  }
+  check_ok(wasmtime_debug_frame_num_stacks(context, frame, &num));
+  for (uint32_t i = 0; i < num; i++) {
+    wasmtime_val_t val;
+    check_ok(wasmtime_debug_frame_stack(context, frame, i, &val));
+    EXPECT_EQ(val.kind, WASMTIME_I32);
+    state->stack.push_back(val.of.i32);
+  }
+
+  wasmtime_debug_frame_t *parent = nullptr;
+  check_ok(wasmtime_debug_frame_parent(context, frame, &parent));
+  state->has_parent = parent != nullptr;
+  if (parent != nullptr) {
+    wasmtime_debug_frame_delete(parent);
+  }
+  wasmtime_debug_frame_delete(frame);
+}
+
+} // namespace
+
+TEST(Trap, Breakpoint) {
+  Config config;
+  config.guest_debug(true);
+  Engine engine(std::move(config));
+  Module m = Module::compile(engine, R"(
+    (module
+      (func (export "main") (param i32 i32) (result i32)
+        local.get 0
+        local.get 1
+        i32.add))
+  )")
+                 .unwrap();
+  Store store(engine);
+  Instance i = Instance::create(store, m, {}).unwrap();
+  auto func = std::get<Func>(*i.get(store, "main"));
+
+  BreakpointState state;
+  wasmtime_store_set_debug_handler(store.capi(), on_debug_event, &state,
+                                   nullptr);
+  // The `i32.add` instruction.
+  check_ok(wasmtime_context_add_breakpoint(store.context().capi(), m.capi(),
+                                           0x28));
+
+  wasmtime_val_t params[2] = {{.kind = WASMTIME_I32, .of = {.i32 = 1}},
+                              {.kind = WASMTIME_I32, .of = {.i32 = 2}}};
+  wasmtime_val_t results[1] = {};
+  wasm_trap_t *trap = nullptr;
+  wasmtime_error_t *error = nullptr;
+  auto *future = wasmtime_func_call_async(store.context().capi(), &func.capi(),
+                                          params, 2, results, 1, &trap, &error);
+  while (!wasmtime_call_future_poll(future)) {
+  }
+  wasmtime_call_future_delete(future);
+
+  EXPECT_EQ(trap, nullptr);
+  check_ok(error);
+  EXPECT_EQ(results[0].of.i32, 3);
+
+  ASSERT_EQ(state.hits, 1);
+  EXPECT_EQ(state.kind, WASMTIME_DEBUG_EVENT_BREAKPOINT);
+  EXPECT_TRUE(state.is_wasm);
+  EXPECT_EQ(state.func_index, 0);
+  EXPECT_EQ(state.pc, 0x28);
+  EXPECT_EQ(state.locals, (std::vector<int32_t>{1, 2}));
+  EXPECT_EQ(state.stack, (std::vector<int32_t>{1, 2}));
+  EXPECT_FALSE(state.has_parent);
+}
+#endif // WASMTIME_FEATURE_DEBUG
@@ -282,6 +282,12 @@ wasmtime_option_group! {
         /// Whether or not trap metadata is present for detection of gc
         /// corruption in compiled code.
         pub metadata_for_gc_heap_corruption: Option<bool>,
+        /// Start functions out compiled by Winch and recompile them with
+        /// Cranelift once they're hot (requires `-C compiler=winch`).
+        pub tiered_compilation: Option<bool>,
+        /// How many times a function is entered before tiered compilation
+        /// recompiles it with Cranelift.
+        pub tier_up_threshold: Option<u32>,
 
         #[prefixed = "cranelift"]
         #[serde(default)]
@@ -309,6 +315,9 @@ wasmtime_option_group! {
         pub log_to_files: Option<bool>,
         /// Enable coredump generation to this file after a WebAssembly trap.
         pub coredump: Option<String>,
+        /// Write a JSON line to this file for each call between the host and a
+        /// component, with arguments and results in the WAVE format.
+        pub trace_calls: Option<String>,
         /// Load the given debugger component and attach it to the
         /// main module or component.
         pub debugger: Option<PathBuf>,
@@ -1043,6 +1052,16 @@ impl CommonOptions {
         if let Some(enable) = self.codegen.metadata_for_gc_heap_corruption {
             config.metadata_for_gc_heap_corruption(enable);
         }
+        match_feature! {
+            ["winch" : self.codegen.tiered_compilation]
+            enable => config.tiered_compilation(enable),
+            true => err,
+        }
+        match_feature! {
+            ["winch" : self.codegen.tier_up_threshold]
+            entries => config.tier_up_threshold(entries),
+            _ => err,
+        }
 
         // async_stack_size enabled by either async or stack-switching, so
         // cannot directly use match_feature!
@@ -1399,6 +1418,10 @@ impl CommonOptions {
                 parallel_compilation: Some(engine.get_parallel_compilation()),
                 metadata_for_internal_asserts: Some(engine.get_metadata_for_internal_asserts()),
                 metadata_for_gc_heap_corruption: Some(engine.get_metadata_for_gc_heap_corruption()),
+                tiered_compilation: Some(engine.get_tiered_compilation()),
+                tier_up_threshold: engine
+                    .get_tiered_compilation()
+                    .then(|| engine.get_tier_up_threshold()),
                 cranelift: engine
                     .get_cranelift_flags_set()
                     .map(|(k, v)| (k.to_string(), Some(v.to_string())))
@@ -1424,6 +1447,8 @@ impl CommonOptions {
                 // Can't infer a path to emit a core dump to from engine
                 // configuration.
                 coredump: None,
+                // Call tracing is configured on the store, not the engine.
+                trace_calls: None,
                 // debugger configuration not part of engine config
                 debugger: None,
                 arg: Vec::new(),
@@ -39,9 +39,9 @@ use wasmtime_environ::error::{Context as _, Result};
 use wasmtime_environ::obj::{ELF_WASMTIME_EXCEPTIONS, ELF_WASMTIME_FRAMES};
 use wasmtime_environ::{
     Abi, AddressMapSection, BuiltinFunctionIndex, CacheStore, CompileError, CompiledFunctionBody,
-    DefinedFuncIndex, FlagValue, FrameInstPos, FrameStackShape, FrameStateSlotBuilder,
-    FrameTableBuilder, FuncKey, FunctionBodyData, FunctionLoc, GetPtrSize, HostCall,
-    InliningCompiler, ModulePC, ModuleStartup, ModuleTranslation, ModuleTypesBuilder,
+    DefinedFuncIndex, ExecutionProfile, FlagValue, FrameInstPos, FrameStackShape,
+    FrameStateSlotBuilder, FrameTableBuilder, FuncKey, FunctionBodyData, FunctionLoc, GetPtrSize,
+    HostCall, InliningCompiler, ModulePC, ModuleStartup, ModuleTranslation, ModuleTypesBuilder,
     StackMapSection, StaticModuleIndex, TrapEncodingBuilder, TrapSentinel, TripleExt, Tunables,
     WasmFuncType, WasmValType, prelude::*,
 };
@@ -580,6 +580,10 @@ impl wasmtime_environ::Compiler for Compiler {
             &mut func_env,
         )?;
 
+        if let Some(profile) = &translation.execution_profile {
+            set_block_profile(context, profile, body.range());
+        }
+
         let needs_gc_heap = func_env.needs_gc_heap();
 
         if let Some((_, slot_builder)) = func_env.state_slot {
@@ -1100,6 +1104,35 @@ impl cranelift_codegen::incremental_cache::CacheKvStore for CraneliftCacheStore
     }
 }
 
+/// Fill in the block profile of the function in `context`, translated from the
+/// Wasm function body at `body`, from the execution counts of the Wasm
+/// instructions in each block.
+///
+/// Functions the profile has no counts for are left without a block profile.
+/// Otherwise, blocks whose instructions have no counts are taken to not have
+/// executed.
+fn set_block_profile(context: &mut Context, profile: &ExecutionProfile, body: Range<usize>) {
+    let (Ok(start), Ok(end)) = (u32::try_from(body.start), u32::try_from(body.end)) else {
+        return;
+    };
+    if !profile.covers(start..end) {
+        return;
+    }
+    let func = &context.func;
+    for block in func.layout.blocks() {
+        let count = func
+            .layout
+            .block_insts(block)
+            .map(|inst| func.srcloc(inst))
+            .filter(|srcloc| !srcloc.is_default())
+            .map(|srcloc| profile.count(srcloc.bits()).unwrap_or(0))
+            .max();
+        if let Some(count) = count {
+            context.profile.set_block_count(block, count);
+        }
+    }
+}
+
 fn compile_maybe_cached<'a>(
     context: &'a mut Context,
     isa: &dyn TargetIsa,
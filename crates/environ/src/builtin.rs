@@ -170,6 +170,10 @@ macro_rules! foreach_builtin_function {
 
             // Process a debug breakpoint.
             breakpoint(vmctx: vmctx) -> bool;
+
+            // Invoked when a function compiled for tiered compilation has
+            // been entered often enough to be recompiled.
+            tier_up(vmctx: vmctx, func: u32) -> bool;
         }
     };
 }
@@ -0,0 +1,106 @@
+use crate::prelude::*;
+use core::ops::Range;
+use std::collections::BTreeMap;
+
+/// Execution counts for the instructions of a WebAssembly module, used to
+/// guide the layout of compiled code.
+///
+/// Counts are keyed by the module-relative byte offset of an instruction,
+/// which is the same offset found in a compiled module's address map. Only the
+/// ratios between counts matter, so sample counts from a sampling profiler work
+/// as well as exact counts, and `wasmtime hot-blocks --profile-out` writes such
+/// a profile.
+///
+/// The textual format has one `<offset> <count>` pair per line, where the
+/// offset is either decimal or `0x`-prefixed hexadecimal. Empty lines and
+/// lines starting with `#` are ignored, and counts for the same offset add up.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ExecutionProfile {
+    counts: BTreeMap<u32, u64>,
+}
+
+impl ExecutionProfile {
+    /// Creates a new empty profile.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses a profile in the textual format described above.
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut profile = Self::new();
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut parts = line.split_whitespace();
+            let (Some(offset), Some(count), None) = (parts.next(), parts.next(), parts.next())
+            else {
+                bail!(
+                    "line {}: expected `<offset> <count>`, found `{line}`",
+                    i + 1
+                );
+            };
+            let offset = match offset.strip_prefix("0x") {
+                Some(hex) => u32::from_str_radix(hex, 16),
+                None => offset.parse(),
+            }
+            .map_err(|e| format_err!("line {}: invalid offset `{offset}`: {e}", i + 1))?;
+            let count = count
+                .parse()
+                .map_err(|e| format_err!("line {}: invalid count `{count}`: {e}", i + 1))?;
+            profile.add(offset, count);
+        }
+        Ok(profile)
+    }
+
+    /// Records `count` more executions of the instruction at `offset`.
+    pub fn add(&mut self, offset: u32, count: u64) {
+        let total = self.counts.entry(offset).or_default();
+        *total = total.saturating_add(count);
+    }
+
+    /// Returns the execution count of the instruction at `offset`, if known.
+    pub fn count(&self, offset: u32) -> Option<u64> {
+        self.counts.get(&offset).copied()
+    }
+
+    /// Returns whether this profile has any counts for instructions in
+    /// `range`, such as the body of a function.
+    pub fn covers(&self, range: Range<u32>) -> bool {
+        self.counts.range(range).next().is_some()
+    }
+
+    /// Returns whether this profile has no counts.
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let profile = ExecutionProfile::parse(
+            "
+            # offset count
+            0x2a 10
+            42 5
+            100 0
+            ",
+        )
+        .unwrap();
+        assert_eq!(profile.count(42), Some(15));
+        assert_eq!(profile.count(100), Some(0));
+        assert_eq!(profile.count(101), None);
+        assert!(profile.covers(40..50));
+        assert!(!profile.covers(43..100));
+
+        assert!(ExecutionProfile::parse("1").is_err());
+        assert!(ExecutionProfile::parse("1 2 3").is_err());
+        assert!(ExecutionProfile::parse("0xg 1").is_err());
+        assert!(ExecutionProfile::parse("1 -1").is_err());
+    }
+}
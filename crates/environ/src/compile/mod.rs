@@ -16,6 +16,7 @@ use std::path;
 use std::sync::Arc;
 
 mod address_map;
+mod execution_profile;
 mod frame_table;
 mod module_artifacts;
 mod module_environ;
@@ -24,6 +25,7 @@ mod stack_maps;
 mod trap_encoding;
 
 pub use self::address_map::*;
+pub use self::execution_profile::*;
 pub use self::frame_table::*;
 pub use self::module_artifacts::*;
 pub use self::module_environ::*;
@@ -5,12 +5,12 @@ use crate::module::{
 use crate::prelude::*;
 use crate::{
     ConstExpr, ConstOp, DataIndex, DefinedFuncIndex, DefinedGlobalIndex, DefinedMemoryIndex,
-    DefinedTableIndex, ElemIndex, EngineOrModuleTypeIndex, EntityIndex, EntityType, FuncIndex,
-    FuncKey, GlobalIndex, IndexType, MemoryIndex, MemoryInitializer, ModuleInternedTypeIndex,
-    ModuleStartup, ModuleTypesBuilder, PanicOnOom as _, PassiveElemIndex, PrimaryMap,
-    RuntimeDataIndex, StaticModuleIndex, TableIndex, TableInitialValue, TableInitialization, Tag,
-    TagIndex, Trap, Tunables, TypeConvert, TypeIndex, WasmHeapTopType, WasmHeapType, WasmResult,
-    WasmValType, WasmparserTypeConverter,
+    DefinedTableIndex, ElemIndex, EngineOrModuleTypeIndex, EntityIndex, EntityType,
+    ExecutionProfile, FuncIndex, FuncKey, GlobalIndex, IndexType, MemoryIndex, MemoryInitializer,
+    ModuleInternedTypeIndex, ModuleStartup, ModuleTypesBuilder, PanicOnOom as _, PassiveElemIndex,
+    PrimaryMap, RuntimeDataIndex, StaticModuleIndex, TableIndex, TableInitialValue,
+    TableInitialization, Tag, TagIndex, Trap, Tunables, TypeConvert, TypeIndex, WasmHeapTopType,
+    WasmHeapType, WasmResult, WasmValType, WasmparserTypeConverter,
 };
 use alloc::borrow::Cow;
 use cranelift_entity::packed_option::ReservedValue;
@@ -247,6 +247,10 @@ pub struct ModuleTranslation<'data> {
     /// [`Tunables::branch_hinting`] is enabled.
     branch_hints: HashMap<FuncIndex, BranchHintReader<'data>>,
 
+    /// Execution counts used to guide the layout of compiled code, if the
+    /// embedder provided any.
+    pub execution_profile: Option<ExecutionProfile>,
+
     /// The WebAssembly `start` function, if defined.
     pub start_func: Option<FuncIndex>,
 
@@ -334,6 +338,7 @@ impl<'data> ModuleTranslation<'data> {
             runtime_data_map: Default::default(),
             passive_elem_map: Default::default(),
             branch_hints: HashMap::default(),
+            execution_profile: None,
             start_func: None,
             global_initializers: Vec::new(),
             passive_elements: Default::default(),
@@ -855,6 +860,13 @@ impl<'a, 'data> ModuleEnvironment<'a, 'data> {
                     // all be flagged as escaping.
                     self.flag_func_escaped(func_index);
                 }
+                if self.tunables.tiered_compilation {
+                    // Calls between functions go through their
+                    // `VMFuncRef`s so that they can be redirected to
+                    // optimized code, so every function needs one.
+                    self.flag_func_escaped(func_index);
+                    self.result.module.num_tier_up_counters += 1;
+                }
                 self.result
                     .function_body_inputs
                     .push(FunctionBodyData { validator, body });
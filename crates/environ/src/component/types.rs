@@ -387,6 +387,11 @@ impl ComponentTypes {
         }
     }
 
+    /// Returns the resource table `idx`, or `None` if it's out of bounds.
+    pub fn get_resource_table(&self, idx: TypeResourceTableIndex) -> Option<&TypeResourceTable> {
+        self.resource_tables.get(idx)
+    }
+
     /// Adds a new `table` to the list of resource tables for this component.
     pub fn push_resource_table(&mut self, table: TypeResourceTable) -> TypeResourceTableIndex {
         self.resource_tables.push(table)
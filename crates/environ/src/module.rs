@@ -250,6 +250,11 @@ pub struct Module {
     /// an `func_ref` index (and is the maximum func_ref index).
     pub num_escaped_funcs: usize,
 
+    /// Number of entry counters for tiered compilation in this module's
+    /// `VMContext`, one per defined function, or zero when tiered compilation
+    /// is disabled.
+    pub num_tier_up_counters: usize,
+
     /// Types of functions, imported and local.
     pub functions: TryPrimaryMap<FuncIndex, FunctionType>,
 
@@ -317,6 +322,7 @@ impl Module {
             num_imported_tags: Default::default(),
             needs_gc_heap: Default::default(),
             num_escaped_funcs: Default::default(),
+            num_tier_up_counters: Default::default(),
             functions: Default::default(),
             tables: Default::default(),
             memories: Default::default(),
@@ -661,6 +667,7 @@ impl TypeTrace for Module {
             num_imported_globals: _,
             num_imported_tags: _,
             num_escaped_funcs: _,
+            num_tier_up_counters: _,
             needs_gc_heap: _,
             functions,
             tables,
@@ -713,6 +720,7 @@ impl TypeTrace for Module {
             num_imported_globals: _,
             num_imported_tags: _,
             num_escaped_funcs: _,
+            num_tier_up_counters: _,
             needs_gc_heap: _,
             functions,
             tables,
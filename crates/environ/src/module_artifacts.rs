@@ -10,7 +10,6 @@ use core::fmt;
 use core::ops::Range;
 use core::{iter, str};
 use serde_derive::{Deserialize, Serialize};
-#[cfg(feature = "rr")]
 use sha2::{Digest, Sha256};
 
 /// Description of where a function is located in the text section of a
@@ -34,29 +33,16 @@ impl FunctionLoc {
 
 /// The checksum of a Wasm binary.
 ///
-/// Allows for features requiring the exact same Wasm Module (e.g. deterministic replay)
-/// to verify that the binary used matches the one originally compiled.
+/// Allows for features requiring the exact same Wasm Module (e.g. deterministic replay or
+/// restoring instance snapshots) to verify that the binary used matches the one originally
+/// compiled.
 #[derive(Copy, Clone, Default, PartialEq, Eq, Ord, PartialOrd, Debug, Serialize, Deserialize)]
 pub struct WasmChecksum([u8; 32]);
 
 impl WasmChecksum {
-    /// Construct a [`WasmChecksum`] from the given wasm binary, used primarily for integrity
-    /// checks on compiled modules when recording configs are enabled. The checksum is not
-    /// computed when recording is disabled to prevent pessimization of non-recorded compilations.
-    #[cfg(feature = "rr")]
-    pub fn from_binary(bin: &[u8], recording: bool) -> WasmChecksum {
-        if recording {
-            WasmChecksum(Sha256::digest(bin).into())
-        } else {
-            WasmChecksum::default()
-        }
-    }
-
-    /// This method requires the `rr` feature to actual compute a checksum. Since the `rr`
-    /// feature is disabled, this only returns a default checksum value of all zeros.
-    #[cfg(not(feature = "rr"))]
-    pub fn from_binary(_: &[u8], _: bool) -> WasmChecksum {
-        WasmChecksum::default()
+    /// Construct a [`WasmChecksum`] from the given wasm binary.
+    pub fn from_binary(bin: &[u8]) -> WasmChecksum {
+        WasmChecksum(Sha256::digest(bin).into())
     }
 }
 
@@ -603,7 +589,7 @@ pub struct CompiledModuleInfo {
 
 /// The name of a function stored in the
 /// [`ELF_NAME_DATA`](crate::obj::ELF_NAME_DATA) section.
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct FunctionName {
     /// The Wasm function index of this function.
     pub idx: FuncIndex,
@@ -615,7 +601,7 @@ pub struct FunctionName {
 }
 
 /// Metadata associated with a compiled ELF artifact.
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Metadata {
     /// Whether or not the original wasm module contained debug information that
     /// we skipped and did not parse.
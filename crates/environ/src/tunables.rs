@@ -151,8 +151,7 @@ define_tunables! {
         /// enabled.
         pub concurrency_support: bool,
 
-        /// Whether recording in RR is enabled or not. This is used primarily
-        /// to signal checksum computation for compiled artifacts.
+        /// Whether recording in RR is enabled or not.
         pub recording: bool,
 
         /// An allocation counter that triggers GC when it reaches zero.
@@ -199,6 +198,15 @@ define_tunables! {
         /// Whether `metadata.code.branch_hint` sections are parsed and used to
         /// mark cold blocks during compilation.
         pub branch_hinting: bool,
+
+        /// Whether functions start out compiled by the baseline compiler with
+        /// entry counters and are recompiled by the optimizing compiler once
+        /// they become hot.
+        pub tiered_compilation: bool,
+
+        /// The number of entries after which a function compiled for tiered
+        /// compilation asks to be recompiled by the optimizing compiler.
+        pub tier_up_threshold: u32,
     }
 
     pub struct ConfigTunables {
@@ -286,6 +294,8 @@ impl Tunables {
             metadata_for_internal_asserts: false,
             metadata_for_gc_heap_corruption: true,
             branch_hinting: false,
+            tiered_compilation: false,
+            tier_up_threshold: 1000,
             debug_symbols: true,
         }
     }
@@ -209,6 +209,12 @@ macro_rules! for_each_vmctx_type {
                     array {
                         runtime_data_lengths[num_runtime_data; RuntimeDataIndex]: u32
                     }
+
+                    // Function entry counts used by tiered compilation to
+                    // decide when to recompile a function.
+                    array {
+                        tier_up_counters[num_tier_up_counters; DefinedFuncIndex]: u32
+                    }
                 }
             }
 
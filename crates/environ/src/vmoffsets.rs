@@ -7,9 +7,9 @@
 // the offset of one of the layout's fields.
 
 use crate::{
-    DefinedGlobalIndex, DefinedMemoryIndex, DefinedTableIndex, DefinedTagIndex, FuncIndex,
-    FuncRefIndex, GlobalIndex, MemoryIndex, Module, OwnedMemoryIndex, RuntimeDataIndex, TableIndex,
-    TagIndex,
+    DefinedFuncIndex, DefinedGlobalIndex, DefinedMemoryIndex, DefinedTableIndex, DefinedTagIndex,
+    FuncIndex, FuncRefIndex, GlobalIndex, MemoryIndex, Module, OwnedMemoryIndex, RuntimeDataIndex,
+    TableIndex, TagIndex,
 };
 
 /// Number of slots in for `component_context` in the `VMStoreContext`. This is
@@ -472,6 +472,8 @@ pub struct VMOffsets<P> {
     pub num_escaped_funcs: u32,
     /// The number of runtime data segments in the module.
     pub num_runtime_data: u32,
+    /// The number of entry counters for tiered compilation in the module.
+    pub num_tier_up_counters: u32,
     /// Whether or not the module has a start function.
     pub has_startup_func: bool,
 
@@ -490,6 +492,7 @@ pub struct VMOffsets<P> {
     startup_func_ref: u32,
     runtime_data_bases: u32,
     runtime_data_lengths: u32,
+    tier_up_counters: u32,
     size: u32,
 }
 
@@ -828,6 +831,8 @@ pub struct VMOffsetsFields<P> {
     pub num_escaped_funcs: u32,
     /// The number of runtime data segments in the module.
     pub num_runtime_data: u32,
+    /// The number of entry counters for tiered compilation in the module.
+    pub num_tier_up_counters: u32,
     /// Whether or not the module has a start function.
     pub has_startup_func: bool,
 }
@@ -857,6 +862,7 @@ impl<P: PtrSize> VMOffsets<P> {
             num_defined_tags: cast_to_u32(module.tags.len() - module.num_imported_tags),
             num_escaped_funcs: cast_to_u32(module.num_escaped_funcs),
             num_runtime_data: cast_to_u32(module.runtime_data.len()),
+            num_tier_up_counters: cast_to_u32(module.num_tier_up_counters),
             has_startup_func: !module.startup.is_none(),
         })
     }
@@ -890,6 +896,7 @@ impl<P: PtrSize> VMOffsets<P> {
                     num_owned_memories: _,
                     num_escaped_funcs: _,
                     num_runtime_data: _,
+                    num_tier_up_counters: _,
                     has_startup_func: _,
 
                     // used as the initial size below
@@ -919,6 +926,7 @@ impl<P: PtrSize> VMOffsets<P> {
         }
 
         calculate_sizes! {
+            tier_up_counters: "tier-up counters",
             runtime_data_lengths: "runtime data lengths",
             runtime_data_bases: "runtime data base pointers",
             startup_func_ref: "startup funcref",
@@ -962,6 +970,7 @@ impl<P: PtrSize> From<VMOffsetsFields<P>> for VMOffsets<P> {
             num_defined_tags: fields.num_defined_tags,
             num_escaped_funcs: fields.num_escaped_funcs,
             num_runtime_data: fields.num_runtime_data,
+            num_tier_up_counters: fields.num_tier_up_counters,
             has_startup_func: fields.has_startup_func,
             imported_memories: 0,
             memories: 0,
@@ -977,6 +986,7 @@ impl<P: PtrSize> From<VMOffsetsFields<P>> for VMOffsets<P> {
             startup_func_ref: 0,
             runtime_data_bases: 0,
             runtime_data_lengths: 0,
+            tier_up_counters: 0,
             size: 0,
         };
         ret.compute_field_offsets();
@@ -1015,6 +1025,7 @@ impl_vmctx_array_index! {
     DefinedMemoryIndex,
     OwnedMemoryIndex,
     FuncIndex,
+    DefinedFuncIndex,
     TableIndex,
     DefinedTableIndex,
     GlobalIndex,
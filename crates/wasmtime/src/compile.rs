@@ -29,8 +29,8 @@ use crate::prelude::*;
 use std::{any::Any, borrow::Cow, collections::BTreeMap, mem, ops::Range};
 use wasmtime_environ::{
     Abi, CompiledFunctionBody, CompiledFunctionsTable, CompiledFunctionsTableBuilder,
-    CompiledModuleInfo, Compiler, DefinedFuncIndex, FilePos, FinishedObject, FuncKey,
-    FunctionBodyData, Inlining, InliningCompiler, ModuleEnvironment, ModuleTranslation,
+    CompiledModuleInfo, Compiler, DefinedFuncIndex, ExecutionProfile, FilePos, FinishedObject,
+    FuncKey, FunctionBodyData, Inlining, InliningCompiler, ModuleEnvironment, ModuleTranslation,
     ModuleTypes, ModuleTypesBuilder, ObjectKind, PrimaryMap, StaticModuleIndex, Tunables,
     graphs::{EntityGraph, Graph as _},
 };
@@ -44,6 +44,8 @@ pub use self::code_builder::{CodeBuilder, CodeHint, HashedEngineCompileEnv};
 
 #[cfg(feature = "runtime")]
 mod runtime;
+#[cfg(all(feature = "runtime", feature = "winch"))]
+pub(crate) use self::runtime::compile_tier;
 
 /// Converts an input binary-encoded WebAssembly module to compilation
 /// artifacts and type information.
@@ -61,6 +63,7 @@ pub(crate) fn build_module_artifacts<T: FinishedObject>(
     engine: &Engine,
     wasm: &[u8],
     dwarf_package: Option<&[u8]>,
+    execution_profile: Option<&ExecutionProfile>,
     obj_state: &T::State,
 ) -> Result<(
     T,
@@ -85,6 +88,7 @@ pub(crate) fn build_module_artifacts<T: FinishedObject>(
     )
     .translate(parser, wasm)
     .context("failed to parse WebAssembly module")?;
+    translation.execution_profile = execution_profile.cloned();
     prepare_translation(engine, compiler, &mut translation, &mut types);
     let functions = mem::take(&mut translation.function_body_inputs);
 
@@ -232,7 +236,7 @@ pub(crate) fn build_component_artifacts<T: FinishedObject>(
         ty,
         types,
         static_modules: compilation_artifacts.modules,
-        checksum: WasmChecksum::from_binary(binary, tunables.recording),
+        checksum: WasmChecksum::from_binary(binary),
     };
     object.serialize_info(&artifacts);
 
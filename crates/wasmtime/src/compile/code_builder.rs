@@ -2,6 +2,7 @@ use crate::Engine;
 use crate::prelude::*;
 use std::borrow::Cow;
 use std::path::Path;
+use wasmtime_environ::ExecutionProfile;
 
 #[cfg(feature = "compile-time-builtins")]
 use crate::hash_map::HashMap;
@@ -47,6 +48,7 @@ pub struct CodeBuilder<'a> {
     dwarf_package: Option<Cow<'a, [u8]>>,
     dwarf_package_path: Option<Cow<'a, Path>>,
     unsafe_intrinsics_import: Option<String>,
+    execution_profile: Option<ExecutionProfile>,
 
     /// A map from import name to the Wasm bytes of the associated compile-time
     /// builtin and its file path, if any.
@@ -79,6 +81,7 @@ impl<'a> CodeBuilder<'a> {
             dwarf_package: None,
             dwarf_package_path: None,
             unsafe_intrinsics_import: None,
+            execution_profile: None,
             #[cfg(feature = "compile-time-builtins")]
             compile_time_builtins: HashMap::default(),
         }
@@ -762,6 +765,51 @@ impl<'a> CodeBuilder<'a> {
         Ok(self)
     }
 
+    /// Provides an execution profile of the module being compiled, used to
+    /// guide the layout of its compiled code.
+    ///
+    /// The profile is text with one `<offset> <count>` pair per line, giving
+    /// how often the Wasm instruction at a module-relative byte offset
+    /// executed. These are the same offsets as in a compiled module's address
+    /// map, and sample counts work as well as exact counts, such as those
+    /// written by `wasmtime hot-blocks --profile-out`. Empty lines and lines
+    /// starting with `#` are ignored.
+    ///
+    /// Code on hot paths is laid out so that it's reached by falling through,
+    /// and rarely executed code is moved to the end of its function. Only
+    /// modules compiled with Cranelift make use of profiles.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a profile has already been set through this method
+    /// or [`CodeBuilder::execution_profile_file`], or if `profile` isn't a
+    /// valid profile.
+    pub fn execution_profile(&mut self, profile: &str) -> Result<&mut Self> {
+        if self.execution_profile.is_some() {
+            bail!("cannot call `execution_profile` or `execution_profile_file` twice");
+        }
+        let profile = ExecutionProfile::parse(profile).context("invalid execution profile")?;
+        self.execution_profile = Some(profile);
+        Ok(self)
+    }
+
+    /// Same as [`CodeBuilder::execution_profile`] except that the profile is
+    /// read from `file`.
+    ///
+    /// # Errors
+    ///
+    /// In addition to the errors of [`CodeBuilder::execution_profile`], this
+    /// method returns an error if `file` cannot be read.
+    pub fn execution_profile_file(&mut self, file: &Path) -> Result<&mut Self> {
+        let profile = std::fs::read_to_string(file)
+            .with_context(|| format!("failed to read execution profile: {}", file.display()))?;
+        self.execution_profile(&profile)
+    }
+
+    pub(super) fn get_execution_profile(&self) -> Option<&ExecutionProfile> {
+        self.execution_profile.as_ref()
+    }
+
     /// Returns a hint, if possible, of what the provided bytes are.
     ///
     /// This method can be use to detect what the previously supplied bytes to
@@ -811,8 +859,13 @@ impl<'a> CodeBuilder<'a> {
 
         let wasm = self.get_wasm()?;
         let dwarf_package = self.get_dwarf_package();
-        let (v, _) =
-            super::build_module_artifacts(self.engine, &wasm, dwarf_package.as_deref(), &())?;
+        let (v, _) = super::build_module_artifacts(
+            self.engine,
+            &wasm,
+            dwarf_package.as_deref(),
+            self.get_execution_profile(),
+            &(),
+        )?;
         Ok(v)
     }
 
@@ -821,6 +874,11 @@ impl<'a> CodeBuilder<'a> {
     /// instead of a module.
     #[cfg(feature = "component-model")]
     pub fn compile_component_serialized(&self) -> Result<Vec<u8>> {
+        ensure!(
+            self.execution_profile.is_none(),
+            "`CodeBuilder::execution_profile` can only be used with modules"
+        );
+
         let wasm = self.get_wasm()?;
         let (v, _) = super::build_component_artifacts(
             self.engine,
@@ -5,7 +5,7 @@ use crate::runtime::vm::MmapVec;
 use crate::{CodeBuilder, CodeMemory, Engine, Module};
 use object::write::WritableBuffer;
 use std::sync::Arc;
-use wasmtime_environ::{FinishedObject, ObjectBuilder};
+use wasmtime_environ::{ExecutionProfile, FinishedObject, ObjectBuilder};
 
 impl<'a> CodeBuilder<'a> {
     fn compile_cached<T, S>(
@@ -15,6 +15,7 @@ impl<'a> CodeBuilder<'a> {
             &[u8],
             Option<&[u8]>,
             Option<&str>,
+            Option<&ExecutionProfile>,
             &S,
         ) -> Result<(MmapVecWrapper, Option<T>)>,
         state: &S,
@@ -22,6 +23,7 @@ impl<'a> CodeBuilder<'a> {
         let wasm = self.get_wasm()?;
         let dwarf_package = self.get_dwarf_package();
         let unsafe_intrinsics_import = self.get_unsafe_intrinsics_import();
+        let execution_profile = self.get_execution_profile();
 
         self.engine
             .check_compatible_with_native_host()
@@ -34,6 +36,7 @@ impl<'a> CodeBuilder<'a> {
                 &wasm,
                 &dwarf_package,
                 &unsafe_intrinsics_import,
+                &execution_profile,
                 // Don't hash this as it's just its own "pure" function pointer.
                 NotHashed(build_artifacts),
                 // Don't hash the FinishedObject state: this contains
@@ -51,6 +54,7 @@ impl<'a> CodeBuilder<'a> {
                             wasm,
                             dwarf_package,
                             unsafe_intrinsics_import,
+                            execution_profile,
                             build_artifacts,
                             state,
                         )|
@@ -60,17 +64,18 @@ impl<'a> CodeBuilder<'a> {
                                 wasm,
                                 dwarf_package.as_deref(),
                                 **unsafe_intrinsics_import,
+                                **execution_profile,
                                 state.0,
                             )?;
                             let code = publish_mmap(engine.0, mmap.0)?;
                             Ok((code, info))
                         },
                         // Implementation of how to serialize artifacts
-                        |(_engine, _wasm, _, _, _, _), (code, _info_and_types)| {
+                        |(_engine, _wasm, _, _, _, _, _), (code, _info_and_types)| {
                             Some(code.mmap().to_vec())
                         },
                         // Cache hit, deserialize the provided artifacts
-                        |(engine, wasm, _, _, _, _), serialized_bytes| {
+                        |(engine, wasm, _, _, _, _, _), serialized_bytes| {
                             let kind = if wasmparser::Parser::is_component(&wasm) {
                                 wasmtime_environ::ObjectKind::Component
                             } else {
@@ -96,6 +101,7 @@ impl<'a> CodeBuilder<'a> {
                 &wasm,
                 dwarf_package.as_deref(),
                 unsafe_intrinsics_import,
+                execution_profile,
                 state,
             )?;
             let code = publish_mmap(self.engine, mmap.0)?;
@@ -122,22 +128,34 @@ impl<'a> CodeBuilder<'a> {
 
         let custom_alignment = self.custom_alignment();
         let (code, info_and_types) = self.compile_cached(
-            |engine, wasm, dwarf, unsafe_intrinsics_import, state| {
+            |engine, wasm, dwarf, unsafe_intrinsics_import, execution_profile, state| {
                 assert!(unsafe_intrinsics_import.is_none());
-                super::build_module_artifacts(engine, wasm, dwarf, state)
+                super::build_module_artifacts(engine, wasm, dwarf, execution_profile, state)
             },
             &custom_alignment,
         )?;
-        Module::from_parts(self.engine, code, info_and_types)
+        let module = Module::from_parts(self.engine, code, info_and_types)?;
+        #[cfg(feature = "winch")]
+        if self.engine.tunables().tiered_compilation {
+            let wasm = self.get_wasm()?.into_owned();
+            module.set_tier_up(wasm, self.get_execution_profile());
+        }
+        Ok(module)
     }
 
     /// Same as [`CodeBuilder::compile_module`] except that it compiles a
     /// [`Component`] instead of a module.
     #[cfg(feature = "component-model")]
     pub fn compile_component(&self) -> Result<Component> {
+        ensure!(
+            self.get_execution_profile().is_none(),
+            "`CodeBuilder::execution_profile` can only be used with modules"
+        );
+
         let custom_alignment = self.custom_alignment();
         let (code, artifacts) = self.compile_cached(
-            |engine, wasm, dwarf, unsafe_intrinsics_import, state| {
+            |engine, wasm, dwarf, unsafe_intrinsics_import, execution_profile, state| {
+                assert!(execution_profile.is_none());
                 super::build_component_artifacts(
                     engine,
                     wasm,
@@ -152,16 +170,32 @@ impl<'a> CodeBuilder<'a> {
     }
 
     fn custom_alignment(&self) -> CustomAlignment {
-        CustomAlignment {
-            alignment: self
-                .engine
-                .custom_code_memory()
-                .map(|c| c.required_alignment())
-                .unwrap_or(1),
-        }
+        CustomAlignment::new(self.engine)
     }
 }
 
+/// Compiles `wasm` again for tiered compilation, with the functions covered by
+/// `profile` compiled by the optimizing compiler.
+///
+/// Unlike [`CodeBuilder::compile_module`] this bypasses the cache and the
+/// resulting module doesn't recompile itself any further.
+#[cfg(feature = "winch")]
+pub(crate) fn compile_tier(
+    engine: &Engine,
+    wasm: &[u8],
+    profile: &ExecutionProfile,
+) -> Result<Module> {
+    let (mmap, info_and_types) = super::build_module_artifacts::<MmapVecWrapper>(
+        engine,
+        wasm,
+        None,
+        Some(profile),
+        &CustomAlignment::new(engine),
+    )?;
+    let code = publish_mmap(engine, mmap.0)?;
+    Module::from_parts(engine, code, info_and_types)
+}
+
 fn publish_mmap(engine: &Engine, mmap: MmapVec) -> Result<Arc<CodeMemory>> {
     let mut code = CodeMemory::new(engine, mmap)?;
     code.publish()?;
@@ -176,6 +210,17 @@ pub(crate) struct CustomAlignment {
     alignment: usize,
 }
 
+impl CustomAlignment {
+    fn new(engine: &Engine) -> CustomAlignment {
+        CustomAlignment {
+            alignment: engine
+                .custom_code_memory()
+                .map(|c| c.required_alignment())
+                .unwrap_or(1),
+        }
+    }
+}
+
 impl FinishedObject for MmapVecWrapper {
     type State = CustomAlignment;
     fn finish_object(obj: ObjectBuilder<'_>, align: &CustomAlignment) -> Result<Self> {
@@ -1432,6 +1432,48 @@ impl Config {
         self
     }
 
+    /// Configures whether modules are compiled with tiered compilation.
+    ///
+    /// With tiered compilation, every function of a module is first compiled
+    /// by Winch, which compiles quickly, and counts how many times it's
+    /// entered. Once a function has been entered
+    /// [`Config::tier_up_threshold`] times, the module is recompiled on a
+    /// background thread with Cranelift optimizing the hot functions.
+    /// Functions which become hot later are batched into a following
+    /// recompilation, which waits for more of them the more functions are
+    /// already optimized. Running instances switch to the new code the next
+    /// time one of their functions is entered: calls between functions of an
+    /// instance, indirect calls, and calls from the host all go through the
+    /// instance's function references, which are redirected to the optimized
+    /// code. Frames already executing Winch code finish executing it, and
+    /// instances that imported a function before the switch keep calling its
+    /// Winch code.
+    ///
+    /// Tiered compilation requires [`Strategy::Winch`], and modules are only
+    /// recompiled when they were compiled in this process: deserialized
+    /// modules and modules inside components keep running their Winch code.
+    ///
+    /// The default value for this is `false`.
+    #[cfg(feature = "winch")]
+    pub fn tiered_compilation(&mut self, enable: bool) -> &mut Self {
+        self.tunables.tiered_compilation = Some(enable);
+        self
+    }
+
+    /// Configures how many times a function has to be entered before
+    /// [tiered compilation](Config::tiered_compilation) recompiles it with
+    /// Cranelift.
+    ///
+    /// Entries are counted per instance. This setting has no effect unless
+    /// tiered compilation is enabled, and must not be zero.
+    ///
+    /// The default value for this is 1000.
+    #[cfg(feature = "winch")]
+    pub fn tier_up_threshold(&mut self, entries: u32) -> &mut Self {
+        self.tunables.tier_up_threshold = Some(entries);
+        self
+    }
+
     /// Configures which garbage collector will be used for Wasm modules.
     ///
     /// This method can be used to configure which garbage collector
@@ -2761,6 +2803,21 @@ impl Config {
             );
         }
 
+        if tunables.tiered_compilation {
+            ensure!(
+                tunables.winch_callable,
+                "tiered compilation requires the Winch compilation strategy"
+            );
+            ensure!(
+                !tunables.debug_guest,
+                "tiered compilation cannot be used with guest debugging"
+            );
+            ensure!(
+                tunables.tier_up_threshold > 0,
+                "the tier-up threshold must not be zero"
+            );
+        }
+
         // Concurrency support is required for some component model features.
         let requires_concurrency = WasmFeatures::CM_ASYNC
             | WasmFeatures::CM_MORE_ASYNC_BUILTINS
@@ -5018,6 +5075,16 @@ impl Engine {
         self.tunables().branch_hinting
     }
 
+    /// Returns the configured [`Config::tiered_compilation`] value.
+    pub fn get_tiered_compilation(&self) -> bool {
+        self.tunables().tiered_compilation
+    }
+
+    /// Returns the configured [`Config::tier_up_threshold`] value.
+    pub fn get_tier_up_threshold(&self) -> u32 {
+        self.tunables().tier_up_threshold
+    }
+
     /// Returns the configured [`Config::concurrency_support`] value.
     pub fn get_concurrency_support(&self) -> bool {
         self.tunables().concurrency_support
@@ -503,6 +503,9 @@ information about this check\
             | "tls_model" // wasmtime doesn't use tls right now
             | "opt_level" // opt level doesn't change semantics
             | "enable_alias_analysis" // alias analysis-based opts don't change semantics
+            | "enable_loop_unrolling" // loop opts don't change semantics
+            | "enable_slp_vectorization" // vectorization doesn't change semantics
+            | "egraph_register_pressure_limit" // code placement doesn't change semantics
             | "probestack_size_log2" // probestack above asserted disabled
             | "regalloc" // shouldn't change semantics
             | "enable_incremental_compilation_cache_checks" // shouldn't change semantics
@@ -315,6 +315,8 @@ impl Metadata<'_> {
             inlining_sum_size_threshold,
             concurrency_support,
             recording,
+            tiered_compilation,
+            tier_up_threshold,
 
             // This doesn't affect compilation, it's just a runtime setting.
             memory_reservation_for_growth: _,
@@ -423,6 +425,16 @@ impl Metadata<'_> {
             "concurrency support",
         )?;
         Self::check_bool(recording, other.recording, "RR recording support")?;
+        Self::check_bool(
+            tiered_compilation,
+            other.tiered_compilation,
+            "tiered compilation",
+        )?;
+        Self::check_int(
+            tier_up_threshold,
+            other.tier_up_threshold,
+            "tier-up threshold",
+        )?;
         Self::check_inlining(inlining, other.inlining)?;
         Self::check_int(
             gc_heap_reservation,
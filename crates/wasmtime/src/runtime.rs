@@ -54,6 +54,7 @@ pub(crate) mod module;
 #[cfg(feature = "debug-builtins")]
 pub(crate) mod native_debug;
 pub(crate) mod resources;
+pub(crate) mod snapshot;
 pub(crate) mod store;
 pub(crate) mod trampoline;
 pub(crate) mod trap;
@@ -102,10 +103,12 @@ pub use linker::*;
 pub use memory::*;
 pub use module::{Module, ModuleExport, ModuleFunction};
 pub use resources::*;
+pub use snapshot::{InstanceSnapshot, StoreSnapshot};
 #[cfg(all(feature = "async", feature = "call-hook"))]
 pub use store::CallHookHandler;
 pub use store::{
-    AsContext, AsContextMut, CallHook, Store, StoreContext, StoreContextMut, UpdateDeadline,
+    AsContext, AsContextMut, CallHook, InstanceResourceUsage, ResourceUsage, Store, StoreContext,
+    StoreContextMut, UpdateDeadline,
 };
 pub use trap::*;
 pub use types::*;
@@ -498,7 +498,6 @@ impl Component {
         &self.inner.static_modules[idx]
     }
 
-    #[cfg(any(feature = "profiling", feature = "debug"))]
     pub(crate) fn static_modules(&self) -> impl Iterator<Item = &Module> {
         self.inner.static_modules.values()
     }
@@ -909,11 +908,6 @@ impl Component {
         &self.inner.realloc_func_type
     }
 
-    #[allow(
-        unused,
-        reason = "used only for verification with wasmtime `rr` feature \
-        and requires a lot of unnecessary gating across crates"
-    )]
     pub(crate) fn checksum(&self) -> &WasmChecksum {
         &self.inner.checksum
     }
@@ -5234,6 +5234,12 @@ impl ConcurrentInstanceState {
     pub fn pending_is_empty(&self) -> bool {
         self.pending.is_empty()
     }
+
+    /// Returns whether this instance has no backpressure or pending calls and
+    /// may be entered, as is the case when no tasks are running within it.
+    pub fn is_idle(&self) -> bool {
+        self.backpressure == 0 && !self.do_not_enter && self.pending.is_empty()
+    }
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -5383,6 +5389,18 @@ impl Default for ConcurrentState {
 }
 
 impl ConcurrentState {
+    /// Returns whether no tasks, threads, waitables, or other concurrent work
+    /// exist within the store.
+    pub(crate) fn is_idle(&mut self) -> bool {
+        self.futures.get_mut().as_ref().is_none_or(|f| f.is_empty())
+            && self.table.get_mut().is_empty()
+            && self.high_priority.is_empty()
+            && self.low_priority.is_empty()
+            && self.suspend_reason.is_none()
+            && self.worker_item.is_none()
+            && self.global_error_context_ref_counts.is_empty()
+    }
+
     /// Take ownership of any fibers and futures owned by this object.
     ///
     /// This should be used when disposing of the `Store` containing this object
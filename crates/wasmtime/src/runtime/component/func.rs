@@ -1,6 +1,8 @@
 use crate::component::instance::Instance;
 use crate::component::matching::InstanceType;
 use crate::component::storage::storage_as_slice;
+#[cfg(feature = "wave")]
+use crate::component::trace::{self, CallTraceEvent};
 use crate::component::types::ComponentFunc;
 use crate::component::values::Val;
 use crate::prelude::*;
@@ -476,6 +478,12 @@ impl Func {
             lower(cx, ty, map_maybe_uninit!(space.params))
         })?;
 
+        // SAFETY: the parameters were just lowered into `space`.
+        #[cfg(feature = "wave")]
+        unsafe {
+            self.trace(store.0, CallTraceEvent::ExportCall, space);
+        }
+
         // SAFETY: We are providing the guarantee that all the inputs are valid.
         // The various pointers passed in for the function are all valid since
         // they're coming from our store, and the `params_and_results` should
@@ -502,6 +510,12 @@ impl Func {
             .component_resource_tables(Some(self.instance))?
             .validate_scope_exit()?;
 
+        // SAFETY: the callee returned its results into `space`.
+        #[cfg(feature = "wave")]
+        unsafe {
+            self.trace(store.0, CallTraceEvent::ExportReturn, space);
+        }
+
         // SAFETY: We're relying on the correctness of the structure of
         // `LowerReturn` and the type-checking performed to acquire the
         // `TypedFunc` to make this safe. It should be the case that
@@ -534,6 +548,45 @@ impl Func {
         ))
     }
 
+    /// Reports `event` to the store's call tracer, if any, rendering the
+    /// arguments or results of this function from `space`.
+    ///
+    /// # Safety
+    ///
+    /// `space` must hold the lowered arguments of this function for
+    /// `ExportCall`, or its results for `ExportReturn`.
+    #[cfg(feature = "wave")]
+    unsafe fn trace<S>(&self, store: &mut StoreOpaque, event: CallTraceEvent, space: &S) {
+        if !trace::enabled(store) {
+            return;
+        }
+        let name = trace::export_name(self.instance.id().get(store).component(), self.index);
+        let (options, _flags, ty, _raw_options) = self.abi_info(store);
+        let max_flat = match event {
+            CallTraceEvent::ExportCall => MAX_FLAT_PARAMS,
+            _ => MAX_FLAT_RESULTS,
+        };
+        // SAFETY: `space` is a union of `ValRaw`-based arguments and
+        // results, and it's a contract of this function that the relevant
+        // prefix of it is initialized.
+        unsafe {
+            let src = core::slice::from_raw_parts(
+                (space as *const S).cast::<MaybeUninit<ValRaw>>(),
+                mem::size_of::<S>() / mem::size_of::<ValRaw>(),
+            );
+            trace::emit_values(
+                store,
+                event,
+                &name,
+                self.instance,
+                options,
+                ty,
+                max_flat,
+                src,
+            );
+        }
+    }
+
     #[doc(hidden)]
     #[deprecated(note = "no longer needs to be called; this function has no effect")]
     pub fn post_return(&self, _store: impl AsContextMut) -> Result<()> {
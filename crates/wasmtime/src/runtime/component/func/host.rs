@@ -5,6 +5,8 @@ use crate::component::concurrent::{self, Accessor, Status};
 use crate::component::func::{LiftContext, LowerContext};
 use crate::component::matching::InstanceType;
 use crate::component::storage::{slice_to_storage, slice_to_storage_mut};
+#[cfg(feature = "wave")]
+use crate::component::trace::{self, CallTraceEvent};
 use crate::component::types::ComponentFunc;
 use crate::component::{ComponentNamedList, Instance, Lift, Lower, Val};
 use crate::prelude::*;
@@ -13,6 +15,8 @@ use crate::runtime::vm::component::{
 };
 use crate::runtime::vm::{VMOpaqueContext, VMStore};
 use crate::store::Asyncness;
+#[cfg(feature = "wave")]
+use crate::store::StoreOpaque;
 use crate::{AsContextMut, StoreContextMut, ValRaw};
 use alloc::sync::Arc;
 use core::any::Any;
@@ -359,7 +363,10 @@ where
         ty: TypeFuncIndex,
         options: OptionsIndex,
         storage: &mut [MaybeUninit<ValRaw>],
-    ) -> Result<()> {
+    ) -> Result<()>
+    where
+        Self: Sized,
+    {
         let vminstance = instance.id().get(store.0);
         let async_ = vminstance.component().env_component().options[options].async_;
 
@@ -399,11 +406,36 @@ where
         ty: TypeFuncIndex,
         options: OptionsIndex,
         storage: &mut [MaybeUninit<ValRaw>],
-    ) -> Result<()> {
+    ) -> Result<()>
+    where
+        Self: Sized,
+    {
         let entered_host_task = store.0.host_task_create()?;
 
+        #[cfg(feature = "wave")]
+        let trace_name = self.trace_name(store.0, instance);
+        #[cfg(feature = "wave")]
+        if let Some(name) = &trace_name {
+            // SAFETY: due to the contract of `entrypoint` the parameters, or
+            // the pointer to them, are initialized by wasm.
+            unsafe {
+                trace::emit_values(
+                    store.0,
+                    CallTraceEvent::ImportCall,
+                    name,
+                    instance,
+                    options,
+                    ty,
+                    MAX_FLAT_PARAMS,
+                    storage,
+                );
+            }
+        }
+
         let mut lift = LiftContext::new(store.0.store_opaque_mut(), options, instance)?;
         let (params, rest) = self.load_params(&mut lift, ty, MAX_FLAT_PARAMS, storage)?;
+        #[cfg(feature = "wave")]
+        let rest_start = storage.len() - rest.len();
 
         let ret = match self.run(store.as_context_mut(), params) {
             HostResult::Done(result) => result?,
@@ -416,7 +448,8 @@ where
         let mut lower = LowerContext::new(store, options, instance);
         let fty = &lower.types[ty];
         let result_tys = &lower.types[fty.results];
-        let dst = if let Some(cnt) = result_tys.abi.flat_count(MAX_FLAT_RESULTS) {
+        let flat_results = result_tys.abi.flat_count(MAX_FLAT_RESULTS);
+        let dst = if let Some(cnt) = flat_results {
             Destination::Flat(&mut storage[..cnt])
         } else {
             // SAFETY: due to the contract of `entrypoint` we know that the
@@ -431,7 +464,31 @@ where
         };
         lower.validate_scope_exit()?;
         lower.store.0.host_task_delete(entered_host_task)?;
-        Self::lower_raw(&mut lower, ty, ret, dst)
+        Self::lower_raw(&mut lower, ty, ret, dst)?;
+
+        #[cfg(feature = "wave")]
+        if let Some(name) = &trace_name {
+            let results_start = match flat_results {
+                Some(_) => 0,
+                None => rest_start,
+            };
+            // SAFETY: the results were just lowered into `storage`, or into
+            // linear memory at the return pointer stored after the
+            // parameters.
+            unsafe {
+                trace::emit_values(
+                    lower.store.0,
+                    CallTraceEvent::ImportReturn,
+                    name,
+                    instance,
+                    options,
+                    ty,
+                    MAX_FLAT_RESULTS,
+                    &storage[results_start..],
+                );
+            }
+        }
+        Ok(())
     }
 
     /// Implementation of the "async" ABI of the component model.
@@ -447,7 +504,10 @@ where
         ty: TypeFuncIndex,
         options: OptionsIndex,
         storage: &mut [MaybeUninit<ValRaw>],
-    ) -> Result<()> {
+    ) -> Result<()>
+    where
+        Self: Sized,
+    {
         use wasmtime_environ::component::MAX_FLAT_ASYNC_PARAMS;
 
         let (component, store) = instance.component_and_store_mut(store.0);
@@ -456,6 +516,26 @@ where
         let fty = &types[ty];
         let entered_host_task = store.0.host_task_create()?;
 
+        #[cfg(feature = "wave")]
+        let trace_name = self.trace_name(store.0, instance);
+        #[cfg(feature = "wave")]
+        if let Some(name) = &trace_name {
+            // SAFETY: due to the contract of `entrypoint` the parameters, or
+            // the pointer to them, are initialized by wasm.
+            unsafe {
+                trace::emit_values(
+                    store.0,
+                    CallTraceEvent::ImportCall,
+                    name,
+                    instance,
+                    options,
+                    ty,
+                    MAX_FLAT_ASYNC_PARAMS,
+                    storage,
+                );
+            }
+        }
+
         // Lift the parameters, either from flat storage or from linear
         // memory.
         let mut lift = LiftContext::new(store.0.store_opaque_mut(), options, instance)?;
@@ -486,6 +566,10 @@ where
                 lower.validate_scope_exit()?;
                 lower.store.0.host_task_delete(entered_host_task)?;
                 Self::lower_raw(&mut lower, ty, result, Destination::Memory(retptr))?;
+                #[cfg(feature = "wave")]
+                if let Some(name) = &trace_name {
+                    trace_async_return(lower.store.0, name, instance, options, ty, retptr);
+                }
                 Status::Returned.pack(None)
             }
             HostResult::Future(future) => instance.first_poll(
@@ -505,6 +589,10 @@ where
                     // right answer here, hence the upstream issue.
                     if let Some(result) = ret {
                         Self::lower_raw(&mut lower, ty, result, Destination::Memory(retptr))?;
+                        #[cfg(feature = "wave")]
+                        if let Some(name) = &trace_name {
+                            trace_async_return(lower.store.0, name, instance, options, ty, retptr);
+                        }
                     }
                     Ok(())
                 },
@@ -516,6 +604,21 @@ where
         Ok(())
     }
 
+    /// Returns the name of the import of `instance` that this host function
+    /// was lowered as, if calls in `store` are being traced.
+    #[cfg(feature = "wave")]
+    fn trace_name(&self, store: &StoreOpaque, instance: Instance) -> Option<String>
+    where
+        Self: Sized,
+    {
+        if !trace::enabled(store) {
+            return None;
+        }
+        let callee = Self::cabi_entrypoint as *const u8;
+        let data = (self as *const Self).cast::<u8>();
+        Some(trace::import_name(store, instance, callee, data))
+    }
+
     /// Loads parameters the wasm arguments `storage`.
     ///
     /// This will internally decide the ABI source of the parameters and use
@@ -573,6 +676,34 @@ where
     }
 }
 
+/// Reports the results of an async-lowered import, which were lowered into
+/// linear memory at `retptr`, to the store's call tracer.
+#[cfg(all(feature = "wave", feature = "component-model-async"))]
+fn trace_async_return(
+    store: &mut StoreOpaque,
+    name: &str,
+    instance: Instance,
+    options: OptionsIndex,
+    ty: TypeFuncIndex,
+    retptr: usize,
+) {
+    let src = [MaybeUninit::new(ValRaw::u64(retptr as u64))];
+    // SAFETY: async-lowered results are always stored in linear memory, and
+    // `src` holds the pointer to them.
+    unsafe {
+        trace::emit_values(
+            store,
+            CallTraceEvent::ImportReturn,
+            name,
+            instance,
+            options,
+            ty,
+            0,
+            &src,
+        );
+    }
+}
+
 /// Checks that a host function's `ASYNC`-ness (as encoded by which of
 /// `func_new`/`func_new_async`/`func_new_concurrent` — or their `_wrap`
 /// counterparts — constructed it) matches whether the component's WIT type
@@ -347,6 +347,14 @@ pub struct LiftContext<'a> {
     /// This is decremented for strings/lists, for example, to cap the size of
     /// data the host allocates on behalf of the guest.
     hostcall_fuel: usize,
+
+    /// The store's limit on the number of entries in a resource table.
+    resource_table_entry_limit: usize,
+
+    /// Whether handles are lifted as placeholders carrying their index rather
+    /// than being taken from their tables, used when tracing calls.
+    #[cfg(feature = "wave")]
+    peek_handles: bool,
 }
 
 #[doc(hidden)]
@@ -360,6 +368,7 @@ impl<'a> LiftContext<'a> {
     ) -> Result<LiftContext<'a>> {
         let store_id = store.id();
         let hostcall_fuel = store.hostcall_fuel();
+        let resource_table_entry_limit = store.resource_table_entry_limit;
         let current_scope_id = store.current_scope_id()?;
         // From `&mut StoreOpaque` provided the goal here is to project out
         // three different disjoint fields owned by the store: memory,
@@ -385,9 +394,26 @@ impl<'a> LiftContext<'a> {
             host_table,
             host_resource_data,
             hostcall_fuel,
+            resource_table_entry_limit,
+            #[cfg(feature = "wave")]
+            peek_handles: false,
         })
     }
 
+    /// Configures this context to lift handles as placeholder values which
+    /// leave the handle in its table, so that values can be inspected without
+    /// being consumed.
+    #[cfg(feature = "wave")]
+    pub(crate) fn set_peek_handles(&mut self) {
+        self.peek_handles = true;
+    }
+
+    /// Returns whether [`Self::set_peek_handles`] was called.
+    #[cfg(feature = "wave")]
+    pub(crate) fn peek_handles(&self) -> bool {
+        self.peek_handles
+    }
+
     /// Returns the canonical options that are being used during lifting.
     pub fn options(&self) -> &CanonicalOptions {
         &self.instance.component().env_component().options[self.options]
@@ -494,6 +520,7 @@ impl<'a> LiftContext<'a> {
                 task_state: self.task_state,
                 guest: Some(self.instance.as_mut().instance_states()),
                 current_scope_id: self.current_scope_id,
+                entry_limit: self.resource_table_entry_limit,
             },
             self.host_resource_data,
         )
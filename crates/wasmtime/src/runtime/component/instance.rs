@@ -12,11 +12,12 @@ use crate::prelude::*;
 use crate::runtime::vm::component::{ComponentInstance, TypedResource, TypedResourceIndex};
 use crate::runtime::vm::{self, VMFuncRef};
 use crate::store::{AsStoreOpaque, Asyncness, StoreOpaque};
-use crate::{AsContext, AsContextMut, Engine, Module, StoreContextMut};
+use crate::{AsContext, AsContextMut, Engine, InstanceSnapshot, Module, StoreContextMut};
 use alloc::sync::Arc;
 use core::marker;
 use core::pin::Pin;
 use core::ptr::NonNull;
+use core::sync::atomic::{AtomicUsize, Ordering};
 use wasmtime_environ::{EngineOrModuleTypeIndex, component::*};
 use wasmtime_environ::{EntityIndex, EntityType, PrimaryMap};
 
@@ -661,6 +662,10 @@ struct Instantiator<'a> {
     id: ComponentInstanceId,
     core_imports: OwnedImports,
     imports: &'a PrimaryMap<RuntimeImportIndex, RuntimeImport>,
+
+    /// Snapshots of each core instance, by `RuntimeInstanceIndex`, when
+    /// restoring a store snapshot, or empty otherwise.
+    snapshots: &'a [InstanceSnapshot],
 }
 
 pub(crate) enum RuntimeImport {
@@ -694,6 +699,7 @@ impl<'a> Instantiator<'a> {
         component: &'a Component,
         store: &mut StoreOpaque,
         imports: &'a Arc<PrimaryMap<RuntimeImportIndex, RuntimeImport>>,
+        snapshots: &'a [InstanceSnapshot],
     ) -> Result<Instantiator<'a>> {
         let env_component = component.env_component();
         let (modules, engine, breakpoints) = store.modules_and_engine_and_breakpoints_mut();
@@ -715,6 +721,7 @@ impl<'a> Instantiator<'a> {
             imports,
             core_imports: OwnedImports::empty(),
             id,
+            snapshots,
         })
     }
 
@@ -785,11 +792,13 @@ impl<'a> Instantiator<'a> {
             );
         }
 
+        let restoring = !self.snapshots.is_empty();
+        let mut snapshots = self.snapshots.iter();
         for initializer in env_component.initializers.iter() {
             match initializer {
                 GlobalInitializer::InstantiateModule(m, component_instance) => {
                     let instance = self.id;
-                    let module;
+                    let mut module;
                     let imports = match m {
                         // Since upvars are statically know we know that the
                         // `args` list is already in the right order.
@@ -818,6 +827,22 @@ impl<'a> Instantiator<'a> {
                         }
                     };
 
+                    // When restoring a snapshot the instance is created from
+                    // the snapshot's forked module instead, which has the same
+                    // imports as the original.
+                    let snapshot = if !restoring {
+                        None
+                    } else {
+                        let snapshot = snapshots
+                            .next()
+                            .filter(|s| s.module().checksum() == module.checksum())
+                            .ok_or_else(|| {
+                                format_err!("store snapshot does not match its components")
+                            })?;
+                        module = snapshot.fork_module();
+                        Some(snapshot)
+                    };
+
                     let exit = if let Some(component_instance) = *component_instance {
                         store.0.enter_guest_sync_call(
                             None,
@@ -850,6 +875,10 @@ impl<'a> Instantiator<'a> {
                         store.0.exit_guest_sync_call()?;
                     }
 
+                    if let Some(snapshot) = snapshot {
+                        snapshot.restore(store.0, i)?;
+                    }
+
                     self.instance_mut(store.0).push_instance_id(i.id())?;
                 }
 
@@ -1062,6 +1091,9 @@ pub struct InstancePre<T: 'static> {
     component: Component,
     imports: Arc<PrimaryMap<RuntimeImportIndex, RuntimeImport>>,
     resource_types: Arc<TryPrimaryMap<ResourceIndex, ResourceType>>,
+    /// Components whose exports were defined in the linker this was created
+    /// from, which are instantiated first in each store.
+    linked: Arc<Vec<Arc<LinkedComponent<T>>>>,
     asyncness: Asyncness,
     _marker: marker::PhantomData<fn() -> T>,
 }
@@ -1073,12 +1105,53 @@ impl<T: 'static> Clone for InstancePre<T> {
             component: self.component.clone(),
             imports: self.imports.clone(),
             resource_types: self.resource_types.clone(),
+            linked: self.linked.clone(),
             asyncness: self.asyncness,
             _marker: self._marker,
         }
     }
 }
 
+/// A component whose exports were defined in a linker with
+/// [`Linker::define_component_exports`](crate::component::Linker::define_component_exports).
+///
+/// The component is instantiated at most once in each store, before any
+/// component created from that linker, and calls to its exports through the
+/// linker are forwarded to the instance in the calling store.
+pub(crate) struct LinkedComponent<T: 'static> {
+    id: usize,
+    pre: InstancePre<T>,
+}
+
+impl<T: 'static> LinkedComponent<T> {
+    pub(crate) fn new(pre: InstancePre<T>) -> LinkedComponent<T> {
+        static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+        LinkedComponent {
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            pre,
+        }
+    }
+
+    pub(crate) fn id(&self) -> usize {
+        self.id
+    }
+
+    pub(crate) fn pre(&self) -> &InstancePre<T> {
+        &self.pre
+    }
+
+    /// Returns the instance of this component in `store`.
+    pub(crate) fn instance(&self, store: &StoreOpaque) -> Result<Instance> {
+        store.linked_component_instance(self.id).ok_or_else(|| {
+            format_err!(
+                "linked component was not instantiated in this store; \
+                 components importing its exports must be instantiated \
+                 through the linker it was defined in"
+            )
+        })
+    }
+}
+
 impl<T: 'static> InstancePre<T> {
     /// This function is `unsafe` since there's no guarantee that the
     /// `RuntimeImport` items provided are guaranteed to work with the `T` of
@@ -1090,8 +1163,12 @@ impl<T: 'static> InstancePre<T> {
         component: Component,
         imports: Arc<PrimaryMap<RuntimeImportIndex, RuntimeImport>>,
         resource_types: Arc<TryPrimaryMap<ResourceIndex, ResourceType>>,
+        linked: Arc<Vec<Arc<LinkedComponent<T>>>>,
     ) -> InstancePre<T> {
         let mut asyncness = Asyncness::No;
+        for linked in linked.iter() {
+            asyncness = asyncness | linked.pre.asyncness;
+        }
         for (_, import) in imports.iter() {
             asyncness = asyncness
                 | match import {
@@ -1104,6 +1181,7 @@ impl<T: 'static> InstancePre<T> {
             component,
             imports,
             resource_types,
+            linked,
             asyncness,
             _marker: marker::PhantomData,
         }
@@ -1130,6 +1208,16 @@ impl<T: 'static> InstancePre<T> {
         self.component.engine()
     }
 
+    pub(crate) fn runtime_import(&self, index: RuntimeImportIndex) -> &RuntimeImport {
+        &self.imports[index]
+    }
+
+    /// Returns the components linked to this one, which are instantiated
+    /// before it in each store.
+    pub(crate) fn linked(&self) -> &[Arc<LinkedComponent<T>>] {
+        &self.linked
+    }
+
     /// Performs the instantiation process into the store specified.
     ///
     /// # Errors
@@ -1171,8 +1259,58 @@ impl<T: 'static> InstancePre<T> {
         mut store: impl AsContextMut<Data = T>,
         asyncness: Asyncness,
     ) -> Result<Instance> {
-        let store = store.as_context_mut();
+        let mut store = store.as_context_mut();
+
+        // Linked components are instantiated once per store, in the order
+        // they were defined, so they can be called by the instance created
+        // here. The list already includes the components they link to.
+        for linked in self.linked.iter() {
+            if store.0.linked_component_instance(linked.id).is_none() {
+                let instance = linked
+                    .pre
+                    .instantiate_component(store.as_context_mut(), &[], asyncness)
+                    .await?;
+                store
+                    .0
+                    .push_linked_component_instance(linked.id, instance)?;
+            }
+        }
+
+        self.instantiate_component(store, &[], asyncness).await
+    }
+
+    /// Instantiates this component with the state of `snapshots`, the
+    /// snapshots of each of its core instances, as part of restoring a store
+    /// snapshot.
+    pub(crate) async fn instantiate_snapshot(
+        &self,
+        store: StoreContextMut<'_, T>,
+        snapshots: &[InstanceSnapshot],
+        asyncness: Asyncness,
+    ) -> Result<Instance> {
+        if !self.linked.is_empty() {
+            bail!(
+                "cannot restore a store snapshot with components linked with \
+                 `Linker::define_component_exports`"
+            );
+        }
+        if asyncness == Asyncness::No {
+            store.0.set_async_required(self.asyncness);
+            store.0.validate_sync_call()?;
+        }
+        self.instantiate_component(store, snapshots, asyncness)
+            .await
+    }
+
+    /// Instantiates only this component, without the components it links to.
+    async fn instantiate_component(
+        &self,
+        store: StoreContextMut<'_, T>,
+        snapshots: &[InstanceSnapshot],
+        asyncness: Asyncness,
+    ) -> Result<Instance> {
         store.0.set_async_required(self.asyncness);
+        store.0.bump_component_instance_count()?;
         store
             .engine()
             .allocator()
@@ -1186,7 +1324,7 @@ impl<T: 'static> InstancePre<T> {
         };
 
         let mut instantiator =
-            Instantiator::new(&self.component, decrement.store.0, &self.imports)?;
+            Instantiator::new(&self.component, decrement.store.0, &self.imports, snapshots)?;
         instantiator.run(&mut decrement.store, asyncness).await?;
 
         let instance = Instance::from_wasmtime(decrement.store.0, instantiator.id);
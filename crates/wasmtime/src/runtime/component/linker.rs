@@ -1,19 +1,21 @@
 #[cfg(feature = "component-model-async")]
 use crate::component::concurrent::Accessor;
 use crate::component::func::HostFunc;
-use crate::component::instance::RuntimeImport;
+use crate::component::instance::{LinkedComponent, RuntimeImport};
 use crate::component::matching::{InstanceType, TypeChecker};
 use crate::component::types;
 use crate::component::{
-    Component, ComponentNamedList, Instance, InstancePre, Lift, Lower, ResourceType, Val,
+    Component, ComponentNamedList, Func, Instance, InstancePre, Lift, Lower, ResourceType, Val,
 };
 use crate::prelude::*;
-use crate::{AsContextMut, Engine, Module, StoreContextMut};
+use crate::runtime::vm;
+use crate::store::Asyncness;
+use crate::{AsContextMut, Engine, Module, StoreContextMut, StoreSnapshot};
 use alloc::sync::Arc;
 use core::marker;
 #[cfg(feature = "component-model-async")]
 use core::pin::Pin;
-use wasmtime_environ::component::{NameMap, NameMapIntern};
+use wasmtime_environ::component::{ExportIndex, NameMap, NameMapIntern};
 use wasmtime_environ::{Atom, PrimaryMap, StringPool};
 
 /// A type used to instantiate [`Component`]s.
@@ -63,6 +65,9 @@ pub struct Linker<T: 'static> {
     map: NameMap<Atom, Definition>,
     path: Vec<Atom>,
     allow_shadowing: bool,
+    /// Components whose exports are defined in this linker, in the order they
+    /// must be instantiated in.
+    linked: Vec<Arc<LinkedComponent<T>>>,
     _marker: marker::PhantomData<fn() -> T>,
 }
 
@@ -74,6 +79,7 @@ impl<T: 'static> Clone for Linker<T> {
             map: self.map.clone_panic_on_oom(),
             path: self.path.clone(),
             allow_shadowing: self.allow_shadowing,
+            linked: self.linked.clone(),
             _marker: self._marker,
         }
     }
@@ -123,6 +129,7 @@ impl<T: 'static> Linker<T> {
             map: NameMap::default(),
             allow_shadowing: false,
             path: Vec::new(),
+            linked: Vec::new(),
             _marker: marker::PhantomData,
         }
     }
@@ -141,6 +148,17 @@ impl<T: 'static> Linker<T> {
         self
     }
 
+    /// Returns whether an item named `name` is defined in the root namespace
+    /// of this linker.
+    ///
+    /// Names are looked up the same way as the imports of a component, so
+    /// for example `a:b/c@1.0.0` is considered defined if `a:b/c@1.0.1` is.
+    /// Note that this does not check the type of the item, which is done by
+    /// [`Linker::instantiate_pre`] for example.
+    pub fn is_defined(&self, name: &str) -> bool {
+        self.map.get(name, &self.strings).is_some()
+    }
+
     /// Returns the "root instance" of this linker, used to define names into
     /// the root namespace.
     pub fn root(&mut self) -> LinkerInstance<'_, T> {
@@ -285,6 +303,7 @@ impl<T: 'static> Linker<T> {
                 component.clone(),
                 try_new::<Arc<_>>(imports)?,
                 imported_resources,
+                try_new::<Arc<_>>(self.linked.clone())?,
             )
         })
     }
@@ -344,6 +363,44 @@ impl<T: 'static> Linker<T> {
             .await
     }
 
+    /// Recreates all component instances of `snapshot` within `store`,
+    /// resolving their imports with this linker.
+    ///
+    /// The returned instances are in the order they were created in the store
+    /// which was snapshotted. See [`StoreSnapshot`] for more information.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for the same reasons as [`Linker::instantiate`], if
+    /// `snapshot` contains core instances which don't belong to a component,
+    /// or if its state is otherwise invalid for its components.
+    pub fn instantiate_store_snapshot(
+        &self,
+        mut store: impl AsContextMut<Data = T>,
+        snapshot: &StoreSnapshot,
+    ) -> Result<Vec<Instance>> {
+        let mut store = store.as_context_mut();
+        store.0.validate_sync_call()?;
+        vm::assert_ready(snapshot.instantiate_components(self, &mut store, Asyncness::No))
+    }
+
+    /// Same as [`Linker::instantiate_store_snapshot`], except for
+    /// [asynchronous execution](crate#async).
+    #[cfg(feature = "async")]
+    pub async fn instantiate_store_snapshot_async(
+        &self,
+        mut store: impl AsContextMut<Data = T>,
+        snapshot: &StoreSnapshot,
+    ) -> Result<Vec<Instance>>
+    where
+        T: Send,
+    {
+        let mut store = store.as_context_mut();
+        snapshot
+            .instantiate_components(self, &mut store, Asyncness::Yes)
+            .await
+    }
+
     /// Implement any imports of the given [`Component`] with a function which traps.
     ///
     /// By default a [`Linker`] will error when unknown imports are encountered when instantiating a [`Component`].
@@ -355,15 +412,145 @@ impl<T: 'static> Linker<T> {
     /// memory allocation fails. See the `OutOfMemory` type's documentation for
     /// details on Wasmtime's out-of-memory handling.
     pub fn define_unknown_imports_as_traps(&mut self, component: &Component) -> Result<()> {
+        self.define_unknown_imports(
+            component,
+            &mut |linker, item_name, fully_qualified_name, _async| {
+                // An `async func`-typed import can never be satisfied by
+                // `func_new` (only a sync-typed import can) — see
+                // `typecheck_async`'s doc comment. Stub it with
+                // `func_new_concurrent` instead so unsatisfied async
+                // imports can be stubbed-as-traps too, not just sync
+                // ones; if concurrency support isn't enabled there's no
+                // way to stub it here, so fall through to `func_new` and
+                // let instantiation fail with that same explanatory
+                // error.
+                #[cfg(feature = "component-model-async")]
+                if _async && linker.engine.tunables().concurrency_support {
+                    return linker.func_new_concurrent(item_name, move |_, _, _, _| {
+                        let fully_qualified_name = fully_qualified_name.try_clone();
+                        Box::pin(async move {
+                            let fully_qualified_name = fully_qualified_name?;
+                            bail!("unknown import: `{fully_qualified_name}` has not been defined")
+                        })
+                    });
+                }
+
+                linker.func_new(item_name, move |_, _, _, _| {
+                    bail!("unknown import: `{fully_qualified_name}` has not been defined")
+                })
+            },
+        )
+    }
+
+    /// Implements any unknown imports of the given [`Component`] by routing
+    /// calls to them to `handler`.
+    ///
+    /// This is similar to [`Linker::define_unknown_imports_as_traps`] except
+    /// that calls to unknown functions are passed to `handler` alongside the
+    /// fully-qualified name of the import, for example
+    /// `my:pkg/iface#func`, and its type. The `handler` receives the
+    /// function's parameters as [`Val`]s and is expected to fill in its
+    /// results, which makes it possible to mock out imports which aren't
+    /// available in the host. Unknown resource types are defined with a
+    /// destructor which does nothing.
+    ///
+    /// Note that imports of `async` functions can't be implemented this way
+    /// and instantiating a component which imports an unknown `async`
+    /// function will fail.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the component imports a core module or a
+    /// component which isn't defined in this linker.
+    ///
+    /// This function will return an [`OutOfMemory`][crate::OutOfMemory] error when
+    /// memory allocation fails. See the `OutOfMemory` type's documentation for
+    /// details on Wasmtime's out-of-memory handling.
+    pub fn define_unknown_imports_with(
+        &mut self,
+        component: &Component,
+        handler: impl Fn(
+            StoreContextMut<'_, T>,
+            &str,
+            types::ComponentFunc,
+            &[Val],
+            &mut [Val],
+        ) -> Result<()>
+        + Send
+        + Sync
+        + 'static,
+    ) -> Result<()> {
+        let handler = try_new::<Arc<_>>(handler)?;
+        self.define_unknown_imports(
+            component,
+            &mut |linker, item_name, fully_qualified_name, _async| {
+                let handler = handler.clone();
+                linker.func_new(item_name, move |store, ty, params, results| {
+                    handler(store, &fully_qualified_name, ty, params, results)
+                })
+            },
+        )
+    }
+
+    /// Implements any unknown imports of the given [`Component`] with a
+    /// function which ignores its arguments and returns default values.
+    ///
+    /// Default values are zero for numbers, `false`, empty strings, lists,
+    /// maps, and flags, `none` for options, `ok` for results, and the first
+    /// case of variants and enums. Aggregates such as records and tuples are
+    /// filled in with the default values of their fields. Calling a function
+    /// which returns a resource, future, stream, or error context traps as
+    /// there is no default value of those types.
+    ///
+    /// This method can be used to smoke-test components outside of their real
+    /// host.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`Linker::define_unknown_imports_with`].
+    pub fn define_unknown_imports_as_default_values(
+        &mut self,
+        component: &Component,
+    ) -> Result<()> {
+        self.define_unknown_imports_with(component, |_, name, ty, _params, results| {
+            for (result, ty) in results.iter_mut().zip(ty.results()) {
+                *result = default_val(&ty)
+                    .with_context(|| format!("unknown import `{name}` has no default result"))?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Recursively defines all imports of `component` not already defined in
+    /// this linker, using `define_func` to define functions.
+    ///
+    /// `define_func` is given the name to define, the fully-qualified name
+    /// of the import, and whether it's an `async` function.
+    fn define_unknown_imports(
+        &mut self,
+        component: &Component,
+        define_func: &mut dyn FnMut(
+            &mut LinkerInstance<'_, T>,
+            &str,
+            TryString,
+            bool,
+        ) -> Result<()>,
+    ) -> Result<()> {
         use wasmtime_environ::component::ComponentTypes;
         use wasmtime_environ::component::TypeDef;
-        // Recursively stub out all imports of the component with a function that traps.
+        // Recursively stub out all imports of the component.
         fn stub_item<T>(
             linker: &mut LinkerInstance<T>,
             item_name: &str,
             item_def: &TypeDef,
             parent_instance: Option<&str>,
             types: &ComponentTypes,
+            define_func: &mut dyn FnMut(
+                &mut LinkerInstance<'_, T>,
+                &str,
+                TryString,
+                bool,
+            ) -> Result<()>,
         ) -> Result<()> {
             // Skip if the item isn't an instance and has already been defined in the linker.
             if !matches!(item_def, TypeDef::ComponentInstance(_)) && linker.get(item_name).is_some()
@@ -372,7 +559,7 @@ impl<T: 'static> Linker<T> {
             }
 
             match item_def {
-                TypeDef::ComponentFunc(_func_idx) => {
+                TypeDef::ComponentFunc(func_idx) => {
                     let fully_qualified_name = match parent_instance {
                         Some(parent) => {
                             let mut s = TryString::new();
@@ -387,33 +574,12 @@ impl<T: 'static> Linker<T> {
                             s
                         }
                     };
-
-                    // An `async func`-typed import can never be satisfied by
-                    // `func_new` (only a sync-typed import can) — see
-                    // `typecheck_async`'s doc comment. Stub it with
-                    // `func_new_concurrent` instead so unsatisfied async
-                    // imports can be stubbed-as-traps too, not just sync
-                    // ones; if concurrency support isn't enabled there's no
-                    // way to stub it here, so fall through to `func_new` and
-                    // let instantiation fail with that same explanatory
-                    // error.
-                    #[cfg(feature = "component-model-async")]
-                    if types[*_func_idx].async_ && linker.engine.tunables().concurrency_support {
-                        linker.func_new_concurrent(&item_name, move |_, _, _, _| {
-                            let fully_qualified_name = fully_qualified_name.try_clone();
-                            Box::pin(async move {
-                                let fully_qualified_name = fully_qualified_name?;
-                                bail!(
-                                    "unknown import: `{fully_qualified_name}` has not been defined"
-                                )
-                            })
-                        })?;
-                        return Ok(());
-                    }
-
-                    linker.func_new(&item_name, move |_, _, _, _| {
-                        bail!("unknown import: `{fully_qualified_name}` has not been defined")
-                    })?;
+                    define_func(
+                        linker,
+                        item_name,
+                        fully_qualified_name,
+                        types[*func_idx].async_,
+                    )?;
                 }
                 TypeDef::ComponentInstance(i) => {
                     let instance = &types[*i];
@@ -425,6 +591,7 @@ impl<T: 'static> Linker<T> {
                             &export.ty,
                             Some(item_name),
                             types,
+                            define_func,
                         )?;
                     }
                 }
@@ -433,7 +600,7 @@ impl<T: 'static> Linker<T> {
                     linker.resource(item_name, ty, |_, _| Ok(()))?;
                 }
                 TypeDef::Component(_) | TypeDef::Module(_) => {
-                    bail!("unable to define {} imports as traps", item_def.desc())
+                    bail!("unable to define unknown {} imports", item_def.desc())
                 }
                 _ => {}
             }
@@ -447,10 +614,374 @@ impl<T: 'static> Linker<T> {
                 &import_type.ty,
                 None,
                 component.types(),
+                define_func,
             )?;
         }
         Ok(())
     }
+
+    /// Defines all exports of the component instantiated by `provider` within
+    /// this linker, making them available as imports to components
+    /// instantiated later.
+    ///
+    /// This enables composing components at runtime: the exported interfaces
+    /// of `provider`'s component, for example `my:db/adapter@1.0.0`, are
+    /// registered in this linker under the same names. A component which
+    /// imports those interfaces can then be instantiated with this linker, and
+    /// its calls to them are forwarded to an instance of `provider`. This makes
+    /// it possible to swap out the implementation of an interface without
+    /// statically composing components ahead of time.
+    ///
+    /// The provider is instantiated once in each store, when the first
+    /// component is instantiated there through this linker, or through an
+    /// [`InstancePre`] created from it, and before that component. Providers
+    /// are instantiated in the order they were defined in, so `provider` may
+    /// itself have been created from this linker to import the exports of
+    /// components defined earlier. Calls to the exports are forwarded to the
+    /// instance within the calling store, so this linker, and any
+    /// [`InstancePre`] created from it, can be used with any number of stores.
+    ///
+    /// Exported functions and core modules are defined, recursively, within
+    /// exported instances. Exported types other than resources are skipped as
+    /// they are not defined through a linker.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `provider`'s component exports a resource type,
+    /// which cannot currently be forwarded between components through a
+    /// linker, if `provider` belongs to a different engine, or if a name is
+    /// already defined in this linker and shadowing is disallowed.
+    ///
+    /// This function will return an [`OutOfMemory`][crate::OutOfMemory] error when
+    /// memory allocation fails. See the `OutOfMemory` type's documentation for
+    /// details on Wasmtime's out-of-memory handling.
+    pub fn define_component_exports(&mut self, provider: &InstancePre<T>) -> Result<()> {
+        self.define_component_exports_with(provider, |linker, name, provider, index, _| {
+            linker.func_new(name, move |mut store, _ty, params, results| {
+                let instance = provider.instance(store.0)?;
+                Func::from_lifted_func(instance, index).call(&mut store, params, results)
+            })
+        })
+    }
+
+    /// Same as [`Linker::define_component_exports`] except for use with
+    /// [asynchronous execution](crate#async).
+    ///
+    /// The forwarded functions are defined with [`LinkerInstance::func_new_async`]
+    /// and must be invoked asynchronously. When
+    /// [`Config::concurrency_support`](crate::Config::concurrency_support) is
+    /// enabled, `async` functions are instead defined with
+    /// [`LinkerInstance::func_new_concurrent`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`Linker::define_component_exports`].
+    #[cfg(feature = "async")]
+    pub fn define_component_exports_async(&mut self, provider: &InstancePre<T>) -> Result<()>
+    where
+        T: Send,
+    {
+        #[cfg(feature = "component-model-async")]
+        let concurrent = self.engine.tunables().concurrency_support;
+        self.define_component_exports_with(provider, |linker, name, provider, index, async_| {
+            #[cfg(feature = "component-model-async")]
+            if concurrent && async_ {
+                return linker.func_new_concurrent(name, move |accessor, _ty, params, results| {
+                    let provider = provider.clone();
+                    Box::pin(async move {
+                        let instance = accessor
+                            .with(|mut store| provider.instance(store.as_context_mut().0))?;
+                        Func::from_lifted_func(instance, index)
+                            .call_concurrent(accessor, params, results)
+                            .await
+                    })
+                });
+            }
+            #[cfg(not(feature = "component-model-async"))]
+            let _ = async_;
+            linker.func_new_async(name, move |store, _ty, params, results| {
+                let provider = provider.clone();
+                Box::new(async move {
+                    let instance = provider.instance(store.0)?;
+                    Func::from_lifted_func(instance, index)
+                        .call_async(store, params, results)
+                        .await
+                })
+            })
+        })
+    }
+
+    fn define_component_exports_with(
+        &mut self,
+        provider: &InstancePre<T>,
+        mut define_func: impl FnMut(
+            &mut LinkerInstance<'_, T>,
+            &str,
+            Arc<LinkedComponent<T>>,
+            ExportIndex,
+            bool,
+        ) -> Result<()>,
+    ) -> Result<()> {
+        use wasmtime_environ::component::{ComponentExternData, Export, TypeDef};
+
+        fn define_item<T: 'static>(
+            linker: &mut LinkerInstance<'_, T>,
+            provider: &Arc<LinkedComponent<T>>,
+            exports: &NameMap<TryString, (ExportIndex, ComponentExternData)>,
+            define_func: &mut dyn FnMut(
+                &mut LinkerInstance<'_, T>,
+                &str,
+                Arc<LinkedComponent<T>>,
+                ExportIndex,
+                bool,
+            ) -> Result<()>,
+        ) -> Result<()> {
+            let pre = provider.pre();
+            for (name, (index, _)) in exports.raw_iter() {
+                match &pre.component().env_component().export_items[*index] {
+                    Export::LiftedFunction { ty, .. } => {
+                        let async_ = pre.component().types()[*ty].async_;
+                        define_func(linker, name, provider.clone(), *index, async_)?;
+                    }
+                    Export::ModuleStatic { index, .. } => {
+                        let module = pre.component().static_module(*index).clone();
+                        linker.module(name, &module)?;
+                    }
+                    Export::ModuleImport { import, .. } => match pre.runtime_import(*import) {
+                        RuntimeImport::Module(module) => linker.module(name, &module.clone())?,
+                        _ => unreachable!(),
+                    },
+                    Export::Instance { exports, .. } => {
+                        let mut linker_instance = linker.instance(name)?;
+                        define_item(&mut linker_instance, provider, exports, define_func)?;
+                    }
+                    Export::Type(TypeDef::Resource(_)) => {
+                        bail!("unable to define exported resource `{name}` in a linker")
+                    }
+                    Export::Type(_) => {}
+                }
+            }
+            Ok(())
+        }
+
+        ensure!(
+            Engine::same(&self.engine, provider.engine()),
+            "cross-`Engine` linking is not currently supported"
+        );
+        let provider = try_new::<Arc<_>>(LinkedComponent::new(provider.clone()))?;
+        define_item(
+            &mut self.root(),
+            &provider,
+            &provider.pre().component().env_component().exports,
+            &mut define_func,
+        )?;
+
+        // Components linked into `provider` are instantiated before it.
+        for linked in provider.pre().linked() {
+            if !self.linked.iter().any(|l| l.id() == linked.id()) {
+                self.linked.push(linked.clone());
+            }
+        }
+        self.linked.push(provider);
+        Ok(())
+    }
+
+    /// Defines every import of the WIT `world` within this linker, forwarding
+    /// all of them to the single `dispatch` closure.
+    ///
+    /// This enables implementing arbitrary WIT interfaces with [`Val`]s, where
+    /// the WIT is only known at runtime, for example when it's parsed from a
+    /// file with [`wit_parser::Resolve::push_path`]. This is intended for use
+    /// cases such as proxies, mocks, and hosts for other languages.
+    ///
+    /// Each function imported by `world`, either directly or through an
+    /// imported interface, is defined with [`LinkerInstance::func_new`]. When
+    /// invoked, `dispatch` is called with a [`DynamicImport`] describing which
+    /// function is being called alongside its parameters and a slice to fill
+    /// in with its results. Resource constructors, methods, and static
+    /// functions are dispatched like any other function with their
+    /// component-model names, for example `[constructor]file` or
+    /// `[method]file.read`.
+    ///
+    /// Each resource type imported by `world` is defined with
+    /// [`ResourceType::host_dynamic`], using the index of its
+    /// [`wit_parser::TypeId`] as the payload. The host can create handles to
+    /// these resources with [`ResourceDynamic`](crate::component::ResourceDynamic)
+    /// using that same payload as its type. When a guest drops an owned
+    /// resource, `dispatch` is called with a function name of
+    /// `[resource-drop]{name}` and a single [`Val::U32`] parameter holding the
+    /// resource's rep.
+    ///
+    /// Note that `async` functions can't be implemented this way and
+    /// instantiating a component which imports one will fail.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any import of `world` is already defined in this
+    /// linker and shadowing is disallowed.
+    ///
+    /// This function will return an [`OutOfMemory`][crate::OutOfMemory] error when
+    /// memory allocation fails. See the `OutOfMemory` type's documentation for
+    /// details on Wasmtime's out-of-memory handling.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use wasmtime::component::{Linker, Val, wit_parser::Resolve};
+    /// use wasmtime::{Engine, Result};
+    ///
+    /// # fn main() -> Result<()> {
+    /// let mut resolve = Resolve::default();
+    /// let pkg = resolve.push_str(
+    ///     "host.wit",
+    ///     r#"
+    ///         package my:host;
+    ///
+    ///         interface math {
+    ///             double: func(x: u32) -> u32;
+    ///         }
+    ///
+    ///         world plugin {
+    ///             import math;
+    ///         }
+    ///     "#,
+    /// )?;
+    /// let world = resolve.select_world(&[pkg], Some("plugin"))?;
+    ///
+    /// let engine = Engine::default();
+    /// let mut linker = Linker::<()>::new(&engine);
+    /// linker.define_wit_world_dynamic(&resolve, world, |_store, import, params, results| {
+    ///     match (import.interface(), import.name(), params) {
+    ///         (Some("my:host/math"), "double", [Val::U32(x)]) => {
+    ///             results[0] = Val::U32(x * 2);
+    ///             Ok(())
+    ///         }
+    ///         _ => wasmtime::bail!("unimplemented import `{import}`"),
+    ///     }
+    /// })?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "wit-parser")]
+    pub fn define_wit_world_dynamic(
+        &mut self,
+        resolve: &wit_parser::Resolve,
+        world: wit_parser::WorldId,
+        dispatch: impl Fn(StoreContextMut<'_, T>, &DynamicImport, &[Val], &mut [Val]) -> Result<()>
+        + Send
+        + Sync
+        + 'static,
+    ) -> Result<()> {
+        use wit_parser::{TypeDefKind, TypeId, WorldItem};
+
+        type Dispatch<T> = dyn Fn(StoreContextMut<'_, T>, &DynamicImport, &[Val], &mut [Val]) -> Result<()>
+            + Send
+            + Sync;
+
+        fn define_func<T: 'static>(
+            linker: &mut LinkerInstance<'_, T>,
+            dispatch: &Arc<Dispatch<T>>,
+            interface: Option<&str>,
+            name: &str,
+        ) -> Result<()> {
+            let import = DynamicImport::new(interface, TryString::try_from(name)?)?;
+            let dispatch = dispatch.clone();
+            linker.func_new(name, move |store, _ty, params, results| {
+                dispatch(store, &import, params, results)
+            })
+        }
+
+        fn define_resource<T: 'static>(
+            linker: &mut LinkerInstance<'_, T>,
+            dispatch: &Arc<Dispatch<T>>,
+            interface: Option<&str>,
+            id: TypeId,
+            name: &str,
+        ) -> Result<()> {
+            let mut drop_name = TryString::new();
+            drop_name.push_str("[resource-drop]")?;
+            drop_name.push_str(name)?;
+            let import = DynamicImport::new(interface, drop_name)?;
+            let dispatch = dispatch.clone();
+            let ty = ResourceType::host_dynamic(u32::try_from(id.index())?);
+            linker.resource(name, ty, move |store, rep| {
+                dispatch(store, &import, &[Val::U32(rep)], &mut [])
+            })
+        }
+
+        let dispatch: Arc<Dispatch<T>> = try_new::<Arc<_>>(dispatch)?;
+        for (key, item) in resolve.worlds[world].imports.iter() {
+            let name = resolve.name_world_key(key);
+            match item {
+                WorldItem::Function(_) => {
+                    define_func(&mut self.root(), &dispatch, None, &name)?;
+                }
+                WorldItem::Type { id, .. } => {
+                    if matches!(resolve.types[*id].kind, TypeDefKind::Resource) {
+                        define_resource(&mut self.root(), &dispatch, None, *id, &name)?;
+                    }
+                }
+                WorldItem::Interface { id, .. } => {
+                    let interface = &resolve.interfaces[*id];
+                    let mut linker = self.instance(&name)?;
+                    for (ty_name, ty) in interface.types.iter() {
+                        if matches!(resolve.types[*ty].kind, TypeDefKind::Resource) {
+                            define_resource(&mut linker, &dispatch, Some(&name), *ty, ty_name)?;
+                        }
+                    }
+                    for func_name in interface.functions.keys() {
+                        define_func(&mut linker, &dispatch, Some(&name), func_name)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Describes which import of a WIT world is being invoked through a
+/// dispatcher registered with [`Linker::define_wit_world_dynamic`].
+#[cfg(feature = "wit-parser")]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DynamicImport {
+    interface: Option<String>,
+    name: String,
+}
+
+#[cfg(feature = "wit-parser")]
+impl DynamicImport {
+    fn new(interface: Option<&str>, name: TryString) -> Result<DynamicImport, OutOfMemory> {
+        Ok(DynamicImport {
+            interface: match interface {
+                Some(interface) => Some(TryString::try_from(interface)?.into()),
+                None => None,
+            },
+            name: name.into(),
+        })
+    }
+
+    /// Returns the name of the interface the function was imported through,
+    /// for example `wasi:cli/stdout@0.2.0`, or `None` if the function was
+    /// imported directly by the world.
+    pub fn interface(&self) -> Option<&str> {
+        self.interface.as_deref()
+    }
+
+    /// Returns the name of the function being invoked, for example `get` or
+    /// `[method]file.read`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+#[cfg(feature = "wit-parser")]
+impl core::fmt::Display for DynamicImport {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match &self.interface {
+            Some(interface) => write!(f, "{interface}#{}", self.name),
+            None => f.write_str(&self.name),
+        }
+    }
 }
 
 impl<T: 'static> LinkerInstance<'_, T> {
@@ -950,3 +1481,66 @@ impl<T: 'static> LinkerInstance<'_, T> {
         self.map.get(name, self.strings)
     }
 }
+
+/// Returns the default value of `ty` used by
+/// [`Linker::define_unknown_imports_as_default_values`].
+fn default_val(ty: &types::Type) -> Result<Val> {
+    use types::Type;
+    Ok(match ty {
+        Type::Bool => Val::Bool(false),
+        Type::S8 => Val::S8(0),
+        Type::U8 => Val::U8(0),
+        Type::S16 => Val::S16(0),
+        Type::U16 => Val::U16(0),
+        Type::S32 => Val::S32(0),
+        Type::U32 => Val::U32(0),
+        Type::S64 => Val::S64(0),
+        Type::U64 => Val::U64(0),
+        Type::Float32 => Val::Float32(0.0),
+        Type::Float64 => Val::Float64(0.0),
+        Type::Char => Val::Char('\0'),
+        Type::String => Val::String(String::new()),
+        Type::List(_) => Val::List(Vec::new()),
+        Type::Map(_) => Val::Map(Vec::new()),
+        Type::Record(record) => Val::Record(
+            record
+                .fields()
+                .map(|field| Ok((field.name.to_string(), default_val(&field.ty)?)))
+                .collect::<Result<_>>()?,
+        ),
+        Type::Tuple(tuple) => Val::Tuple(
+            tuple
+                .types()
+                .map(|ty| default_val(&ty))
+                .collect::<Result<_>>()?,
+        ),
+        Type::Variant(variant) => {
+            let case = variant
+                .cases()
+                .next()
+                .expect("variants have at least one case");
+            let payload = match &case.ty {
+                Some(ty) => Some(Box::new(default_val(ty)?)),
+                None => None,
+            };
+            Val::Variant(case.name.to_string(), payload)
+        }
+        Type::Enum(enum_) => {
+            let name = enum_.names().next().expect("enums have at least one case");
+            Val::Enum(name.to_string())
+        }
+        Type::Option(_) => Val::Option(None),
+        Type::Result(result) => Val::Result(Ok(match result.ok() {
+            Some(ty) => Some(Box::new(default_val(&ty)?)),
+            None => None,
+        })),
+        Type::Flags(_) => Val::Flags(Vec::new()),
+        Type::FixedLengthList(list) => {
+            let elem = default_val(&list.ty())?;
+            Val::FixedLengthList((0..list.len()).map(|_| elem.clone()).collect())
+        }
+        Type::Own(_) | Type::Borrow(_) | Type::Future(_) | Type::Stream(_) | Type::ErrorContext => {
+            bail!("resources, futures, streams, and error contexts have no default value")
+        }
+    })
+}
@@ -114,6 +114,8 @@ mod resource_table;
 mod resources;
 mod storage;
 pub(crate) mod store;
+#[cfg(feature = "wave")]
+mod trace;
 pub mod types;
 mod values;
 pub use self::component::{Component, ComponentExportIndex, ExportLookup};
@@ -130,11 +132,15 @@ pub use self::func::{
 };
 pub use self::has_data::*;
 pub use self::instance::{Instance, InstancePre};
+#[cfg(feature = "wit-parser")]
+pub use self::linker::DynamicImport;
 pub use self::linker::{Linker, LinkerInstance};
 pub use self::resource_table::{ResourceTable, ResourceTableError};
 pub use self::resources::{Resource, ResourceAny, ResourceDynamic};
+#[cfg(feature = "wave")]
+pub use self::trace::{CallTrace, CallTraceEvent};
 pub use self::types::{ResourceType, Type};
-pub use self::values::Val;
+pub use self::values::{ErrorContextAny, Val};
 
 pub(crate) use self::instance::RuntimeImport;
 pub(crate) use self::resources::HostResourceData;
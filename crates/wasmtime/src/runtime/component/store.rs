@@ -1,4 +1,8 @@
 use crate::prelude::*;
+#[cfg(feature = "wave")]
+use crate::runtime::component::CallTrace;
+#[cfg(feature = "wave")]
+use crate::runtime::component::trace::CallTracer;
 use crate::runtime::component::{HostResourceData, Instance};
 use crate::runtime::vm;
 use crate::runtime::vm::component::{
@@ -56,6 +60,15 @@ pub struct ComponentStoreData {
     /// Caps the size of the allocations made on the host to this amount
     /// effectively.
     hostcall_fuel: usize,
+    /// Callback invoked for each traced component call, configured with
+    /// `Store::trace_component_calls`.
+    #[cfg(feature = "wave")]
+    pub(crate) call_tracer: Option<CallTracer>,
+
+    /// Instances of components whose exports were defined in a linker with
+    /// `Linker::define_component_exports`, keyed by the id of each such
+    /// definition.
+    linked_instances: TryVec<(usize, Instance)>,
 }
 
 /// State tracking for tasks within components.
@@ -104,6 +117,9 @@ impl ComponentStoreData {
                 ComponentTaskState::NotConcurrent(Default::default())
             },
             hostcall_fuel: DEFAULT_HOSTCALL_FUEL,
+            #[cfg(feature = "wave")]
+            call_tracer: None,
+            linked_instances: TryVec::new(),
         }
     }
 
@@ -311,7 +327,62 @@ impl StoreOpaque {
         self.store_data().component_instance(id)
     }
 
-    #[cfg(feature = "component-model-async")]
+    /// Returns the instance in this store of the linked component `id`, if
+    /// it has been instantiated.
+    pub(crate) fn linked_component_instance(&self, id: usize) -> Option<Instance> {
+        self.component_data()
+            .linked_instances
+            .iter()
+            .find(|(linked, _)| *linked == id)
+            .map(|(_, instance)| *instance)
+    }
+
+    /// Records `instance` as the instance in this store of the linked
+    /// component `id`.
+    pub(crate) fn push_linked_component_instance(
+        &mut self,
+        id: usize,
+        instance: Instance,
+    ) -> Result<(), OutOfMemory> {
+        debug_assert!(self.linked_component_instance(id).is_none());
+        self.component_data_mut()
+            .linked_instances
+            .push((id, instance))
+    }
+
+    /// Returns the ids of all component instances within this store, or an
+    /// error if the store holds component state outside of them, such as
+    /// running tasks, which a snapshot of the store can't capture.
+    pub(crate) fn snapshot_component_instances(&mut self) -> Result<Vec<ComponentInstanceId>> {
+        let data = self.component_data_mut();
+        if data.trapped {
+            bail!("cannot snapshot a store in which a component instance has trapped");
+        }
+        if !data.linked_instances.is_empty() {
+            bail!(
+                "cannot snapshot a store containing components linked with \
+                 `Linker::define_component_exports`"
+            );
+        }
+        if !data.component_host_table.is_empty() {
+            bail!("cannot snapshot a store in which the host owns component resources");
+        }
+        let idle = match &mut data.task_state {
+            ComponentTaskState::NotConcurrent(state) => state.scopes.is_empty(),
+            #[cfg(feature = "component-model-async")]
+            ComponentTaskState::Concurrent(state) => state.is_idle(),
+        };
+        if !idle {
+            bail!("cannot snapshot a store with component tasks which are still running");
+        }
+        Ok(data
+            .instances
+            .iter()
+            .filter(|(_, instance)| instance.is_some())
+            .map(|(id, _)| id)
+            .collect())
+    }
+
     pub(crate) fn component_instance_mut(
         &mut self,
         id: ComponentInstanceId,
@@ -396,6 +467,7 @@ impl StoreOpaque {
         &mut crate::component::HostResourceData,
     )> {
         let current_scope_id = self.current_scope_id()?;
+        let entry_limit = self.resource_table_entry_limit;
 
         let store_id = self.id();
         let data = self.component_data_mut();
@@ -415,6 +487,7 @@ impl StoreOpaque {
                 task_state: &mut data.task_state,
                 guest,
                 current_scope_id,
+                entry_limit,
             },
             &mut data.host_resource_data,
         ))
@@ -510,6 +583,26 @@ impl<T> Store<T> {
         self.as_context_mut().set_hostcall_fuel(fuel)
     }
 
+    /// Configures a function to be invoked for each call between the host and
+    /// components within this store.
+    ///
+    /// The `tracer` observes every call of a component's exported function by
+    /// the host and every call of a host-defined import by a component, each
+    /// once as it's called and once as it returns. The arguments and results
+    /// are rendered in the [`wasm_wave`](crate::component::wasm_wave) format,
+    /// see [`CallTrace`] for more information.
+    ///
+    /// Rendering values has a cost proportional to their size, so tracing is
+    /// intended for debugging rather than production use. Replaces any
+    /// previously configured tracer.
+    #[cfg(feature = "wave")]
+    pub fn trace_component_calls(
+        &mut self,
+        tracer: impl FnMut(&CallTrace<'_>) + Send + Sync + 'static,
+    ) {
+        self.as_context_mut().0.component_data_mut().call_tracer = Some(Box::new(tracer));
+    }
+
     /// Returns the underlying [`ResourceTable`] that the implementation of
     /// concurrency in the component model is using.
     ///
@@ -538,6 +631,14 @@ impl<T> StoreContextMut<'_, T> {
     pub fn concurrent_resource_table(&mut self) -> Option<&mut ResourceTable> {
         self.0.concurrent_resource_table()
     }
+    /// See [`Store::trace_component_calls`].
+    #[cfg(feature = "wave")]
+    pub fn trace_component_calls(
+        &mut self,
+        tracer: impl FnMut(&CallTrace<'_>) + Send + Sync + 'static,
+    ) {
+        self.0.component_data_mut().call_tracer = Some(Box::new(tracer));
+    }
 }
 
 #[derive(Default)]
@@ -0,0 +1,313 @@
+//! Runtime tracing of component-level calls.
+//!
+//! This is configured with [`Store::trace_component_calls`] and observes every
+//! call the host makes into a component export as well as every call a
+//! component makes to a host import. Arguments and results are rendered with
+//! [`wasm_wave`] as they appear in the canonical ABI, which means that tracing
+//! works the same for typed functions, [`Func::call`], `bindgen!`-generated
+//! bindings, and [`LinkerInstance::func_new`].
+//!
+//! [`Store::trace_component_calls`]: crate::Store::trace_component_calls
+//! [`wasm_wave`]: crate::component::wasm_wave
+//! [`Func::call`]: crate::component::Func::call
+//! [`LinkerInstance::func_new`]: crate::component::LinkerInstance::func_new
+
+use crate::ValRaw;
+use crate::component::func::LiftContext;
+use crate::component::{Component, Instance, Val};
+use crate::prelude::*;
+use crate::store::StoreOpaque;
+use core::fmt;
+use core::mem::{self, MaybeUninit};
+use wasmtime_environ::component::{
+    Component as EnvComponent, ComponentExternData, Export, ExportIndex, GlobalInitializer,
+    InterfaceType, NameMap, OptionsIndex, TypeFuncIndex, TypeTupleIndex,
+};
+use wasmtime_environ::prelude::TryString;
+
+/// The kind of event described by a [`CallTrace`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CallTraceEvent {
+    /// The host is calling a function exported by a component, and the values
+    /// are the arguments of the call.
+    ExportCall,
+    /// A function exported by a component returned to the host, and the values
+    /// are its results.
+    ExportReturn,
+    /// A component is calling a host-defined import, and the values are the
+    /// arguments of the call.
+    ImportCall,
+    /// A host-defined import returned to the component, and the values are its
+    /// results.
+    ImportReturn,
+}
+
+/// A single call or return observed by [`Store::trace_component_calls`].
+///
+/// A call which traps or whose host implementation returns an error has no
+/// matching return event; the error is instead propagated as usual.
+///
+/// [`Store::trace_component_calls`]: crate::Store::trace_component_calls
+#[derive(Debug, Clone, Copy)]
+pub struct CallTrace<'a> {
+    event: CallTraceEvent,
+    func: &'a str,
+    values: &'a str,
+}
+
+impl<'a> CallTrace<'a> {
+    /// Returns what happened.
+    pub fn event(&self) -> CallTraceEvent {
+        self.event
+    }
+
+    /// Returns the name of the function being called or returning from.
+    ///
+    /// Functions within an instance are named `{instance}#{func}`, for
+    /// example `wasi:cli/run@0.2.0#run`.
+    pub fn func(&self) -> &'a str {
+        self.func
+    }
+
+    /// Returns the comma-separated arguments or results of this call,
+    /// rendered in the WAVE format.
+    ///
+    /// Handles are not valid WAVE values, so `own`, `borrow`, `future`,
+    /// `stream`, and `error-context` handles are rendered as, for example,
+    /// `own(3)` where `3` is the handle's index in the component's table.
+    pub fn values(&self) -> &'a str {
+        self.values
+    }
+}
+
+impl fmt::Display for CallTrace<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.event {
+            CallTraceEvent::ExportCall => write!(f, "call export {}({})", self.func, self.values),
+            CallTraceEvent::ExportReturn => {
+                write!(f, "return export {} -> ({})", self.func, self.values)
+            }
+            CallTraceEvent::ImportCall => write!(f, "call import {}({})", self.func, self.values),
+            CallTraceEvent::ImportReturn => {
+                write!(f, "return import {} -> ({})", self.func, self.values)
+            }
+        }
+    }
+}
+
+pub(crate) type CallTracer = Box<dyn FnMut(&CallTrace<'_>) + Send + Sync>;
+
+/// Returns whether a call tracer is configured for `store`.
+#[inline]
+pub(crate) fn enabled(store: &StoreOpaque) -> bool {
+    store.component_data().call_tracer.is_some()
+}
+
+/// Invokes the configured call tracer, if any.
+pub(crate) fn emit(store: &mut StoreOpaque, event: CallTraceEvent, func: &str, values: &str) {
+    let Some(mut tracer) = store.component_data_mut().call_tracer.take() else {
+        return;
+    };
+    tracer(&CallTrace {
+        event,
+        func,
+        values,
+    });
+    store.component_data_mut().call_tracer = Some(tracer);
+}
+
+/// Returns the name under which `index` is exported from `component`.
+pub(crate) fn export_name(component: &Component, index: ExportIndex) -> String {
+    let env = component.env_component();
+    let mut path = Vec::new();
+    if find_export(env, &env.exports, index, &mut path) {
+        path.join("#")
+    } else {
+        format!("<export {}>", index.as_u32())
+    }
+}
+
+fn find_export<'a>(
+    env: &'a EnvComponent,
+    exports: &'a NameMap<TryString, (ExportIndex, ComponentExternData)>,
+    index: ExportIndex,
+    path: &mut Vec<&'a str>,
+) -> bool {
+    for (name, (i, _)) in exports.raw_iter() {
+        path.push(name);
+        if *i == index {
+            return true;
+        }
+        if let Export::Instance { exports, .. } = &env.export_items[*i]
+            && find_export(env, exports, index, path)
+        {
+            return true;
+        }
+        path.pop();
+    }
+    false
+}
+
+/// Returns the name of the import of `instance` which was lowered from the
+/// host function whose entrypoint is `callee` with `data`.
+///
+/// If the same host function is imported under several names then the first
+/// one is returned.
+pub(crate) fn import_name(
+    store: &StoreOpaque,
+    instance: Instance,
+    callee: *const u8,
+    data: *const u8,
+) -> String {
+    let vminstance = instance.id().get(store);
+    let env = vminstance.component().env_component();
+    for init in env.initializers.iter() {
+        let GlobalInitializer::LowerImport { index, import } = init else {
+            continue;
+        };
+        let lowering = vminstance.lowering(*index);
+        if lowering.callee.as_ptr().cast_const().cast::<u8>() != callee
+            || lowering.data.as_ptr().cast_const() != data
+        {
+            continue;
+        }
+        let (import_index, path) = &env.imports[*import];
+        let (name, _) = &env.import_types[*import_index];
+        let mut ret = name.clone();
+        for segment in path {
+            ret.push('#');
+            ret.push_str(segment);
+        }
+        return ret;
+    }
+    String::from("<unknown import>")
+}
+
+/// Reports `event` for the function `ty`, named `func`, to the store's call
+/// tracer, rendering its arguments or results from their canonical ABI
+/// representation in `src`.
+///
+/// # Safety
+///
+/// For call events `src` must start with the initialized flat arguments of
+/// `ty`, or with a pointer to them in linear memory if they don't fit in
+/// `max_flat` values. The same applies to the results for return events.
+pub(crate) unsafe fn emit_values(
+    store: &mut StoreOpaque,
+    event: CallTraceEvent,
+    func: &str,
+    instance: Instance,
+    options: OptionsIndex,
+    ty: TypeFuncIndex,
+    max_flat: usize,
+    src: &[MaybeUninit<ValRaw>],
+) {
+    let fty = &instance.id().get(store).component().types()[ty];
+    let tuple = match event {
+        CallTraceEvent::ExportCall | CallTraceEvent::ImportCall => fty.params,
+        CallTraceEvent::ExportReturn | CallTraceEvent::ImportReturn => fty.results,
+    };
+    let values = unsafe { render(store, instance, options, tuple, max_flat, src) };
+    emit(store, event, func, &values);
+}
+
+unsafe fn render(
+    store: &mut StoreOpaque,
+    instance: Instance,
+    options: OptionsIndex,
+    ty: TypeTupleIndex,
+    max_flat: usize,
+    src: &[MaybeUninit<ValRaw>],
+) -> String {
+    let vals = (|| -> Result<Val> {
+        let mut cx = LiftContext::new(store, options, instance)?;
+        cx.set_peek_handles();
+        let types = cx.types;
+        let abi = &types[ty].abi;
+        match abi.flat_count(max_flat) {
+            Some(count) => {
+                // SAFETY: it's a contract of this function that the first
+                // `count` values of `src` are initialized.
+                let src =
+                    unsafe { mem::transmute::<&[MaybeUninit<ValRaw>], &[ValRaw]>(&src[..count]) };
+                Val::lift(&mut cx, InterfaceType::Tuple(ty), &mut src.iter())
+            }
+            None => {
+                // SAFETY: it's a contract of this function that the pointer
+                // to the values is initialized.
+                let ptr = unsafe { src[0].assume_init_ref() }.get_u32();
+                let ptr = usize::try_from(ptr)?;
+                let size = usize::try_from(abi.size32)?;
+                let bytes = ptr
+                    .checked_add(size)
+                    .and_then(|end| cx.memory().get(ptr..end))
+                    .ok_or_else(|| format_err!("pointer out of bounds"))?;
+                Val::load(&mut cx, InterfaceType::Tuple(ty), bytes)
+            }
+        }
+    })();
+
+    let vals = match vals {
+        Ok(Val::Tuple(vals)) => vals,
+        Ok(_) => unreachable!(),
+        Err(e) => return format!("<failed to render: {e}>"),
+    };
+    let mut ret = String::new();
+    for (i, val) in vals.into_iter().enumerate() {
+        if i > 0 {
+            ret.push_str(", ");
+        }
+        match wasm_wave::to_string(&wave_compatible(val)) {
+            Ok(s) => ret.push_str(&s),
+            Err(e) => ret.push_str(&format!("<failed to render: {e}>")),
+        }
+    }
+    ret
+}
+
+/// Returns the name of the handle kind of `ty`, if it's a handle.
+pub(crate) fn handle_kind(ty: InterfaceType) -> Option<&'static str> {
+    match ty {
+        InterfaceType::Own(_) => Some("own"),
+        InterfaceType::Borrow(_) => Some("borrow"),
+        InterfaceType::Future(_) => Some("future"),
+        InterfaceType::Stream(_) => Some("stream"),
+        InterfaceType::ErrorContext(_) => Some("error-context"),
+        _ => None,
+    }
+}
+
+/// Returns the value which stands in for the handle `index` of `kind` when
+/// lifting with [`LiftContext::set_peek_handles`].
+pub(crate) fn handle_placeholder(kind: &str, index: u32) -> Val {
+    Val::Variant(kind.to_string(), Some(Box::new(Val::U32(index))))
+}
+
+/// Converts the parts of `val` which WAVE doesn't support into an equivalent
+/// representation which it does.
+fn wave_compatible(val: Val) -> Val {
+    let all = |vals: Vec<Val>| vals.into_iter().map(wave_compatible).collect();
+    let boxed = |val: Option<Box<Val>>| val.map(|v| Box::new(wave_compatible(*v)));
+    match val {
+        Val::Map(entries) => Val::List(
+            entries
+                .into_iter()
+                .map(|(k, v)| Val::Tuple(vec![wave_compatible(k), wave_compatible(v)]))
+                .collect(),
+        ),
+        Val::List(vals) => Val::List(all(vals)),
+        Val::FixedLengthList(vals) => Val::FixedLengthList(all(vals)),
+        Val::Tuple(vals) => Val::Tuple(all(vals)),
+        Val::Record(fields) => Val::Record(
+            fields
+                .into_iter()
+                .map(|(name, v)| (name, wave_compatible(v)))
+                .collect(),
+        ),
+        Val::Variant(name, payload) => Val::Variant(name, boxed(payload)),
+        Val::Option(payload) => Val::Option(boxed(payload)),
+        Val::Result(Ok(payload)) => Val::Result(Ok(boxed(payload))),
+        Val::Result(Err(payload)) => Val::Result(Err(boxed(payload))),
+        other => other,
+    }
+}
@@ -2,6 +2,8 @@ use crate::ValRaw;
 use crate::component::ResourceAny;
 use crate::component::concurrent::{self, ErrorContext, FutureAny, StreamAny};
 use crate::component::func::{Lift, LiftContext, Lower, LowerContext, desc};
+#[cfg(feature = "wave")]
+use crate::component::trace;
 use crate::prelude::*;
 use core::mem::MaybeUninit;
 use core::slice::{Iter, IterMut};
@@ -103,6 +105,13 @@ impl Val {
         ty: InterfaceType,
         src: &mut Iter<'_, ValRaw>,
     ) -> Result<Val> {
+        #[cfg(feature = "wave")]
+        if let Some(kind) = trace::handle_kind(ty)
+            && cx.peek_handles()
+        {
+            let index = u32::linear_lift_from_flat(cx, InterfaceType::U32, next(src))?;
+            return Ok(trace::handle_placeholder(kind, index));
+        }
         Ok(match ty {
             InterfaceType::Bool => Val::Bool(bool::linear_lift_from_flat(cx, ty, next(src))?),
             InterfaceType::S8 => Val::S8(i8::linear_lift_from_flat(cx, ty, next(src))?),
@@ -235,6 +244,13 @@ impl Val {
 
     /// Deserialize a value of this type from the heap.
     pub(crate) fn load(cx: &mut LiftContext<'_>, ty: InterfaceType, bytes: &[u8]) -> Result<Val> {
+        #[cfg(feature = "wave")]
+        if let Some(kind) = trace::handle_kind(ty)
+            && cx.peek_handles()
+        {
+            let index = u32::linear_lift_from_memory(cx, InterfaceType::U32, bytes)?;
+            return Ok(trace::handle_placeholder(kind, index));
+        }
         Ok(match ty {
             InterfaceType::Bool => Val::Bool(bool::linear_lift_from_memory(cx, ty, bytes)?),
             InterfaceType::S8 => Val::S8(i8::linear_lift_from_memory(cx, ty, bytes)?),
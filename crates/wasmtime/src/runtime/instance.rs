@@ -9,8 +9,9 @@ use crate::store::{
 };
 use crate::types::matching;
 use crate::{
-    AsContextMut, Engine, Export, Extern, Func, Global, Memory, Module, ModuleExport, SharedMemory,
-    StoreContext, StoreContextMut, Table, Tag, TypedFunc,
+    AsContext, AsContextMut, Engine, Export, Extern, Func, Global, InstanceResourceUsage,
+    InstanceSnapshot, Memory, Module, ModuleExport, SharedMemory, StoreContext, StoreContextMut,
+    Table, Tag, TypedFunc,
 };
 use alloc::sync::Arc;
 use core::ptr::NonNull;
@@ -208,7 +209,7 @@ impl Instance {
         unsafe { Instance::new_started(&mut store, module, imports.as_ref(), Asyncness::Yes).await }
     }
 
-    fn typecheck_externs(
+    pub(crate) fn typecheck_externs(
         store: &mut StoreOpaque,
         module: &Module,
         imports: &[Extern],
@@ -267,7 +268,7 @@ impl Instance {
         // function itself, but it's finalization of initialization of this
         // instance, for example for complicated global initialization
         // expressions.
-        if instance.id.get_mut(store.0).needs_startup() {
+        if !module.skip_startup() && instance.id.get_mut(store.0).needs_startup() {
             if asyncness == Asyncness::No {
                 instance.start_raw(store)?;
             } else {
@@ -465,7 +466,7 @@ impl Instance {
         Some(self._get_export(store, export.entity))
     }
 
-    fn _get_export(&self, store: &mut StoreOpaque, entity: EntityIndex) -> Extern {
+    pub(crate) fn _get_export(&self, store: &mut StoreOpaque, entity: EntityIndex) -> Extern {
         let id = store.id();
         // SAFETY: the store `id` owns this instance and all exports contained
         // within.
@@ -587,6 +588,45 @@ impl Instance {
         self.get_export(store, name)?.into_tag()
     }
 
+    /// Captures the current state of this instance into an
+    /// [`InstanceSnapshot`].
+    ///
+    /// The snapshot records the contents of all memories, tables, and globals
+    /// defined by this instance and can be used to create any number of new
+    /// instances, in any store using the same [`Engine`], which start out in
+    /// this same state. See [`InstanceSnapshot`] for more information.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if this instance contains state which cannot be
+    /// captured in a snapshot, such as a shared memory or a GC reference. See
+    /// [`InstanceSnapshot`] for the full list of limitations.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `store` does not own this instance.
+    pub fn snapshot(&self, mut store: impl AsContextMut) -> Result<InstanceSnapshot> {
+        InstanceSnapshot::new(store.as_context_mut().0, *self)
+    }
+
+    /// Returns a summary of the resources currently defined by this instance.
+    ///
+    /// This reports the linear memories and tables defined by this instance,
+    /// as opposed to [`Store::resource_usage`](crate::Store::resource_usage)
+    /// which reports all resources within the store. See
+    /// [`InstanceResourceUsage`] for more information.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `store` does not own this instance.
+    pub fn resource_usage(&self, store: impl AsContext) -> InstanceResourceUsage {
+        let handle = self.id.get(store.as_context().0);
+        InstanceResourceUsage {
+            memory_bytes: handle.defined_memories_byte_size(),
+            table_elements: handle.defined_tables_size(),
+        }
+    }
+
     #[allow(
         dead_code,
         reason = "c-api crate does not yet support exnrefs and causes this method to be dead."
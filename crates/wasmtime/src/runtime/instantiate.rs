@@ -64,6 +64,24 @@ impl CompiledModule {
         Ok(ret)
     }
 
+    /// Creates a new `CompiledModule` which shares all compiled code with
+    /// `self` but is described by the `module` provided instead.
+    ///
+    /// The returned module is assigned a fresh unique ID so instance
+    /// allocators treat it as distinct from `self`. The `module` provided must
+    /// have the same functions, types, and entities as `self.module()` and may
+    /// only differ in how instances are initialized.
+    pub fn with_env_module(&self, module: Module) -> Result<Self> {
+        Ok(Self {
+            unique_id: CompiledModuleId::new(),
+            engine_code: self.engine_code.clone(),
+            module: try_new::<Arc<_>>(module)?,
+            meta: self.meta.clone(),
+            index: self.index.clone(),
+            func_names: self.func_names.clone(),
+        })
+    }
+
     fn register_profiling(&mut self, profiler: &dyn ProfilingAgent) -> Result<()> {
         // TODO-Bug?: "code_memory" is not exclusive for this module in the case of components,
         // so we may be registering the same code range multiple times here.
@@ -6,6 +6,14 @@ pub const DEFAULT_INSTANCE_LIMIT: usize = 10000;
 pub const DEFAULT_TABLE_LIMIT: usize = 10000;
 /// Value returned by [`ResourceLimiter::memories`] default method
 pub const DEFAULT_MEMORY_LIMIT: usize = 10000;
+/// Value returned by [`ResourceLimiter::component_instances`] default method
+#[cfg(feature = "component-model")]
+pub const DEFAULT_COMPONENT_INSTANCE_LIMIT: usize = 10000;
+/// Value returned by [`ResourceLimiter::resource_table_entries`] default
+/// method, which is the maximum size of a table permitted by the component
+/// model.
+#[cfg(feature = "component-model")]
+pub const DEFAULT_RESOURCE_TABLE_ENTRIES_LIMIT: usize = 1 << 28;
 
 /// Used by hosts to limit resource consumption of instances.
 ///
@@ -13,8 +21,10 @@ pub const DEFAULT_MEMORY_LIMIT: usize = 10000;
 /// [`Store::limiter`](crate::Store::limiter) to synchronously limit the
 /// allocation of resources within a store. As a store-level limit this means
 /// that all creation of instances, memories, and tables are limited within the
-/// store. Resources limited via this trait are primarily related to memory and
-/// limiting CPU resources needs to be done with something such as
+/// store, as well as the growth of the GC heap and of the tables of resource
+/// handles used by components. Resources limited via this trait are primarily
+/// related to memory and limiting CPU resources needs to be done with
+/// something such as
 /// [`Config::consume_fuel`](crate::Config::consume_fuel) or
 /// [`Config::epoch_interruption`](crate::Config::epoch_interruption).
 ///
@@ -151,13 +161,86 @@ pub trait ResourceLimiter: Send {
     fn memories(&self) -> usize {
         DEFAULT_MEMORY_LIMIT
     }
+
+    /// Notifies the resource limiter that the store's GC heap has been
+    /// requested to grow.
+    ///
+    /// * `current` is the current size of the GC heap in bytes.
+    /// * `desired` is the desired size of the GC heap in bytes.
+    /// * `maximum` is the maximum size of the GC heap in bytes, or `None` if
+    ///   it is unbounded.
+    ///
+    /// This is invoked with a `current` size of zero when the GC heap is first
+    /// allocated, and afterwards whenever a GC allocation cannot be satisfied
+    /// without growing the heap. This includes growth driven by WebAssembly
+    /// allocating GC objects, for example with `struct.new`, as well as by
+    /// objects allocated by the host.
+    ///
+    /// By default this delegates to [`ResourceLimiter::memory_growing`], as
+    /// the GC heap is backed by a linear memory.
+    ///
+    /// See the details on the return values for `memory_growing` for what the
+    /// return value of this function indicates. When growth is rejected the
+    /// GC allocation which triggered the growth will fail, raising a trap in
+    /// WebAssembly.
+    fn gc_heap_growing(
+        &mut self,
+        current: usize,
+        desired: usize,
+        maximum: Option<usize>,
+    ) -> Result<bool> {
+        self.memory_growing(current, desired, maximum)
+    }
+
+    /// Notifies the resource limiter that growing the GC heap, permitted by
+    /// the `gc_heap_growing` method, has failed.
+    ///
+    /// By default this delegates to [`ResourceLimiter::memory_grow_failed`].
+    ///
+    /// See the details on the return values for `memory_growing` for what the
+    /// return value of this function indicates.
+    fn gc_heap_grow_failed(&mut self, error: crate::Error) -> Result<()> {
+        self.memory_grow_failed(error)
+    }
+
+    /// The maximum number of component instances that can be created for a
+    /// `Store`.
+    ///
+    /// Component instantiation will fail if this limit is exceeded. Note that
+    /// the core instances created as part of instantiating a component are
+    /// additionally limited by [`ResourceLimiter::instances`].
+    ///
+    /// This value defaults to 10,000.
+    #[cfg(feature = "component-model")]
+    fn component_instances(&self) -> usize {
+        DEFAULT_COMPONENT_INSTANCE_LIMIT
+    }
+
+    /// The maximum number of entries in each table of handles to component
+    /// resources within a `Store`.
+    ///
+    /// Each component instance has its own table of handles, in addition to
+    /// the table of handles owned by the host. Creating a handle, for example
+    /// with `resource.new` or by passing an `own` or `borrow` to a component,
+    /// will fail with a trap if the table it's inserted into would exceed
+    /// this many entries. Note that this table is also used for handles to
+    /// other items, such as streams and futures, which count towards this
+    /// limit.
+    ///
+    /// This value defaults to 2<sup>28</sup>, the maximum permitted by the
+    /// component model.
+    #[cfg(feature = "component-model")]
+    fn resource_table_entries(&self) -> usize {
+        DEFAULT_RESOURCE_TABLE_ENTRIES_LIMIT
+    }
 }
 
 /// Used by hosts to limit resource consumption of instances, blocking
 /// asynchronously if necessary.
 ///
 /// This trait is identical to [`ResourceLimiter`], except that the
-/// `memory_growing` and `table_growing` functions are `async`.
+/// `memory_growing`, `table_growing`, and `gc_heap_growing` functions are
+/// `async`.
 ///
 /// This trait is used with
 /// [`Store::limiter_async`](`crate::Store::limiter_async`)`: see those docs
@@ -213,6 +296,33 @@ pub trait ResourceLimiterAsync: Send {
     fn memories(&self) -> usize {
         DEFAULT_MEMORY_LIMIT
     }
+
+    /// Asynchronous version of [`ResourceLimiter::gc_heap_growing`]
+    async fn gc_heap_growing(
+        &mut self,
+        current: usize,
+        desired: usize,
+        maximum: Option<usize>,
+    ) -> Result<bool> {
+        self.memory_growing(current, desired, maximum).await
+    }
+
+    /// Identical to [`ResourceLimiter::gc_heap_grow_failed`]
+    fn gc_heap_grow_failed(&mut self, error: crate::Error) -> Result<()> {
+        self.memory_grow_failed(error)
+    }
+
+    /// Identical to [`ResourceLimiter::component_instances`]`
+    #[cfg(feature = "component-model")]
+    fn component_instances(&self) -> usize {
+        DEFAULT_COMPONENT_INSTANCE_LIMIT
+    }
+
+    /// Identical to [`ResourceLimiter::resource_table_entries`]`
+    #[cfg(feature = "component-model")]
+    fn resource_table_entries(&self) -> usize {
+        DEFAULT_RESOURCE_TABLE_ENTRIES_LIMIT
+    }
 }
 
 /// Used to build [`StoreLimits`].
@@ -282,6 +392,42 @@ impl StoreLimitsBuilder {
         self
     }
 
+    /// The maximum number of bytes the GC heap can grow to.
+    ///
+    /// Growing the GC heap beyond this limit will fail, causing GC
+    /// allocations which require the growth to fail.
+    ///
+    /// By default, the GC heap is limited by [`StoreLimitsBuilder::memory_size`]
+    /// if configured and is otherwise not limited.
+    pub fn gc_heap_size(mut self, limit: usize) -> Self {
+        self.0.gc_heap_size = Some(limit);
+        self
+    }
+
+    /// The maximum number of component instances that can be created for a
+    /// [`Store`](crate::Store).
+    ///
+    /// Component instantiation will fail if this limit is exceeded.
+    ///
+    /// This value defaults to 10,000.
+    #[cfg(feature = "component-model")]
+    pub fn component_instances(mut self, limit: usize) -> Self {
+        self.0.component_instances = limit;
+        self
+    }
+
+    /// The maximum number of entries in each table of handles to component
+    /// resources.
+    ///
+    /// Creating a handle in a table which would exceed this limit will fail.
+    ///
+    /// This value defaults to 2<sup>28</sup>.
+    #[cfg(feature = "component-model")]
+    pub fn resource_table_entries(mut self, limit: usize) -> Self {
+        self.0.resource_table_entries = limit;
+        self
+    }
+
     /// Indicates that a trap should be raised whenever a growth operation
     /// would fail.
     ///
@@ -317,6 +463,11 @@ pub struct StoreLimits {
     instances: usize,
     tables: usize,
     memories: usize,
+    gc_heap_size: Option<usize>,
+    #[cfg(feature = "component-model")]
+    component_instances: usize,
+    #[cfg(feature = "component-model")]
+    resource_table_entries: usize,
     trap_on_grow_failure: bool,
 }
 
@@ -328,6 +479,11 @@ impl Default for StoreLimits {
             instances: DEFAULT_INSTANCE_LIMIT,
             tables: DEFAULT_TABLE_LIMIT,
             memories: DEFAULT_MEMORY_LIMIT,
+            gc_heap_size: None,
+            #[cfg(feature = "component-model")]
+            component_instances: DEFAULT_COMPONENT_INSTANCE_LIMIT,
+            #[cfg(feature = "component-model")]
+            resource_table_entries: DEFAULT_RESOURCE_TABLE_ENTRIES_LIMIT,
             trap_on_grow_failure: false,
         }
     }
@@ -403,4 +559,43 @@ impl ResourceLimiter for StoreLimits {
     fn memories(&self) -> usize {
         self.memories
     }
+
+    fn gc_heap_growing(
+        &mut self,
+        _current: usize,
+        desired: usize,
+        maximum: Option<usize>,
+    ) -> Result<bool> {
+        let allow = match self.gc_heap_size.or(self.memory_size) {
+            Some(limit) if desired > limit => false,
+            _ => match maximum {
+                Some(max) if desired > max => false,
+                _ => true,
+            },
+        };
+        if !allow && self.trap_on_grow_failure {
+            bail!("forcing trap when growing GC heap to {desired} bytes")
+        } else {
+            Ok(allow)
+        }
+    }
+
+    fn gc_heap_grow_failed(&mut self, error: crate::Error) -> Result<()> {
+        if self.trap_on_grow_failure {
+            Err(error.context("forcing a GC heap growth failure to be a trap"))
+        } else {
+            log::debug!("ignoring GC heap growth failure error: {error:?}");
+            Ok(())
+        }
+    }
+
+    #[cfg(feature = "component-model")]
+    fn component_instances(&self) -> usize {
+        self.component_instances
+    }
+
+    #[cfg(feature = "component-model")]
+    fn resource_table_entries(&self) -> usize {
+        self.resource_table_entries
+    }
 }
@@ -1,10 +1,12 @@
 use crate::error::OutOfMemory;
 use crate::func::HostFunc;
 use crate::instance::InstancePre;
-use crate::store::StoreOpaque;
+use crate::runtime::vm;
+use crate::store::{Asyncness, StoreOpaque};
 use crate::{
     AsContext, AsContextMut, Caller, Engine, Extern, ExternType, Func, FuncType, ImportType,
-    Instance, IntoFunc, Module, Result, StoreContextMut, Val, ValRaw, prelude::*,
+    Instance, InstanceSnapshot, IntoFunc, Module, Result, StoreContextMut, StoreSnapshot, Val,
+    ValRaw, prelude::*,
 };
 use alloc::sync::Arc;
 use core::fmt::{self, Debug};
@@ -1121,6 +1123,100 @@ impl<T> Linker<T> {
             .await
     }
 
+    /// Creates a new instance from the `snapshot` provided, resolving its
+    /// imports with this linker.
+    ///
+    /// This is the same as [`Linker::instantiate`] except that the new
+    /// instance starts out in the state captured by `snapshot` instead of
+    /// being initialized from scratch. See [`InstanceSnapshot::instantiate`]
+    /// for more information.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for the same reasons as [`Linker::instantiate`].
+    ///
+    /// # Panics
+    ///
+    /// Panics for the same reasons as [`Linker::instantiate`].
+    pub fn instantiate_snapshot(
+        &self,
+        mut store: impl AsContextMut<Data = T>,
+        snapshot: &InstanceSnapshot,
+    ) -> Result<Instance>
+    where
+        T: 'static,
+    {
+        let mut store = store.as_context_mut();
+        let instance = self
+            ._instantiate_pre(snapshot.fork_module(), Some(store.0))?
+            .instantiate(&mut store)?;
+        snapshot.restore(store.0, instance)?;
+        Ok(instance)
+    }
+
+    /// Same as [`Linker::instantiate_snapshot`], except for async `Store`s.
+    #[cfg(feature = "async")]
+    pub async fn instantiate_snapshot_async(
+        &self,
+        mut store: impl AsContextMut<Data = T>,
+        snapshot: &InstanceSnapshot,
+    ) -> Result<Instance>
+    where
+        T: Send + 'static,
+    {
+        let mut store = store.as_context_mut();
+        let instance = self
+            ._instantiate_pre(snapshot.fork_module(), Some(store.0))?
+            .instantiate_async(&mut store)
+            .await?;
+        snapshot.restore(store.0, instance)?;
+        Ok(instance)
+    }
+
+    /// Recreates all instances of `snapshot` within `store`, resolving
+    /// imports which weren't provided by other instances of the snapshot with
+    /// this linker.
+    ///
+    /// The returned instances are in the order they were created in the store
+    /// which was snapshotted. See [`StoreSnapshot`] for more information.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for the same reasons as [`Linker::instantiate`], or if
+    /// `snapshot` contains component instances, which are instead restored
+    /// with
+    /// [`component::Linker::instantiate_store_snapshot`](crate::component::Linker::instantiate_store_snapshot).
+    ///
+    /// # Panics
+    ///
+    /// Panics for the same reasons as [`Linker::instantiate`].
+    pub fn instantiate_store_snapshot(
+        &self,
+        mut store: impl AsContextMut<Data = T>,
+        snapshot: &StoreSnapshot,
+    ) -> Result<Vec<Instance>>
+    where
+        T: 'static,
+    {
+        let mut store = store.as_context_mut();
+        vm::assert_ready(snapshot.instantiate(self, &mut store, Asyncness::No))
+    }
+
+    /// Same as [`Linker::instantiate_store_snapshot`], except for async
+    /// `Store`s.
+    #[cfg(feature = "async")]
+    pub async fn instantiate_store_snapshot_async(
+        &self,
+        mut store: impl AsContextMut<Data = T>,
+        snapshot: &StoreSnapshot,
+    ) -> Result<Vec<Instance>>
+    where
+        T: Send + 'static,
+    {
+        let mut store = store.as_context_mut();
+        snapshot.instantiate(self, &mut store, Asyncness::Yes).await
+    }
+
     /// Performs all checks necessary for instantiating `module` with this
     /// linker, except that instantiation doesn't actually finish.
     ///
@@ -23,8 +23,12 @@ use wasmtime_environ::{
     ObjectKind, StaticModuleIndex, TypeTrace, VMOffsets, VMSharedTypeIndex, WasmChecksum,
 };
 mod registry;
+#[cfg(feature = "winch")]
+mod tier_up;
 
 pub use registry::*;
+#[cfg(feature = "winch")]
+use tier_up::TierUp;
 
 /// A compiled WebAssembly module, ready to be instantiated.
 ///
@@ -160,6 +164,15 @@ struct ModuleInner {
 
     /// The checksum of the source binary from which this module was compiled.
     checksum: WasmChecksum,
+
+    /// Whether instantiation of this module skips running its startup
+    /// function, for example because instances are restored from a snapshot.
+    skip_startup: bool,
+
+    /// State for recompiling hot functions, set when this module was compiled
+    /// for tiered compilation.
+    #[cfg(feature = "winch")]
+    tier_up: OnceLock<TierUp>,
 }
 
 impl fmt::Debug for Module {
@@ -558,6 +571,51 @@ impl Module {
                 serializable,
                 offsets,
                 checksum,
+                skip_startup: false,
+                #[cfg(feature = "winch")]
+                tier_up: OnceLock::new(),
+            })?,
+        })
+    }
+
+    /// Creates a variant of this module which shares all of its compiled code
+    /// but is instantiated according to `env_module` and `memory_images`.
+    ///
+    /// This is used by [`InstanceSnapshot`](crate::InstanceSnapshot) to create
+    /// modules whose instances start out in a previously captured state. The
+    /// `env_module` must be derived from `self.env_module()` and may only
+    /// differ in initialization-related state such as initial sizes and
+    /// initializers. Instances of the returned module never run the startup
+    /// function, which is retained only to keep the `VMContext` layout intact.
+    pub(crate) fn with_initialization(
+        &self,
+        env_module: wasmtime_environ::Module,
+        memory_images: Option<ModuleMemoryImages>,
+    ) -> Result<Module> {
+        let engine = self.engine();
+        let module = self.compiled_module().with_env_module(env_module)?;
+        let offsets = VMOffsets::new(HostPtr, module.module());
+        debug_assert_eq!(offsets.size_of_vmctx(), self.offsets().size_of_vmctx());
+        engine
+            .allocator()
+            .validate_module(module.module(), &offsets)?;
+
+        let images = OnceLock::new();
+        images.get_or_init(|| memory_images);
+
+        Ok(Self {
+            inner: try_new::<Arc<_>>(ModuleInner {
+                engine: engine.clone(),
+                code: self.inner.code.clone(),
+                memory_images: images,
+                module,
+                #[cfg(any(feature = "cranelift", feature = "winch"))]
+                serializable: false,
+                offsets,
+                checksum: self.inner.checksum,
+                skip_startup: true,
+                #[cfg(feature = "winch")]
+                tier_up: OnceLock::new(),
             })?,
         })
     }
@@ -657,11 +715,6 @@ impl Module {
         self.inner.code.module_types()
     }
 
-    #[cfg(any(
-        feature = "gc-drc",
-        feature = "gc-copying",
-        feature = "component-model"
-    ))]
     pub(crate) fn signatures(&self) -> &crate::type_registry::TypeCollection {
         self.inner.code.signatures()
     }
@@ -913,11 +966,6 @@ impl Module {
         &self.inner.engine
     }
 
-    #[allow(
-        unused,
-        reason = "used only for verification with wasmtime `rr` feature \
-        and requires a lot of unnecessary gating across crates"
-    )]
     pub(crate) fn checksum(&self) -> &WasmChecksum {
         &self.inner.checksum
     }
@@ -1118,6 +1166,11 @@ impl Module {
         &self.inner.offsets
     }
 
+    /// Returns whether instantiating this module skips its startup function.
+    pub(crate) fn skip_startup(&self) -> bool {
+        self.inner.skip_startup
+    }
+
     /// Return the unique-within-Engine ID for this module.
     ///
     /// Allows distinguishing module identities when introspecting
@@ -1204,6 +1257,24 @@ impl Module {
     pub(crate) fn index(&self) -> &Arc<CompiledFunctionsTable> {
         &self.inner.module.index()
     }
+
+    /// Enables recompilation of this module's hot functions for tiered
+    /// compilation, given the `wasm` it was compiled from.
+    #[cfg(feature = "winch")]
+    pub(crate) fn set_tier_up(
+        &self,
+        wasm: Vec<u8>,
+        profile: Option<&wasmtime_environ::ExecutionProfile>,
+    ) {
+        self.inner
+            .tier_up
+            .get_or_init(|| TierUp::new(wasm, profile));
+    }
+
+    #[cfg(feature = "winch")]
+    fn tier_up_state(&self) -> Option<&TierUp> {
+        self.inner.tier_up.get()
+    }
 }
 
 /// Describes a function for a given module.
@@ -0,0 +1,122 @@
+//! Recompilation of hot functions for tiered compilation.
+//!
+//! Modules compiled with tiered compilation enabled start out with all of
+//! their functions compiled by Winch, with a counter of entries into each
+//! function. Once a function's counter reaches the configured threshold it
+//! calls into the runtime, which records the function as hot here and
+//! recompiles the module on a background thread with the hot functions
+//! compiled by Cranelift instead. Instances then switch over to the
+//! recompiled code the next time one of their functions asks to tier up.
+//!
+//! Each recompilation compiles the whole module again, so functions which
+//! become hot later are batched: the module is only recompiled again once
+//! functions left out of the latest recompilation have asked to tier up as
+//! many times as there are hot functions in it. Since functions ask again
+//! each time their counter reaches the threshold, every hot function is
+//! eventually recompiled, and the work spent recompiling stays proportional
+//! to the number of requests.
+
+use crate::Module;
+use crate::prelude::*;
+use std::collections::BTreeSet;
+use std::sync::Mutex;
+use wasmtime_environ::{DefinedFuncIndex, ExecutionProfile, FuncKey};
+
+/// Tiered compilation state of a [`Module`].
+pub(crate) struct TierUp {
+    /// The original WebAssembly binary, which is compiled again for each
+    /// recompilation.
+    wasm: Vec<u8>,
+    /// The execution profile the module was originally compiled with.
+    profile: ExecutionProfile,
+    state: Mutex<TierUpState>,
+}
+
+#[derive(Default)]
+struct TierUpState {
+    /// Functions which have asked to be recompiled.
+    hot: BTreeSet<DefinedFuncIndex>,
+    /// The hot functions included in the latest recompilation, whether it
+    /// is still in progress, succeeded or failed.
+    compiled: BTreeSet<DefinedFuncIndex>,
+    /// Number of requests to tier up from functions not in `compiled` since
+    /// the latest recompilation started.
+    pending: usize,
+    /// Whether a recompilation is in progress on a background thread.
+    compiling: bool,
+    /// The latest successful recompilation.
+    code: Option<Module>,
+}
+
+impl TierUp {
+    pub(crate) fn new(wasm: Vec<u8>, profile: Option<&ExecutionProfile>) -> TierUp {
+        TierUp {
+            wasm,
+            profile: profile.cloned().unwrap_or_default(),
+            state: Mutex::new(TierUpState::default()),
+        }
+    }
+}
+
+impl Module {
+    /// Records that `func` is hot, starting a recompilation in the background
+    /// once enough requests from functions left out of the latest one have
+    /// added up, and returns the latest recompilation of this module, if any.
+    ///
+    /// Returns `None` if this module wasn't compiled for tiered compilation.
+    pub(crate) fn tier_up(&self, func: DefinedFuncIndex) -> Option<Module> {
+        let tier_up = self.tier_up_state()?;
+        let mut state = tier_up.state.lock().unwrap();
+        state.hot.insert(func);
+        if !state.compiled.contains(&func) {
+            state.pending += 1;
+        }
+        if !state.compiling && state.pending >= state.compiled.len().max(1) {
+            let hot = state.hot.clone();
+            let module = self.clone();
+            let spawned = std::thread::Builder::new()
+                .name("wasmtime-tier-up".to_string())
+                .spawn(move || module.recompile(hot));
+            match spawned {
+                Ok(_) => {
+                    state.compiling = true;
+                    state.compiled = state.hot.clone();
+                    state.pending = 0;
+                }
+                Err(e) => log::warn!("failed to spawn tier-up thread: {e}"),
+            }
+        }
+        state.code.clone()
+    }
+
+    /// Compiles this module again with the functions in `hot` compiled by
+    /// Cranelift, and publishes the result for instances to switch to.
+    fn recompile(&self, hot: BTreeSet<DefinedFuncIndex>) {
+        let tier_up = self.tier_up_state().unwrap();
+
+        // The optimizing compiler is selected for functions covered by the
+        // execution profile. A zero count marks a function as covered without
+        // making the rest of it look cold to profile-guided block layout.
+        let mut profile = tier_up.profile.clone();
+        let module_index = self.env_module().module_index;
+        for func in hot.iter() {
+            let key = FuncKey::DefinedWasmFunction(module_index, *func);
+            if let Some(offset) = self.compiled_module().func_start_srcloc(key).file_offset() {
+                profile.add(offset, 0);
+            }
+        }
+
+        log::debug!(
+            "recompiling {} hot functions for tiered compilation",
+            hot.len()
+        );
+        let result = crate::compile::compile_tier(self.engine(), &tier_up.wasm, &profile);
+
+        let mut state = tier_up.state.lock().unwrap();
+        state.compiling = false;
+        match result {
+            Ok(code) => state.code = Some(code),
+            Err(e) => log::warn!("failed to recompile module for tiered compilation: {e:?}"),
+        }
+    }
+}
@@ -0,0 +1,975 @@
+//! Capturing the state of a live instance, or of all instances within a store,
+//! and creating new instances from it.
+//!
+//! See [`InstanceSnapshot`] and [`StoreSnapshot`] for more information.
+
+#[cfg(feature = "component-model")]
+mod component;
+#[cfg(feature = "gc")]
+mod gc;
+mod store;
+
+pub use self::store::StoreSnapshot;
+
+use crate::hash_map::HashMap;
+use crate::prelude::*;
+use crate::runtime::vm::{
+    self, MmapVec, ModuleMemoryImageSource, ModuleMemoryImages, VMFuncRef, VMGcRef, ValRaw,
+};
+use crate::store::StoreOpaque;
+use crate::{AsContextMut, Engine, Extern, Instance, Module};
+use alloc::sync::Arc;
+use core::ops::Range;
+use core::ptr::NonNull;
+use serde_derive::{Deserialize, Serialize};
+use wasmtime_environ::{
+    DefinedGlobalIndex, DefinedMemoryIndex, DefinedTableIndex, EntityRef, FuncIndex,
+    GlobalConstValue, MemoryInitialization, PassiveElemIndex, RuntimeDataIndex, WasmHeapTopType,
+    WasmHeapType, WasmRefType, WasmValType, packed_option::ReservedValue,
+};
+
+/// An in-memory image of the state of an [`Instance`] which can be used to
+/// cheaply create new instances starting out in that same state.
+///
+/// A snapshot is created with [`Instance::snapshot`] and captures the contents
+/// of all linear memories, tables, and globals defined by an instance, as well
+/// as the state of its passive data and element segments. This is intended for
+/// embeddings which run initialization code once, for example a language
+/// runtime's startup or code which calls host imports to load configuration,
+/// and then want to create many copies of the initialized instance. Each copy
+/// is created with [`InstanceSnapshot::instantiate`] or
+/// [`Linker::instantiate_snapshot`](crate::Linker::instantiate_snapshot) and
+/// does not re-run any initialization, including the module's start function.
+///
+/// This is similar in spirit to [Wizer] except that it happens entirely at
+/// runtime and does not produce a new wasm module. Linear memories of a
+/// snapshot are, where supported, turned into copy-on-write images in the same
+/// manner as the data segments of a [`Module`], so creating an instance from a
+/// snapshot does not copy memory contents and is about as cheap as
+/// instantiating the original module.
+///
+/// A snapshot is only tied to the [`Engine`](crate::Engine) of the instance it
+/// was taken from, not its [`Store`](crate::Store). Snapshots are cheap to clone
+/// and can be shared across threads to instantiate in many stores. A snapshot
+/// can additionally be converted to bytes with [`InstanceSnapshot::serialize`]
+/// and recreated, possibly in another process or on another host, with
+/// [`InstanceSnapshot::deserialize`] to migrate an idle instance elsewhere.
+///
+/// # Limitations
+///
+/// Only state owned by the instance itself is captured. Imports are provided
+/// again when instantiating from a snapshot, so for example the contents of an
+/// imported memory are not part of the snapshot. Likewise state owned by the
+/// [`Store`](crate::Store), such as its host data, GC heap, or any suspended
+/// wasm execution, is not captured. To capture all instances within a store
+/// together with their GC objects see [`StoreSnapshot`] instead. Additionally
+/// a snapshot cannot be taken, and an error is returned, if the instance:
+///
+/// * defines a shared memory,
+/// * contains a non-null reference, other than a `funcref`, in a table, global,
+///   or passive element segment, for example a GC object or an `externref`,
+/// * contains a `funcref` to a function which is neither defined nor imported
+///   by the instance.
+///
+/// [Wizer]: https://github.com/bytecodealliance/wizer
+#[derive(Clone)]
+pub struct InstanceSnapshot {
+    inner: Arc<SnapshotInner>,
+}
+
+struct SnapshotInner {
+    /// The module that the snapshotted instance was an instance of.
+    module: Module,
+
+    /// A variant of `module`, sharing its compiled code, which is actually
+    /// instantiated to create instances from this snapshot.
+    ///
+    /// Initial sizes, global initializers, and funcref table contents are all
+    /// updated to reflect the snapshot, and its startup function is never run.
+    fork: Module,
+
+    /// The captured state of the instance.
+    state: SnapshotState,
+
+    /// Backing storage for the non-zero contents of all linear memories.
+    data: Arc<SnapshotData>,
+}
+
+/// The state of an instance captured in a snapshot.
+///
+/// This is described purely in terms of the instance's module, so it is
+/// independent of the `Engine` and host the snapshot was taken on, and is what
+/// [`InstanceSnapshot::serialize`] writes out.
+#[derive(Serialize, Deserialize)]
+struct SnapshotState {
+    /// Size and contents of each defined linear memory.
+    memories: Vec<SnapshotMemory>,
+
+    /// Values of defined globals which don't contain a reference.
+    globals: Vec<(DefinedGlobalIndex, GlobalConstValue)>,
+
+    /// Defined globals which contain a non-null `funcref`, which is not
+    /// representable in `wasmtime_environ::Module::global_initializers`.
+    func_globals: Vec<(DefinedGlobalIndex, FuncIndex)>,
+
+    /// Size and contents of each defined table. Tables of non-function
+    /// references have no recorded contents as they must be all null.
+    tables: Vec<SnapshotTable>,
+
+    /// Contents of each passive element segment, or `None` if it has been
+    /// dropped. `FuncIndex::reserved_value()` is used for null entries.
+    passive_elements: Vec<(PassiveElemIndex, Option<Vec<FuncIndex>>)>,
+
+    /// Passive data segments which have been dropped.
+    dropped_data: Vec<RuntimeDataIndex>,
+
+    /// Sorted locations holding references which aren't representable in
+    /// terms of this instance alone, such as GC objects or functions of other
+    /// instances. These are recorded as null above and are only present in
+    /// snapshots which are part of a [`StoreSnapshot`], which fills them in.
+    deferred: Vec<RefLocation>,
+}
+
+/// A location within an instance which holds a reference.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize)]
+pub(crate) enum RefLocation {
+    Global(DefinedGlobalIndex),
+    Table(DefinedTableIndex, u64),
+    Element(PassiveElemIndex, u32),
+}
+
+/// A reference within an instance which is deferred to the [`StoreSnapshot`]
+/// the instance's snapshot is a part of.
+pub(crate) enum DeferredRef {
+    /// A function which is neither defined nor imported by the instance.
+    Func(NonNull<VMFuncRef>),
+    /// A non-null GC reference, in its raw form.
+    #[cfg_attr(
+        not(feature = "gc"),
+        allow(dead_code, reason = "GC references only exist with the `gc` feature")
+    )]
+    Gc(u32),
+}
+
+/// A defined linear memory in a snapshot.
+#[derive(Serialize, Deserialize)]
+struct SnapshotMemory {
+    index: DefinedMemoryIndex,
+    /// Size of the memory, in units of its page size.
+    pages: u64,
+    /// Offset in linear memory at which `data` starts.
+    offset: usize,
+    /// Range within `SnapshotData::data` of the bytes at `offset`. All bytes
+    /// of the memory outside of this range are zero.
+    data: Range<usize>,
+}
+
+/// A defined table in a snapshot.
+#[derive(Serialize, Deserialize)]
+struct SnapshotTable {
+    index: DefinedTableIndex,
+    size: u64,
+    /// Contents of a funcref table, using `FuncIndex::reserved_value()` for
+    /// null entries.
+    funcs: Vec<FuncIndex>,
+}
+
+struct SnapshotData {
+    data: Vec<u8>,
+}
+
+impl ModuleMemoryImageSource for SnapshotData {
+    fn wasm_data(&self) -> &[u8] {
+        &self.data
+    }
+
+    fn mmap(&self) -> Option<&MmapVec> {
+        None
+    }
+}
+
+impl SnapshotState {
+    /// Validates that this state describes a valid state of an instance of
+    /// `env`, whose memory contents are stored in `data_len` bytes.
+    fn validate(
+        &self,
+        engine: &Engine,
+        env: &wasmtime_environ::Module,
+        data_len: usize,
+    ) -> Result<()> {
+        let mismatch = || format_err!("instance snapshot does not match module");
+
+        if self.memories.len() != env.num_defined_memories() {
+            return Err(mismatch());
+        }
+        for (i, memory) in self.memories.iter().enumerate() {
+            if memory.index != DefinedMemoryIndex::new(i) {
+                return Err(mismatch());
+            }
+            let ty = &env.memories[env.memory_index(memory.index)];
+            if ty.shared
+                || memory.pages < ty.limits.min
+                || ty.limits.max.is_some_and(|max| memory.pages > max)
+            {
+                return Err(mismatch());
+            }
+            let size = memory
+                .pages
+                .checked_mul(ty.page_size())
+                .and_then(|size| usize::try_from(size).ok())
+                .ok_or_else(mismatch)?;
+            if memory.data.start > memory.data.end
+                || memory.data.end > data_len
+                || memory.offset > size
+                || memory.data.len() > size - memory.offset
+            {
+                return Err(mismatch());
+            }
+        }
+
+        for (index, value) in self.globals.iter() {
+            let ty = env
+                .globals
+                .get(env.global_index(*index))
+                .ok_or_else(mismatch)?;
+            let matches = match (ty.wasm_ty, value) {
+                (WasmValType::I32, GlobalConstValue::I32(_))
+                | (WasmValType::I64, GlobalConstValue::I64(_))
+                | (WasmValType::F32, GlobalConstValue::F32(_))
+                | (WasmValType::F64, GlobalConstValue::F64(_))
+                | (WasmValType::V128, GlobalConstValue::V128(_)) => true,
+                _ => false,
+            };
+            if !matches {
+                return Err(mismatch());
+            }
+        }
+        for (index, func) in self.func_globals.iter() {
+            let ty = env
+                .globals
+                .get(env.global_index(*index))
+                .ok_or_else(mismatch)?;
+            let WasmValType::Ref(ty) = ty.wasm_ty else {
+                return Err(mismatch());
+            };
+            if func.is_reserved_value() || !func_matches(engine, env, &ty, *func) {
+                return Err(mismatch());
+            }
+        }
+
+        if self.tables.len() != env.num_defined_tables() {
+            return Err(mismatch());
+        }
+        for (i, table) in self.tables.iter().enumerate() {
+            if table.index != DefinedTableIndex::new(i) {
+                return Err(mismatch());
+            }
+            let ty = &env.tables[env.table_index(table.index)];
+            if table.size < ty.limits.min || ty.limits.max.is_some_and(|max| table.size > max) {
+                return Err(mismatch());
+            }
+            let expected_len = if ty.ref_type.heap_type.top() == WasmHeapTopType::Func {
+                table.size
+            } else {
+                0
+            };
+            if u64::try_from(table.funcs.len())? != expected_len
+                || !table.funcs.iter().enumerate().all(|(i, f)| {
+                    func_matches(engine, env, &ty.ref_type, *f)
+                        || (f.is_reserved_value()
+                            && u64::try_from(i).is_ok_and(|i| {
+                                self.is_deferred(RefLocation::Table(table.index, i))
+                            }))
+                })
+            {
+                return Err(mismatch());
+            }
+        }
+
+        for (index, elements) in self.passive_elements.iter() {
+            let (ty, len) = env.passive_elements.get(*index).ok_or_else(mismatch)?;
+            if let Some(elements) = elements {
+                if ty.heap_type.top() != WasmHeapTopType::Func
+                    || u64::try_from(elements.len())? != *len
+                    || !elements.iter().enumerate().all(|(i, f)| {
+                        func_matches(engine, env, ty, *f)
+                            || (f.is_reserved_value()
+                                && u32::try_from(i).is_ok_and(|i| {
+                                    self.is_deferred(RefLocation::Element(*index, i))
+                                }))
+                    })
+                {
+                    return Err(mismatch());
+                }
+            }
+        }
+
+        if !self.deferred.is_sorted_by(|a, b| a < b) {
+            return Err(mismatch());
+        }
+        for location in self.deferred.iter() {
+            let valid = match *location {
+                RefLocation::Global(index) => {
+                    index.index() < env.num_defined_globals()
+                        && matches!(
+                            env.globals[env.global_index(index)].wasm_ty,
+                            WasmValType::Ref(_)
+                        )
+                }
+                RefLocation::Table(index, i) => self
+                    .tables
+                    .get(index.index())
+                    .is_some_and(|table| i < table.size),
+                RefLocation::Element(index, i) => {
+                    let dropped = self
+                        .passive_elements
+                        .iter()
+                        .any(|(e, elements)| *e == index && elements.is_none());
+                    env.passive_elements
+                        .get(index)
+                        .is_some_and(|(_, len)| u64::from(i) < *len)
+                        && !dropped
+                }
+            };
+            if !valid {
+                return Err(mismatch());
+            }
+        }
+
+        for index in self.dropped_data.iter() {
+            if env.runtime_data.get(*index).is_none() {
+                return Err(mismatch());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns whether the reference at `location` is deferred to the store
+    /// snapshot this is a part of.
+    fn is_deferred(&self, location: RefLocation) -> bool {
+        self.deferred.binary_search(&location).is_ok()
+    }
+}
+
+/// Returns whether `func`, or null if it's the reserved value, is a valid
+/// value of type `ty` which can be recorded in a snapshot of an instance of
+/// `env`.
+fn func_matches(
+    engine: &Engine,
+    env: &wasmtime_environ::Module,
+    ty: &WasmRefType,
+    func: FuncIndex,
+) -> bool {
+    if func.is_reserved_value() {
+        return ty.nullable;
+    }
+    let Some(func_ty) = env.functions.get(func) else {
+        return false;
+    };
+    if !env.is_imported_function(func) && !func_ty.is_escaping() {
+        return false;
+    }
+    match ty.heap_type {
+        WasmHeapType::Func => true,
+        WasmHeapType::ConcreteFunc(index) => engine.signatures().is_subtype(
+            func_ty.signature.unwrap_engine_type_index(),
+            index.unwrap_engine_type_index(),
+        ),
+        _ => false,
+    }
+}
+
+/// Alignment of the contents of memory images within linear memory.
+#[cfg(has_virtual_memory)]
+fn image_align() -> usize {
+    vm::host_page_size()
+}
+
+#[cfg(not(has_virtual_memory))]
+fn image_align() -> usize {
+    1
+}
+
+/// Identity of a function as seen through its `VMFuncRef`.
+///
+/// Different `VMFuncRef` pointers may refer to the same function, for example
+/// an imported function's `VMFunctionImport` and the exporter's own
+/// `VMFuncRef`, so functions are instead identified by their entrypoint and
+/// closure pointer.
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+struct FuncKey {
+    array_call: usize,
+    vmctx: usize,
+}
+
+impl FuncKey {
+    /// # Safety
+    ///
+    /// Requires that `func_ref` is a valid pointer.
+    unsafe fn new(func_ref: NonNull<VMFuncRef>) -> FuncKey {
+        // SAFETY: the validity of `func_ref` is a requirement of this function.
+        let func_ref = unsafe { func_ref.as_ref() };
+        FuncKey {
+            array_call: func_ref.array_call.as_ptr().addr(),
+            vmctx: func_ref.vmctx.as_ptr().addr(),
+        }
+    }
+}
+
+/// Returns a map of all functions `instance` could have placed into a table or
+/// global, which are the only functions representable in its snapshot.
+fn instance_funcs(store: &mut StoreOpaque, instance: Instance) -> HashMap<FuncKey, FuncIndex> {
+    let mut funcs = HashMap::new();
+    let (mut handle, registry) = instance.id.get_mut_and_module_registry(store);
+    let env = handle.env_module().clone();
+    for (index, func) in env.functions.iter() {
+        if !env.is_imported_function(index) && !func.is_escaping() {
+            continue;
+        }
+        if let Some(func_ref) = handle.as_mut().get_func_ref(registry, index) {
+            // SAFETY: `get_func_ref` returns valid pointers.
+            funcs.insert(unsafe { FuncKey::new(func_ref) }, index);
+        }
+    }
+    funcs
+}
+
+/// Prefix of the serialized form of a snapshot, which is followed by the
+/// version of Wasmtime that produced it.
+const SERIALIZED_MAGIC: &[u8] = b"\0wasmtime-snapshot\0";
+
+/// Strips the `magic` prefix and Wasmtime version from the serialized `bytes`
+/// of a `what` snapshot, returning the remaining bytes.
+fn strip_header<'a>(magic: &[u8], what: &str, bytes: &'a [u8]) -> Result<&'a [u8]> {
+    let Some(bytes) = bytes.strip_prefix(magic) else {
+        bail!("bytes are not a serialized {what}");
+    };
+    let Some(version_len) = bytes.iter().position(|b| *b == 0) else {
+        bail!("bytes are not a serialized {what}");
+    };
+    let version = &bytes[..version_len];
+    if version != env!("CARGO_PKG_VERSION").as_bytes() {
+        bail!(
+            "{what} was serialized by Wasmtime {}, but this is Wasmtime {}",
+            String::from_utf8_lossy(version),
+            env!("CARGO_PKG_VERSION"),
+        );
+    }
+    Ok(&bytes[version_len + 1..])
+}
+
+/// Records the references within an instance while it's captured into a
+/// snapshot.
+struct CaptureRefs<'a> {
+    /// All functions the instance could have placed into a table or global.
+    funcs: HashMap<FuncKey, FuncIndex>,
+
+    /// Where references which aren't representable in terms of the instance
+    /// alone are recorded, or `None` if an error is returned for them.
+    deferred: Option<&'a mut Vec<(RefLocation, DeferredRef)>>,
+}
+
+impl CaptureRefs<'_> {
+    /// Returns the function index to record for `func_ref` at `location`.
+    fn func(
+        &mut self,
+        location: RefLocation,
+        func_ref: Option<NonNull<VMFuncRef>>,
+    ) -> Result<FuncIndex> {
+        let Some(func_ref) = func_ref else {
+            return Ok(FuncIndex::reserved_value());
+        };
+        // SAFETY: all funcrefs reachable from the instance are valid.
+        let key = unsafe { FuncKey::new(func_ref) };
+        if let Some(index) = self.funcs.get(&key) {
+            return Ok(*index);
+        }
+        match &mut self.deferred {
+            Some(deferred) => {
+                deferred.push((location, DeferredRef::Func(func_ref)));
+                Ok(FuncIndex::reserved_value())
+            }
+            None => bail!(
+                "cannot snapshot a reference to a function which is not \
+                 defined or imported by the instance"
+            ),
+        }
+    }
+
+    /// Records the raw GC reference `gc_ref`, found at `location`.
+    fn gc(&mut self, location: RefLocation, gc_ref: Option<&VMGcRef>) -> Result<()> {
+        let Some(gc_ref) = gc_ref else {
+            return Ok(());
+        };
+        match &mut self.deferred {
+            Some(deferred) => {
+                deferred.push((location, DeferredRef::Gc(gc_ref.as_raw_u32())));
+                Ok(())
+            }
+            None => bail!("cannot snapshot a non-null reference other than a `funcref`"),
+        }
+    }
+}
+
+impl InstanceSnapshot {
+    pub(crate) fn new(store: &mut StoreOpaque, instance: Instance) -> Result<InstanceSnapshot> {
+        InstanceSnapshot::capture(store, instance, None)
+    }
+
+    /// Captures the state of `instance`.
+    ///
+    /// References which aren't representable in terms of the instance alone
+    /// are pushed onto `deferred` if it's provided, or otherwise result in an
+    /// error.
+    pub(crate) fn capture(
+        store: &mut StoreOpaque,
+        instance: Instance,
+        deferred: Option<&mut Vec<(RefLocation, DeferredRef)>>,
+    ) -> Result<InstanceSnapshot> {
+        let module = instance._module(store).clone();
+        let env = module.env_module();
+        let first_deferred = deferred.as_ref().map_or(0, |d| d.len());
+
+        let funcs = instance_funcs(store, instance);
+        let mut refs = CaptureRefs { funcs, deferred };
+
+        // Linear memories: record the smallest host-page-aligned region which
+        // contains all non-zero bytes of each memory.
+        let align = image_align();
+        let mut data = Vec::new();
+        let mut memories = Vec::new();
+        for (index, vmmemory) in env.memories.iter().skip(env.num_imported_memories) {
+            let defined = env.defined_memory_index(index).unwrap();
+            if vmmemory.shared {
+                bail!("cannot snapshot an instance which defines a shared memory");
+            }
+            let definition = store[instance.id].memory(defined);
+            let len = definition.current_length();
+            // SAFETY: the memory is not shared, so this store has exclusive
+            // access to its contents, which are `len` bytes starting at `base`.
+            let contents = unsafe { core::slice::from_raw_parts(definition.base.as_ptr(), len) };
+
+            let pages = u64::try_from(len)? >> vmmemory.page_size_log2;
+            let (offset, range) = match contents.iter().position(|b| *b != 0) {
+                Some(first) => {
+                    let last = contents.iter().rposition(|b| *b != 0).unwrap();
+                    let start = first / align * align;
+                    let end = (last + 1).next_multiple_of(align).min(len);
+                    let data_start = data.len();
+                    data.extend_from_slice(&contents[start..end]);
+                    (start, data_start..data.len())
+                }
+                None => (0, 0..0),
+            };
+            memories.push(SnapshotMemory {
+                index: defined,
+                pages,
+                offset,
+                data: range,
+            });
+        }
+
+        // Globals: numeric values are recorded directly, null references are
+        // the default value of a global, and non-null funcrefs are recorded by
+        // function index. Everything else is deferred.
+        let mut globals = Vec::new();
+        let mut func_globals = Vec::new();
+        for (index, global) in env.globals.iter().skip(env.num_imported_globals) {
+            let defined = env.defined_global_index(index).unwrap();
+            // SAFETY: the global is defined by this instance and its value is
+            // interpreted according to its type.
+            let definition = unsafe { store[instance.id].global_ptr(defined).as_ref() };
+            let value = unsafe {
+                match global.wasm_ty {
+                    WasmValType::I32 => GlobalConstValue::I32(*definition.as_i32()),
+                    WasmValType::I64 => GlobalConstValue::I64(*definition.as_i64()),
+                    WasmValType::F32 => GlobalConstValue::F32(*definition.as_f32_bits()),
+                    WasmValType::F64 => GlobalConstValue::F64(*definition.as_f64_bits()),
+                    WasmValType::V128 => GlobalConstValue::V128(definition.get_u128()),
+                    WasmValType::Ref(ty) if ty.heap_type.top() == WasmHeapTopType::Func => {
+                        let func_ref = NonNull::new(definition.as_func_ref());
+                        let func = refs.func(RefLocation::Global(defined), func_ref)?;
+                        if !func.is_reserved_value() {
+                            func_globals.push((defined, func));
+                        }
+                        continue;
+                    }
+                    WasmValType::Ref(ty) if ty.heap_type.top() == WasmHeapTopType::Cont => {
+                        if *definition.as_u128_bits() != [0; 16] {
+                            bail!("cannot snapshot a global containing a non-null reference");
+                        }
+                        continue;
+                    }
+                    WasmValType::Ref(_) => {
+                        refs.gc(RefLocation::Global(defined), definition.as_gc_ref())?;
+                        continue;
+                    }
+                }
+            };
+            globals.push((defined, value));
+        }
+
+        // Tables: funcref tables have their contents recorded, and the
+        // contents of all other tables are deferred.
+        let mut tables = Vec::new();
+        {
+            let (mut handle, registry) = instance.id.get_mut_and_module_registry(store);
+            for (index, ty) in env.tables.iter().skip(env.num_imported_tables) {
+                let defined = env.defined_table_index(index).unwrap();
+                let size = handle.as_mut().get_defined_table(defined).size();
+
+                let mut funcs = Vec::new();
+                if ty.ref_type.heap_type.top() == WasmHeapTopType::Func {
+                    let table = handle.as_mut().get_defined_table_with_lazy_init(
+                        registry,
+                        defined,
+                        0..u64::try_from(size)?,
+                    );
+                    funcs.reserve(size);
+                    for i in 0..size {
+                        let i = u64::try_from(i)?;
+                        let location = RefLocation::Table(defined, i);
+                        funcs.push(refs.func(location, table.get_func(i)?)?);
+                    }
+                } else {
+                    let table = handle.as_mut().get_defined_table(defined);
+                    for i in 0..size {
+                        let i = u64::try_from(i)?;
+                        match table.element_type() {
+                            vm::TableElementType::Func => unreachable!(),
+                            vm::TableElementType::GcRef => {
+                                refs.gc(RefLocation::Table(defined, i), table.get_gc_ref(i)?)?
+                            }
+                            vm::TableElementType::Cont => {
+                                if table.get_cont(i)?.is_some() {
+                                    bail!(
+                                        "cannot snapshot a table containing a non-null \
+                                         continuation reference"
+                                    );
+                                }
+                            }
+                        }
+                    }
+                }
+                tables.push(SnapshotTable {
+                    index: defined,
+                    size: u64::try_from(size)?,
+                    funcs,
+                });
+            }
+        }
+
+        // Passive element segments: record their current contents, or that
+        // they have been dropped.
+        let mut passive_elements = Vec::new();
+        {
+            let mut handle = instance.id.get_mut(store);
+            for (index, (ty, len)) in env.passive_elements.iter() {
+                let elements = handle.as_mut().passive_element_segment(index);
+                if elements.is_empty() && *len > 0 {
+                    passive_elements.push((index, None));
+                    continue;
+                }
+                if ty.heap_type.top() == WasmHeapTopType::Func {
+                    let elements = elements
+                        .iter()
+                        .enumerate()
+                        .map(|(i, e)| {
+                            let location = RefLocation::Element(index, u32::try_from(i)?);
+                            refs.func(location, NonNull::new(e.get_funcref().cast()))
+                        })
+                        .collect::<Result<Vec<_>>>()?;
+                    passive_elements.push((index, Some(elements)));
+                } else {
+                    for (i, e) in elements.iter().enumerate() {
+                        let location = RefLocation::Element(index, u32::try_from(i)?);
+                        refs.gc(location, VMGcRef::from_raw_u32(e.get_anyref()).as_ref())?;
+                    }
+                }
+            }
+        }
+
+        // Passive data segments: record which have been dropped.
+        let dropped_data = env
+            .runtime_data
+            .iter()
+            .filter(|(index, range)| {
+                !range.is_empty() && store[instance.id].runtime_data_len(*index) == 0
+            })
+            .map(|(index, _)| index)
+            .collect();
+
+        let mut deferred = refs.deferred.map_or(Vec::new(), |d| {
+            d[first_deferred..].iter().map(|(l, _)| *l).collect()
+        });
+        deferred.sort();
+
+        let state = SnapshotState {
+            memories,
+            globals,
+            func_globals,
+            tables,
+            passive_elements,
+            dropped_data,
+            deferred,
+        };
+        InstanceSnapshot::from_state(module, state, data)
+    }
+
+    /// Creates a snapshot of an instance of `module` in the `state` provided,
+    /// with the contents of linear memories described by `data`.
+    ///
+    /// The `state` is validated against `module` here as it may have come
+    /// from [`InstanceSnapshot::deserialize`].
+    fn from_state(module: Module, state: SnapshotState, data: Vec<u8>) -> Result<InstanceSnapshot> {
+        let engine = module.engine();
+        let env = module.env_module();
+        state.validate(engine, env, data.len())?;
+
+        // `wasmtime_environ::Module` is not `Clone`, so create the copy which
+        // will describe forked instances by round-tripping it through its
+        // serialized form, the same way modules are loaded from disk.
+        let mut fork: wasmtime_environ::Module =
+            postcard::from_bytes(&postcard::to_allocvec(&**env)?)?;
+        fork.memory_initialization = MemoryInitialization::Segmented;
+
+        // Each memory's contents become, where possible, a copy-on-write image
+        // for forked instances. This requires that the contents are aligned to
+        // host pages, which depends on the host the snapshot was taken on, so
+        // memories which can't be imaged are copied in by `restore` instead.
+        let align = image_align();
+        let mut images = Vec::new();
+        for memory in state.memories.iter() {
+            let index = env.memory_index(memory.index);
+            fork.memories[index].limits.min = memory.pages;
+            let image = !memory.data.is_empty()
+                && memory.offset % align == 0
+                && memory.data.len() % align == 0;
+            images.push(image.then(|| (memory.offset, memory.data.clone())));
+        }
+
+        fork.global_initializers = TryVec::new();
+        for (index, value) in state.globals.iter() {
+            fork.global_initializers.push((*index, *value))?;
+        }
+
+        for table in state.tables.iter() {
+            let index = env.table_index(table.index);
+            fork.tables[index].limits.min = table.size;
+            let mut init = TryVec::new();
+            init.reserve(table.funcs.len())?;
+            for func in table.funcs.iter() {
+                init.push(*func)?;
+            }
+            fork.table_initialization[table.index] = init;
+        }
+
+        let data = try_new::<Arc<_>>(SnapshotData { data })?;
+        let memory_images = if engine.tunables().memory_init_cow {
+            ModuleMemoryImages::from_images(engine, &data, images.into_iter())?
+        } else {
+            None
+        };
+        let fork = module.with_initialization(fork, memory_images)?;
+
+        Ok(InstanceSnapshot {
+            inner: try_new::<Arc<_>>(SnapshotInner {
+                module,
+                fork,
+                state,
+                data,
+            })?,
+        })
+    }
+
+    /// Serializes this snapshot into a sequence of bytes which can later be
+    /// passed to [`InstanceSnapshot::deserialize`].
+    ///
+    /// This can be used to move an instance to another process or host: the
+    /// instance's state is captured with [`Instance::snapshot`], serialized
+    /// here, and then deserialized and instantiated elsewhere to resume it.
+    ///
+    /// The serialized form contains only the state of the instance and a
+    /// checksum of its module's wasm binary, not the module itself. The
+    /// compiled code of [`InstanceSnapshot::module`] is instead provided when
+    /// deserializing, for example by compiling the same wasm binary or through
+    /// [`Module::serialize`]. The format is specific to the
+    /// version of Wasmtime used to produce it, but is otherwise independent of
+    /// the [`Engine`](crate::Engine) and host the snapshot was taken on.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if memory allocation fails.
+    pub fn serialize(&self) -> Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(SERIALIZED_MAGIC);
+        bytes.extend_from_slice(env!("CARGO_PKG_VERSION").as_bytes());
+        bytes.push(0);
+        bytes.extend_from_slice(&**self.inner.module.checksum());
+        let state = (&self.inner.state, &self.inner.data.data);
+        Ok(postcard::to_extend(&state, bytes)?)
+    }
+
+    /// Deserializes a snapshot previously created with
+    /// [`InstanceSnapshot::serialize`], for a snapshot of an instance of
+    /// `module`.
+    ///
+    /// The `module` must be compiled from the same wasm binary as the module
+    /// the snapshotted instance was an instance of, though it may belong to a
+    /// different [`Engine`](crate::Engine). The returned snapshot creates
+    /// instances within `module`'s engine.
+    ///
+    /// Unlike [`Module::deserialize`] this function is safe to call with
+    /// arbitrary input: the snapshot only describes the contents of wasm
+    /// state, which is validated against `module`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `bytes` was not produced by
+    /// [`InstanceSnapshot::serialize`] of the same version of Wasmtime, if the
+    /// snapshot was taken of an instance of a different module, or if it
+    /// doesn't describe a valid state for an instance of `module`.
+    pub fn deserialize(module: &Module, bytes: &[u8]) -> Result<InstanceSnapshot> {
+        let bytes = strip_header(SERIALIZED_MAGIC, "instance snapshot", bytes)?;
+        let Some((checksum, bytes)) = bytes.split_first_chunk::<32>() else {
+            bail!("bytes are not a serialized instance snapshot");
+        };
+        if *checksum != **module.checksum() {
+            bail!("instance snapshot was taken of an instance of a different module");
+        }
+        let (state, data): (SnapshotState, Vec<u8>) =
+            postcard::from_bytes(bytes).context("failed to deserialize instance snapshot")?;
+        if !state.deferred.is_empty() {
+            bail!("instance snapshot does not match module");
+        }
+        InstanceSnapshot::from_state(module.clone(), state, data)
+    }
+
+    /// Returns the module that the snapshotted instance was an instance of.
+    pub fn module(&self) -> &Module {
+        &self.inner.module
+    }
+
+    /// Creates a new instance starting out in the state captured by this
+    /// snapshot.
+    ///
+    /// This is the snapshot equivalent of [`Instance::new`] and `imports` are
+    /// provided in the same way, corresponding to [`Module::imports`] of
+    /// [`InstanceSnapshot::module`]. No initialization of the new instance
+    /// happens beyond restoring the snapshot: active data and element
+    /// segments are not applied and the start function is not run.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for the same reasons as [`Instance::new`], for example
+    /// if the `imports` don't match the module's imports or if resource limits
+    /// are exceeded, and if the `store` belongs to a different
+    /// [`Engine`](crate::Engine) than the snapshot.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any [`Extern`] supplied is not owned by `store`.
+    pub fn instantiate(
+        &self,
+        mut store: impl AsContextMut,
+        imports: &[Extern],
+    ) -> Result<Instance> {
+        let mut store = store.as_context_mut();
+        let instance = Instance::new(&mut store, &self.inner.fork, imports)?;
+        self.restore(store.0, instance)?;
+        Ok(instance)
+    }
+
+    /// Same as [`InstanceSnapshot::instantiate`], except for async `Store`s.
+    #[cfg(feature = "async")]
+    pub async fn instantiate_async(
+        &self,
+        mut store: impl AsContextMut,
+        imports: &[Extern],
+    ) -> Result<Instance> {
+        let mut store = store.as_context_mut();
+        let instance = Instance::new_async(&mut store, &self.inner.fork, imports).await?;
+        self.restore(store.0, instance)?;
+        Ok(instance)
+    }
+
+    /// The module which is instantiated to create instances from this
+    /// snapshot, which must then be passed to [`InstanceSnapshot::restore`].
+    pub(crate) fn fork_module(&self) -> &Module {
+        &self.inner.fork
+    }
+
+    /// Finishes restoring the state of `instance`, a fresh instance of
+    /// `self.fork_module()`, for state which isn't representable in the forked
+    /// module itself.
+    pub(crate) fn restore(&self, store: &mut StoreOpaque, instance: Instance) -> Result<()> {
+        let fork = self.inner.fork.env_module();
+        let table_lazy_init = store.engine().tunables().table_lazy_init;
+        let (mut handle, registry) = instance.id.get_mut_and_module_registry(store);
+        debug_assert!(Arc::ptr_eq(handle.env_module(), fork));
+
+        // Memories which couldn't be initialized with a copy-on-write image
+        // have their contents copied in.
+        for memory in self.inner.state.memories.iter() {
+            if !handle.get_defined_memory(memory.index).needs_init() {
+                continue;
+            }
+            let definition = handle.memory(memory.index);
+            let src = &self.inner.data.data[memory.data.clone()];
+            assert!(memory.offset + src.len() <= definition.current_length());
+            // SAFETY: the memory was just created and is not shared, and the
+            // destination is in-bounds as asserted above.
+            unsafe {
+                core::ptr::copy_nonoverlapping(
+                    src.as_ptr(),
+                    definition.base.as_ptr().add(memory.offset),
+                    src.len(),
+                );
+            }
+        }
+
+        // Without lazy table initialization the precomputed funcref table
+        // images in the forked module aren't consulted, so apply them here.
+        if !table_lazy_init {
+            for (defined, init) in fork.table_initialization.iter() {
+                for (i, func) in init.iter().enumerate() {
+                    let func_ref = handle.as_mut().get_func_ref(registry, *func);
+                    handle
+                        .as_mut()
+                        .get_defined_table(defined)
+                        .set_func(u64::try_from(i)?, func_ref)?;
+                }
+            }
+        }
+
+        for (defined, func) in self.inner.state.func_globals.iter() {
+            let func_ref = handle.as_mut().get_func_ref(registry, *func).unwrap();
+            // SAFETY: the global is defined by this instance and has a funcref
+            // type, as recorded when the snapshot was taken.
+            unsafe {
+                *handle.global_ptr(*defined).as_mut().as_func_ref_mut() = func_ref.as_ptr();
+            }
+        }
+
+        for (index, elements) in self.inner.state.passive_elements.iter() {
+            match elements {
+                Some(elements) => {
+                    for (i, func) in elements.iter().enumerate() {
+                        let func_ref = handle.as_mut().get_func_ref(registry, *func);
+                        let func_ref = func_ref.map_or(core::ptr::null_mut(), |f| f.as_ptr());
+                        handle.as_mut().passive_element_segment(*index)[i] =
+                            ValRaw::funcref(func_ref.cast());
+                    }
+                }
+                None => handle.as_mut().passive_elem_drop(None, *index)?,
+            }
+        }
+
+        for index in self.inner.state.dropped_data.iter() {
+            handle.as_mut().drop_runtime_data(*index);
+        }
+
+        Ok(())
+    }
+}
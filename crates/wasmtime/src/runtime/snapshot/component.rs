@@ -0,0 +1,248 @@
+//! Capturing and restoring the component instances of a [`StoreSnapshot`].
+
+use super::store::{
+    SerializedComponentInstance, SnapshotHandle, SnapshotHandleTable, SnapshotInstance,
+    StoreSnapshot, invalid,
+};
+use crate::component::{Component, Instance, Linker};
+use crate::hash_map::HashMap;
+use crate::prelude::*;
+use crate::runtime::vm::component::{HandleTable, ResourceSlot, TypedResource};
+use crate::store::{Asyncness, InstanceId, StoreOpaque};
+use crate::{AsContextMut, StoreContextMut};
+use wasmtime_environ::component::{
+    RuntimeInstanceIndex, TypeResourceTable, TypeResourceTableIndex,
+};
+
+/// A component instance within a store snapshot.
+pub(super) struct ComponentInstanceSnapshot {
+    pub(super) component: Component,
+
+    /// The index within the store snapshot of each of the component
+    /// instance's core instances, by `RuntimeInstanceIndex`.
+    pub(super) instances: Vec<u32>,
+
+    /// The resource handles of each of the component instance's
+    /// (sub)component instances, by `RuntimeComponentInstanceIndex`.
+    pub(super) handles: Vec<SnapshotHandleTable>,
+}
+
+/// A component instance captured from a store, whose core instances haven't
+/// been assigned positions within the snapshot yet.
+pub(super) struct CapturedComponentInstance {
+    component: Component,
+    pub(super) instances: Vec<InstanceId>,
+    handles: Vec<SnapshotHandleTable>,
+}
+
+impl CapturedComponentInstance {
+    pub(super) fn into_snapshot(
+        self,
+        positions: &HashMap<InstanceId, u32>,
+    ) -> ComponentInstanceSnapshot {
+        ComponentInstanceSnapshot {
+            component: self.component,
+            instances: self.instances.iter().map(|id| positions[id]).collect(),
+            handles: self.handles,
+        }
+    }
+}
+
+/// Captures the component instances of `store` along with the resource
+/// handles they own.
+pub(super) fn capture(store: &mut StoreOpaque) -> Result<Vec<CapturedComponentInstance>> {
+    let ids = store.snapshot_component_instances()?;
+    let mut result = Vec::with_capacity(ids.len());
+    for id in ids {
+        let mut instance = store.component_instance_mut(id);
+        let component = instance.component().clone();
+        let instances = (0..component.env_component().num_runtime_instances)
+            .map(|i| instance.instance(RuntimeInstanceIndex::from_u32(i)))
+            .collect();
+
+        let (states, _) = instance.as_mut().instance_states();
+        let mut handles = Vec::with_capacity(states.len());
+        for (_, state) in states.iter_mut() {
+            #[cfg(feature = "component-model-async")]
+            if !state.concurrent_state().is_idle() || !state.thread_handle_table().is_empty() {
+                bail!("cannot snapshot a store with component tasks which are still running");
+            }
+            let (next, slots) = state.handle_table().resource_slots().ok_or_else(|| {
+                format_err!(
+                    "cannot snapshot a component instance with handles other than owned \
+                     resources, or with resources which are lent out"
+                )
+            })?;
+            let slots = slots
+                .into_iter()
+                .map(|slot| match slot {
+                    ResourceSlot::Free { next } => SnapshotHandle::Free { next },
+                    ResourceSlot::Own(TypedResource::Host(rep)) => SnapshotHandle::Host(rep),
+                    ResourceSlot::Own(TypedResource::Component { rep, ty }) => {
+                        SnapshotHandle::Component {
+                            rep,
+                            ty: ty.as_u32(),
+                        }
+                    }
+                })
+                .collect();
+            handles.push(SnapshotHandleTable { next, slots });
+        }
+
+        result.push(CapturedComponentInstance {
+            component,
+            instances,
+            handles,
+        });
+    }
+    Ok(result)
+}
+
+/// Matches the deserialized component instances of a store snapshot with
+/// `components`, checking that they agree with the snapshot's `instances`.
+pub(super) fn deserialize(
+    components: &[Component],
+    checksums: &[[u8; 32]],
+    component_instances: Vec<SerializedComponentInstance>,
+    instances: &[SnapshotInstance],
+) -> Result<Vec<ComponentInstanceSnapshot>> {
+    let components = checksums
+        .iter()
+        .map(|checksum| {
+            components
+                .iter()
+                .find(|c| **c.checksum() == *checksum)
+                .ok_or_else(|| {
+                    format_err!(
+                        "a component instantiated within the store snapshot was not provided"
+                    )
+                })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    // Each core instance must belong to exactly one component instance, and
+    // if there are any component instances then there are no standalone core
+    // instances.
+    let mut claimed = Vec::new();
+    claimed.resize(instances.len(), false);
+    let mut result = Vec::with_capacity(component_instances.len());
+    for (component, core, handles) in component_instances {
+        let component = *components
+            .get(usize::try_from(component)?)
+            .ok_or_else(invalid)?;
+        let env = component.env_component();
+        if core.len() != usize::try_from(env.num_runtime_instances)?
+            || handles.len() != usize::try_from(env.num_runtime_component_instances)?
+        {
+            return Err(invalid());
+        }
+        for i in core.iter() {
+            let i = usize::try_from(*i)?;
+            match (claimed.get_mut(i), instances.get(i)) {
+                (Some(claimed @ false), Some(instance)) if instance.imports.is_none() => {
+                    *claimed = true;
+                }
+                _ => return Err(invalid()),
+            }
+        }
+        result.push(ComponentInstanceSnapshot {
+            component: component.clone(),
+            instances: core,
+            handles,
+        });
+    }
+    if !result.is_empty() && claimed.contains(&false) {
+        return Err(invalid());
+    }
+    Ok(result)
+}
+
+impl StoreSnapshot {
+    /// Creates all component instances of this snapshot within `store`,
+    /// resolving their imports with `linker`.
+    pub(crate) async fn instantiate_components<T: 'static>(
+        &self,
+        linker: &Linker<T>,
+        store: &mut StoreContextMut<'_, T>,
+        asyncness: Asyncness,
+    ) -> Result<Vec<Instance>> {
+        self.check_engine(store.0)?;
+        let inner = self.inner();
+        if inner.component_instances.is_empty() && !inner.instances.is_empty() {
+            bail!(
+                "store snapshot contains core instances, which must be restored with \
+                 `Linker::instantiate_store_snapshot`"
+            );
+        }
+
+        let mut core = Vec::new();
+        core.resize(inner.instances.len(), None);
+        let mut result = Vec::with_capacity(inner.component_instances.len());
+        for entry in inner.component_instances.iter() {
+            let pre = linker.instantiate_pre(&entry.component)?;
+            let snapshots = entry
+                .instances
+                .iter()
+                .map(|i| Ok(inner.instances[usize::try_from(*i)?].snapshot.clone()))
+                .collect::<Result<Vec<_>>>()?;
+            let instance = pre
+                .instantiate_snapshot(store.as_context_mut(), &snapshots, asyncness)
+                .await?;
+
+            for (i, index) in entry.instances.iter().enumerate() {
+                let id = instance
+                    .id()
+                    .get(store.0)
+                    .instance(RuntimeInstanceIndex::from_u32(u32::try_from(i)?));
+                core[usize::try_from(*index)?] = Some(crate::Instance::from_wasmtime(id, store.0));
+            }
+            restore_handles(store.0, instance, &entry.handles)?;
+            result.push(instance);
+        }
+
+        let core = core
+            .into_iter()
+            .map(|i| i.ok_or_else(invalid))
+            .collect::<Result<Vec<_>>>()?;
+        self.restore_refs(store.0, &core, asyncness).await?;
+        Ok(result)
+    }
+}
+
+/// Fills in the handle tables of the freshly created component `instance`.
+fn restore_handles(
+    store: &mut StoreOpaque,
+    instance: Instance,
+    handles: &[SnapshotHandleTable],
+) -> Result<()> {
+    let (states, types) = instance.id().get_mut(store).instance_states();
+    for ((index, state), table) in states.iter_mut().zip(handles) {
+        let slots = table
+            .slots
+            .iter()
+            .map(|slot| {
+                Ok(match *slot {
+                    SnapshotHandle::Free { next } => ResourceSlot::Free { next },
+                    SnapshotHandle::Host(rep) => ResourceSlot::Own(TypedResource::Host(rep)),
+                    SnapshotHandle::Component { rep, ty } => {
+                        // A handle's resource table must belong to the
+                        // instance holding the handle.
+                        let ty = (ty != u32::MAX)
+                            .then(|| TypeResourceTableIndex::from_u32(ty))
+                            .ok_or_else(invalid)?;
+                        match types.get_resource_table(ty) {
+                            Some(TypeResourceTable::Concrete { instance, .. })
+                                if *instance == index => {}
+                            _ => return Err(invalid()),
+                        }
+                        ResourceSlot::Own(TypedResource::Component { rep, ty })
+                    }
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let handle_table = state.handle_table();
+        debug_assert!(handle_table.is_empty());
+        *handle_table = HandleTable::from_resource_slots(table.next, slots)?;
+    }
+    Ok(())
+}
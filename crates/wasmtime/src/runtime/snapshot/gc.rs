@@ -0,0 +1,367 @@
+//! Capturing and recreating the GC objects of a [`StoreSnapshot`].
+//!
+//! [`StoreSnapshot`]: super::StoreSnapshot
+
+use super::FuncKey;
+use super::store::{
+    Resolver, SnapshotObject, SnapshotRef, SnapshotType, SnapshotVal, StoreSnapshotInner, func,
+    invalid,
+};
+use crate::hash_map::HashMap;
+use crate::prelude::*;
+use crate::runtime::vm::{VMGcRef, VMStore};
+use crate::store::{Asyncness, AutoAssertNoGc, StoreOpaque};
+use crate::{
+    AnyRef, ArrayRef, ArrayRefPre, ArrayType, FieldType, Rooted, StorageType, StructRef,
+    StructRefPre, StructType, V128, Val, ValType,
+};
+use wasmtime_environ::{VMGcKind, VMSharedTypeIndex};
+
+/// Records the GC objects reachable from a store's instances, in the order
+/// they're discovered.
+pub(super) struct ObjectCapture<'a> {
+    funcs: &'a HashMap<FuncKey, SnapshotRef>,
+    types: &'a HashMap<VMSharedTypeIndex, SnapshotType>,
+
+    /// Index of each object discovered so far, keyed by its raw GC reference.
+    ids: HashMap<u32, u32>,
+
+    /// All objects discovered so far, or `None` for those whose contents
+    /// haven't been recorded yet.
+    objects: Vec<Option<SnapshotObject>>,
+
+    /// Objects which still need their contents recorded.
+    worklist: Vec<(u32, Rooted<AnyRef>)>,
+}
+
+impl<'a> ObjectCapture<'a> {
+    pub(super) fn new(
+        funcs: &'a HashMap<FuncKey, SnapshotRef>,
+        types: &'a HashMap<VMSharedTypeIndex, SnapshotType>,
+    ) -> Self {
+        ObjectCapture {
+            funcs,
+            types,
+            ids: HashMap::new(),
+            objects: Vec::new(),
+            worklist: Vec::new(),
+        }
+    }
+
+    /// Records the non-null raw GC reference `raw`, and everything reachable
+    /// from it, returning how to refer to it.
+    pub(super) fn capture(&mut self, store: &mut StoreOpaque, raw: u32) -> Result<SnapshotRef> {
+        let scope = store.enter_gc_lifo_scope();
+        let mut store = AutoAssertNoGc::new(store);
+        let result = self.capture_in_scope(&mut store, raw);
+        store.exit_gc_lifo_scope(scope);
+        result
+    }
+
+    fn capture_in_scope(
+        &mut self,
+        store: &mut AutoAssertNoGc<'_>,
+        raw: u32,
+    ) -> Result<SnapshotRef> {
+        let r = self.reference(store, raw)?;
+        while let Some((index, object)) = self.worklist.pop() {
+            let object = self.object(store, object)?;
+            self.objects[usize::try_from(index)?] = Some(object);
+        }
+        Ok(r)
+    }
+
+    pub(super) fn finish(self) -> Vec<SnapshotObject> {
+        debug_assert!(self.worklist.is_empty());
+        self.objects.into_iter().map(|o| o.unwrap()).collect()
+    }
+
+    fn reference(&mut self, store: &mut AutoAssertNoGc<'_>, raw: u32) -> Result<SnapshotRef> {
+        let gc_ref = VMGcRef::from_raw_u32(raw).unwrap();
+        if let Some(i31) = gc_ref.as_i31() {
+            return Ok(SnapshotRef::I31(i31.get_u32()));
+        }
+        if let Some(index) = self.ids.get(&raw) {
+            return Ok(SnapshotRef::Object(*index));
+        }
+        match store.require_gc_store()?.header(&gc_ref)?.kind() {
+            VMGcKind::StructRef | VMGcKind::ArrayRef => {}
+            VMGcKind::ExternRef => {
+                bail!("cannot snapshot a store which contains a non-null `externref`")
+            }
+            VMGcKind::ExnRef => bail!("cannot snapshot a store which contains an exception"),
+            kind => bail!("cannot snapshot a GC object of kind {kind:?}"),
+        }
+        let index = u32::try_from(self.objects.len())?;
+        self.ids.insert(raw, index);
+        self.objects.push(None);
+        let object = AnyRef::_from_raw(store, raw).unwrap();
+        self.worklist.push((index, object));
+        Ok(SnapshotRef::Object(index))
+    }
+
+    fn object(
+        &mut self,
+        store: &mut AutoAssertNoGc<'_>,
+        object: Rooted<AnyRef>,
+    ) -> Result<SnapshotObject> {
+        if let Some(s) = object._as_struct(store)? {
+            let ty = self.ty(s.type_index(store)?)?;
+            let len = s._ty(store)?.fields().len();
+            let mut fields = Vec::with_capacity(len);
+            for i in 0..len {
+                let value = s._field(store, i)?;
+                fields.push(self.val(store, value)?);
+            }
+            Ok(SnapshotObject::Struct { ty, fields })
+        } else {
+            let a = object._unwrap_array(store)?;
+            let ty = self.ty(a.type_index(store)?)?;
+            let len = a._len(store)?;
+            let mut elems = Vec::with_capacity(usize::try_from(len)?);
+            for i in 0..len {
+                let value = a._get(store, i)?;
+                elems.push(self.val(store, value)?);
+            }
+            Ok(SnapshotObject::Array { ty, elems })
+        }
+    }
+
+    fn ty(&self, index: VMSharedTypeIndex) -> Result<SnapshotType> {
+        self.types.get(&index).copied().ok_or_else(|| {
+            format_err!("cannot snapshot a GC object whose type isn't defined by any instance")
+        })
+    }
+
+    fn val(&mut self, store: &mut AutoAssertNoGc<'_>, value: Val) -> Result<SnapshotVal> {
+        Ok(match value {
+            Val::I32(i) => SnapshotVal::I32(i),
+            Val::I64(i) => SnapshotVal::I64(i),
+            Val::F32(f) => SnapshotVal::F32(f),
+            Val::F64(f) => SnapshotVal::F64(f),
+            Val::V128(v) => SnapshotVal::V128(v.as_u128()),
+            Val::FuncRef(None)
+            | Val::ExternRef(None)
+            | Val::AnyRef(None)
+            | Val::ExnRef(None)
+            | Val::ContRef(None) => SnapshotVal::Ref(None),
+            Val::FuncRef(Some(f)) => {
+                SnapshotVal::Ref(Some(func(self.funcs, f.vm_func_ref(store))?))
+            }
+            Val::AnyRef(Some(a)) => {
+                let raw = a.try_gc_ref(store)?.as_raw_u32();
+                SnapshotVal::Ref(Some(self.reference(store, raw)?))
+            }
+            Val::ExternRef(Some(_)) => {
+                bail!("cannot snapshot a store which contains a non-null `externref`")
+            }
+            Val::ExnRef(Some(_)) => bail!("cannot snapshot a store which contains an exception"),
+            Val::ContRef(Some(_)) => {
+                bail!("cannot snapshot a store which contains a continuation reference")
+            }
+        })
+    }
+}
+
+/// The type of a GC object being recreated.
+enum ObjectType {
+    Struct(StructType, StructRefPre),
+    Array(ArrayType, ArrayRefPre),
+}
+
+impl ObjectType {
+    fn field(&self, index: usize) -> Option<FieldType> {
+        match self {
+            ObjectType::Struct(ty, _) => ty.field(index),
+            ObjectType::Array(ty, _) => Some(ty.field_type()),
+        }
+    }
+}
+
+/// Returns whether a field of type `ty` must refer to an object which exists
+/// at the time its containing object is created, rather than being filled in
+/// afterwards.
+fn is_required(ty: &FieldType) -> bool {
+    match ty.element_type() {
+        StorageType::ValType(ValType::Ref(r)) => !ty.mutability().is_var() || !r.is_nullable(),
+        _ => true,
+    }
+}
+
+/// Recreates all GC objects of `snapshot` within `store`, recording them in
+/// `resolver`.
+///
+/// Objects are created in an order such that everything referenced through an
+/// immutable or non-nullable field exists before the object referencing it,
+/// and all other references are filled in once all objects exist.
+pub(super) async fn restore(
+    store: &mut dyn VMStore,
+    snapshot: &StoreSnapshotInner,
+    resolver: &mut Resolver<'_>,
+    asyncness: Asyncness,
+) -> Result<()> {
+    let objects = &snapshot.objects;
+    let (mut limiter, store) = store.resource_limiter_and_store_opaque();
+    let engine = store.engine().clone();
+
+    let mut pres: HashMap<VMSharedTypeIndex, ObjectType> = HashMap::new();
+    let mut types = Vec::with_capacity(objects.len());
+    for object in objects.iter() {
+        let ty = object.ty();
+        let instance = snapshot
+            .instances
+            .get(usize::try_from(ty.instance)?)
+            .ok_or_else(invalid)?;
+        let index = instance
+            .snapshot
+            .module()
+            .signatures()
+            .shared_type(ty.index)
+            .ok_or_else(invalid)?;
+        let sub = engine.signatures().borrow(index).ok_or_else(invalid)?;
+        let matches = match object {
+            SnapshotObject::Struct { fields, .. } => {
+                sub.is_struct() && sub.unwrap_struct().fields.len() == fields.len()
+            }
+            SnapshotObject::Array { .. } => sub.is_array(),
+        };
+        if !matches {
+            return Err(invalid());
+        }
+        if !pres.contains_key(&index) {
+            let pre = match object {
+                SnapshotObject::Struct { .. } => {
+                    let ty = StructType::from_shared_type_index(&engine, index);
+                    ObjectType::Struct(ty.clone(), StructRefPre::_new(store, ty))
+                }
+                SnapshotObject::Array { .. } => {
+                    let ty = ArrayType::from_shared_type_index(&engine, index);
+                    ObjectType::Array(ty.clone(), ArrayRefPre::_new(store, ty))
+                }
+            };
+            pres.insert(index, pre);
+        }
+        types.push(index);
+    }
+
+    // Depth-first search over required references, which can't be cyclic, to
+    // determine the order in which to create objects.
+    let required = |object: usize, field: usize| -> Option<usize> {
+        let Some(SnapshotRef::Object(target)) = (match objects[object].fields()[field] {
+            SnapshotVal::Ref(r) => r,
+            _ => None,
+        }) else {
+            return None;
+        };
+        let ty = pres[&types[object]].field(field)?;
+        if is_required(&ty) {
+            usize::try_from(target).ok()
+        } else {
+            None
+        }
+    };
+    #[derive(Copy, Clone, PartialEq)]
+    enum Visit {
+        No,
+        InProgress,
+        Done,
+    }
+    let mut visited = vec![Visit::No; objects.len()];
+    let mut order = Vec::with_capacity(objects.len());
+    for root in 0..objects.len() {
+        if visited[root] != Visit::No {
+            continue;
+        }
+        visited[root] = Visit::InProgress;
+        let mut stack = vec![(root, 0)];
+        while let Some((object, field)) = stack.last_mut() {
+            let object = *object;
+            if *field == objects[object].fields().len() {
+                visited[object] = Visit::Done;
+                order.push(object);
+                stack.pop();
+                continue;
+            }
+            let target = required(object, *field);
+            *field += 1;
+            if let Some(target) = target {
+                match visited.get(target).ok_or_else(invalid)? {
+                    Visit::No => {
+                        visited[target] = Visit::InProgress;
+                        stack.push((target, 0));
+                    }
+                    Visit::InProgress => bail!(
+                        "cannot restore a cycle of GC objects through immutable or \
+                         non-nullable fields"
+                    ),
+                    Visit::Done => {}
+                }
+            }
+        }
+    }
+
+    resolver.objects = vec![None; objects.len()];
+    let mut patches = Vec::new();
+    let mut values = Vec::new();
+    for object in order {
+        let pre = &pres[&types[object]];
+        values.clear();
+        for (i, value) in objects[object].fields().iter().enumerate() {
+            let ty = pre.field(i).ok_or_else(invalid)?;
+            let value = match *value {
+                SnapshotVal::I32(i) => Val::I32(i),
+                SnapshotVal::I64(i) => Val::I64(i),
+                SnapshotVal::F32(f) => Val::F32(f),
+                SnapshotVal::F64(f) => Val::F64(f),
+                SnapshotVal::V128(v) => Val::V128(V128::from(v)),
+                SnapshotVal::Ref(r) => {
+                    let StorageType::ValType(ValType::Ref(ty)) = ty.element_type() else {
+                        return Err(invalid());
+                    };
+                    match r {
+                        Some(r) => match resolver.resolve(store, r)? {
+                            Some(r) => r.into(),
+                            None => {
+                                patches.push((object, i, r));
+                                Val::null_ref(ty.heap_type())
+                            }
+                        },
+                        None => Val::null_ref(ty.heap_type()),
+                    }
+                }
+            };
+            values.push(value);
+        }
+        let created = match pre {
+            ObjectType::Struct(_, pre) => {
+                StructRef::_new_async(store, limiter.as_mut(), pre, &values, asyncness)
+                    .await?
+                    .to_anyref()
+            }
+            ObjectType::Array(_, pre) => {
+                ArrayRef::_new_fixed_async(store, limiter.as_mut(), pre, &values, asyncness)
+                    .await?
+                    .to_anyref()
+            }
+        };
+        resolver.objects[object] = Some(created);
+    }
+
+    for (object, field, target) in patches {
+        let value = resolver.resolve(store, target)?.ok_or_else(invalid)?;
+        let created = resolver.objects[object].unwrap();
+        match &pres[&types[object]] {
+            ObjectType::Struct(..) => {
+                created
+                    ._unwrap_struct(store)?
+                    ._set_field(store, field, value.into())?;
+            }
+            ObjectType::Array(..) => {
+                created
+                    ._unwrap_array(store)?
+                    ._set(store, u32::try_from(field)?, value.into())?;
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,773 @@
+//! Snapshots of all instances within a store.
+
+use super::{
+    DeferredRef, FuncKey, InstanceSnapshot, RefLocation, SnapshotState, instance_funcs,
+    strip_header,
+};
+use crate::hash_map::HashMap;
+use crate::prelude::*;
+use crate::runtime::vm::{VMFuncRef, VMStore};
+use crate::store::{Asyncness, AutoAssertNoGc, StoreOpaque};
+use crate::{
+    Engine, Extern, Func, Global, Instance, Linker, Module, Ref, RefType, StoreContextMut, Table,
+    Val,
+};
+use alloc::sync::Arc;
+use core::ptr::NonNull;
+use serde_derive::{Deserialize, Serialize};
+use wasmtime_environ::{FuncIndex, Initializer, ModuleInternedTypeIndex};
+
+#[cfg(feature = "component-model")]
+use super::component::ComponentInstanceSnapshot;
+#[cfg(feature = "component-model")]
+use crate::component::Component;
+#[cfg(feature = "gc")]
+use crate::{AnyRef, I31, Rooted};
+
+/// An in-memory image of all instances within a [`Store`](crate::Store),
+/// along with the GC objects they reference, which can be used to recreate
+/// them in another store.
+///
+/// A store snapshot is created with
+/// [`Store::snapshot`](crate::Store::snapshot) and is made up of an
+/// [`InstanceSnapshot`] of each instance in the store. Unlike a snapshot of a
+/// single instance it additionally captures how instances refer to one
+/// another, for example when one instance imports another's exports or
+/// stores another's functions in a table, and the contents of the store's GC
+/// heap which are reachable from its instances. When the store contains
+/// component instances, the resource handles owned by each component
+/// instance are captured as well.
+///
+/// Instances are recreated with
+/// [`Linker::instantiate_store_snapshot`] for stores of core wasm instances
+/// and with
+/// [`component::Linker::instantiate_store_snapshot`](crate::component::Linker::instantiate_store_snapshot)
+/// for stores of component instances. Like an [`InstanceSnapshot`], a store
+/// snapshot can be converted to bytes with [`StoreSnapshot::serialize`] and
+/// recreated on another [`Engine`] with [`StoreSnapshot::deserialize`] to
+/// migrate an idle store elsewhere.
+///
+/// # Limitations
+///
+/// Only state owned by wasm is captured. Imports which are not exports of
+/// another instance in the store, such as host functions or a
+/// [`Memory`](crate::Memory) created by the host, are resolved with the
+/// linker again when restoring, and the store's host data is not part of the
+/// snapshot. The representation of host-defined resources within component
+/// resource tables is copied as-is, so the embedder is responsible for
+/// recreating the host state it refers to. In addition to the limitations of
+/// an [`InstanceSnapshot`], a snapshot cannot be taken, and an error is
+/// returned, if the store:
+///
+/// * contains both core wasm instances, other than those of components, and
+///   component instances,
+/// * contains a reference to a host function which isn't imported by any
+///   instance, an `externref`, an exception object, or a GC object whose type
+///   isn't defined by any instance,
+/// * contains component tasks which are still running, such as parked async
+///   calls, or component resources owned by the host,
+/// * contains instances of components linked with
+///   [`component::Linker::define_component_exports`](crate::component::Linker::define_component_exports),
+///   or a component instance which has trapped.
+#[derive(Clone)]
+pub struct StoreSnapshot {
+    inner: Arc<StoreSnapshotInner>,
+}
+
+pub(super) struct StoreSnapshotInner {
+    engine: Engine,
+
+    /// All instances within the store, in the order they were created.
+    pub(super) instances: Vec<SnapshotInstance>,
+
+    /// All component instances within the store, in the order they were
+    /// created.
+    #[cfg(feature = "component-model")]
+    pub(super) component_instances: Vec<ComponentInstanceSnapshot>,
+
+    /// All GC objects reachable from the store's instances.
+    pub(super) objects: Vec<SnapshotObject>,
+}
+
+/// An instance within a store snapshot.
+pub(super) struct SnapshotInstance {
+    /// Where each import of the instance came from, or `None` if this is a
+    /// core instance of a component instance, whose imports are provided by
+    /// the component.
+    pub(super) imports: Option<Vec<SnapshotImport>>,
+
+    /// The state of the instance itself.
+    pub(super) snapshot: InstanceSnapshot,
+
+    /// Values of each of `snapshot`'s deferred references, in the same order.
+    refs: Vec<SnapshotRef>,
+}
+
+/// Where an import of an instance in a store snapshot came from.
+#[derive(Serialize, Deserialize)]
+pub(super) enum SnapshotImport {
+    /// The import is resolved with the linker.
+    Host,
+    /// The import is the export `name` of an earlier instance.
+    Instance { instance: u32, name: String },
+}
+
+/// A non-null reference in a store snapshot.
+#[derive(Copy, Clone, Serialize, Deserialize)]
+pub(super) enum SnapshotRef {
+    /// A function which is defined or imported by an instance.
+    Func { instance: u32, func: FuncIndex },
+    /// An `i31ref`.
+    I31(u32),
+    /// A GC object, by index within the snapshot's objects.
+    Object(u32),
+}
+
+/// A GC object in a store snapshot.
+#[derive(Serialize, Deserialize)]
+pub(super) enum SnapshotObject {
+    Struct {
+        ty: SnapshotType,
+        fields: Vec<SnapshotVal>,
+    },
+    Array {
+        ty: SnapshotType,
+        elems: Vec<SnapshotVal>,
+    },
+}
+
+/// The type of a GC object, as a type defined by an instance's module.
+#[derive(Copy, Clone, Serialize, Deserialize)]
+pub(super) struct SnapshotType {
+    pub(super) instance: u32,
+    pub(super) index: ModuleInternedTypeIndex,
+}
+
+/// A field of a GC object in a store snapshot.
+#[derive(Copy, Clone, Serialize, Deserialize)]
+pub(super) enum SnapshotVal {
+    I32(i32),
+    I64(i64),
+    F32(u32),
+    F64(u64),
+    V128(u128),
+    Ref(Option<SnapshotRef>),
+}
+
+/// The resource handles of a (sub)component instance in a store snapshot.
+#[derive(Serialize, Deserialize)]
+pub(super) struct SnapshotHandleTable {
+    /// Index of the first free slot.
+    pub(super) next: u32,
+    pub(super) slots: Vec<SnapshotHandle>,
+}
+
+/// A slot of a component instance's handle table in a store snapshot.
+#[derive(Copy, Clone, Serialize, Deserialize)]
+pub(super) enum SnapshotHandle {
+    Free { next: u32 },
+    Host(u32),
+    Component { rep: u32, ty: u32 },
+}
+
+/// A component instance in the serialized form of a store snapshot: the index
+/// of its component, the indices of its core instances, and its handles.
+pub(super) type SerializedComponentInstance = (u32, Vec<u32>, Vec<SnapshotHandleTable>);
+
+/// Identity of an item which may be imported by an instance.
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+enum ExternKey {
+    Func(FuncKey),
+    /// A table, memory, global, or tag, by the address of its definition.
+    Definition(usize),
+}
+
+impl ExternKey {
+    fn new(store: &StoreOpaque, item: &Extern) -> Option<ExternKey> {
+        Some(match item {
+            // SAFETY: `vm_func_ref` returns a valid pointer.
+            Extern::Func(f) => ExternKey::Func(unsafe { FuncKey::new(f.vm_func_ref(store)) }),
+            Extern::Table(t) => ExternKey::Definition(t.vmimport(store).from.as_ptr().addr()),
+            Extern::Memory(m) => ExternKey::Definition(m.vmimport(store).from.as_ptr().addr()),
+            Extern::Global(g) => ExternKey::Definition(g.vmimport(store).from.as_ptr().addr()),
+            Extern::Tag(t) => ExternKey::Definition(t.vmimport(store).from.as_ptr().addr()),
+            Extern::SharedMemory(_) => return None,
+        })
+    }
+}
+
+/// Prefix of the serialized form of a store snapshot, which is followed by
+/// the version of Wasmtime that produced it.
+const SERIALIZED_MAGIC: &[u8] = b"\0wasmtime-store-snapshot\0";
+
+pub(super) fn invalid() -> Error {
+    format_err!("store snapshot does not match its modules")
+}
+
+impl StoreSnapshot {
+    pub(crate) fn new(store: &mut StoreOpaque) -> Result<StoreSnapshot> {
+        #[cfg(feature = "component-model")]
+        let components = super::component::capture(store)?;
+        let instances = store.all_instances().collect::<Vec<_>>();
+
+        // Core instances of components are owned by their component instance,
+        // which is responsible for their imports.
+        #[cfg(feature = "component-model")]
+        let owned = components.iter().map(|c| c.instances.len()).sum::<usize>();
+        #[cfg(not(feature = "component-model"))]
+        let owned = 0;
+        if owned != 0 && owned != instances.len() {
+            bail!(
+                "cannot snapshot a store which contains both core instances and \
+                 component instances"
+            );
+        }
+        let mut positions = HashMap::new();
+        for (i, instance) in instances.iter().enumerate() {
+            positions.insert(instance.id(), index_u32(i)?);
+        }
+
+        let mut funcs = HashMap::new();
+        let mut types = HashMap::new();
+        let mut exports: HashMap<ExternKey, (u32, String)> = HashMap::new();
+        let mut entries = Vec::with_capacity(instances.len());
+        let mut deferred = Vec::with_capacity(instances.len());
+        for (i, instance) in instances.iter().enumerate() {
+            let index = u32::try_from(i)?;
+            let module = instance._module(store).clone();
+            let env = module.env_module();
+
+            // Functions and GC types of earlier instances take precedence, so
+            // references are resolved on restore with instances which exist.
+            for (key, func) in instance_funcs(store, *instance) {
+                funcs.entry(key).or_insert(SnapshotRef::Func {
+                    instance: index,
+                    func,
+                });
+            }
+            for (ty, shared) in module.signatures().as_module_map() {
+                types.entry(*shared).or_insert(SnapshotType {
+                    instance: index,
+                    index: ty,
+                });
+            }
+
+            let imports = if owned != 0 {
+                None
+            } else {
+                let mut imports = Vec::new();
+                for initializer in env.initializers.iter() {
+                    let Initializer::Import { index, .. } = initializer;
+                    let item = instance._get_export(store, *index);
+                    let import = match ExternKey::new(store, &item).and_then(|k| exports.get(&k)) {
+                        Some((instance, name)) => SnapshotImport::Instance {
+                            instance: *instance,
+                            name: name.clone(),
+                        },
+                        None => SnapshotImport::Host,
+                    };
+                    imports.push(import);
+                }
+                for (name, entity) in env.exports.iter() {
+                    let item = instance._get_export(store, *entity);
+                    if let Some(key) = ExternKey::new(store, &item) {
+                        exports
+                            .entry(key)
+                            .or_insert_with(|| (index, String::from(&env.strings[*name])));
+                    }
+                }
+                Some(imports)
+            };
+
+            let mut refs = Vec::new();
+            let snapshot = InstanceSnapshot::capture(store, *instance, Some(&mut refs))?;
+            refs.sort_by_key(|(location, _)| *location);
+            deferred.push(refs);
+            entries.push((imports, snapshot));
+        }
+
+        // With all instances captured, resolve references between them and
+        // into the GC heap.
+        #[cfg(feature = "gc")]
+        let mut objects = super::gc::ObjectCapture::new(&funcs, &types);
+        #[cfg(not(feature = "gc"))]
+        let _ = &types;
+        let mut snapshot_instances = Vec::with_capacity(entries.len());
+        for ((imports, snapshot), deferred) in entries.into_iter().zip(deferred) {
+            let mut refs = Vec::with_capacity(deferred.len());
+            for (_, value) in deferred {
+                refs.push(match value {
+                    DeferredRef::Func(func_ref) => func(&funcs, func_ref)?,
+                    #[cfg(feature = "gc")]
+                    DeferredRef::Gc(raw) => objects.capture(store, raw)?,
+                    #[cfg(not(feature = "gc"))]
+                    DeferredRef::Gc(_) => unreachable!(),
+                });
+            }
+            snapshot_instances.push(SnapshotInstance {
+                imports,
+                snapshot,
+                refs,
+            });
+        }
+
+        Ok(StoreSnapshot {
+            inner: try_new::<Arc<_>>(StoreSnapshotInner {
+                engine: store.engine().clone(),
+                instances: snapshot_instances,
+                #[cfg(feature = "component-model")]
+                component_instances: components
+                    .into_iter()
+                    .map(|c| c.into_snapshot(&positions))
+                    .collect(),
+                #[cfg(feature = "gc")]
+                objects: objects.finish(),
+                #[cfg(not(feature = "gc"))]
+                objects: Vec::new(),
+            })?,
+        })
+    }
+
+    /// Serializes this snapshot into a sequence of bytes which can later be
+    /// passed to [`StoreSnapshot::deserialize`].
+    ///
+    /// As with [`InstanceSnapshot::serialize`] the serialized form contains
+    /// checksums of the modules and components which were instantiated, not
+    /// the modules themselves, and is specific to the version of Wasmtime used
+    /// to produce it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if memory allocation fails.
+    pub fn serialize(&self) -> Result<Vec<u8>> {
+        let mut checksums = Vec::new();
+        let mut checksum_index = |checksum: &[u8; 32]| -> Result<u32> {
+            match checksums.iter().position(|c| c == checksum) {
+                Some(i) => index_u32(i),
+                None => {
+                    checksums.push(*checksum);
+                    index_u32(checksums.len() - 1)
+                }
+            }
+        };
+        let mut instances = Vec::with_capacity(self.inner.instances.len());
+        for entry in self.inner.instances.iter() {
+            let snapshot = &entry.snapshot.inner;
+            instances.push((
+                checksum_index(&**snapshot.module.checksum())?,
+                &entry.imports,
+                &snapshot.state,
+                &snapshot.data.data,
+                &entry.refs,
+            ));
+        }
+
+        #[allow(unused_mut, reason = "only mutated with components enabled")]
+        let mut component_checksums: Vec<[u8; 32]> = Vec::new();
+        #[allow(unused_mut, reason = "only mutated with components enabled")]
+        let mut component_instances: Vec<(u32, &Vec<u32>, &Vec<SnapshotHandleTable>)> = Vec::new();
+        #[cfg(feature = "component-model")]
+        for entry in self.inner.component_instances.iter() {
+            let checksum = **entry.component.checksum();
+            let index = match component_checksums.iter().position(|c| *c == checksum) {
+                Some(i) => i,
+                None => {
+                    component_checksums.push(checksum);
+                    component_checksums.len() - 1
+                }
+            };
+            component_instances.push((index_u32(index)?, &entry.instances, &entry.handles));
+        }
+
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(SERIALIZED_MAGIC);
+        bytes.extend_from_slice(env!("CARGO_PKG_VERSION").as_bytes());
+        bytes.push(0);
+        let state = (
+            &checksums,
+            &component_checksums,
+            &instances,
+            &component_instances,
+            &self.inner.objects,
+        );
+        Ok(postcard::to_extend(&state, bytes)?)
+    }
+
+    /// Deserializes a snapshot previously created with
+    /// [`StoreSnapshot::serialize`], of a store of instances of `modules`.
+    ///
+    /// Every module instantiated within the snapshotted store must be
+    /// provided in `modules`, compiled from the same wasm binary and for the
+    /// same [`Engine`]. The order of `modules` doesn't matter. Snapshots of
+    /// stores containing component instances are instead deserialized with
+    /// [`StoreSnapshot::deserialize_with_components`].
+    ///
+    /// Like [`InstanceSnapshot::deserialize`] this function is safe to call
+    /// with arbitrary input.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `bytes` was not produced by
+    /// [`StoreSnapshot::serialize`] of the same version of Wasmtime, if one of
+    /// the snapshotted instances was an instance of a module not in
+    /// `modules`, or if the snapshot doesn't describe a valid state for
+    /// instances of those modules.
+    pub fn deserialize(modules: &[Module], bytes: &[u8]) -> Result<StoreSnapshot> {
+        StoreSnapshot::_deserialize(
+            modules,
+            #[cfg(feature = "component-model")]
+            &[],
+            bytes,
+        )
+    }
+
+    /// Same as [`StoreSnapshot::deserialize`], but for a snapshot of a store
+    /// which contains instances of `components`.
+    ///
+    /// Modules which are defined within `components` don't need to be
+    /// provided in `modules`, only those which were imported by components.
+    #[cfg(feature = "component-model")]
+    pub fn deserialize_with_components(
+        modules: &[Module],
+        components: &[Component],
+        bytes: &[u8],
+    ) -> Result<StoreSnapshot> {
+        StoreSnapshot::_deserialize(modules, components, bytes)
+    }
+
+    fn _deserialize(
+        modules: &[Module],
+        #[cfg(feature = "component-model")] components: &[Component],
+        bytes: &[u8],
+    ) -> Result<StoreSnapshot> {
+        let bytes = strip_header(SERIALIZED_MAGIC, "store snapshot", bytes)?;
+        let (checksums, component_checksums, instances, component_instances, objects): (
+            Vec<[u8; 32]>,
+            Vec<[u8; 32]>,
+            Vec<(
+                u32,
+                Option<Vec<SnapshotImport>>,
+                SnapshotState,
+                Vec<u8>,
+                Vec<SnapshotRef>,
+            )>,
+            Vec<SerializedComponentInstance>,
+            Vec<SnapshotObject>,
+        ) = postcard::from_bytes(bytes).context("failed to deserialize store snapshot")?;
+
+        #[cfg(feature = "component-model")]
+        let static_modules = components.iter().flat_map(|c| c.static_modules());
+        #[cfg(not(feature = "component-model"))]
+        let static_modules = core::iter::empty();
+        let candidates = modules.iter().chain(static_modules).collect::<Vec<_>>();
+        let engine = match candidates.first() {
+            Some(module) => module.engine().clone(),
+            None => bail!("no modules were provided to deserialize a store snapshot"),
+        };
+        if candidates
+            .iter()
+            .any(|m| !Engine::same(m.engine(), &engine))
+        {
+            bail!("all modules must belong to the same engine");
+        }
+        let modules = checksums
+            .iter()
+            .map(|checksum| {
+                candidates
+                    .iter()
+                    .find(|m| **m.checksum() == *checksum)
+                    .copied()
+                    .ok_or_else(|| {
+                        format_err!(
+                            "a module instantiated within the store snapshot was not provided"
+                        )
+                    })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut snapshot_instances = Vec::with_capacity(instances.len());
+        for (i, (module, imports, state, data, refs)) in instances.into_iter().enumerate() {
+            let module = *modules.get(usize::try_from(module)?).ok_or_else(invalid)?;
+            if refs.len() != state.deferred.len() {
+                return Err(invalid());
+            }
+            if let Some(imports) = &imports {
+                if imports.len() != module.env_module().initializers.len() {
+                    return Err(invalid());
+                }
+                for import in imports {
+                    if let SnapshotImport::Instance { instance, .. } = import {
+                        let instance = usize::try_from(*instance)?;
+                        if instance >= i {
+                            return Err(invalid());
+                        }
+                    }
+                }
+            }
+            let snapshot = InstanceSnapshot::from_state(module.clone(), state, data)?;
+            snapshot_instances.push(SnapshotInstance {
+                imports,
+                snapshot,
+                refs,
+            });
+        }
+
+        #[cfg(feature = "component-model")]
+        let component_instances = super::component::deserialize(
+            components,
+            &component_checksums,
+            component_instances,
+            &snapshot_instances,
+        )?;
+        #[cfg(not(feature = "component-model"))]
+        if !component_checksums.is_empty() || !component_instances.is_empty() {
+            bail!("support for components was disabled at compile time");
+        }
+
+        Ok(StoreSnapshot {
+            inner: try_new::<Arc<_>>(StoreSnapshotInner {
+                engine,
+                instances: snapshot_instances,
+                #[cfg(feature = "component-model")]
+                component_instances,
+                objects,
+            })?,
+        })
+    }
+
+    #[cfg(feature = "component-model")]
+    pub(super) fn inner(&self) -> &StoreSnapshotInner {
+        &self.inner
+    }
+
+    /// Creates all core wasm instances of this snapshot within `store`,
+    /// resolving imports which didn't come from other instances with
+    /// `linker`.
+    pub(crate) async fn instantiate<T: 'static>(
+        &self,
+        linker: &Linker<T>,
+        store: &mut StoreContextMut<'_, T>,
+        asyncness: Asyncness,
+    ) -> Result<Vec<Instance>> {
+        self.check_engine(store.0)?;
+        #[cfg(feature = "component-model")]
+        if !self.inner.component_instances.is_empty() {
+            bail!(
+                "store snapshot contains component instances, which must be restored with \
+                 `component::Linker::instantiate_store_snapshot`"
+            );
+        }
+        if asyncness == Asyncness::No {
+            store.0.validate_sync_call()?;
+        }
+
+        let mut instances = Vec::with_capacity(self.inner.instances.len());
+        for entry in self.inner.instances.iter() {
+            let module = entry.snapshot.module();
+            let Some(imports) = &entry.imports else {
+                return Err(invalid());
+            };
+            let mut items = Vec::with_capacity(imports.len());
+            for (ty, import) in module.imports().zip(imports) {
+                let item = match import {
+                    SnapshotImport::Host => linker.try_get_by_import(&mut *store, &ty)?,
+                    SnapshotImport::Instance { instance, name } => {
+                        let instance: Instance = instances[usize::try_from(*instance)?];
+                        instance.get_export(&mut *store, name)
+                    }
+                };
+                let item = item.ok_or_else(|| {
+                    format_err!(
+                        "unknown import: `{}::{}` has not been defined",
+                        ty.module(),
+                        ty.name()
+                    )
+                })?;
+                items.push(item);
+            }
+
+            let fork = entry.snapshot.fork_module();
+            let imports = Instance::typecheck_externs(store.0, fork, &items)?;
+            // SAFETY: the imports were type-checked above.
+            let instance =
+                unsafe { Instance::new_started(store, fork, imports.as_ref(), asyncness).await? };
+            entry.snapshot.restore(store.0, instance)?;
+            instances.push(instance);
+        }
+
+        self.restore_refs(store.0, &instances, asyncness).await?;
+        Ok(instances)
+    }
+
+    pub(super) fn check_engine(&self, store: &StoreOpaque) -> Result<()> {
+        if !Engine::same(store.engine(), &self.inner.engine) {
+            bail!("cross-`Engine` instantiation is not currently supported");
+        }
+        Ok(())
+    }
+
+    /// Writes the deferred references of all `instances`, the restored
+    /// instances of this snapshot, recreating GC objects as necessary.
+    pub(super) async fn restore_refs(
+        &self,
+        store: &mut dyn VMStore,
+        instances: &[Instance],
+        asyncness: Asyncness,
+    ) -> Result<()> {
+        let scope = store.store_opaque().enter_gc_lifo_scope();
+        let result = self
+            .restore_refs_in_scope(store, instances, asyncness)
+            .await;
+        store.store_opaque_mut().exit_gc_lifo_scope(scope);
+        result
+    }
+
+    async fn restore_refs_in_scope(
+        &self,
+        store: &mut dyn VMStore,
+        instances: &[Instance],
+        asyncness: Asyncness,
+    ) -> Result<()> {
+        #[allow(unused_mut, reason = "only mutated with GC enabled")]
+        let mut resolver = Resolver {
+            instances,
+            #[cfg(feature = "gc")]
+            objects: Vec::new(),
+        };
+        #[cfg(feature = "gc")]
+        super::gc::restore(store, &self.inner, &mut resolver, asyncness).await?;
+        #[cfg(not(feature = "gc"))]
+        let _ = asyncness;
+
+        let store = store.store_opaque_mut();
+        for (entry, instance) in self.inner.instances.iter().zip(instances) {
+            let locations = &entry.snapshot.inner.state.deferred;
+            for (location, value) in locations.iter().zip(&entry.refs) {
+                let value = resolver.resolve(store, *value)?.ok_or_else(invalid)?;
+                write_ref(store, *instance, *location, value)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns how to refer to the function `func_ref` in a store snapshot.
+pub(super) fn func(
+    funcs: &HashMap<FuncKey, SnapshotRef>,
+    func_ref: NonNull<VMFuncRef>,
+) -> Result<SnapshotRef> {
+    // SAFETY: all funcrefs reachable within a store are valid.
+    let key = unsafe { FuncKey::new(func_ref) };
+    funcs.get(&key).copied().ok_or_else(|| {
+        format_err!(
+            "cannot snapshot a reference to a function which is not defined or \
+             imported by any instance"
+        )
+    })
+}
+
+fn index_u32(i: usize) -> Result<u32> {
+    Ok(u32::try_from(i)?)
+}
+
+/// Turns references in a store snapshot back into references within the
+/// store it's being restored into.
+pub(super) struct Resolver<'a> {
+    instances: &'a [Instance],
+
+    /// GC objects which have been recreated so far.
+    #[cfg(feature = "gc")]
+    pub(super) objects: Vec<Option<Rooted<AnyRef>>>,
+}
+
+impl Resolver<'_> {
+    /// Returns the reference `r`, or `None` if it's a GC object which hasn't
+    /// been recreated yet.
+    pub(super) fn resolve(&self, store: &mut StoreOpaque, r: SnapshotRef) -> Result<Option<Ref>> {
+        match r {
+            SnapshotRef::Func { instance, func } => {
+                let instance = *self
+                    .instances
+                    .get(usize::try_from(instance)?)
+                    .ok_or_else(invalid)?;
+                let env = instance._module(store).env_module();
+                let valid = env
+                    .functions
+                    .get(func)
+                    .is_some_and(|f| env.is_imported_function(func) || f.is_escaping());
+                if !valid {
+                    return Err(invalid());
+                }
+                let store_id = store.id();
+                let (mut handle, registry) = instance.id.get_mut_and_module_registry(store);
+                let func_ref = handle.as_mut().get_func_ref(registry, func).unwrap();
+                // SAFETY: the funcref belongs to an instance within this store.
+                let func = unsafe { Func::from_vm_func_ref(store_id, func_ref) };
+                Ok(Some(Ref::Func(Some(func))))
+            }
+            #[cfg(feature = "gc")]
+            SnapshotRef::I31(value) => {
+                let mut store = AutoAssertNoGc::new(store);
+                let value = AnyRef::_from_i31(&mut store, I31::wrapping_u32(value));
+                Ok(Some(Ref::Any(Some(value))))
+            }
+            #[cfg(feature = "gc")]
+            SnapshotRef::Object(index) => {
+                let object = self
+                    .objects
+                    .get(usize::try_from(index)?)
+                    .ok_or_else(invalid)?;
+                Ok(object.map(|o| Ref::Any(Some(o))))
+            }
+            #[cfg(not(feature = "gc"))]
+            SnapshotRef::I31(_) | SnapshotRef::Object(_) => {
+                bail!("support for GC was disabled at compile time")
+            }
+        }
+    }
+}
+
+/// Writes `value` into `location` of `instance`, type-checking it first.
+fn write_ref(
+    store: &mut StoreOpaque,
+    instance: Instance,
+    location: RefLocation,
+    value: Ref,
+) -> Result<()> {
+    match location {
+        RefLocation::Global(index) => {
+            let global = Global::from_core(instance.id, index);
+            let ty = global._ty(store).content().clone();
+            let value = Val::from(value);
+            value.ensure_matches_ty(store, &ty)?;
+            // SAFETY: the value was type-checked above, and the global is
+            // being initialized for the first time.
+            unsafe { global.set_unchecked(store, &value) }
+        }
+        RefLocation::Table(index, i) => Table::from_raw(instance.id, index).set_(store, i, value),
+        RefLocation::Element(index, i) => {
+            let (ty, _) = instance._module(store).env_module().passive_elements[index];
+            let ty = RefType::from_wasm_type(store.engine(), &ty);
+            value.ensure_matches_ty(store, &ty)?;
+            let raw = Val::from(value).to_raw_(&mut AutoAssertNoGc::new(store))?;
+            instance.id.get_mut(store).passive_element_segment(index)[usize::try_from(i)?] = raw;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "gc")]
+impl SnapshotObject {
+    pub(super) fn ty(&self) -> SnapshotType {
+        match self {
+            SnapshotObject::Struct { ty, .. } | SnapshotObject::Array { ty, .. } => *ty,
+        }
+    }
+
+    pub(super) fn fields(&self) -> &[SnapshotVal] {
+        match self {
+            SnapshotObject::Struct { fields, .. } => fields,
+            SnapshotObject::Array { elems, .. } => elems,
+        }
+    }
+}
@@ -94,7 +94,7 @@ use crate::trampoline::VMHostGlobalContext;
 #[cfg(feature = "debug")]
 use crate::{BreakpointState, DebugHandler, FrameDataCache};
 use crate::{Engine, Module, Val, ValRaw, module::ModuleRegistry};
-use crate::{Global, Instance, Table};
+use crate::{Global, Instance, StoreSnapshot, Table};
 use core::convert::Infallible;
 use core::fmt;
 #[cfg(any(feature = "async", feature = "gc"))]
@@ -107,16 +107,25 @@ use core::pin::Pin;
 use core::ptr::NonNull;
 #[cfg(any(feature = "async", feature = "gc"))]
 use core::task::Poll;
+#[cfg(all(feature = "std", target_has_atomic = "64"))]
+use core::time::Duration;
 use wasmtime_environ::{DefinedGlobalIndex, DefinedTableIndex, EntityRef, TripleExt};
 
 mod context;
 pub use self::context::*;
+#[cfg(all(feature = "std", target_has_atomic = "64"))]
+mod cpu_time;
+#[cfg(all(feature = "std", target_has_atomic = "64"))]
+use self::cpu_time::CpuTimeBudget;
 mod data;
 pub use self::data::*;
 mod func_refs;
 use func_refs::FuncRefs;
 #[cfg(feature = "component-model-async")]
 mod token;
+mod usage;
+use self::usage::CallUsage;
+pub use self::usage::{InstanceResourceUsage, ResourceUsage};
 #[cfg(feature = "component-model-async")]
 pub(crate) use token::StoreToken;
 #[cfg(feature = "async")]
@@ -365,6 +374,29 @@ impl StoreResourceLimiter<'_> {
             Self::Async(s) => s.table_grow_failed(error),
         }
     }
+
+    #[cfg(feature = "gc")]
+    pub(crate) async fn gc_heap_growing(
+        &mut self,
+        current: usize,
+        desired: usize,
+        maximum: Option<usize>,
+    ) -> Result<bool, Error> {
+        match self {
+            Self::Sync(s) => s.gc_heap_growing(current, desired, maximum),
+            #[cfg(feature = "async")]
+            Self::Async(s) => s.gc_heap_growing(current, desired, maximum).await,
+        }
+    }
+
+    #[cfg(feature = "gc")]
+    pub(crate) fn gc_heap_grow_failed(&mut self, error: crate::Error) -> Result<()> {
+        match self {
+            Self::Sync(s) => s.gc_heap_grow_failed(error),
+            #[cfg(feature = "async")]
+            Self::Async(s) => s.gc_heap_grow_failed(error),
+        }
+    }
 }
 
 enum CallHookInner<T: 'static> {
@@ -486,6 +518,12 @@ pub struct StoreOpaque {
     memory_limit: usize,
     table_count: usize,
     table_limit: usize,
+    #[cfg(feature = "component-model")]
+    component_instance_count: usize,
+    #[cfg(feature = "component-model")]
+    component_instance_limit: usize,
+    #[cfg(feature = "component-model")]
+    pub(crate) resource_table_entry_limit: usize,
     #[cfg(feature = "async")]
     async_state: fiber::AsyncState,
 
@@ -495,6 +533,17 @@ pub struct StoreOpaque {
     // until the reserve is empty.
     fuel_reserve: u64,
     pub(crate) fuel_yield_interval: Option<NonZeroU64>,
+    // Fuel accounting for `ResourceUsage::fuel_consumed`: the amount of fuel
+    // last provided via `set_fuel`, and the amount consumed prior to that.
+    fuel_last_set: u64,
+    fuel_consumed_before_last_set: u64,
+    /// Statistics about calls between wasm and the host, which are only
+    /// collected if `track_call_usage` is set.
+    call_usage: CallUsage,
+    track_call_usage: bool,
+    /// The CPU-time budget configured with `set_cpu_time_budget`, if any.
+    #[cfg(all(feature = "std", target_has_atomic = "64"))]
+    cpu_time_budget: Option<CpuTimeBudget>,
     /// Indexed data within this `Store`, used to store information about
     /// globals, functions, memories, etc.
     store_data: StoreData,
@@ -744,10 +793,22 @@ impl<T> Store<T> {
             memory_limit: crate::DEFAULT_MEMORY_LIMIT,
             table_count: 0,
             table_limit: crate::DEFAULT_TABLE_LIMIT,
+            #[cfg(feature = "component-model")]
+            component_instance_count: 0,
+            #[cfg(feature = "component-model")]
+            component_instance_limit: crate::DEFAULT_COMPONENT_INSTANCE_LIMIT,
+            #[cfg(feature = "component-model")]
+            resource_table_entry_limit: crate::DEFAULT_RESOURCE_TABLE_ENTRIES_LIMIT,
             #[cfg(feature = "async")]
             async_state: Default::default(),
             fuel_reserve: 0,
             fuel_yield_interval: None,
+            fuel_last_set: 0,
+            fuel_consumed_before_last_set: 0,
+            call_usage: CallUsage::default(),
+            track_call_usage: false,
+            #[cfg(all(feature = "std", target_has_atomic = "64"))]
+            cpu_time_budget: None,
             store_data,
             traitobj: StorePtr(None),
             default_caller_vmctx: SendSyncPtr::new(NonNull::dangling()),
@@ -941,6 +1002,15 @@ impl<T> Store<T> {
         innermost.instance_limit = instance_limit;
         innermost.table_limit = table_limit;
         innermost.memory_limit = memory_limit;
+        #[cfg(feature = "component-model")]
+        {
+            let l = limiter(inner.data_mut());
+            let (component_instance_limit, resource_table_entry_limit) =
+                (l.component_instances(), l.resource_table_entries());
+            let innermost = &mut inner.inner;
+            innermost.component_instance_limit = component_instance_limit;
+            innermost.resource_table_entry_limit = resource_table_entry_limit;
+        }
 
         // Save the limiter accessor function:
         inner.limiter = Some(ResourceLimiterInner::Sync(Box::new(limiter)));
@@ -1017,6 +1087,54 @@ impl<T> Store<T> {
         self.inner.set_fuel(fuel)
     }
 
+    /// Returns a summary of the resources currently used by this [`Store`].
+    ///
+    /// This reports the size of linear memories, tables, and the GC heap
+    /// within this store, the number of instances it contains, and the fuel
+    /// consumed by it. Statistics about calls between WebAssembly and the host
+    /// are additionally reported if enabled with
+    /// [`Store::track_call_usage`].
+    ///
+    /// This is intended for embeddings which need to account for the
+    /// resources used by each store, for example to bill tenants of a
+    /// multi-tenant system, without needing to track allocations through a
+    /// [`ResourceLimiter`](crate::ResourceLimiter). See [`ResourceUsage`] for
+    /// more information about each measurement, and
+    /// [`Instance::resource_usage`] for the memories and tables defined by a
+    /// single instance.
+    pub fn resource_usage(&self) -> ResourceUsage {
+        self.inner.resource_usage()
+    }
+
+    /// Captures the state of all instances within this [`Store`], along with
+    /// the GC objects and component resources they refer to.
+    ///
+    /// The returned snapshot can be restored into another store with
+    /// [`Linker::instantiate_store_snapshot`](crate::Linker::instantiate_store_snapshot).
+    /// See [`StoreSnapshot`] for more information.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store contains state which can't be captured,
+    /// as described in the limitations of [`StoreSnapshot`].
+    pub fn snapshot(&mut self) -> Result<StoreSnapshot> {
+        StoreSnapshot::new(&mut self.inner)
+    }
+
+    /// Configures whether this [`Store`] collects statistics about calls
+    /// between WebAssembly and the host, reported by
+    /// [`Store::resource_usage`].
+    ///
+    /// When enabled, the number of calls into WebAssembly and into the host
+    /// are counted, and the wall-clock and CPU time spent in each is
+    /// measured. This imposes a small cost on each such call and is disabled
+    /// by default. Disabling collection retains the statistics collected so
+    /// far.
+    #[cfg(feature = "call-hook")]
+    pub fn track_call_usage(&mut self, enable: bool) {
+        self.inner.track_call_usage(enable)
+    }
+
     /// Configures a [`Store`] to yield execution of async WebAssembly code
     /// periodically.
     ///
@@ -1151,6 +1269,55 @@ impl<T> Store<T> {
         self.inner.epoch_deadline_callback(Box::new(callback));
     }
 
+    /// Configures a budget of CPU time that WebAssembly may consume within
+    /// this store before the epoch deadline is considered reached.
+    ///
+    /// Fuel and epochs both allow interrupting WebAssembly, but fuel imposes
+    /// a cost on every instruction executed and epochs measure wall-clock
+    /// time. When many stores share threads neither is suitable to limit the
+    /// CPU time used by a single store, so this method enables charging the
+    /// CPU time of the current thread, while this store is executing
+    /// WebAssembly, against `budget`.
+    ///
+    /// The budget is checked each time the engine's epoch is incremented via
+    /// [`Engine::increment_epoch`](crate::Engine::increment_epoch), so the
+    /// frequency of incrementing the epoch determines the granularity of
+    /// this budget. Once the budget is exhausted the store behaves as if its
+    /// epoch deadline had been reached: by default execution traps, but this
+    /// can be configured with [`Store::epoch_deadline_callback`] or
+    /// [`Store::epoch_deadline_async_yield_and_update`] to, for example,
+    /// yield to the async executor. Within such a callback
+    /// [`StoreContext::cpu_time_budget`] can be used to determine whether the
+    /// budget was exhausted and [`StoreContextMut::set_cpu_time_budget`] can
+    /// be used to refill it. Otherwise the budget remains exhausted and the
+    /// epoch-deadline behavior will be invoked on each subsequent epoch.
+    ///
+    /// Calling this method with `Some` while a budget is already configured
+    /// replaces the remaining budget, and `None` removes the budget. The CPU
+    /// time consumed by host functions called from WebAssembly is charged to
+    /// the budget as well. Time during which an async call is suspended may
+    /// also be charged to the budget, except for yields made due to the
+    /// epoch-deadline behavior. The budget is first charged when WebAssembly
+    /// is next entered from the host.
+    ///
+    /// # Errors
+    ///
+    /// This method will error if
+    /// [`Config::epoch_interruption`](crate::Config::epoch_interruption) is
+    /// not enabled or if the current platform does not support measuring the
+    /// CPU time of a thread.
+    #[cfg(all(feature = "std", target_has_atomic = "64"))]
+    pub fn set_cpu_time_budget(&mut self, budget: Option<Duration>) -> Result<()> {
+        self.inner.set_cpu_time_budget(budget)
+    }
+
+    /// Returns the amount of CPU time remaining in the budget configured with
+    /// [`Store::set_cpu_time_budget`], or `None` if no budget is configured.
+    #[cfg(all(feature = "std", target_has_atomic = "64"))]
+    pub fn cpu_time_budget(&self) -> Option<Duration> {
+        self.inner.cpu_time_budget()
+    }
+
     /// Tests whether there is a pending exception.
     ///
     /// Ordinarily, a pending exception will be set on a store if and
@@ -1282,6 +1449,21 @@ impl<'a, T> StoreContext<'a, T> {
     pub fn get_fuel(&self) -> Result<u64> {
         self.0.get_fuel()
     }
+
+    /// Returns a summary of the resources used by this store.
+    ///
+    /// For more information see [`Store::resource_usage`].
+    pub fn resource_usage(&self) -> ResourceUsage {
+        self.0.resource_usage()
+    }
+
+    /// Returns the amount of CPU time remaining in this store's budget.
+    ///
+    /// For more information see [`Store::cpu_time_budget`].
+    #[cfg(all(feature = "std", target_has_atomic = "64"))]
+    pub fn cpu_time_budget(&self) -> Option<Duration> {
+        self.0.cpu_time_budget()
+    }
 }
 
 impl<'a, T> StoreContextMut<'a, T> {
@@ -1318,6 +1500,29 @@ impl<'a, T> StoreContextMut<'a, T> {
         self.0.set_fuel(fuel)
     }
 
+    /// Returns a summary of the resources used by this store.
+    ///
+    /// For more information see [`Store::resource_usage`].
+    pub fn resource_usage(&self) -> ResourceUsage {
+        self.0.resource_usage()
+    }
+
+    /// Captures the state of all instances within this store.
+    ///
+    /// For more information see [`Store::snapshot`].
+    pub fn snapshot(&mut self) -> Result<StoreSnapshot> {
+        StoreSnapshot::new(self.0)
+    }
+
+    /// Configures whether this store collects statistics about calls between
+    /// WebAssembly and the host.
+    ///
+    /// For more information see [`Store::track_call_usage`].
+    #[cfg(feature = "call-hook")]
+    pub fn track_call_usage(&mut self, enable: bool) {
+        self.0.track_call_usage(enable)
+    }
+
     /// Configures this `Store` to periodically yield while executing futures.
     ///
     /// For more information see [`Store::fuel_async_yield_interval`]
@@ -1342,6 +1547,22 @@ impl<'a, T> StoreContextMut<'a, T> {
         self.0.epoch_deadline_trap();
     }
 
+    /// Configures a budget of CPU time for WebAssembly within this store.
+    ///
+    /// For more information see [`Store::set_cpu_time_budget`].
+    #[cfg(all(feature = "std", target_has_atomic = "64"))]
+    pub fn set_cpu_time_budget(&mut self, budget: Option<Duration>) -> Result<()> {
+        self.0.set_cpu_time_budget(budget)
+    }
+
+    /// Returns the amount of CPU time remaining in this store's budget.
+    ///
+    /// For more information see [`Store::cpu_time_budget`].
+    #[cfg(all(feature = "std", target_has_atomic = "64"))]
+    pub fn cpu_time_budget(&self) -> Option<Duration> {
+        self.0.cpu_time_budget()
+    }
+
     /// Tests whether there is a pending exception.
     ///
     /// See [`Store::has_pending_exception`] for more details.
@@ -1400,7 +1621,11 @@ impl<T> StoreInner<T> {
 
     #[inline]
     pub fn call_hook(&mut self, s: CallHook) -> Result<()> {
-        if self.inner.pkey.is_none() && self.call_hook.is_none() {
+        if self.inner.pkey.is_none()
+            && self.call_hook.is_none()
+            && !self.inner.track_call_usage
+            && !self.inner.has_cpu_time_budget()
+        {
             Ok(())
         } else {
             self.call_hook_slow_path(s)
@@ -1408,6 +1633,14 @@ impl<T> StoreInner<T> {
     }
 
     fn call_hook_slow_path(&mut self, s: CallHook) -> Result<()> {
+        if self.inner.track_call_usage {
+            self.inner.call_usage.transition(s);
+        }
+        #[cfg(all(feature = "std", target_has_atomic = "64"))]
+        if let Some(budget) = &mut self.inner.cpu_time_budget {
+            budget.transition(s);
+        }
+
         if let Some(pkey) = &self.inner.pkey {
             let allocator = self.engine().allocator();
             match s {
@@ -1546,6 +1779,16 @@ impl StoreOpaque {
         Ok(())
     }
 
+    #[cfg(feature = "component-model")]
+    pub fn bump_component_instance_count(&mut self) -> Result<()> {
+        let new = self.component_instance_count.saturating_add(1);
+        if new > self.component_instance_limit {
+            bail!("resource limit exceeded: component instance count too high at {new}");
+        }
+        self.component_instance_count = new;
+        Ok(())
+    }
+
     #[inline]
     pub fn engine(&self) -> &Engine {
         &self.engine
@@ -1898,6 +2141,11 @@ impl StoreOpaque {
             "fuel is not configured in this store"
         );
         let injected_fuel = unsafe { &mut *self.vm_store_context.fuel_consumed.get() };
+        let remaining = get_fuel(*injected_fuel, self.fuel_reserve);
+        self.fuel_consumed_before_last_set = self
+            .fuel_consumed_before_last_set
+            .saturating_add(self.fuel_last_set.saturating_sub(remaining));
+        self.fuel_last_set = fuel;
         set_fuel(
             injected_fuel,
             &mut self.fuel_reserve,
@@ -1907,6 +2155,43 @@ impl StoreOpaque {
         Ok(())
     }
 
+    pub fn resource_usage(&self) -> ResourceUsage {
+        let mut usage = ResourceUsage::default();
+        // NB: host-created memories and tables have dummy instances, so
+        // iterating over all instances, including dummy instances, accounts
+        // for all memories and tables within this store.
+        for (_, instance) in self.instances.iter() {
+            if let StoreInstanceKind::Real { .. } = instance.kind {
+                usage.instances += 1;
+            }
+            let handle = instance.handle.get();
+            usage.memory_bytes += handle.defined_memories_byte_size();
+            usage.table_elements += handle.defined_tables_size();
+        }
+        usage.gc_heap_bytes = self
+            .gc_store
+            .as_ref()
+            .map_or(0, |gc_store| gc_store.gc_heap_capacity());
+        if self.engine().tunables().consume_fuel {
+            // SAFETY: wasm can only run, and update the fuel counter, while
+            // the store is borrowed mutably, so nothing writes to the counter
+            // while it's read here through `&self`.
+            let injected_fuel = unsafe { *self.vm_store_context.fuel_consumed.get() };
+            let remaining = get_fuel(injected_fuel, self.fuel_reserve);
+            usage.fuel_consumed = Some(
+                self.fuel_consumed_before_last_set
+                    .saturating_add(self.fuel_last_set.saturating_sub(remaining)),
+            );
+        }
+        usage.calls = self.call_usage;
+        usage
+    }
+
+    #[cfg(feature = "call-hook")]
+    pub fn track_call_usage(&mut self, enable: bool) {
+        self.track_call_usage = enable;
+    }
+
     #[cfg(feature = "async")]
     pub fn fuel_async_yield_interval(&mut self, interval: Option<u64>) -> Result<()> {
         crate::ensure!(
@@ -2235,8 +2520,89 @@ at https://bytecodealliance.org/security.
         // on the stack, the Wasm will reload the new value once we
         // return into it.
         let current_epoch = self.engine().current_epoch();
+        let deadline = current_epoch + delta;
+
+        // While a CPU-time budget is configured the deadline observed by wasm
+        // is at most the next epoch so the budget is checked on every tick.
+        #[cfg(all(feature = "std", target_has_atomic = "64"))]
+        let deadline = match &mut self.cpu_time_budget {
+            Some(budget) => {
+                budget.epoch_deadline = deadline;
+                deadline.min(current_epoch + 1)
+            }
+            None => deadline,
+        };
+
         let epoch_deadline = self.vm_store_context.epoch_deadline.get_mut();
-        *epoch_deadline = current_epoch + delta;
+        *epoch_deadline = deadline;
+    }
+
+    #[inline]
+    fn has_cpu_time_budget(&self) -> bool {
+        #[cfg(all(feature = "std", target_has_atomic = "64"))]
+        return self.cpu_time_budget.is_some();
+        #[cfg(not(all(feature = "std", target_has_atomic = "64")))]
+        return false;
+    }
+
+    #[cfg(all(feature = "std", target_has_atomic = "64"))]
+    pub fn set_cpu_time_budget(&mut self, budget: Option<Duration>) -> Result<()> {
+        crate::ensure!(
+            self.engine().tunables().epoch_interruption,
+            "epoch interruption is not configured in this store"
+        );
+        crate::ensure!(
+            crate::runtime::vm::thread_cpu_time().is_some(),
+            "measuring thread CPU time is not supported on this platform"
+        );
+        let Some(remaining) = budget else {
+            // Restore the epoch deadline that the budget was shadowing.
+            if let Some(budget) = self.cpu_time_budget.take() {
+                *self.vm_store_context.epoch_deadline.get_mut() = budget.epoch_deadline;
+            }
+            return Ok(());
+        };
+        if let Some(budget) = &mut self.cpu_time_budget {
+            budget.set_remaining(remaining);
+            return Ok(());
+        }
+        let epoch_deadline = self.get_epoch_deadline();
+        self.cpu_time_budget = Some(CpuTimeBudget::new(remaining, epoch_deadline));
+        let current_epoch = self.engine().current_epoch();
+        let deadline = self.vm_store_context.epoch_deadline.get_mut();
+        *deadline = epoch_deadline.min(current_epoch + 1);
+        Ok(())
+    }
+
+    #[cfg(all(feature = "std", target_has_atomic = "64"))]
+    pub fn cpu_time_budget(&self) -> Option<Duration> {
+        let budget = self.cpu_time_budget.as_ref()?;
+        Some(budget.remaining())
+    }
+
+    /// Invoked when wasm observes that its epoch deadline has been reached,
+    /// returning the new deadline if this was only a check of the CPU-time
+    /// budget and the store's epoch-deadline behavior shouldn't be invoked.
+    #[cfg(all(feature = "std", target_has_atomic = "64"))]
+    pub(crate) fn check_cpu_time_budget(&mut self) -> Option<u64> {
+        let current_epoch = self.engine().current_epoch();
+        let budget = self.cpu_time_budget.as_mut()?;
+        budget.charge();
+        if budget.exhausted() || current_epoch >= budget.epoch_deadline {
+            return None;
+        }
+        let deadline = budget.epoch_deadline.min(current_epoch + 1);
+        *self.vm_store_context.epoch_deadline.get_mut() = deadline;
+        Some(deadline)
+    }
+
+    /// Restarts CPU-time measurement after the epoch-deadline behavior was
+    /// invoked, which may have yielded and resumed on another thread.
+    #[cfg(all(feature = "std", target_has_atomic = "64"))]
+    pub(crate) fn restart_cpu_time_budget(&mut self) {
+        if let Some(budget) = &mut self.cpu_time_budget {
+            budget.restart();
+        }
     }
 
     pub(crate) fn get_epoch_deadline(&mut self) -> u64 {
@@ -60,6 +60,15 @@ impl<T> Store<T> {
         innermost.instance_limit = instance_limit;
         innermost.table_limit = table_limit;
         innermost.memory_limit = memory_limit;
+        #[cfg(feature = "component-model")]
+        {
+            let l = limiter(inner.data_mut());
+            let (component_instance_limit, resource_table_entry_limit) =
+                (l.component_instances(), l.resource_table_entries());
+            let innermost = &mut inner.inner;
+            innermost.component_instance_limit = component_instance_limit;
+            innermost.resource_table_entry_limit = resource_table_entry_limit;
+        }
 
         // Save the limiter accessor function:
         inner.limiter = Some(ResourceLimiterInner::Async(Box::new(limiter)));
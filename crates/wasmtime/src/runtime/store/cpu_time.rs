@@ -0,0 +1,94 @@
+use crate::CallHook;
+use crate::runtime::vm::thread_cpu_time;
+use core::time::Duration;
+
+/// State for the CPU-time budget of a store, configured with
+/// [`Store::set_cpu_time_budget`](crate::Store::set_cpu_time_budget).
+///
+/// CPU time is measured with the current thread's CPU clock from when the
+/// host calls into WebAssembly until it returns, and is charged against the
+/// budget each time WebAssembly returns or observes a new epoch.
+pub(crate) struct CpuTimeBudget {
+    /// The amount of CPU time remaining before the budget is exhausted.
+    remaining: Duration,
+
+    /// The epoch deadline configured with `set_epoch_deadline`, which is
+    /// shadowed by the deadline in `VMStoreContext` while a budget is
+    /// configured so that every epoch tick checks the budget.
+    pub(crate) epoch_deadline: u64,
+
+    /// The number of calls into WebAssembly currently on the stack.
+    depth: usize,
+
+    /// The thread CPU time when CPU time was last charged to this budget, if
+    /// WebAssembly is currently on the stack.
+    last: Option<Duration>,
+}
+
+impl CpuTimeBudget {
+    pub(crate) fn new(remaining: Duration, epoch_deadline: u64) -> CpuTimeBudget {
+        CpuTimeBudget {
+            remaining,
+            epoch_deadline,
+            depth: 0,
+            last: None,
+        }
+    }
+
+    /// Returns the amount of CPU time remaining in this budget.
+    pub(crate) fn remaining(&self) -> Duration {
+        self.remaining
+    }
+
+    /// Refills this budget to `remaining`, retaining the in-progress
+    /// measurement of the current call, if any.
+    pub(crate) fn set_remaining(&mut self, remaining: Duration) {
+        self.charge();
+        self.remaining = remaining;
+    }
+
+    /// Returns whether this budget has been exhausted.
+    pub(crate) fn exhausted(&self) -> bool {
+        self.remaining.is_zero()
+    }
+
+    /// Records the transition `s` between WebAssembly and the host.
+    pub(crate) fn transition(&mut self, s: CallHook) {
+        match s {
+            CallHook::CallingWasm => {
+                if self.depth == 0 {
+                    self.last = thread_cpu_time();
+                }
+                self.depth += 1;
+            }
+            CallHook::ReturningFromWasm => {
+                self.depth = self.depth.saturating_sub(1);
+                if self.depth == 0 {
+                    self.charge();
+                    self.last = None;
+                }
+            }
+            CallHook::CallingHost | CallHook::ReturningFromHost => {}
+        }
+    }
+
+    /// Charges the CPU time used since the last measurement to this budget.
+    pub(crate) fn charge(&mut self) {
+        let Some(last) = self.last else {
+            return;
+        };
+        let Some(now) = thread_cpu_time() else {
+            return;
+        };
+        self.remaining = self.remaining.saturating_sub(now.saturating_sub(last));
+        self.last = Some(now);
+    }
+
+    /// Restarts measurement from the current thread's CPU time, for example
+    /// after an async yield which may resume on another thread.
+    pub(crate) fn restart(&mut self) {
+        if self.last.is_some() {
+            self.last = thread_cpu_time();
+        }
+    }
+}
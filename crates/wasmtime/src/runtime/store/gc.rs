@@ -337,15 +337,42 @@ impl StoreOpaque {
         let delta_pages_for_alloc = delta_pages_for_alloc.max(pages_needed);
         assert!(delta_pages_for_alloc > 0);
 
+        // Give the store's limiter a chance to reject the growth. Note that
+        // the limiter is consulted here, rather than within the memory's own
+        // growth, so it's informed that this is growth of the GC heap as
+        // opposed to a linear memory.
+        let mut limiter = limiter;
+        if let Some(limiter) = &mut limiter {
+            let current = usize::try_from(current_size_in_bytes)?;
+            let desired = delta_pages_for_alloc
+                .checked_mul(page_size)
+                .and_then(|delta| usize::try_from(delta).ok())
+                .and_then(|delta| current.checked_add(delta))
+                .ok_or(GcHeapGrowthFailed)?;
+            let maximum = usize::try_from(max_size_in_bytes).ok();
+            if !limiter.gc_heap_growing(current, desired, maximum).await? {
+                return Err(GcHeapGrowthFailed.into());
+            }
+        }
+
         // Safety: we pair growing the GC heap with updating its associated
         // `VMMemoryDefinition` in the `VMStoreContext` immediately
         // afterwards.
-        unsafe {
-            heap.memory
-                .grow(delta_pages_for_alloc, limiter)
-                .await
-                .context(GcHeapGrowthFailed)?
-                .ok_or(GcHeapGrowthFailed)?;
+        let grown = unsafe { heap.memory.grow(delta_pages_for_alloc, None).await };
+        match grown {
+            Ok(Some(_)) => {}
+            Ok(None) => {
+                if let Some(limiter) = limiter {
+                    limiter.gc_heap_grow_failed(crate::Error::new(GcHeapGrowthFailed))?;
+                }
+                return Err(GcHeapGrowthFailed.into());
+            }
+            Err(e) => {
+                if let Some(limiter) = limiter {
+                    limiter.gc_heap_grow_failed(e)?;
+                }
+                return Err(GcHeapGrowthFailed.into());
+            }
         }
         *heap.store.vm_store_context.gc_heap.get_mut() = heap.memory.vmmemory();
 
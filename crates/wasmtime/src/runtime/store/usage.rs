@@ -0,0 +1,239 @@
+use crate::CallHook;
+#[cfg(feature = "std")]
+use core::time::Duration;
+
+/// A summary of the resources used by a [`Store`](crate::Store).
+///
+/// This is returned by [`Store::resource_usage`](crate::Store::resource_usage)
+/// and is a point-in-time measurement of the store: values describing
+/// resources currently held, such as memory, reflect the store at the time of
+/// the call, while counters, such as fuel consumed, are totals over the
+/// lifetime of the store.
+///
+/// Statistics about calls between WebAssembly and the host are only collected
+/// when enabled with
+/// [`Store::track_call_usage`](crate::Store::track_call_usage), and are
+/// otherwise zero.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct ResourceUsage {
+    pub(super) instances: usize,
+    pub(super) memory_bytes: usize,
+    pub(super) table_elements: usize,
+    pub(super) gc_heap_bytes: usize,
+    pub(super) fuel_consumed: Option<u64>,
+    pub(super) calls: CallUsage,
+}
+
+impl ResourceUsage {
+    /// Returns the number of core WebAssembly instances within the store.
+    ///
+    /// This includes core instances created as part of instantiating a
+    /// component.
+    pub fn instances(&self) -> usize {
+        self.instances
+    }
+
+    /// Returns the total size, in bytes, of all linear memories defined
+    /// within the store.
+    ///
+    /// This is the current size of each memory as seen by WebAssembly, which
+    /// includes host-created memories and is the upper bound of memory that
+    /// can be committed for linear memories. Memories imported into an
+    /// instance are only counted once, within the instance or host which
+    /// defined them.
+    pub fn memory_bytes(&self) -> usize {
+        self.memory_bytes
+    }
+
+    /// Returns the total number of elements of all tables defined within the
+    /// store, including host-created tables.
+    pub fn table_elements(&self) -> usize {
+        self.table_elements
+    }
+
+    /// Returns the current capacity, in bytes, of the store's GC heap, or 0 if
+    /// the GC heap has not been initialized.
+    ///
+    /// This is the same as [`Store::gc_heap_capacity`](crate::Store::gc_heap_capacity).
+    pub fn gc_heap_bytes(&self) -> usize {
+        self.gc_heap_bytes
+    }
+
+    /// Returns the total amount of fuel consumed by WebAssembly within the
+    /// store, or `None` if fuel consumption is not enabled via
+    /// [`Config::consume_fuel`](crate::Config::consume_fuel).
+    ///
+    /// This is the sum of fuel consumed across all calls to
+    /// [`Store::set_fuel`](crate::Store::set_fuel), so it is not affected by
+    /// refueling the store.
+    pub fn fuel_consumed(&self) -> Option<u64> {
+        self.fuel_consumed
+    }
+
+    /// Returns the number of calls from the host into WebAssembly.
+    pub fn wasm_calls(&self) -> u64 {
+        self.calls.wasm_calls
+    }
+
+    /// Returns the number of calls from WebAssembly into the host.
+    ///
+    /// This includes calls to host functions as well as calls to the
+    /// Wasmtime runtime made on behalf of WebAssembly instructions, for
+    /// example `memory.grow`.
+    pub fn host_calls(&self) -> u64 {
+        self.calls.host_calls
+    }
+
+    /// Returns the total wall-clock time spent executing WebAssembly.
+    ///
+    /// This is measured from the time the host calls into WebAssembly until
+    /// it returns, excluding time spent in calls back to the host. Note that
+    /// time that an async call is suspended, for example while yielding due
+    /// to fuel or epochs, is included.
+    #[cfg(feature = "std")]
+    pub fn wasm_time(&self) -> Duration {
+        self.calls.wasm_time
+    }
+
+    /// Returns the total wall-clock time spent in calls from WebAssembly into
+    /// the host, as counted by [`ResourceUsage::host_calls`].
+    #[cfg(feature = "std")]
+    pub fn host_time(&self) -> Duration {
+        self.calls.host_time
+    }
+
+    /// Returns the total CPU time consumed by the current thread while
+    /// executing WebAssembly.
+    ///
+    /// This is measured in the same manner as [`ResourceUsage::wasm_time`],
+    /// but only counts time during which the thread was actually scheduled.
+    /// Returns `None` if the platform does not support measuring per-thread
+    /// CPU time or if no calls have been tracked. Note that if an async call is resumed on a different thread
+    /// the CPU time of the thread it was suspended on is not included.
+    #[cfg(feature = "std")]
+    pub fn wasm_cpu_time(&self) -> Option<Duration> {
+        self.calls.cpu_supported.then_some(self.calls.wasm_cpu_time)
+    }
+
+    /// Returns the total CPU time consumed while executing calls from
+    /// WebAssembly into the host, as counted by [`ResourceUsage::host_calls`].
+    ///
+    /// For more information see [`ResourceUsage::wasm_cpu_time`].
+    #[cfg(feature = "std")]
+    pub fn host_cpu_time(&self) -> Option<Duration> {
+        self.calls.cpu_supported.then_some(self.calls.host_cpu_time)
+    }
+}
+
+/// A summary of the resources defined by a single [`Instance`](crate::Instance).
+///
+/// This is returned by
+/// [`Instance::resource_usage`](crate::Instance::resource_usage) and, like
+/// [`ResourceUsage`], is a point-in-time measurement. Only resources owned by
+/// the instance itself are reported here: the GC heap, fuel, and statistics
+/// about calls are shared by all instances within a store and are only
+/// reported for the whole store by
+/// [`Store::resource_usage`](crate::Store::resource_usage).
+#[derive(Copy, Clone, Debug, Default)]
+pub struct InstanceResourceUsage {
+    pub(crate) memory_bytes: usize,
+    pub(crate) table_elements: usize,
+}
+
+impl InstanceResourceUsage {
+    /// Returns the total size, in bytes, of all linear memories defined by
+    /// the instance.
+    ///
+    /// Memories imported by the instance are not included and are instead
+    /// counted by the instance, or host, which defined them.
+    pub fn memory_bytes(&self) -> usize {
+        self.memory_bytes
+    }
+
+    /// Returns the total number of elements of all tables defined by the
+    /// instance.
+    ///
+    /// Tables imported by the instance are not included.
+    pub fn table_elements(&self) -> usize {
+        self.table_elements
+    }
+}
+
+/// Statistics about calls between WebAssembly and the host, collected when a
+/// store has [`Store::track_call_usage`](crate::Store::track_call_usage)
+/// enabled.
+#[derive(Copy, Clone, Debug, Default)]
+pub(crate) struct CallUsage {
+    wasm_calls: u64,
+    host_calls: u64,
+    #[cfg(feature = "std")]
+    wasm_time: Duration,
+    #[cfg(feature = "std")]
+    host_time: Duration,
+    #[cfg(feature = "std")]
+    wasm_cpu_time: Duration,
+    #[cfg(feature = "std")]
+    host_cpu_time: Duration,
+    #[cfg(feature = "std")]
+    cpu_supported: bool,
+
+    /// Number of calls into the host currently on the stack.
+    host_depth: usize,
+
+    /// The time of the last transition between WebAssembly and the host.
+    #[cfg(feature = "std")]
+    last_transition: Option<std::time::Instant>,
+    #[cfg(feature = "std")]
+    last_cpu_time: Option<Duration>,
+}
+
+impl CallUsage {
+    /// Records the transition `s` between WebAssembly and the host.
+    pub(crate) fn transition(&mut self, s: CallHook) {
+        #[cfg(feature = "std")]
+        {
+            let now = std::time::Instant::now();
+            let cpu_now = crate::runtime::vm::thread_cpu_time();
+            if let Some(last) = self.last_transition {
+                let elapsed = now - last;
+                let cpu_elapsed = match (cpu_now, self.last_cpu_time) {
+                    (Some(now), Some(last)) => now.saturating_sub(last),
+                    _ => Duration::ZERO,
+                };
+                match s {
+                    CallHook::ReturningFromWasm | CallHook::CallingHost => {
+                        self.wasm_time += elapsed;
+                        self.wasm_cpu_time += cpu_elapsed;
+                    }
+                    CallHook::ReturningFromHost => {
+                        self.host_time += elapsed;
+                        self.host_cpu_time += cpu_elapsed;
+                    }
+                    // Time before entering WebAssembly is only attributed to
+                    // the host if this is a reentrant call made while within
+                    // a host call; otherwise it's time spent outside of the
+                    // store entirely.
+                    CallHook::CallingWasm => {
+                        if self.host_depth > 0 {
+                            self.host_time += elapsed;
+                            self.host_cpu_time += cpu_elapsed;
+                        }
+                    }
+                }
+            }
+            self.last_transition = Some(now);
+            self.last_cpu_time = cpu_now;
+            self.cpu_supported |= cpu_now.is_some();
+        }
+
+        match s {
+            CallHook::CallingWasm => self.wasm_calls += 1,
+            CallHook::CallingHost => {
+                self.host_calls += 1;
+                self.host_depth += 1;
+            }
+            CallHook::ReturningFromHost => self.host_depth = self.host_depth.saturating_sub(1),
+            CallHook::ReturningFromWasm => {}
+        }
+    }
+}
@@ -9,6 +9,7 @@
 use crate::Result;
 use crate::component::{Component, Instance, InstancePre, ResourceType, RuntimeImport};
 use crate::module::ModuleRegistry;
+use crate::prelude::*;
 #[cfg(feature = "component-model-async")]
 use crate::runtime::component::concurrent::ConcurrentInstanceState;
 use crate::runtime::component::{ComponentInstanceId, RuntimeInstance};
@@ -42,7 +43,7 @@ mod handle_table;
 mod libcalls;
 mod resources;
 
-pub use self::handle_table::{HandleTable, RemovedResource};
+pub use self::handle_table::{HandleTable, RemovedResource, ResourceSlot};
 #[cfg(feature = "component-model-async")]
 pub use self::handle_table::{ThreadHandleTable, TransmitLocalState, Waitable};
 pub use self::resources::{CallContext, ResourceTables, TypedResource, TypedResourceIndex};
@@ -958,6 +959,7 @@ impl ComponentInstance {
                 self.component.clone(),
                 self.imports.clone(),
                 self.resource_types.clone(),
+                Arc::new(Vec::new()),
             )
         }
     }
@@ -1,6 +1,7 @@
 use super::{TypedResource, TypedResourceIndex};
 use crate::prelude::TryVec;
 use crate::{Result, bail};
+use alloc::vec::Vec;
 use core::mem;
 use wasmtime_environ::component::{TypeFutureTableIndex, TypeStreamTableIndex};
 
@@ -46,6 +47,15 @@ pub enum Waitable {
     Stream,
 }
 
+/// A slot of a [`HandleTable`] which only contains `own` resource handles, as
+/// returned by [`HandleTable::resource_slots`].
+pub enum ResourceSlot {
+    /// An unused slot, followed in the free list by `next`.
+    Free { next: u32 },
+    /// An owned resource handle which isn't lent out.
+    Own(TypedResource),
+}
+
 #[derive(Debug)]
 enum Slot {
     Free {
@@ -134,6 +144,67 @@ impl HandleTable {
             .all(|slot| matches!(slot, Slot::Free { .. }))
     }
 
+    /// Returns the head of the free list and the slots of this table, or
+    /// `None` if it contains anything other than `own` resource handles which
+    /// aren't currently lent out.
+    pub fn resource_slots(&self) -> Option<(u32, Vec<ResourceSlot>)> {
+        let mut slots = Vec::with_capacity(self.slots.len());
+        for slot in self.slots.iter() {
+            slots.push(match slot {
+                Slot::Free { next } => ResourceSlot::Free { next: *next },
+                Slot::ResourceOwn {
+                    resource,
+                    lend_count: 0,
+                } => ResourceSlot::Own(match *resource {
+                    TypedResource::Host(rep) => TypedResource::Host(rep),
+                    TypedResource::Component { rep, ty } => TypedResource::Component { rep, ty },
+                }),
+                _ => return None,
+            });
+        }
+        Some((self.next, slots))
+    }
+
+    /// Recreates a table from the result of [`HandleTable::resource_slots`].
+    ///
+    /// Returns an error if the free list starting at `next` doesn't visit
+    /// each free slot exactly once before ending just past the last slot.
+    pub fn from_resource_slots(next: u32, slots: Vec<ResourceSlot>) -> Result<HandleTable> {
+        let len = match u32::try_from(slots.len()) {
+            Ok(len) if len < MAX_HANDLE => len,
+            _ => bail!("too many handles"),
+        };
+        let mut visited = Vec::new();
+        visited.resize(slots.len(), false);
+        let mut cur = next;
+        while cur != len {
+            let i = usize::try_from(cur)?;
+            match slots.get(i) {
+                Some(ResourceSlot::Free { next }) if !visited[i] => {
+                    visited[i] = true;
+                    cur = *next;
+                }
+                _ => bail!("invalid handle table free list"),
+            }
+        }
+
+        let mut table = HandleTable {
+            next,
+            slots: TryVec::new(),
+        };
+        for (slot, visited) in slots.into_iter().zip(visited) {
+            table.slots.push(match slot {
+                ResourceSlot::Free { next } if visited => Slot::Free { next },
+                ResourceSlot::Free { .. } => bail!("invalid handle table free list"),
+                ResourceSlot::Own(resource) => Slot::ResourceOwn {
+                    resource,
+                    lend_count: 0,
+                },
+            })?;
+        }
+        Ok(table)
+    }
+
     fn insert(&mut self, slot: Slot) -> Result<u32> {
         let next = self.next;
 
@@ -161,6 +232,18 @@ impl HandleTable {
         Ok(next + 1)
     }
 
+    /// Returns an error if inserting a new handle would grow this table to
+    /// more than `limit` entries.
+    pub fn ensure_can_insert(&self, limit: usize) -> Result<()> {
+        if self.next as usize == self.slots.len() && self.slots.len() >= limit {
+            bail!(
+                "resource limit exceeded: resource table entry count too high at {}",
+                self.slots.len() + 1
+            );
+        }
+        Ok(())
+    }
+
     fn remove(&mut self, idx: u32) -> Result<()> {
         let to_fill = Slot::Free { next: self.next };
         let slot = self.get_mut(idx)?;
@@ -585,6 +668,11 @@ pub struct ThreadHandleTable(HandleTable);
 
 #[cfg(feature = "component-model-async")]
 impl ThreadHandleTable {
+    /// Returns whether or not this table is empty.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
     /// Inserts the guest thread `rep` into this table, returning the index it
     /// now resides at.
     pub fn guest_thread_insert(&mut self, rep: u32) -> Result<u32> {
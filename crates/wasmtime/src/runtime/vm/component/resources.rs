@@ -76,6 +76,10 @@ pub struct ResourceTables<'a> {
     /// Identifier for the current "scope" which is used for various functions
     /// on `task_state` above to mutate borrows/etc of the current scope.
     pub current_scope_id: Option<u32>,
+
+    /// The maximum number of entries in any one table, as configured by the
+    /// store's `ResourceLimiter`.
+    pub entry_limit: usize,
 }
 
 /// Typed representation of a "rep" for a resource.
@@ -189,6 +193,15 @@ impl ResourceTables<'_> {
         }
     }
 
+    /// Same as `table_for_resource`, but additionally verifies that the
+    /// table has room for another entry.
+    fn table_for_insert(&mut self, resource: &TypedResource) -> Result<&mut HandleTable> {
+        let limit = self.entry_limit;
+        let table = self.table_for_resource(resource);
+        table.ensure_can_insert(limit)?;
+        Ok(table)
+    }
+
     fn table_for_index(&mut self, index: &TypedResourceIndex) -> &mut HandleTable {
         match index {
             TypedResourceIndex::Host(_) => self.host_table,
@@ -203,7 +216,7 @@ impl ResourceTables<'_> {
     ///
     /// Note that this is the same as `resource_lower_own`.
     pub fn resource_new(&mut self, resource: TypedResource) -> Result<u32> {
-        self.table_for_resource(&resource)
+        self.table_for_insert(&resource)?
             .resource_own_insert(resource)
     }
 
@@ -247,7 +260,7 @@ impl ResourceTables<'_> {
     ///
     /// This is an implementation of the canonical ABI `lower_own` function.
     pub fn resource_lower_own(&mut self, resource: TypedResource) -> Result<u32> {
-        self.table_for_resource(&resource)
+        self.table_for_insert(&resource)?
             .resource_own_insert(resource)
     }
 
@@ -305,6 +318,7 @@ impl ResourceTables<'_> {
     /// tracking entirely.
     pub fn resource_lower_borrow(&mut self, resource: TypedResource) -> Result<u32> {
         let scope = self.current_scope_id()?;
+        self.table_for_insert(&resource)?;
         let cx = self.task_state.call_context(scope)?;
         cx.borrow_count = cx.borrow_count.checked_add(1).unwrap();
         self.table_for_resource(&resource)
@@ -244,6 +244,39 @@ impl ModuleMemoryImages {
 
         Ok(Some(ModuleMemoryImages { memories }))
     }
+
+    /// Create a new `ModuleMemoryImages` from explicitly provided contents of
+    /// each defined memory rather than from a module's data segments.
+    ///
+    /// Each item of `images` describes one defined memory, in order. An item
+    /// is `None` if the memory starts out as all zeros, or otherwise the
+    /// host-page-aligned offset in linear memory at which to map the
+    /// host-page-aligned range of `source.wasm_data()`.
+    ///
+    /// If the platform cannot create an image for a memory then that memory's
+    /// entry is `None` and the caller is responsible for initializing it.
+    pub fn from_images(
+        engine: &Engine,
+        source: &Arc<impl ModuleMemoryImageSource>,
+        images: impl ExactSizeIterator<Item = Option<(usize, Range<usize>)>>,
+    ) -> Result<Option<ModuleMemoryImages>> {
+        let mut memories = TryPrimaryMap::with_capacity(images.len())?;
+        let page_size = u32::try_from(crate::runtime::vm::host_page_size()).unwrap();
+        for image in images {
+            let image = match image {
+                Some((offset, data_range)) => {
+                    let offset = HostAlignedByteCount::new(offset)
+                        .expect("memory image offset is a multiple of the host page size");
+                    MemoryImage::new(engine, page_size, offset, source, data_range)?
+                        .map(try_new::<Arc<_>>)
+                        .transpose()?
+                }
+                None => None,
+            };
+            memories.push(image)?;
+        }
+        Ok(Some(ModuleMemoryImages { memories }))
+    }
 }
 
 /// Slot management of a copy-on-write image which can be reused for the pooling
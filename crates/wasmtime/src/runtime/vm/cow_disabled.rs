@@ -31,6 +31,14 @@ impl ModuleMemoryImages {
     ) -> Result<Option<ModuleMemoryImages>> {
         Ok(None)
     }
+
+    pub fn from_images(
+        _engine: &Engine,
+        _source: &Arc<impl ModuleMemoryImageSource>,
+        _images: impl ExactSizeIterator<Item = Option<(usize, core::ops::Range<usize>)>>,
+    ) -> Result<Option<ModuleMemoryImages>> {
+        Ok(None)
+    }
 }
 
 #[derive(Debug)]
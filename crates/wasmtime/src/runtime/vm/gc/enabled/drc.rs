@@ -1385,6 +1385,7 @@ mod tests {
             num_defined_tags: 0,
             num_escaped_funcs: 0,
             num_runtime_data: 0,
+            num_tier_up_counters: 0,
             has_startup_func: false,
         });
 
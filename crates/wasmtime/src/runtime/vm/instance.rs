@@ -138,6 +138,14 @@ pub struct Instance {
     #[cfg(feature = "wmemcheck")]
     pub(crate) wmemcheck_state: Option<Wmemcheck>,
 
+    /// Code recompiled by tiered compilation that this instance's defined
+    /// functions have been switched over to, if any.
+    ///
+    /// This is a different compilation of the same module as `runtime_info`
+    /// and is also registered in the store.
+    #[cfg(feature = "winch")]
+    tiered_code: Option<crate::Module>,
+
     /// Self-pointer back to `Store<T>` and its functions. Not present for
     /// the brief time that `Store<T>` is itself being created. Also not
     /// present for some niche uses that are disconnected from stores (e.g.
@@ -208,6 +216,8 @@ impl Instance {
             passive_elements,
             #[cfg(feature = "wmemcheck")]
             wmemcheck_state,
+            #[cfg(feature = "winch")]
+            tiered_code: None,
             store: None,
             vmctx: OwnedVMContext::new(),
         })?;
@@ -960,12 +970,13 @@ impl Instance {
         // it's better for instantiation performance if we don't have to track
         // "is-initialized" state at all!
 
-        let module_with_code = ModuleWithCode::in_store(
-            registry,
-            self.runtime_module()
-                .expect("funcref impossible in fake module"),
-        )
-        .expect("module not in store");
+        let module = self
+            .runtime_module()
+            .expect("funcref impossible in fake module");
+        #[cfg(feature = "winch")]
+        let module = self.tiered_code.as_ref().unwrap_or(module);
+        let module_with_code =
+            ModuleWithCode::in_store(registry, module).expect("module not in store");
 
         let array_call =
             VmPtr::from(NonNull::from(module_with_code.function(array_to_wasm_key)).cast());
@@ -1001,6 +1012,53 @@ impl Instance {
         Some(func_ref_ptr)
     }
 
+    /// Handles a request from `func` to be recompiled by tiered compilation.
+    ///
+    /// This resets the entry counter of `func` and returns the most recent
+    /// recompilation of this instance's module if this instance isn't using
+    /// it yet.
+    #[cfg(feature = "winch")]
+    pub(crate) fn tier_up(
+        mut self: Pin<&mut Self>,
+        func: DefinedFuncIndex,
+    ) -> Option<crate::Module> {
+        let offset = self.offsets().tier_up_counters().at(func);
+        // SAFETY: the offset is that of the `u32` counter of a defined
+        // function within this instance's `VMContext`.
+        unsafe {
+            *self.as_mut().vmctx_plus_offset_mut::<u32>(offset) = 0;
+        }
+
+        let code = self.runtime_module()?.tier_up(func)?;
+        match &self.tiered_code {
+            Some(current) if crate::Module::same(current, &code) => None,
+            _ => Some(code),
+        }
+    }
+
+    /// Switches the defined functions of this instance over to `code`, a
+    /// recompilation of this instance's module by tiered compilation.
+    ///
+    /// The `code` must already be registered in `registry`. Calls between
+    /// functions of this instance, as well as calls through function
+    /// references, go through the `VMFuncRef`s that are updated here, so they
+    /// pick up the new code on their next call.
+    #[cfg(feature = "winch")]
+    pub(crate) fn set_tiered_code(
+        mut self: Pin<&mut Self>,
+        registry: &ModuleRegistry,
+        code: crate::Module,
+    ) {
+        // SAFETY: not moving data out of `self`.
+        unsafe {
+            self.as_mut().get_unchecked_mut().tiered_code = Some(code);
+        }
+        for i in 0..self.env_module().num_defined_funcs() {
+            self.as_mut()
+                .initialize_defined_funcref(registry, DefinedFuncIndex::new(i));
+        }
+    }
+
     /// Get the passive elements segment at the given index.
     pub(crate) fn passive_element_segment(
         self: Pin<&mut Self>,
@@ -1063,6 +1121,35 @@ impl Instance {
         self.wasm_data(range)
     }
 
+    /// Returns the current length of the runtime segment identified by
+    /// `index`, which is zero if the segment has been dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out-of-bounds.
+    pub(crate) fn runtime_data_len(&self, index: RuntimeDataIndex) -> u32 {
+        let offsets = self.runtime_info.offsets();
+        // SAFETY: `at` panics on out-of-bounds indices and the length of a
+        // runtime data segment is always stored as a `u32`.
+        unsafe { *self.vmctx_plus_offset(offsets.runtime_data_lengths().at(index)) }
+    }
+
+    /// Drops the runtime segment identified by `index`, the same as the
+    /// `data.drop` instruction.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out-of-bounds.
+    pub(crate) fn drop_runtime_data(self: Pin<&mut Self>, index: RuntimeDataIndex) {
+        let offsets = self.runtime_info.offsets();
+        // SAFETY: `at` panics on out-of-bounds indices and the length of a
+        // runtime data segment is always stored as a `u32`.
+        unsafe {
+            self.vmctx_plus_offset_raw(offsets.runtime_data_lengths().at(index))
+                .write(0u32);
+        }
+    }
+
     /// Get a table by index regardless of whether it is locally-defined
     /// or an imported, foreign table. Ensure that the given range of
     /// elements in the table is lazily initialized.  We define this
@@ -1269,6 +1356,24 @@ impl Instance {
         // eagerly construct each element in it whenever asked for a reference
         // to that element. In other words, there is no state needed to track
         // the lazy-init, so we don't need to initialize any state now.
+        //
+        // The exception is tiered compilation, where compiled code calls
+        // defined functions through their funcrefs, so those are initialized
+        // here along with the functions' entry counters.
+        if module.num_tier_up_counters > 0 {
+            // SAFETY: the counters are `u32`s within the vmctx, one per
+            // defined function, which is safe to initialize here.
+            unsafe {
+                let offsets = instance.runtime_info.offsets();
+                let ptr = instance.vmctx_plus_offset_raw::<u32>(offsets.tier_up_counters().begin());
+                ptr::write_bytes(ptr.as_ptr(), 0, module.num_tier_up_counters);
+            }
+            for i in 0..module.num_defined_funcs() {
+                instance
+                    .as_mut()
+                    .initialize_defined_funcref(store.modules(), DefinedFuncIndex::new(i));
+            }
+        }
 
         // Initialize the defined tables
         //
@@ -1441,6 +1546,21 @@ impl Instance {
         fault
     }
 
+    /// Returns the total size, in bytes, of all linear memories defined by
+    /// this instance.
+    pub fn defined_memories_byte_size(&self) -> usize {
+        self.memories
+            .iter()
+            .map(|(_, (_, memory))| memory.byte_size())
+            .sum()
+    }
+
+    /// Returns the total number of elements of all tables defined by this
+    /// instance.
+    pub fn defined_tables_size(&self) -> usize {
+        self.tables.iter().map(|(_, (_, table))| table.size()).sum()
+    }
+
     /// Returns the id, within this instance's store, that it's assigned.
     pub fn id(&self) -> InstanceId {
         self.id
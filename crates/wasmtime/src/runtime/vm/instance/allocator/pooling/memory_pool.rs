@@ -435,6 +435,10 @@ impl MemoryPool {
             base_capacity.byte_count(),
             slot,
             request.limiter.as_deref_mut(),
+            match memory_index {
+                Some(_) => MemoryKind::LinearMemory,
+                None => MemoryKind::GcHeap,
+            },
         )
         .await?;
         guard.active = false;
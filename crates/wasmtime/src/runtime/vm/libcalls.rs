@@ -692,6 +692,14 @@ fn out_of_gas(store: &mut dyn VMStore, _instance: InstanceId) -> Result<()> {
 fn new_epoch(store: &mut dyn VMStore, _instance: InstanceId) -> Result<NextEpoch> {
     use crate::UpdateDeadline;
 
+    // If this epoch was only observed to check the store's CPU-time budget,
+    // and neither it nor the store's epoch deadline has been exhausted, then
+    // continue executing without invoking the epoch-deadline behavior.
+    #[cfg(all(feature = "std", target_has_atomic = "64"))]
+    if let Some(deadline) = store.check_cpu_time_budget() {
+        return Ok(NextEpoch(deadline));
+    }
+
     #[cfg(feature = "debug")]
     {
         store.block_on_debug_handler(crate::DebugEvent::EpochYield)?;
@@ -735,6 +743,8 @@ fn new_epoch(store: &mut dyn VMStore, _instance: InstanceId) -> Result<NextEpoch
 
         // Set a new deadline and return the new epoch deadline so
         // the Wasm code doesn't have to reload it.
+        #[cfg(all(feature = "std", target_has_atomic = "64"))]
+        store.restart_cpu_time_budget();
         store.set_epoch_deadline(delta);
         Ok(NextEpoch(store.get_epoch_deadline()))
     })?
@@ -1145,3 +1155,20 @@ fn breakpoint(store: &mut dyn VMStore, _instance: InstanceId) -> Result<()> {
     let _ = store;
     Ok(())
 }
+
+fn tier_up(store: &mut dyn VMStore, instance: InstanceId, func: u32) -> Result<()> {
+    #[cfg(feature = "winch")]
+    {
+        let func = wasmtime_environ::DefinedFuncIndex::from_u32(func);
+        let store = store.store_opaque_mut();
+        if let Some(code) = store.instance_mut(instance).tier_up(func) {
+            let (modules, engine, breakpoints) = store.modules_and_engine_and_breakpoints_mut();
+            modules.register_module(&code, engine, breakpoints)?;
+            let (instance, registry) = store.instance_and_module_registry_mut(instance);
+            instance.set_tiered_code(registry, code);
+        }
+    }
+    // Avoid unused-argument warnings in builds without Winch.
+    let _ = (store, instance, func);
+    Ok(())
+}
@@ -246,7 +246,7 @@ impl Memory {
         limiter: Option<&mut StoreResourceLimiter<'_>>,
         kind: MemoryKind,
     ) -> Result<Self> {
-        let (minimum, maximum) = Self::limit_new(ty, limiter).await?;
+        let (minimum, maximum) = Self::limit_new(ty, limiter, kind).await?;
         let tunables = engine.tunables();
         let memory_tunables = MemoryTunables::new(tunables, kind);
         let allocation = creator.new_memory(ty, &memory_tunables, minimum, maximum)?;
@@ -268,8 +268,9 @@ impl Memory {
         base_capacity: usize,
         memory_image: MemoryImageSlot,
         limiter: Option<&mut StoreResourceLimiter<'_>>,
+        kind: MemoryKind,
     ) -> Result<Self> {
-        let (minimum, maximum) = Self::limit_new(ty, limiter).await?;
+        let (minimum, maximum) = Self::limit_new(ty, limiter, kind).await?;
         let pooled_memory = StaticMemory::new(base, base_capacity, minimum, maximum)?;
         let allocation = try_new::<Box<_>>(pooled_memory)?;
 
@@ -294,11 +295,15 @@ impl Memory {
 
     /// Calls the `store`'s limiter to optionally prevent a memory from being allocated.
     ///
+    /// The limiter is informed of the allocation as growth of the GC heap if
+    /// `kind` is `MemoryKind::GcHeap`.
+    ///
     /// Returns a tuple of the minimum size, optional maximum size, and log(page
     /// size) of the memory, all in bytes.
     pub(crate) async fn limit_new(
         ty: &wasmtime_environ::Memory,
         limiter: Option<&mut StoreResourceLimiter<'_>>,
+        kind: MemoryKind,
     ) -> Result<(usize, Option<usize>)> {
         let page_size = usize::try_from(ty.page_size()).unwrap();
 
@@ -342,10 +347,13 @@ impl Memory {
         // informing the limiter is lossy and may not be 100% accurate, but for
         // now the expected uses of limiter means that's ok.
         if let Some(limiter) = limiter {
-            if !limiter
-                .memory_growing(0, minimum.unwrap_or(absolute_max), maximum)
-                .await?
-            {
+            let desired = minimum.unwrap_or(absolute_max);
+            let allowed = match kind {
+                #[cfg(feature = "gc")]
+                MemoryKind::GcHeap => limiter.gc_heap_growing(0, desired, maximum).await?,
+                _ => limiter.memory_growing(0, desired, maximum).await?,
+            };
+            if !allowed {
                 bail!(
                     "memory minimum size of {} pages exceeds memory limits",
                     ty.limits.min
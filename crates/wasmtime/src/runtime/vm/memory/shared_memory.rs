@@ -39,7 +39,11 @@ impl SharedMemory {
         );
         // Note that without a limiter being passed to `limit_new` this
         // `assert_ready` should never panic.
-        let (minimum_bytes, maximum_bytes) = vm::assert_ready(Memory::limit_new(ty, None))?;
+        let (minimum_bytes, maximum_bytes) = vm::assert_ready(Memory::limit_new(
+            ty,
+            None,
+            wasmtime_environ::MemoryKind::LinearMemory,
+        ))?;
         let mmap_memory = MmapMemory::new(ty, &memory_tunables, minimum_bytes, maximum_bytes)?;
         let boxed: Box<dyn crate::runtime::vm::RuntimeLinearMemory> =
             try_new::<Box<_>>(mmap_memory)?;
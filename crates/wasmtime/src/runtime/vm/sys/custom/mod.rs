@@ -51,3 +51,11 @@ pub fn component_async_tls_get() -> *mut u8 {
 pub fn component_async_tls_set(ptr: *mut u8) {
     unsafe { capi::wasmtime_tls_set(1, ptr) }
 }
+
+/// Returns the CPU time consumed by the current thread, if available.
+///
+/// This isn't supported on custom platforms at this time.
+#[cfg(feature = "std")]
+pub fn thread_cpu_time() -> Option<core::time::Duration> {
+    None
+}
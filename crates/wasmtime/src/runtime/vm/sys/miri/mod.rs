@@ -12,3 +12,8 @@ pub mod vm;
 #[path = "../std_tls.rs"]
 mod std_tls;
 pub use std_tls::*;
+
+/// Returns the CPU time consumed by the current thread, if available.
+pub fn thread_cpu_time() -> Option<core::time::Duration> {
+    None
+}
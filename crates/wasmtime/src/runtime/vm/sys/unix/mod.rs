@@ -24,3 +24,12 @@ use crate::vm::pagemap_disabled as pagemap;
 #[path = "../std_tls.rs"]
 mod std_tls;
 pub use std_tls::*;
+
+/// Returns the CPU time consumed by the current thread, if available.
+pub fn thread_cpu_time() -> Option<core::time::Duration> {
+    let ts = rustix::time::clock_gettime(rustix::time::ClockId::ThreadCPUTime);
+    Some(core::time::Duration::new(
+        ts.tv_sec.try_into().ok()?,
+        ts.tv_nsec.try_into().ok()?,
+    ))
+}
@@ -18,3 +18,33 @@ compile_error!("don't know how to unwind non-64 bit platforms");
 #[path = "../std_tls.rs"]
 mod std_tls;
 pub use std_tls::*;
+
+/// Returns the CPU time consumed by the current thread, if available.
+pub fn thread_cpu_time() -> Option<core::time::Duration> {
+    use windows_sys::Win32::Foundation::FILETIME;
+    use windows_sys::Win32::System::Threading::{GetCurrentThread, GetThreadTimes};
+
+    let mut creation = FILETIME::default();
+    let mut exit = FILETIME::default();
+    let mut kernel = FILETIME::default();
+    let mut user = FILETIME::default();
+    // SAFETY: `GetCurrentThread` returns a pseudo-handle which is always
+    // valid for the calling thread, and all other arguments point to
+    // `FILETIME`s on the stack which the call fills in.
+    let rc = unsafe {
+        GetThreadTimes(
+            GetCurrentThread(),
+            &mut creation,
+            &mut exit,
+            &mut kernel,
+            &mut user,
+        )
+    };
+    if rc == 0 {
+        return None;
+    }
+    // `FILETIME` values are measured in units of 100 nanoseconds.
+    let ticks = |t: FILETIME| (u64::from(t.dwHighDateTime) << 32) | u64::from(t.dwLowDateTime);
+    let nanos = (ticks(kernel) + ticks(user)).checked_mul(100)?;
+    Some(core::time::Duration::from_nanos(nanos))
+}
@@ -66,6 +66,19 @@ impl Compiler {
         self.contexts.lock().unwrap().push(context);
     }
 
+    /// Returns whether the execution profile of `translation` has counts for
+    /// the body of the function in `data`.
+    fn is_hot(&self, translation: &ModuleTranslation, data: &FunctionBodyData) -> bool {
+        let Some(profile) = &translation.execution_profile else {
+            return false;
+        };
+        let range = data.body.range();
+        match (u32::try_from(range.start), u32::try_from(range.end)) {
+            (Ok(start), Ok(end)) => profile.covers(start..end),
+            _ => false,
+        }
+    }
+
     /// Emit unwind info into the [`CompiledFunction`].
     fn emit_unwind_info(
         &self,
@@ -123,6 +136,15 @@ impl wasmtime_environ::Compiler for Compiler {
         let (module_index, def_func_index) = key.unwrap_defined_wasm_function();
         debug_assert_eq!(module_index, translation.module_index());
 
+        // With tiered compilation, functions that the runtime found to be hot
+        // are listed in the execution profile and are recompiled with
+        // Cranelift, using the Winch calling convention.
+        if self.tunables.tiered_compilation && self.is_hot(translation, &data) {
+            return self
+                .trampolines
+                .compile_function(translation, key, data, types, symbol);
+        }
+
         let index = translation.module.func_index(def_func_index);
         let sig = translation.module.functions[index]
             .signature
@@ -138,6 +160,7 @@ impl wasmtime_environ::Compiler for Compiler {
             .compile_function(
                 ty,
                 &body,
+                def_func_index,
                 translation,
                 types,
                 &mut context.builtins,
@@ -96,6 +96,14 @@ enum Subcommand {
 
     #[cfg(feature = "wizer")]
     Wizer(wasmtime_cli::commands::WizerCommand),
+
+    /// Inspect WebAssembly components.
+    #[cfg(feature = "component")]
+    Component(wasmtime_cli::commands::ComponentCommand),
+
+    /// Interactively invoke the exports of a WebAssembly module or component.
+    #[cfg(feature = "repl")]
+    Repl(wasmtime_cli::commands::ReplCommand),
 }
 
 impl Wasmtime {
@@ -139,6 +147,12 @@ impl Wasmtime {
 
             #[cfg(feature = "wizer")]
             Subcommand::Wizer(c) => c.execute(),
+
+            #[cfg(feature = "component")]
+            Subcommand::Component(c) => c.execute(),
+
+            #[cfg(feature = "repl")]
+            Subcommand::Repl(c) => c.execute(),
         }
     }
 }
@@ -45,6 +45,16 @@ mod hot_blocks;
 #[cfg(all(feature = "hot-blocks", target_os = "linux"))]
 pub use self::hot_blocks::*;
 
+#[cfg(feature = "component")]
+mod component;
+#[cfg(feature = "component")]
+pub use self::component::*;
+
+#[cfg(feature = "repl")]
+mod repl;
+#[cfg(feature = "repl")]
+pub use self::repl::*;
+
 #[cfg(feature = "wizer")]
 mod wizer;
 #[cfg(feature = "wizer")]
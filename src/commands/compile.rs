@@ -42,6 +42,12 @@ pub struct CompileCommand {
     #[arg(long = "emit-clif", value_name = "PATH")]
     pub emit_clif: Option<PathBuf>,
 
+    /// The path of an execution profile used to lay out the compiled code,
+    /// with one `<wasm-offset> <count>` pair per line, such as one written by
+    /// `wasmtime hot-blocks --profile-out`.
+    #[arg(long = "execution-profile", value_name = "PATH")]
+    pub execution_profile: Option<PathBuf>,
+
     /// The path of the WebAssembly to compile
     #[arg(index = 1, value_name = "MODULE")]
     pub module: PathBuf,
@@ -80,6 +86,9 @@ impl CompileCommand {
 
         let mut code = CodeBuilder::new(&engine);
         code.wasm_binary_or_text_file(&self.module)?;
+        if let Some(path) = &self.execution_profile {
+            code.execution_profile_file(path)?;
+        }
 
         let output = self.output.take().unwrap_or_else(|| {
             let mut output: PathBuf = self.module.file_name().unwrap().into();
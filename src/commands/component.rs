@@ -0,0 +1,400 @@
+//! Implementation of the `wasmtime component` CLI command.
+
+use clap::Parser;
+use std::collections::HashSet;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use wasmparser::{CanonicalFunction, CanonicalOption, Encoding, Payload};
+use wasmtime::component::types::{ComponentFunc, ComponentItem, Type};
+use wasmtime::component::{Component, Linker};
+use wasmtime::{Engine, Error, Result, bail, error::Context as _};
+use wasmtime_cli_flags::CommonOptions;
+
+/// Inspect WebAssembly components.
+#[derive(Parser)]
+pub struct ComponentCommand {
+    #[command(subcommand)]
+    subcommand: ComponentSubcommand,
+}
+
+#[derive(Parser)]
+enum ComponentSubcommand {
+    /// Print the world of a component as WIT.
+    Wit(WitCommand),
+
+    /// Print the imports, exports, core modules and canonical functions of a
+    /// component.
+    Inspect(InspectCommand),
+}
+
+impl ComponentCommand {
+    /// Executes the command.
+    pub fn execute(self) -> Result<()> {
+        match self.subcommand {
+            ComponentSubcommand::Wit(c) => c.execute(),
+            ComponentSubcommand::Inspect(c) => c.execute(),
+        }
+    }
+}
+
+/// Print the world of a component as WIT.
+#[derive(Parser)]
+pub struct WitCommand {
+    /// The path of the WebAssembly component.
+    #[arg(required = true, value_name = "COMPONENT")]
+    component: PathBuf,
+}
+
+impl WitCommand {
+    /// Executes the command.
+    pub fn execute(self) -> Result<()> {
+        let bytes = read_component(&self.component)?;
+        let decoded = wit_component::decode(&bytes)
+            .map_err(Error::from_anyhow)
+            .with_context(|| format!("failed to decode {}", self.component.display()))?;
+        let mut printer = wit_component::WitPrinter::default();
+        printer
+            .print(decoded.resolve(), decoded.package(), &[])
+            .map_err(Error::from_anyhow)?;
+        print!("{}", printer.output);
+        Ok(())
+    }
+}
+
+/// Print the imports, exports, core modules and canonical functions of a
+/// component.
+#[derive(Parser)]
+pub struct InspectCommand {
+    #[command(flatten)]
+    common: CommonOptions,
+
+    /// The path of the WebAssembly component.
+    #[arg(required = true, value_name = "COMPONENT")]
+    component: PathBuf,
+}
+
+impl InspectCommand {
+    /// Executes the command.
+    pub fn execute(mut self) -> Result<()> {
+        self.common.init_logging()?;
+
+        let bytes = read_component(&self.component)?;
+        let mut config = self.common.config(None)?;
+        config.wasm_component_model(true);
+        let engine = Engine::new(&config)?;
+        let component = Component::new(&engine, &bytes)
+            .with_context(|| format!("failed to compile {}", self.component.display()))?;
+
+        let hosts = self.host_support(&engine, &component)?;
+        let ty = component.component_type();
+
+        println!("imports:");
+        for (name, item) in ty.imports(&engine) {
+            let provided_by = hosts
+                .iter()
+                .filter(|(_, support)| support.defined.contains(name))
+                .map(|(host, _)| format!("`{host}`"))
+                .collect::<Vec<_>>();
+            if provided_by.is_empty() {
+                print_item(&engine, 1, name, &item.ty, "");
+            } else {
+                let note = format!(" (provided by {})", provided_by.join(", "));
+                print_item(&engine, 1, name, &item.ty, &note);
+            }
+        }
+
+        println!("exports:");
+        for (name, item) in ty.exports(&engine) {
+            print_item(&engine, 1, name, &item.ty, "");
+        }
+
+        print_sections(&bytes)?;
+
+        if !hosts.is_empty() {
+            println!("hosts:");
+        }
+        for (host, support) in hosts.iter() {
+            match &support.check {
+                Ok(()) => println!("  `{host}`: all imports satisfied"),
+                Err(e) => println!("  `{host}`: {e:#}"),
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns which imports of `component` the host interfaces of `wasmtime
+    /// run` and `wasmtime serve` provide with their default options.
+    fn host_support(
+        &self,
+        engine: &Engine,
+        component: &Component,
+    ) -> Result<Vec<(&'static str, HostSupport)>> {
+        #[allow(unused_mut, reason = "only mutated with some features enabled")]
+        let mut hosts = Vec::new();
+
+        #[cfg(feature = "run")]
+        {
+            let mut run = crate::commands::RunCommand::try_parse_from([
+                OsStr::new("run"),
+                self.component.as_os_str(),
+            ])?;
+            let main = crate::common::RunTarget::Component(component.clone());
+            if let (_, crate::commands::CliLinker::Component(linker)) =
+                run.new_store_and_linker(engine, &main)?
+            {
+                hosts.push(("wasmtime run", HostSupport::new(&linker, component)));
+            }
+        }
+
+        #[cfg(feature = "serve")]
+        {
+            let serve = crate::commands::ServeCommand::try_parse_from([
+                OsStr::new("serve"),
+                self.component.as_os_str(),
+            ])?;
+            hosts.push(("wasmtime serve", serve.host_support(engine, component)?));
+        }
+
+        #[cfg(not(any(feature = "run", feature = "serve")))]
+        let _ = (engine, component);
+
+        Ok(hosts)
+    }
+}
+
+/// Which imports of a component are provided by a host's [`Linker`].
+pub(crate) struct HostSupport {
+    /// The names of imports defined by the host.
+    defined: HashSet<String>,
+    /// The result of type-checking all imports against the host.
+    check: Result<()>,
+}
+
+impl HostSupport {
+    pub(crate) fn new<T: 'static>(linker: &Linker<T>, component: &Component) -> HostSupport {
+        let defined = component
+            .component_type()
+            .imports(linker.engine())
+            .map(|(name, _)| name)
+            .filter(|name| linker.is_defined(name))
+            .map(|name| name.to_string())
+            .collect();
+        let check = linker.substituted_component_type(component).map(drop);
+        HostSupport { defined, check }
+    }
+}
+
+fn read_component(path: &Path) -> Result<Vec<u8>> {
+    let bytes = std::fs::read(path)
+        .with_context(|| format!("failed to read component: {}", path.display()))?;
+    #[cfg(feature = "wat")]
+    let bytes = wat::parse_bytes(&bytes)
+        .map_err(|mut e| {
+            e.set_path(path);
+            e
+        })?
+        .into_owned();
+    if !wasmparser::Parser::is_component(&bytes) {
+        bail!("`{}` is not a WebAssembly component", path.display());
+    }
+    Ok(bytes)
+}
+
+/// Prints the component item `ty` named `name`, and any items nested within
+/// it, at the `depth` level of indentation.
+fn print_item(engine: &Engine, depth: usize, name: &str, ty: &ComponentItem, note: &str) {
+    let indent = "  ".repeat(depth);
+    match ty {
+        ComponentItem::ComponentFunc(f) => println!("{indent}{name}: {}{note}", func(f)),
+        ComponentItem::CoreFunc(_) => println!("{indent}{name}: core func{note}"),
+        ComponentItem::Module(m) => println!(
+            "{indent}{name}: core module ({} imports, {} exports){note}",
+            m.imports(engine).len(),
+            m.exports(engine).len(),
+        ),
+        ComponentItem::Component(_) => println!("{indent}{name}: component{note}"),
+        ComponentItem::ComponentInstance(i) => {
+            println!("{indent}{name}: instance{note}");
+            for (name, item) in i.exports(engine) {
+                print_item(engine, depth + 1, name, &item.ty, "");
+            }
+        }
+        ComponentItem::Type(t) => println!("{indent}{name}: type {}{note}", value_type(t)),
+        ComponentItem::Resource(_) => println!("{indent}{name}: resource{note}"),
+    }
+}
+
+/// Renders the component function type `f` in WIT syntax.
+fn func(f: &ComponentFunc) -> String {
+    let params = f
+        .params()
+        .map(|(name, ty)| format!("{name}: {}", value_type(&ty)))
+        .collect::<Vec<_>>();
+    let mut s = format!(
+        "{}func({})",
+        if f.async_() { "async " } else { "" },
+        params.join(", ")
+    );
+    for result in f.results() {
+        s.push_str(" -> ");
+        s.push_str(&value_type(&result));
+    }
+    s
+}
+
+/// Renders the component value type `ty` in WIT syntax.
+///
+/// Component types are structural, so named types such as records are
+/// rendered with their full definition.
+fn value_type(ty: &Type) -> String {
+    let list = |items: Vec<String>| items.join(", ");
+    match ty {
+        Type::Bool => "bool".to_string(),
+        Type::S8 => "s8".to_string(),
+        Type::U8 => "u8".to_string(),
+        Type::S16 => "s16".to_string(),
+        Type::U16 => "u16".to_string(),
+        Type::S32 => "s32".to_string(),
+        Type::U32 => "u32".to_string(),
+        Type::S64 => "s64".to_string(),
+        Type::U64 => "u64".to_string(),
+        Type::Float32 => "f32".to_string(),
+        Type::Float64 => "f64".to_string(),
+        Type::Char => "char".to_string(),
+        Type::String => "string".to_string(),
+        Type::List(l) => format!("list<{}>", value_type(&l.ty())),
+        Type::FixedLengthList(l) => format!("list<{}, {}>", value_type(&l.ty()), l.len()),
+        Type::Map(m) => format!("map<{}, {}>", value_type(&m.key()), value_type(&m.value())),
+        Type::Record(r) => format!(
+            "record {{ {} }}",
+            list(
+                r.fields()
+                    .map(|f| format!("{}: {}", f.name, value_type(&f.ty)))
+                    .collect()
+            )
+        ),
+        Type::Tuple(t) => format!(
+            "tuple<{}>",
+            list(t.types().map(|t| value_type(&t)).collect())
+        ),
+        Type::Variant(v) => format!(
+            "variant {{ {} }}",
+            list(
+                v.cases()
+                    .map(|c| match &c.ty {
+                        Some(ty) => format!("{}({})", c.name, value_type(ty)),
+                        None => c.name.to_string(),
+                    })
+                    .collect()
+            )
+        ),
+        Type::Enum(e) => format!("enum {{ {} }}", list(e.names().map(String::from).collect())),
+        Type::Flags(f) => format!(
+            "flags {{ {} }}",
+            list(f.names().map(String::from).collect())
+        ),
+        Type::Option(o) => format!("option<{}>", value_type(&o.ty())),
+        Type::Result(r) => match (r.ok(), r.err()) {
+            (None, None) => "result".to_string(),
+            (Some(ok), None) => format!("result<{}>", value_type(&ok)),
+            (None, Some(err)) => format!("result<_, {}>", value_type(&err)),
+            (Some(ok), Some(err)) => {
+                format!("result<{}, {}>", value_type(&ok), value_type(&err))
+            }
+        },
+        Type::Own(_) => "own<resource>".to_string(),
+        Type::Borrow(_) => "borrow<resource>".to_string(),
+        Type::Future(f) => match f.ty() {
+            Some(ty) => format!("future<{}>", value_type(&ty)),
+            None => "future".to_string(),
+        },
+        Type::Stream(s) => match s.ty() {
+            Some(ty) => format!("stream<{}>", value_type(&ty)),
+            None => "stream".to_string(),
+        },
+        Type::ErrorContext => "error-context".to_string(),
+    }
+}
+
+/// Prints the core modules and canonical functions defined within the
+/// component `bytes`, including those of nested components.
+fn print_sections(bytes: &[u8]) -> Result<()> {
+    let mut modules = Vec::new();
+    let mut canonicals = Vec::new();
+
+    // Tracks the encoding of the module or component currently being parsed,
+    // along with the number of modules seen so far in each component.
+    let mut stack = Vec::new();
+    for payload in wasmparser::Parser::new(0).parse_all(bytes) {
+        match payload? {
+            Payload::Version { encoding, .. } => stack.push((encoding, 0)),
+            Payload::End(_) => {
+                stack.pop();
+            }
+            Payload::ModuleSection {
+                unchecked_range, ..
+            } => {
+                let depth = stack.len();
+                let (_, count) = stack.last_mut().unwrap();
+                modules.push((depth, *count, unchecked_range.len()));
+                *count += 1;
+            }
+            Payload::ComponentCanonicalSection(reader) => {
+                debug_assert!(matches!(stack.last(), Some((Encoding::Component, _))));
+                for func in reader {
+                    canonicals.push((stack.len(), canonical_function(&func?)));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    println!("core modules:");
+    for (depth, index, size) in modules {
+        println!("{}module {index}: {size} bytes", "  ".repeat(depth));
+    }
+    println!("canonical functions:");
+    for (depth, desc) in canonicals {
+        println!("{}{desc}", "  ".repeat(depth));
+    }
+    Ok(())
+}
+
+/// Describes the canonical function `func`, including its canonical options.
+fn canonical_function(func: &CanonicalFunction) -> String {
+    match func {
+        CanonicalFunction::Lift {
+            core_func_index,
+            type_index,
+            options,
+        } => format!(
+            "lift core func {core_func_index} as type {type_index}{}",
+            canonical_options(options)
+        ),
+        CanonicalFunction::Lower {
+            func_index,
+            options,
+        } => format!("lower func {func_index}{}", canonical_options(options)),
+        other => format!("{other:?}"),
+    }
+}
+
+fn canonical_options(options: &[CanonicalOption]) -> String {
+    let mut s = String::new();
+    for option in options {
+        s.push(' ');
+        match option {
+            CanonicalOption::UTF8 => s.push_str("string-encoding=utf8"),
+            CanonicalOption::UTF16 => s.push_str("string-encoding=utf16"),
+            CanonicalOption::CompactUTF16 => s.push_str("string-encoding=latin1+utf16"),
+            CanonicalOption::Memory(i) => s.push_str(&format!("memory={i}")),
+            CanonicalOption::Realloc(i) => s.push_str(&format!("realloc={i}")),
+            CanonicalOption::PostReturn(i) => s.push_str(&format!("post-return={i}")),
+            CanonicalOption::Async => s.push_str("async"),
+            CanonicalOption::Callback(i) => s.push_str(&format!("callback={i}")),
+            CanonicalOption::CoreType(i) => s.push_str(&format!("core-type={i}")),
+            CanonicalOption::Gc => s.push_str("gc"),
+        }
+    }
+    s
+}
@@ -51,6 +51,15 @@ pub struct HotBlocksCommand {
     #[clap(short, long)]
     output: Option<PathBuf>,
 
+    /// Also write the samples to this file as an execution profile.
+    ///
+    /// The profile has one `<offset> <count>` line for each Wasm instruction
+    /// with samples, giving its module-relative byte offset and the number of
+    /// samples in the code compiled from it. It can be passed to `wasmtime
+    /// compile --execution-profile` to guide code layout.
+    #[clap(long, value_name = "PATH")]
+    profile_out: Option<PathBuf>,
+
     /// The WebAssembly module or component to profile.
     #[arg(required = true, value_name = "MODULE")]
     module: PathBuf,
@@ -189,6 +198,15 @@ impl HotBlocksCommand {
             &mut *output,
         )?;
 
+        if let Some(path) = &self.profile_out {
+            let file = std::fs::File::create(path)
+                .with_context(|| format!("failed to create profile file: {}", path.display()))?;
+            let mut file = BufWriter::new(file);
+            let counts = count_wasm_offset_samples(&samples, &functions, &address_map);
+            write_execution_profile(&counts, &mut file)?;
+            file.flush()?;
+        }
+
         Ok(())
     }
 
@@ -694,6 +712,51 @@ fn find_wasm_offset_for_address(
         .and_then(|(_, wasm_off)| *wasm_off)
 }
 
+/// Count the samples in the code compiled from each Wasm instruction, keyed
+/// by the instruction's module-relative byte offset.
+///
+/// `address_map` must be sorted by code offset, as a module's address map is.
+fn count_wasm_offset_samples(
+    samples: &[PerfSample],
+    functions: &[ModuleFunction],
+    address_map: &[(usize, Option<u32>)],
+) -> BTreeMap<u32, u64> {
+    let mut counts = BTreeMap::new();
+    for sample in samples {
+        let Some(func) = find_function_for_sample(sample, functions) else {
+            continue;
+        };
+        let offset_in_func = usize::try_from(sample.offset).unwrap();
+        if offset_in_func >= func.len {
+            continue;
+        }
+        let addr = func.offset + offset_in_func;
+        let i = address_map.partition_point(|&(code_offset, _)| code_offset <= addr);
+        let Some(&(code_offset, Some(wasm_offset))) = i.checked_sub(1).map(|i| &address_map[i])
+        else {
+            continue;
+        };
+        // Skip entries from before this function.
+        if code_offset < func.offset {
+            continue;
+        }
+        *counts.entry(wasm_offset).or_default() += 1;
+    }
+    counts
+}
+
+/// Write sample counts in the textual format of execution profiles.
+fn write_execution_profile(counts: &BTreeMap<u32, u64>, output: &mut dyn Write) -> Result<()> {
+    writeln!(
+        output,
+        "# Wasm offset and sample count of each sampled instruction."
+    )?;
+    for (offset, count) in counts {
+        writeln!(output, "{offset:#x} {count}")?;
+    }
+    Ok(())
+}
+
 /// Build a map from wasm bytecode offset to WAT disassembly text using wasmprinter.
 fn build_wat_offset_map(wasm_bytes: &[u8]) -> BTreeMap<u32, String> {
     let mut map = BTreeMap::new();
@@ -1133,6 +1196,7 @@ mod test {
             event: Event::CpuCycles,
             frequency: None,
             output: None,
+            profile_out: None,
             module: PathBuf::from("dummy.wasm"),
             module_args: Vec::new(),
         };
@@ -1160,6 +1224,68 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_execution_profile() {
+        let functions = vec![
+            ModuleFunction {
+                module: StaticModuleIndex::from_u32(0),
+                index: FuncIndex::from_u32(0),
+                name: None,
+                offset: 0x100,
+                len: 0x10,
+            },
+            ModuleFunction {
+                module: StaticModuleIndex::from_u32(0),
+                index: FuncIndex::from_u32(1),
+                name: None,
+                offset: 0x110,
+                len: 0x10,
+            },
+        ];
+
+        let address_map = vec![
+            (0x100, Some(0x20u32)),
+            (0x104, Some(0x22)),
+            (0x108, None),
+            (0x10c, Some(0x24)),
+            (0x114, Some(0x30)),
+        ];
+
+        let sample = |func: u32, offset: u64| PerfSample {
+            symbol: format!("wasm[0]::function[{func}]"),
+            offset,
+        };
+        let samples = vec![
+            sample(0, 0x0),
+            sample(0, 0x5),
+            sample(0, 0x6),
+            // No Wasm offset.
+            sample(0, 0x9),
+            // Before the first address map entry of the function.
+            sample(1, 0x2),
+            sample(1, 0x4),
+            // Past the end of the function.
+            sample(1, 0x10),
+            sample(2, 0x0),
+        ];
+
+        let counts = count_wasm_offset_samples(&samples, &functions, &address_map);
+        assert_eq!(
+            counts.into_iter().collect::<Vec<_>>(),
+            [(0x20, 1), (0x22, 2), (0x30, 1)]
+        );
+
+        let mut counts = BTreeMap::new();
+        counts.insert(0x20, 3);
+        counts.insert(0x1234, 40);
+        let mut output = Vec::new();
+        write_execution_profile(&counts, &mut output).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "# Wasm offset and sample count of each sampled instruction.\n0x20 3\n0x1234 40\n"
+        );
+    }
+
     #[test]
     fn test_find_function_binary_search() {
         let functions = vec![
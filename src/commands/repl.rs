@@ -0,0 +1,683 @@
+//! The module that implements the `wasmtime repl` command.
+
+#![cfg_attr(
+    not(feature = "component-model"),
+    allow(irrefutable_let_patterns, unreachable_patterns)
+)]
+
+use crate::commands::run::{
+    CliLinker, Host, Preloads, RunCommand, display_core_val, parse_core_arg,
+};
+use crate::common::{RunCommon, RunTarget};
+use clap::Parser;
+use std::collections::BTreeMap;
+use std::io::{BufRead, IsTerminal};
+use std::path::PathBuf;
+use std::time::Instant;
+use wasmtime::{Result, Store, bail, error::Context as _, format_err};
+
+/// Interactively invoke the exports of a WebAssembly module or component.
+///
+/// The module or component is instantiated once and then each line read from
+/// stdin is evaluated as a call to one of its exported functions, such as
+/// `add(1, 2)`. For components arguments and results are encoded with WAVE,
+/// for example `your:pkg/iface.func("hello", [1, 2])`.
+///
+/// The result of a call can be saved into a variable with `let NAME = CALL`
+/// and passed to later calls as `$NAME`, which is how resource handles are
+/// passed between calls. Variables can only be passed as a whole argument, not
+/// nested within another value such as a list or record.
+///
+/// When stdin is a terminal, pressing tab completes the names of exported
+/// functions, variables, and REPL commands. Type `:help` to list the other
+/// commands available in the REPL.
+#[derive(Parser)]
+pub struct ReplCommand {
+    #[command(flatten)]
+    #[expect(missing_docs, reason = "don't want to mess with clap doc-strings")]
+    pub run: RunCommon,
+
+    /// The WebAssembly module or component to instantiate.
+    #[arg(required = true, value_name = "WASM")]
+    pub module: PathBuf,
+}
+
+const HELP: &str = "\
+Commands:
+  NAME(ARGS...)           invoke the exported function NAME
+  let VAR = NAME(ARGS...) invoke NAME and save its result as `$VAR`, which
+                          can then be passed as a whole argument to later calls
+  :exports                list exported functions and their types
+  :complete PREFIX        list completions of PREFIX, as with tab
+  :vars                   list saved variables
+  :help                   show this message
+  :quit                   exit the REPL
+";
+
+impl ReplCommand {
+    /// Executes the command.
+    pub fn execute(self) -> Result<()> {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_time()
+            .enable_io()
+            .build()?;
+
+        runtime.block_on(self.repl())
+    }
+
+    async fn repl(self) -> Result<()> {
+        let mut run = RunCommand {
+            run: self.run,
+            invoke: None,
+            preloads: Preloads::default(),
+            links: Vec::new(),
+            argv0: None,
+            module_and_args: vec![self.module.clone().into()],
+            module_bytes: None,
+        };
+        run.run.common.init_logging()?;
+
+        let engine = run.new_engine()?;
+        let main = run.run.load_module(&engine, &self.module, None)?;
+        let (mut store, linker) = run.new_store_and_linker(&engine, &main)?;
+        let instance = match (linker, main) {
+            (CliLinker::Core(linker), RunTarget::Core(module)) => {
+                let instance = linker
+                    .instantiate_async(&mut store, &module)
+                    .await
+                    .with_context(|| format!("failed to instantiate {:?}", self.module))?;
+                ReplInstance::Core {
+                    instance,
+                    vars: BTreeMap::new(),
+                }
+            }
+            #[cfg(feature = "component-model")]
+            (CliLinker::Component(linker), RunTarget::Component(component)) => {
+                let instance = linker
+                    .instantiate_async(&mut store, &component)
+                    .await
+                    .with_context(|| format!("failed to instantiate {:?}", self.module))?;
+                ReplInstance::Component {
+                    component,
+                    instance,
+                    vars: BTreeMap::new(),
+                }
+            }
+            _ => unreachable!(),
+        };
+
+        let mut repl = Repl {
+            run,
+            store,
+            instance,
+        };
+
+        let mut line = String::new();
+        loop {
+            line.clear();
+            let completer = repl.completer();
+            if !read_line(&mut line, &completer)? {
+                break;
+            }
+
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            } else if line == ":quit" || line == ":q" {
+                break;
+            } else if line == ":help" {
+                print!("{HELP}");
+            } else if line == ":exports" {
+                for (name, ty) in repl.exports() {
+                    println!("{name}: {ty}");
+                }
+            } else if let Some(prefix) = line.strip_prefix(":complete") {
+                for candidate in repl.completer().complete(prefix.trim()).1 {
+                    println!("{candidate}");
+                }
+            } else if line == ":vars" {
+                for (name, val) in repl.vars() {
+                    println!("${name} = {val}");
+                }
+            } else if line.starts_with(':') {
+                eprintln!("error: unknown command `{line}`, see `:help`");
+            } else if let Err(e) = repl.eval(line).await {
+                eprintln!("error: {e:?}");
+            }
+        }
+
+        Ok(())
+    }
+}
+
+struct Repl {
+    run: RunCommand,
+    store: Store<Host>,
+    instance: ReplInstance,
+}
+
+enum ReplInstance {
+    Core {
+        instance: wasmtime::Instance,
+        vars: BTreeMap<String, wasmtime::Val>,
+    },
+    #[cfg(feature = "component-model")]
+    Component {
+        component: wasmtime::component::Component,
+        instance: wasmtime::component::Instance,
+        vars: BTreeMap<String, wasmtime::component::Val>,
+    },
+}
+
+impl Repl {
+    /// Returns the names of all exported functions along with a description
+    /// of their type.
+    fn exports(&mut self) -> Vec<(String, String)> {
+        match &self.instance {
+            ReplInstance::Core { instance, .. } => {
+                let exports = instance
+                    .exports(&mut self.store)
+                    .map(|export| (export.name().to_string(), export.into_func()))
+                    .collect::<Vec<_>>();
+                exports
+                    .into_iter()
+                    .filter_map(|(name, func)| Some((name, func?.ty(&self.store).to_string())))
+                    .collect()
+            }
+            #[cfg(feature = "component-model")]
+            ReplInstance::Component { component, .. } => {
+                use wasmtime::component::types::ComponentItem;
+                use wasmtime::component::wasm_wave::wasm::DisplayFunc;
+                use wasmtime::component::wit_parser::ItemName;
+
+                let engine = self.store.engine();
+                let mut exports = Vec::new();
+                for (name, item) in component.component_type().exports(engine) {
+                    match item.ty {
+                        ComponentItem::ComponentFunc(f) => {
+                            exports.push((name.to_string(), DisplayFunc(f).to_string()));
+                        }
+                        ComponentItem::ComponentInstance(i) => {
+                            for (leaf, item) in i.exports(engine) {
+                                let ComponentItem::ComponentFunc(f) = item.ty else {
+                                    continue;
+                                };
+                                // Render the function's name in the same way
+                                // that it's parsed as an `ItemName`.
+                                let name = match name.parse::<ItemName>() {
+                                    Ok(mut item_name) => {
+                                        item_name.interface = Some(item_name.name.clone());
+                                        item_name.name = leaf.to_string();
+                                        item_name.to_string()
+                                    }
+                                    Err(_) => format!("{name}.{leaf}"),
+                                };
+                                exports.push((name, DisplayFunc(f).to_string()));
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                exports
+            }
+        }
+    }
+
+    /// Returns a completer for the current exports and variables.
+    fn completer(&mut self) -> Completer {
+        let exports = self.exports().into_iter().map(|(name, _)| name).collect();
+        let vars = self
+            .vars()
+            .into_iter()
+            .map(|(name, _)| format!("${name}"))
+            .collect();
+        Completer { exports, vars }
+    }
+
+    /// Returns the names of all saved variables along with their values.
+    fn vars(&self) -> Vec<(String, String)> {
+        match &self.instance {
+            ReplInstance::Core { vars, .. } => vars
+                .iter()
+                .map(|(name, val)| (name.clone(), display_core_val(val)))
+                .collect(),
+            #[cfg(feature = "component-model")]
+            ReplInstance::Component { vars, .. } => vars
+                .iter()
+                .map(|(name, val)| (name.clone(), display_component_val(val)))
+                .collect(),
+        }
+    }
+
+    /// Evaluates a single line of input, `line`, which is either a call or a
+    /// `let` binding of a call's result.
+    async fn eval(&mut self, line: &str) -> Result<()> {
+        let (var, call) = match line.strip_prefix("let ") {
+            Some(rest) => {
+                let (var, call) = rest
+                    .split_once('=')
+                    .ok_or_else(|| format_err!("expected `let VAR = NAME(ARGS...)`"))?;
+                let var = var.trim();
+                if !is_identifier(var) {
+                    bail!("invalid variable name `{var}`");
+                }
+                (Some(var.to_string()), call.trim())
+            }
+            None => (None, line),
+        };
+        let (name, args) = split_call(call)?;
+        for arg in args.iter() {
+            check_vars(arg)?;
+        }
+
+        let fuel_before = self.store.get_fuel().ok();
+        let start = Instant::now();
+        match &mut self.instance {
+            ReplInstance::Core { instance, vars } => {
+                let func = instance
+                    .get_func(&mut self.store, name)
+                    .ok_or_else(|| format_err!("no func export named `{name}` found"))?;
+                let ty = func.ty(&self.store);
+                check_call(
+                    name,
+                    &var,
+                    ty.params().len(),
+                    args.len(),
+                    ty.results().len(),
+                )?;
+                let params = ty
+                    .params()
+                    .zip(&args)
+                    .map(|(ty, arg)| match arg.strip_prefix('$') {
+                        Some(var) => lookup(vars, var).cloned(),
+                        None => parse_core_arg(&ty, arg),
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+
+                let mut results = vec![wasmtime::Val::null_func_ref(); ty.results().len()];
+                func.call_async(&mut self.store, &params, &mut results)
+                    .await?;
+                for result in results.iter() {
+                    println!("{}", display_core_val(result));
+                }
+                if let (Some(var), Some(result)) = (var, results.pop()) {
+                    vars.insert(var, result);
+                }
+            }
+            #[cfg(feature = "component-model")]
+            ReplInstance::Component {
+                component,
+                instance,
+                vars,
+            } => {
+                use wasmtime::component::Val;
+                use wasmtime::component::wasm_wave::{self, untyped::UntypedFuncCall};
+
+                let item_name = UntypedFuncCall::parse(&format!("{name}()"))
+                    .with_context(|| format!("failed to parse function name `{name}`"))?
+                    .item_name()
+                    .map_err(wasmtime::Error::from_anyhow)?;
+                let (export, ty) =
+                    RunCommand::search_component_funcs(&mut self.store, component, &item_name)?;
+                check_call(
+                    name,
+                    &var,
+                    ty.params().len(),
+                    args.len(),
+                    ty.results().len(),
+                )?;
+                let params = ty
+                    .params()
+                    .zip(&args)
+                    .map(|((param, ty), arg)| match arg.strip_prefix('$') {
+                        Some(var) => lookup(vars, var).cloned(),
+                        None => wasm_wave::from_str::<Val>(&ty, arg)
+                            .with_context(|| format!("failed to parse argument `{param}`")),
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+
+                let func = instance
+                    .get_func(&mut self.store, export)
+                    .expect("found export index");
+                let mut results = vec![Val::Bool(false); ty.results().len()];
+                self.run
+                    .call_component_func(&mut self.store, &params, func, &mut results)
+                    .await?;
+                for result in results.iter() {
+                    println!("{}", display_component_val(result));
+                }
+                if let (Some(var), Some(result)) = (var, results.pop()) {
+                    vars.insert(var, result);
+                }
+            }
+        }
+
+        let elapsed = start.elapsed();
+        match (fuel_before, self.store.get_fuel().ok()) {
+            (Some(before), Some(after)) => eprintln!(
+                "(wall time: {elapsed:?}, fuel consumed: {})",
+                before.saturating_sub(after)
+            ),
+            _ => eprintln!("(wall time: {elapsed:?})"),
+        }
+        Ok(())
+    }
+}
+
+/// Renders the component value `val`, using WAVE where possible.
+///
+/// Values such as resources which can't be represented in WAVE are rendered
+/// with their debug representation instead.
+#[cfg(feature = "component-model")]
+fn display_component_val(val: &wasmtime::component::Val) -> String {
+    wasmtime::component::wasm_wave::to_string(val).unwrap_or_else(|_| format!("{val:?}"))
+}
+
+/// Validates a call to `name` with `args` arguments against its type, and
+/// that its result can be saved in `var` if specified.
+fn check_call(
+    name: &str,
+    var: &Option<String>,
+    params: usize,
+    args: usize,
+    results: usize,
+) -> Result<()> {
+    if params != args {
+        bail!("`{name}` expects {params} argument(s) but {args} were given");
+    }
+    if var.is_some() && results != 1 {
+        bail!("`{name}` returns {results} result(s), only single results can be saved");
+    }
+    Ok(())
+}
+
+/// Returns an error if `arg` refers to a variable anywhere other than as the
+/// whole argument.
+///
+/// Variables are substituted as values rather than as source text, since
+/// values such as resources have no textual form, so they can't be nested
+/// within another value like `[$a, $b]`.
+fn check_vars(arg: &str) -> Result<()> {
+    let mut quote = None;
+    let mut escaped = false;
+    for (i, c) in arg.char_indices() {
+        if let Some(q) = quote {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == q {
+                quote = None;
+            }
+        } else if c == '"' || c == '\'' {
+            quote = Some(c);
+        } else if c == '$' && i != 0 {
+            bail!("`{arg}`: variables can only be passed as a whole argument");
+        }
+    }
+    Ok(())
+}
+
+fn lookup<'a, V>(vars: &'a BTreeMap<String, V>, name: &str) -> Result<&'a V> {
+    vars.get(name)
+        .ok_or_else(|| format_err!("no variable named `${name}`"))
+}
+
+fn is_identifier(s: &str) -> bool {
+    let mut chars = s.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// Splits `call`, of the form `NAME(ARG, ...)`, into the function name and the
+/// source text of each of its arguments.
+///
+/// Arguments are split on top-level commas, that is commas which aren't nested
+/// within brackets or string and character literals.
+fn split_call(call: &str) -> Result<(&str, Vec<&str>)> {
+    let open = call
+        .find('(')
+        .ok_or_else(|| format_err!("expected a call of the form `NAME(ARGS...)`"))?;
+    let name = call[..open].trim();
+    let inner = call[open + 1..]
+        .trim_end()
+        .strip_suffix(')')
+        .ok_or_else(|| format_err!("expected `)` at the end of the call"))?;
+
+    let mut args = Vec::new();
+    let mut depth = 0usize;
+    let mut quote = None;
+    let mut escaped = false;
+    let mut start = 0;
+    for (i, c) in inner.char_indices() {
+        if let Some(q) = quote {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            '"' | '\'' => quote = Some(c),
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => {
+                depth = depth
+                    .checked_sub(1)
+                    .ok_or_else(|| format_err!("unbalanced `{c}` in arguments"))?;
+            }
+            ',' if depth == 0 => {
+                args.push(inner[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    if quote.is_some() || depth != 0 {
+        bail!("unterminated argument in call");
+    }
+    let last = inner[start..].trim();
+    if !last.is_empty() || !args.is_empty() {
+        args.push(last);
+    }
+    Ok((name, args))
+}
+
+const COMMANDS: &[&str] = &[":exports", ":complete", ":vars", ":help", ":quit"];
+
+/// Tab completion of exported function names, variables, and REPL commands.
+struct Completer {
+    exports: Vec<String>,
+    vars: Vec<String>,
+}
+
+impl Completer {
+    /// Returns the byte offset within `line` of the word at its end, and all
+    /// completions of that word.
+    fn complete<'a>(&'a self, line: &str) -> (usize, Vec<&'a str>) {
+        let start = line
+            .rfind(|c: char| c.is_whitespace() || "(),=[]{}".contains(c))
+            .map_or(0, |i| i + 1);
+        let word = &line[start..];
+        let candidates: Vec<&str> = if word.starts_with('$') {
+            self.vars.iter().map(|s| s.as_str()).collect()
+        } else if word.starts_with(':') && start == 0 {
+            COMMANDS.to_vec()
+        } else {
+            self.exports.iter().map(|s| s.as_str()).collect()
+        };
+        let matches = candidates
+            .into_iter()
+            .filter(|c| c.starts_with(word))
+            .collect();
+        (start, matches)
+    }
+}
+
+/// Reads a line of input into `line`, returning `false` at the end of input.
+///
+/// When stdin is a terminal a prompt is printed first, and on Unix the
+/// terminal is switched to raw mode while reading so that tab completes the
+/// word before the cursor with `completer`.
+fn read_line(line: &mut String, completer: &Completer) -> Result<bool> {
+    let stdin = std::io::stdin();
+    if !stdin.is_terminal() {
+        return Ok(stdin.lock().read_line(line)? != 0);
+    }
+    #[cfg(unix)]
+    {
+        raw::read_line(line, completer)
+    }
+    #[cfg(not(unix))]
+    {
+        use std::io::Write;
+
+        let _ = completer;
+        print!(">> ");
+        std::io::stdout().flush()?;
+        Ok(stdin.lock().read_line(line)? != 0)
+    }
+}
+
+/// A minimal line editor for terminals, supporting tab completion, backspace,
+/// and the usual control keys.
+#[cfg(unix)]
+mod raw {
+    use super::Completer;
+    use rustix::termios::{self, LocalModes, OptionalActions, Termios};
+    use std::io::{Read, Write};
+    use wasmtime::Result;
+
+    const PROMPT: &str = ">> ";
+
+    /// Restores the terminal's original settings when dropped.
+    struct RawMode(Termios);
+
+    impl RawMode {
+        fn enable() -> Result<RawMode> {
+            let stdin = std::io::stdin();
+            let original = termios::tcgetattr(&stdin)?;
+            let mut raw = original.clone();
+            raw.local_modes -= LocalModes::ICANON | LocalModes::ECHO | LocalModes::ISIG;
+            termios::tcsetattr(&stdin, OptionalActions::Now, &raw)?;
+            Ok(RawMode(original))
+        }
+    }
+
+    impl Drop for RawMode {
+        fn drop(&mut self) {
+            let _ = termios::tcsetattr(std::io::stdin(), OptionalActions::Now, &self.0);
+        }
+    }
+
+    pub(super) fn read_line(line: &mut String, completer: &Completer) -> Result<bool> {
+        let _raw = RawMode::enable()?;
+        let mut stdin = std::io::stdin().lock();
+        let mut stdout = std::io::stdout().lock();
+        write!(stdout, "{PROMPT}")?;
+        stdout.flush()?;
+
+        let mut buf = Vec::new();
+        let mut byte = [0];
+        loop {
+            if stdin.read(&mut byte)? == 0 {
+                if buf.is_empty() {
+                    return Ok(false);
+                }
+                break;
+            }
+            match byte[0] {
+                b'\r' | b'\n' => break,
+                // Ctrl-D ends input on an empty line.
+                0x04 if buf.is_empty() => {
+                    writeln!(stdout)?;
+                    return Ok(false);
+                }
+                // Ctrl-C discards the current line.
+                0x03 => {
+                    write!(stdout, "^C")?;
+                    buf.clear();
+                    break;
+                }
+                // Backspace removes the last character, including all bytes
+                // of a multi-byte character.
+                0x7f | 0x08 => {
+                    if !buf.is_empty() {
+                        while let Some(b) = buf.pop() {
+                            if b & 0xc0 != 0x80 {
+                                break;
+                            }
+                        }
+                        write!(stdout, "\x08 \x08")?;
+                    }
+                }
+                b'\t' => complete(&mut buf, completer, &mut stdout)?,
+                // Escape sequences, such as those of the arrow keys, aren't
+                // supported and are skipped.
+                0x1b => skip_escape(&mut stdin)?,
+                b if b < 0x20 => {}
+                b => {
+                    buf.push(b);
+                    stdout.write_all(&[b])?;
+                }
+            }
+            stdout.flush()?;
+        }
+        writeln!(stdout)?;
+        line.push_str(&String::from_utf8_lossy(&buf));
+        Ok(true)
+    }
+
+    /// Completes the word at the end of `buf` as far as is unambiguous, or
+    /// lists all completions if it's already ambiguous.
+    fn complete(buf: &mut Vec<u8>, completer: &Completer, stdout: &mut impl Write) -> Result<()> {
+        let text = String::from_utf8_lossy(buf).into_owned();
+        let (start, candidates) = completer.complete(&text);
+        let word = &text[start..];
+        let prefix = common_prefix(&candidates);
+        if prefix.len() > word.len() {
+            let rest = &prefix[word.len()..];
+            buf.extend_from_slice(rest.as_bytes());
+            write!(stdout, "{rest}")?;
+        } else if candidates.len() > 1 {
+            writeln!(stdout)?;
+            for candidate in candidates {
+                writeln!(stdout, "{candidate}")?;
+            }
+            write!(stdout, "{PROMPT}{text}")?;
+        }
+        Ok(())
+    }
+
+    fn common_prefix<'a>(candidates: &[&'a str]) -> &'a str {
+        let Some((first, rest)) = candidates.split_first() else {
+            return "";
+        };
+        let mut len = first.len();
+        for candidate in rest {
+            len = first
+                .bytes()
+                .zip(candidate.bytes())
+                .take(len)
+                .take_while(|(a, b)| a == b)
+                .count();
+        }
+        while !first.is_char_boundary(len) {
+            len -= 1;
+        }
+        &first[..len]
+    }
+
+    /// Skips the rest of an escape sequence whose initial escape byte has
+    /// already been read.
+    fn skip_escape(stdin: &mut impl Read) -> Result<()> {
+        let mut byte = [0];
+        if stdin.read(&mut byte)? == 0 || !matches!(byte[0], b'[' | b'O') {
+            return Ok(());
+        }
+        while stdin.read(&mut byte)? != 0 && !(0x40..=0x7e).contains(&byte[0]) {}
+        Ok(())
+    }
+}
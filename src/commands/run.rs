@@ -60,6 +60,15 @@ pub struct RunCommand {
     #[expect(missing_docs, reason = "don't want to mess with clap doc-strings")]
     pub preloads: Preloads,
 
+    /// Instantiate the given component before the main component and use its
+    /// exports to satisfy imports of the main component.
+    ///
+    /// This option can be specified multiple times. Components are
+    /// instantiated in the order given, so a linked component may itself
+    /// import the exports of components linked before it.
+    #[arg(long = "link", number_of_values = 1, value_name = "COMPONENT_PATH")]
+    pub links: Vec<PathBuf>,
+
     /// Override the value of `argv[0]`, typically the name of the executable of
     /// the application being run.
     ///
@@ -391,6 +400,14 @@ impl RunCommand {
         self.populate_with_wasi(&mut linker, &mut store)?;
         self.run.configure_store(&mut store, |t| &mut t.limits)?;
 
+        if let Some(path) = &self.run.common.debug.trace_calls {
+            match main {
+                RunTarget::Core(_) => bail!("-Dtrace-calls is only supported with components"),
+                #[cfg(feature = "component-model")]
+                RunTarget::Component(_) => trace_component_calls(&mut store, path)?,
+            }
+        }
+
         Ok((store, linker))
     }
 
@@ -466,6 +483,29 @@ impl RunCommand {
                 }
             }
 
+            // Define the exports of the linked components within the linker
+            // for the main component, which instantiates them first.
+            for path in self.links.iter() {
+                let link_target = self.run.load_module(&engine, path, None)?;
+                match (&mut *linker, link_target) {
+                    #[cfg(feature = "component-model")]
+                    (CliLinker::Component(linker), RunTarget::Component(component)) => {
+                        let context = || format!("failed to link component `{}`", path.display());
+                        let pre = linker.instantiate_pre(&component).with_context(context)?;
+                        linker
+                            .define_component_exports_async(&pre)
+                            .with_context(context)?;
+                    }
+                    (_, RunTarget::Core(_)) => {
+                        bail!("core modules cannot be loaded with `--link`")
+                    }
+                    #[cfg(feature = "component-model")]
+                    (CliLinker::Core(_), RunTarget::Component(_)) => {
+                        bail!("--link cannot be used with core modules");
+                    }
+                }
+            }
+
             self.load_main_module(store, linker, &main, profiled_modules)
                 .await
                 .with_context(|| {
@@ -703,7 +743,11 @@ impl RunCommand {
                         main_target.unwrap_core(),
                     )?;
                 }
-                _ => bail!("cannot use `--default-values-unknown-imports` with components"),
+                #[cfg(feature = "component-model")]
+                CliLinker::Component(linker) => {
+                    linker
+                        .define_unknown_imports_as_default_values(main_target.unwrap_component())?;
+                }
             }
         }
 
@@ -819,7 +863,7 @@ impl RunCommand {
     }
 
     #[cfg(feature = "component-model")]
-    async fn call_component_func(
+    pub(crate) async fn call_component_func(
         &self,
         store: &mut Store<Host>,
         params: &[wasmtime::component::Val],
@@ -928,7 +972,7 @@ impl RunCommand {
     }
 
     #[cfg(feature = "component-model")]
-    fn search_component_funcs(
+    pub(crate) fn search_component_funcs(
         store: &mut Store<Host>,
         component: &wasmtime::component::Component,
         item_name: &wasmtime::component::wit_parser::ItemName,
@@ -1039,22 +1083,7 @@ impl RunCommand {
             let val = val
                 .to_str()
                 .ok_or_else(|| format_err!("argument is not valid utf-8: {val:?}"))?;
-            values.push(match ty {
-                // Supports both decimal and hexadecimal notation (with 0x prefix)
-                ValType::I32 => Val::I32(if val.starts_with("0x") || val.starts_with("0X") {
-                    i32::from_str_radix(&val[2..], 16)?
-                } else {
-                    val.parse::<i32>()?
-                }),
-                ValType::I64 => Val::I64(if val.starts_with("0x") || val.starts_with("0X") {
-                    i64::from_str_radix(&val[2..], 16)?
-                } else {
-                    val.parse::<i64>()?
-                }),
-                ValType::F32 => Val::F32(val.parse::<f32>()?.to_bits()),
-                ValType::F64 => Val::F64(val.parse::<f64>()?.to_bits()),
-                t => bail!("unsupported argument type {t:?}"),
-            });
+            values.push(parse_core_arg(&ty, val)?);
         }
 
         // Invoke the function and then afterwards print all the results that came
@@ -1083,23 +1112,7 @@ impl RunCommand {
         }
 
         for result in results {
-            match result {
-                Val::I32(i) => println!("{i}"),
-                Val::I64(i) => println!("{i}"),
-                Val::F32(f) => println!("{}", f32::from_bits(f)),
-                Val::F64(f) => println!("{}", f64::from_bits(f)),
-                Val::V128(i) => println!("{}", i.as_u128()),
-                Val::ExternRef(None) => println!("<null externref>"),
-                Val::ExternRef(Some(_)) => println!("<externref>"),
-                Val::FuncRef(None) => println!("<null funcref>"),
-                Val::FuncRef(Some(_)) => println!("<funcref>"),
-                Val::AnyRef(None) => println!("<null anyref>"),
-                Val::AnyRef(Some(_)) => println!("<anyref>"),
-                Val::ExnRef(None) => println!("<null exnref>"),
-                Val::ExnRef(Some(_)) => println!("<exnref>"),
-                Val::ContRef(None) => println!("<null contref>"),
-                Val::ContRef(Some(_)) => println!("<contref>"),
-            }
+            println!("{}", display_core_val(&result));
         }
 
         Ok(())
@@ -1429,6 +1442,36 @@ impl wasmtime_wasi_tls::WasiTlsView for Host {
     }
 }
 
+/// Writes one JSON object per line to `path` for each call between the host
+/// and a component in `store`, and also logs each call with `tracing`.
+#[cfg(feature = "component-model")]
+fn trace_component_calls(store: &mut Store<Host>, path: &str) -> Result<()> {
+    use std::io::Write;
+    use wasmtime::component::CallTraceEvent;
+
+    let file = std::fs::File::create(path)
+        .with_context(|| format!("failed to create call trace file `{path}`"))?;
+    let mut file = std::io::LineWriter::new(file);
+    store.trace_component_calls(move |trace| {
+        tracing::debug!(target: "wasmtime::trace_calls", "{trace}");
+        let event = match trace.event() {
+            CallTraceEvent::ExportCall => "export-call",
+            CallTraceEvent::ExportReturn => "export-return",
+            CallTraceEvent::ImportCall => "import-call",
+            CallTraceEvent::ImportReturn => "import-return",
+        };
+        let line = serde_json::json!({
+            "event": event,
+            "func": trace.func(),
+            "values": trace.values(),
+        });
+        if let Err(e) = writeln!(file, "{line}") {
+            tracing::warn!("failed to write call trace: {e}");
+        }
+    });
+    Ok(())
+}
+
 #[cfg(feature = "coredump")]
 fn write_core_dump(
     store: &mut Store<Host>,
@@ -1452,3 +1495,44 @@ fn write_core_dump(
         .with_context(|| format!("failed to write core dump file at `{path}`"))?;
     Ok(())
 }
+
+/// Parses the command line argument `val` as a core wasm value of type `ty`.
+pub(crate) fn parse_core_arg(ty: &ValType, val: &str) -> Result<Val> {
+    Ok(match ty {
+        // Supports both decimal and hexadecimal notation (with 0x prefix)
+        ValType::I32 => Val::I32(if val.starts_with("0x") || val.starts_with("0X") {
+            i32::from_str_radix(&val[2..], 16)?
+        } else {
+            val.parse::<i32>()?
+        }),
+        ValType::I64 => Val::I64(if val.starts_with("0x") || val.starts_with("0X") {
+            i64::from_str_radix(&val[2..], 16)?
+        } else {
+            val.parse::<i64>()?
+        }),
+        ValType::F32 => Val::F32(val.parse::<f32>()?.to_bits()),
+        ValType::F64 => Val::F64(val.parse::<f64>()?.to_bits()),
+        t => bail!("unsupported argument type {t:?}"),
+    })
+}
+
+/// Renders the core wasm value `val` for display on the command line.
+pub(crate) fn display_core_val(val: &Val) -> String {
+    match val {
+        Val::I32(i) => i.to_string(),
+        Val::I64(i) => i.to_string(),
+        Val::F32(f) => f32::from_bits(*f).to_string(),
+        Val::F64(f) => f64::from_bits(*f).to_string(),
+        Val::V128(i) => i.as_u128().to_string(),
+        Val::ExternRef(None) => "<null externref>".to_string(),
+        Val::ExternRef(Some(_)) => "<externref>".to_string(),
+        Val::FuncRef(None) => "<null funcref>".to_string(),
+        Val::FuncRef(Some(_)) => "<funcref>".to_string(),
+        Val::AnyRef(None) => "<null anyref>".to_string(),
+        Val::AnyRef(Some(_)) => "<anyref>".to_string(),
+        Val::ExnRef(None) => "<null exnref>".to_string(),
+        Val::ExnRef(Some(_)) => "<exnref>".to_string(),
+        Val::ContRef(None) => "<null contref>".to_string(),
+        Val::ContRef(Some(_)) => "<contref>".to_string(),
+    }
+}
@@ -10,7 +10,7 @@ use std::net::SocketAddr;
 use std::pin::Pin;
 use std::task::{Context, Poll};
 use std::{
-    path::PathBuf,
+    path::{Path, PathBuf},
     sync::{
         Arc, Mutex,
         atomic::{AtomicBool, AtomicU64, Ordering},
@@ -125,6 +125,16 @@ pub struct ServeCommand {
     #[arg(value_name = "WASM", required = true)]
     component: PathBuf,
 
+    /// Instantiate the given component alongside each instance of the main
+    /// component and use its exports to satisfy imports of the main
+    /// component.
+    ///
+    /// This option can be specified multiple times. Components are
+    /// instantiated in the order given, so a linked component may itself
+    /// import the exports of components linked before it.
+    #[arg(long = "link", number_of_values = 1, value_name = "COMPONENT_PATH")]
+    links: Vec<PathBuf>,
+
     /// Maximum number of requests to send to a single component instance before
     /// dropping it.
     ///
@@ -530,6 +540,19 @@ impl ServeCommand {
         Ok(())
     }
 
+    /// Returns which imports of `component` are provided by the host
+    /// interfaces of this command.
+    #[cfg(feature = "component")]
+    pub(crate) fn host_support(
+        &self,
+        engine: &Engine,
+        component: &Component,
+    ) -> Result<super::component::HostSupport> {
+        let mut linker = Linker::<Host>::new(engine);
+        self.add_to_linker(&mut linker)?;
+        Ok(super::component::HostSupport::new(&linker, component))
+    }
+
     async fn serve(mut self) -> Result<()> {
         #[cfg(feature = "debug")]
         let debug_run = self.debugger_setup()?;
@@ -576,12 +599,26 @@ impl ServeCommand {
 
     async fn serve_maybe_debug(
         self,
-        linker: Linker<Host>,
+        mut linker: Linker<Host>,
         component: Component,
         mut debuggee_store: Option<&mut Store<Host>>,
     ) -> Result<()> {
-        let engine = linker.engine();
+        let engine = linker.engine().clone();
         let request_headers = RequestHeaders::parse(&self.headers)?;
+
+        // Linked components are prepared once here and instantiated by the
+        // linker into each store ahead of the main component.
+        for path in self.links.iter() {
+            let context = || format!("failed to link component `{}`", Path::display(path));
+            let component = match self.run.load_module(&engine, path, None)? {
+                RunTarget::Core(_) => bail!("core modules cannot be loaded with `--link`"),
+                RunTarget::Component(c) => c,
+            };
+            let pre = linker.instantiate_pre(&component).with_context(context)?;
+            linker
+                .define_component_exports_async(&pre)
+                .with_context(context)?;
+        }
         let instance = linker.instantiate_pre(&component)?;
         #[cfg(feature = "component-model-async")]
         let instance = match wasmtime_wasi_http::p3::bindings::ServicePre::new(instance.clone()) {
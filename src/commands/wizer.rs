@@ -90,6 +90,7 @@ impl WizerCommand {
             }),
             module_and_args: vec![self.input.clone().into()],
             preloads: self.preloads.clone(),
+            links: Vec::new(),
             module_bytes: None,
         };
         let engine = run.new_engine()?;
@@ -2279,6 +2279,30 @@ start a print 1234
         Ok(())
     }
 
+    #[test]
+    fn component_wit() -> Result<()> {
+        let output = run_wasmtime(&["component", "wit", P2_CLI_HELLO_STDOUT_COMPONENT])?;
+        assert!(output.contains("world root {"), "{output}");
+        assert!(output.contains("import wasi:cli/stdout@0.2"), "{output}");
+        assert!(output.contains("export wasi:cli/run@0.2"), "{output}");
+        Ok(())
+    }
+
+    #[test]
+    fn component_inspect() -> Result<()> {
+        let output = run_wasmtime(&["component", "inspect", P2_CLI_HELLO_STDOUT_COMPONENT])?;
+        assert!(output.contains("imports:\n"), "{output}");
+        assert!(output.contains("exports:\n  wasi:cli/run@0.2"), "{output}");
+        assert!(output.contains("run: func() -> result"), "{output}");
+        assert!(output.contains("core modules:\n"), "{output}");
+        assert!(output.contains("string-encoding=utf8"), "{output}");
+        assert!(
+            output.contains("`wasmtime run`: all imports satisfied"),
+            "{output}"
+        );
+        Ok(())
+    }
+
     #[test]
     fn p2_cli_hello_stdout_invoke() -> Result<()> {
         println!("{P2_CLI_HELLO_STDOUT_COMPONENT}");
@@ -3734,3 +3758,27 @@ fn compile_empty_component_with_debug_info() -> Result<()> {
     assert_eq!(stdout, "");
     Ok(())
 }
+
+#[test]
+fn repl_core_module() -> Result<()> {
+    let mut child = get_wasmtime_command()?
+        .args(&["repl", "tests/all/cli_tests/simple.wat"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    child.stdin.take().unwrap().write_all(
+        b"let x = simple(42)\nsimple($x)\nnope()\necho_f32(1.5)\n:vars\n\
+         simple([$x])\n:complete echo\n:complete $\n",
+    )?;
+    let output = child.wait_with_output()?;
+    assert!(output.status.success(), "{output:#?}");
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "42\n42\n1.5\n$x = 42\necho_f32\necho_f64\n$x\n"
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("nope"));
+    assert!(stderr.contains("whole argument"));
+    Ok(())
+}
@@ -12,6 +12,7 @@ mod r#async;
 mod async_dynamic;
 mod bindgen;
 mod call_hook;
+mod call_trace;
 mod dynamic;
 mod fixed_length_list;
 mod func;
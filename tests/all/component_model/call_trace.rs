@@ -0,0 +1,123 @@
+#![cfg(not(miri))]
+
+use std::sync::{Arc, Mutex};
+use wasmtime::component::*;
+use wasmtime::{Engine, Result, Store};
+
+type Events = Arc<Mutex<Vec<(CallTraceEvent, String, String)>>>;
+
+fn record_calls<T>(store: &mut Store<T>) -> Events {
+    let events = Events::default();
+    let events2 = events.clone();
+    store.trace_component_calls(move |trace| {
+        events2.lock().unwrap().push((
+            trace.event(),
+            trace.func().to_string(),
+            trace.values().to_string(),
+        ));
+    });
+    events
+}
+
+#[test]
+fn trace_exports_and_imports() -> Result<()> {
+    let wat = r#"
+        (component
+            (import "host" (instance $i
+                (export "double" (func (param "x" u32) (result u32)))
+            ))
+            (core func $double (canon lower (func $i "double")))
+            (core module $m
+                (import "" "double" (func $double (param i32) (result i32)))
+                (func (export "run") (param i32) (result i32)
+                    (i32.add (call $double (local.get 0)) (i32.const 1)))
+            )
+            (core instance $m (instantiate $m
+                (with "" (instance (export "double" (func $double))))
+            ))
+            (func (export "run") (param "x" u32) (result u32)
+                (canon lift (core func $m "run")))
+        )
+    "#;
+
+    let engine = Engine::default();
+    let component = Component::new(&engine, wat)?;
+    let mut linker = Linker::new(&engine);
+    linker
+        .instance("host")?
+        .func_wrap("double", |_, (x,): (u32,)| Ok((x * 2,)))?;
+
+    let mut store = Store::new(&engine, ());
+    let events = record_calls(&mut store);
+    let instance = linker.instantiate(&mut store, &component)?;
+    let run = instance.get_typed_func::<(u32,), (u32,)>(&mut store, "run")?;
+    assert_eq!(run.call(&mut store, (20,))?, (41,));
+
+    let events = events.lock().unwrap();
+    let expected = [
+        (CallTraceEvent::ExportCall, "run", "20"),
+        (CallTraceEvent::ImportCall, "host#double", "20"),
+        (CallTraceEvent::ImportReturn, "host#double", "40"),
+        (CallTraceEvent::ExportReturn, "run", "41"),
+    ];
+    assert_eq!(events.len(), expected.len());
+    for ((event, func, values), (e_event, e_func, e_values)) in events.iter().zip(expected) {
+        assert_eq!(*event, e_event);
+        assert_eq!(func, e_func);
+        assert_eq!(values, e_values);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn trace_renders_handles_without_consuming_them() -> Result<()> {
+    let wat = r#"
+        (component
+            (import "host" (instance $i
+                (export "r" (type $r (sub resource)))
+                (export "make" (func (result (own $r))))
+            ))
+            (alias export $i "r" (type $r))
+            (core func $make (canon lower (func $i "make")))
+            (core module $m
+                (import "" "make" (func $make (result i32)))
+                (func (export "run") (result i32) call $make)
+            )
+            (core instance $m (instantiate $m
+                (with "" (instance (export "make" (func $make))))
+            ))
+            (func (export "run") (result (own $r))
+                (canon lift (core func $m "run")))
+        )
+    "#;
+
+    struct R;
+
+    let engine = Engine::default();
+    let component = Component::new(&engine, wat)?;
+    let mut linker = Linker::new(&engine);
+    let mut host = linker.instance("host")?;
+    host.resource("r", ResourceType::host::<R>(), |_, _| Ok(()))?;
+    host.func_wrap("make", |_, ()| Ok((Resource::<R>::new_own(7),)))?;
+
+    let mut store = Store::new(&engine, ());
+    let events = record_calls(&mut store);
+    let instance = linker.instantiate(&mut store, &component)?;
+    let run = instance.get_typed_func::<(), (Resource<R>,)>(&mut store, "run")?;
+    let (r,) = run.call(&mut store, ())?;
+    assert_eq!(r.rep(), 7);
+
+    let events = events.lock().unwrap();
+    assert_eq!(events.len(), 4);
+    let (event, func, imported) = &events[2];
+    assert_eq!(*event, CallTraceEvent::ImportReturn);
+    assert_eq!(func, "host#make");
+    assert!(imported.starts_with("own("), "{imported}");
+    let (event, func, exported) = &events[3];
+    assert_eq!(*event, CallTraceEvent::ExportReturn);
+    assert_eq!(func, "run");
+    assert_eq!(exported, imported);
+
+    Ok(())
+}
@@ -212,6 +212,75 @@ fn linker_fails_to_define_unknown_core_module_imports_as_traps() -> Result<()> {
     Ok(())
 }
 
+const UNKNOWN_IMPORTS_COMPONENT: &str = r#"(component
+    (import "host" (instance $i
+        (export "num" (func (param "x" u32) (result u32)))
+        (export "flag" (func (result bool)))
+    ))
+    (core func $num (canon lower (func $i "num")))
+    (core func $flag (canon lower (func $i "flag")))
+
+    (core module $m
+        (import "" "num" (func $num (param i32) (result i32)))
+        (import "" "flag" (func $flag (result i32)))
+        (func (export "run") (result i32)
+            (i32.add (call $num (i32.const 41)) (call $flag)))
+    )
+    (core instance $m (instantiate $m
+        (with "" (instance
+            (export "num" (func $num))
+            (export "flag" (func $flag))
+        ))
+    ))
+
+    (func (export "run") (result u32) (canon lift (core func $m "run")))
+)"#;
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn linker_defines_unknown_imports_with_handler() -> Result<()> {
+    use wasmtime::component::Val;
+
+    let engine = Engine::default();
+    let mut linker = Linker::<Vec<String>>::new(&engine);
+    let component = Component::new(&engine, UNKNOWN_IMPORTS_COMPONENT)?;
+
+    linker.define_unknown_imports_with(&component, |mut store, name, _ty, params, results| {
+        store.data_mut().push(name.to_string());
+        match (name, params) {
+            ("host#num", [Val::U32(x)]) => results[0] = Val::U32(x + 1),
+            ("host#flag", []) => results[0] = Val::Bool(true),
+            _ => wasmtime::bail!("unexpected call to `{name}`"),
+        }
+        Ok(())
+    })?;
+
+    let mut store = Store::new(&engine, Vec::new());
+    let instance = linker.instantiate(&mut store, &component)?;
+    let run = instance.get_typed_func::<(), (u32,)>(&mut store, "run")?;
+    assert_eq!(run.call(&mut store, ())?, (43,));
+    assert_eq!(store.data(), &["host#num", "host#flag"]);
+
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn linker_defines_unknown_imports_as_default_values() -> Result<()> {
+    let engine = Engine::default();
+    let mut linker = Linker::<()>::new(&engine);
+    let component = Component::new(&engine, UNKNOWN_IMPORTS_COMPONENT)?;
+
+    linker.define_unknown_imports_as_default_values(&component)?;
+
+    let mut store = Store::new(&engine, ());
+    let instance = linker.instantiate(&mut store, &component)?;
+    let run = instance.get_typed_func::<(), (u32,)>(&mut store, "run")?;
+    assert_eq!(run.call(&mut store, ())?, (0,));
+
+    Ok(())
+}
+
 #[test]
 fn open_instance_twice() -> Result<()> {
     let engine = Engine::default();
@@ -228,3 +297,278 @@ fn open_instance_twice() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn define_component_exports() -> Result<()> {
+    let engine = Engine::default();
+    let mut linker = Linker::<()>::new(&engine);
+    let mut store = Store::new(&engine, ());
+
+    let dep = Component::new(
+        &engine,
+        r#"(component
+            (core module $m
+                (func (export "add") (param i32 i32) (result i32)
+                    (i32.add (local.get 0) (local.get 1)))
+            )
+            (core instance $i (instantiate $m))
+            (func $add (param "a" u32) (param "b" u32) (result u32)
+                (canon lift (core func $i "add")))
+            (instance $math (export "add" (func $add)))
+            (export "a:b/math@1.0.0" (instance $math))
+            (export "m" (core module $m))
+        )"#,
+    )?;
+    let dep = linker.instantiate_pre(&dep)?;
+    linker.define_component_exports(&dep)?;
+
+    let main = Component::new(
+        &engine,
+        r#"(component
+            (import "a:b/math@1.0.0" (instance $math
+                (export "add" (func (param "a" u32) (param "b" u32) (result u32)))
+            ))
+            (import "m" (core module (export "add" (func (param i32 i32) (result i32)))))
+            (core func $add (canon lower (func $math "add")))
+            (core module $m
+                (import "" "add" (func $add (param i32 i32) (result i32)))
+                (func (export "run") (result i32)
+                    (call $add (i32.const 40) (i32.const 2)))
+            )
+            (core instance $i (instantiate $m
+                (with "" (instance (export "add" (func $add))))
+            ))
+            (func (export "run") (result u32) (canon lift (core func $i "run")))
+        )"#,
+    )?;
+    let main = linker.instantiate(&mut store, &main)?;
+    let run = main.get_typed_func::<(), (u32,)>(&mut store, "run")?;
+    assert_eq!(run.call(&mut store, ())?, (42,));
+
+    Ok(())
+}
+
+#[test]
+fn define_component_exports_per_store() -> Result<()> {
+    let engine = Engine::default();
+    let mut linker = Linker::<()>::new(&engine);
+
+    let dep = Component::new(
+        &engine,
+        r#"(component
+            (core module $m
+                (global $n (mut i32) (i32.const 0))
+                (func (export "next") (result i32)
+                    (global.set $n (i32.add (global.get $n) (i32.const 1)))
+                    (global.get $n))
+            )
+            (core instance $i (instantiate $m))
+            (func $next (result u32) (canon lift (core func $i "next")))
+            (instance $counter (export "next" (func $next)))
+            (export "a:b/counter" (instance $counter))
+        )"#,
+    )?;
+    let dep = linker.instantiate_pre(&dep)?;
+    linker.define_component_exports(&dep)?;
+
+    let main = Component::new(
+        &engine,
+        r#"(component
+            (import "a:b/counter" (instance $counter
+                (export "next" (func (result u32)))
+            ))
+            (core func $next (canon lower (func $counter "next")))
+            (func (export "next") (result u32) (canon lift (core func $next)))
+        )"#,
+    )?;
+    let main = linker.instantiate_pre(&main)?;
+
+    // Each store gets its own instance of the provider, which is shared by all
+    // components instantiated within that store.
+    let mut store1 = Store::new(&engine, ());
+    let mut store2 = Store::new(&engine, ());
+    let a = main.instantiate(&mut store1)?;
+    let b = main.instantiate(&mut store1)?;
+    let c = main.instantiate(&mut store2)?;
+    let a = a.get_typed_func::<(), (u32,)>(&mut store1, "next")?;
+    let b = b.get_typed_func::<(), (u32,)>(&mut store1, "next")?;
+    let c = c.get_typed_func::<(), (u32,)>(&mut store2, "next")?;
+    assert_eq!(a.call(&mut store1, ())?, (1,));
+    assert_eq!(b.call(&mut store1, ())?, (2,));
+    assert_eq!(c.call(&mut store2, ())?, (1,));
+    assert_eq!(a.call(&mut store1, ())?, (3,));
+
+    Ok(())
+}
+
+#[test]
+fn define_component_exports_rejects_resources() -> Result<()> {
+    let engine = Engine::default();
+    let mut linker = Linker::<()>::new(&engine);
+
+    let dep = Component::new(
+        &engine,
+        r#"(component
+            (type $r (resource (rep i32)))
+            (export "r" (type $r))
+        )"#,
+    )?;
+    let dep = linker.instantiate_pre(&dep)?;
+    let err = linker.define_component_exports(&dep).unwrap_err();
+    assert!(
+        format!("{err:?}").contains("unable to define exported resource `r`"),
+        "{err:?}"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn is_defined() -> Result<()> {
+    let engine = Engine::default();
+    let mut linker = Linker::<()>::new(&engine);
+
+    linker.root().func_wrap("f", |_, (): ()| Ok(()))?;
+    linker.instance("a:b/c@1.0.1")?;
+    assert!(linker.is_defined("f"));
+    assert!(linker.is_defined("a:b/c@1.0.1"));
+    assert!(linker.is_defined("a:b/c@1.0.0"));
+    assert!(!linker.is_defined("a:b/c@2.0.0"));
+    assert!(!linker.is_defined("g"));
+
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn define_wit_world_dynamic() -> Result<()> {
+    use wasmtime::component::wit_parser::Resolve;
+    use wasmtime::component::{ResourceDynamic, Val};
+
+    let mut resolve = Resolve::default();
+    let pkg = resolve.push_str(
+        "test.wit",
+        r#"
+            package test:dynamic;
+
+            interface logger {
+                resource handle {
+                    constructor(x: u32);
+                    get: func() -> u32;
+                }
+                log: func(x: u32) -> u32;
+            }
+
+            world w {
+                import logger;
+                import top: func(x: u32) -> u32;
+            }
+        "#,
+    )?;
+    let world = resolve.select_world(&[pkg], Some("w"))?;
+    let logger = resolve.packages[pkg].interfaces["logger"];
+    let handle = resolve.interfaces[logger].types["handle"];
+    let handle_ty = u32::try_from(handle.index())?;
+
+    let engine = Engine::default();
+    let mut linker = Linker::<Vec<String>>::new(&engine);
+    linker.define_wit_world_dynamic(
+        &resolve,
+        world,
+        move |mut store, import, params, results| {
+            store.data_mut().push(import.to_string());
+            match (import.interface(), import.name(), params) {
+                (Some("test:dynamic/logger"), "[constructor]handle", [Val::U32(x)]) => {
+                    let handle = ResourceDynamic::new_own(x * 2, handle_ty);
+                    results[0] = Val::Resource(handle.try_into_resource_any(&mut store)?);
+                }
+                (Some("test:dynamic/logger"), "[method]handle.get", [Val::Resource(r)]) => {
+                    let handle = ResourceDynamic::try_from_resource_any(*r, &mut store)?;
+                    assert!(!handle.owned());
+                    results[0] = Val::U32(handle.rep());
+                }
+                (Some("test:dynamic/logger"), "[resource-drop]handle", [Val::U32(rep)]) => {
+                    assert_eq!(*rep, 10);
+                }
+                (Some("test:dynamic/logger"), "log", [Val::U32(x)]) => {
+                    results[0] = Val::U32(x + 1);
+                }
+                (None, "top", [Val::U32(x)]) => {
+                    results[0] = Val::U32(x + 2);
+                }
+                _ => wasmtime::bail!("unexpected import `{import}`"),
+            }
+            Ok(())
+        },
+    )?;
+
+    let component = Component::new(
+        &engine,
+        r#"(component
+            (import "test:dynamic/logger" (instance $i
+                (export "handle" (type $h (sub resource)))
+                (export "[constructor]handle" (func (param "x" u32) (result (own $h))))
+                (export "[method]handle.get" (func (param "self" (borrow $h)) (result u32)))
+                (export "log" (func (param "x" u32) (result u32)))
+            ))
+            (import "top" (func $top (param "x" u32) (result u32)))
+            (alias export $i "handle" (type $h))
+
+            (core func $ctor (canon lower (func $i "[constructor]handle")))
+            (core func $get (canon lower (func $i "[method]handle.get")))
+            (core func $log (canon lower (func $i "log")))
+            (core func $top (canon lower (func $top)))
+            (core func $drop (canon resource.drop $h))
+
+            (core module $m
+                (import "" "ctor" (func $ctor (param i32) (result i32)))
+                (import "" "get" (func $get (param i32) (result i32)))
+                (import "" "log" (func $log (param i32) (result i32)))
+                (import "" "top" (func $top (param i32) (result i32)))
+                (import "" "drop" (func $drop (param i32)))
+
+                (func (export "run") (result i32)
+                    (local $h i32)
+                    (local $r i32)
+                    (local.set $h (call $ctor (i32.const 5)))
+                    (local.set $r (call $get (local.get $h)))
+                    (call $drop (local.get $h))
+                    (i32.add
+                        (local.get $r)
+                        (i32.add
+                            (call $log (i32.const 10))
+                            (call $top (i32.const 100)))))
+            )
+            (core instance $m (instantiate $m
+                (with "" (instance
+                    (export "ctor" (func $ctor))
+                    (export "get" (func $get))
+                    (export "log" (func $log))
+                    (export "top" (func $top))
+                    (export "drop" (func $drop))
+                ))
+            ))
+
+            (func (export "run") (result u32)
+                (canon lift (core func $m "run")))
+        )"#,
+    )?;
+
+    let mut store = Store::new(&engine, Vec::new());
+    let instance = linker.instantiate(&mut store, &component)?;
+    let run = instance.get_typed_func::<(), (u32,)>(&mut store, "run")?;
+    let (result,) = run.call(&mut store, ())?;
+    assert_eq!(result, 10 + 11 + 102);
+    assert_eq!(
+        store.data(),
+        &[
+            "test:dynamic/logger#[constructor]handle",
+            "test:dynamic/logger#[method]handle.get",
+            "test:dynamic/logger#[resource-drop]handle",
+            "test:dynamic/logger#log",
+            "top",
+        ]
+    );
+
+    Ok(())
+}
@@ -3,6 +3,7 @@
 use crate::async_functions::{CountPending, PollOnce};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
 use wasmtime::format_err;
 use wasmtime::*;
 use wasmtime_test_macros::wasmtime_test;
@@ -623,3 +624,91 @@ fn gc_during_epoch_callback() -> Result<()> {
     }
     Ok(())
 }
+
+const BUMP_EPOCH_LOOP: &str = r#"
+    (module
+        (import "" "bump_epoch" (func $bump))
+        (func (export "run") (param $n i32)
+            (loop $l
+                (call $bump)
+                (br_if $l (local.tee $n (i32.sub (local.get $n) (i32.const 1)))))))
+"#;
+
+#[test]
+fn cpu_time_budget_requires_epochs() -> Result<()> {
+    let mut store = Store::<()>::default();
+    assert!(store.set_cpu_time_budget(Some(Duration::ZERO)).is_err());
+    assert_eq!(store.cpu_time_budget(), None);
+    Ok(())
+}
+
+#[test]
+fn cpu_time_budget_not_exhausted() -> Result<()> {
+    let engine = build_engine(&mut Config::new())?;
+    let linker = make_env::<()>(&engine);
+    let module = Module::new(&engine, BUMP_EPOCH_LOOP)?;
+    let mut store = Store::new(&engine, ());
+    store.set_epoch_deadline(1_000);
+    store.set_cpu_time_budget(Some(Duration::from_secs(60)))?;
+
+    // Each epoch only checks the budget, which isn't exhausted, so the epoch
+    // deadline isn't reached before the function completes.
+    let instance = linker.instantiate(&mut store, &module)?;
+    let run = instance.get_typed_func::<u32, ()>(&mut store, "run")?;
+    run.call(&mut store, 100)?;
+    let remaining = store.cpu_time_budget().unwrap();
+    assert!(remaining < Duration::from_secs(60));
+
+    // The epoch deadline is still enforced independently of the budget.
+    let err = run.call(&mut store, 1_000).unwrap_err();
+    assert_eq!(err.downcast::<Trap>()?, Trap::Interrupt);
+
+    // Removing the budget restores the previous epoch deadline.
+    store.set_epoch_deadline(1_000);
+    store.set_cpu_time_budget(None)?;
+    assert_eq!(store.cpu_time_budget(), None);
+    run.call(&mut store, 100)?;
+    Ok(())
+}
+
+#[test]
+fn cpu_time_budget_exhausted_traps() -> Result<()> {
+    let engine = build_engine(&mut Config::new())?;
+    let linker = make_env::<()>(&engine);
+    let module = Module::new(&engine, BUMP_EPOCH_LOOP)?;
+    let mut store = Store::new(&engine, ());
+    store.set_epoch_deadline(1_000);
+    store.set_cpu_time_budget(Some(Duration::ZERO))?;
+
+    let instance = linker.instantiate(&mut store, &module)?;
+    let run = instance.get_typed_func::<u32, ()>(&mut store, "run")?;
+    let err = run.call(&mut store, 100).unwrap_err();
+    assert_eq!(err.downcast::<Trap>()?, Trap::Interrupt);
+    Ok(())
+}
+
+#[tokio::test]
+async fn cpu_time_budget_exhausted_yields() -> Result<()> {
+    let engine = build_engine(&mut Config::new())?;
+    let linker = make_env::<usize>(&engine);
+    let module = Module::new(&engine, BUMP_EPOCH_LOOP)?;
+    let mut store = Store::new(&engine, 0);
+    store.set_epoch_deadline(1_000);
+    store.set_cpu_time_budget(Some(Duration::ZERO))?;
+    store.epoch_deadline_callback(|mut cx| {
+        assert_eq!(cx.cpu_time_budget(), Some(Duration::ZERO));
+        *cx.data_mut() += 1;
+        if *cx.data() == 5 {
+            cx.set_cpu_time_budget(Some(Duration::from_secs(60)))?;
+        }
+        Ok(UpdateDeadline::Yield(1_000))
+    });
+
+    let instance = linker.instantiate_async(&mut store, &module).await?;
+    let run = instance.get_typed_func::<u32, ()>(&mut store, "run")?;
+    let (result, yields) = CountPending::new(Box::pin(run.call_async(&mut store, 100))).await;
+    result?;
+    assert_eq!(yields, 5);
+    assert_eq!(*store.data(), 5);
+    Ok(())
+}
@@ -1164,3 +1164,134 @@ fn growth_trap() -> Result<()> {
 
     Ok(())
 }
+
+#[derive(Default)]
+struct GcHeapContext {
+    gc_heap_growths: usize,
+    memory_growths: usize,
+    gc_heap_limit: usize,
+}
+
+impl ResourceLimiter for GcHeapContext {
+    fn memory_growing(&mut self, _: usize, _: usize, _: Option<usize>) -> Result<bool> {
+        self.memory_growths += 1;
+        Ok(true)
+    }
+
+    fn table_growing(&mut self, _: usize, _: usize, _: Option<usize>) -> Result<bool> {
+        Ok(true)
+    }
+
+    fn gc_heap_growing(&mut self, _: usize, desired: usize, _: Option<usize>) -> Result<bool> {
+        self.gc_heap_growths += 1;
+        Ok(desired <= self.gc_heap_limit)
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_custom_gc_heap_limiter() -> Result<()> {
+    let mut config = Config::new();
+    config.wasm_gc(true);
+    let engine = Engine::new(&config)?;
+    let module = Module::new(
+        &engine,
+        r#"(module
+            (type $arr (array (mut i32)))
+            (func (export "alloc") (param i32)
+              (drop (array.new_default $arr (local.get 0))))
+           )"#,
+    )?;
+
+    let mut store = Store::new(
+        &engine,
+        GcHeapContext {
+            gc_heap_limit: 1 << 20,
+            ..Default::default()
+        },
+    );
+    store.limiter(|s| s as &mut dyn ResourceLimiter);
+    let instance = Instance::new(&mut store, &module, &[])?;
+    let alloc = instance.get_typed_func::<u32, ()>(&mut store, "alloc")?;
+
+    // Small allocations fit within the limit.
+    alloc.call(&mut store, 1024)?;
+
+    // Allocations requiring the GC heap to grow beyond the limit fail.
+    assert!(alloc.call(&mut store, 1 << 20).is_err());
+    assert!(store.data().gc_heap_growths > 0);
+    assert!(store.gc_heap_capacity() <= 1 << 20);
+
+    // The GC heap isn't reported as linear memory growth.
+    assert_eq!(store.data().memory_growths, 0);
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_limits_component_instances() -> Result<()> {
+    let engine = Engine::default();
+    let component = component::Component::new(&engine, "(component)")?;
+    let linker = component::Linker::new(&engine);
+
+    let mut store = Store::new(
+        &engine,
+        StoreLimitsBuilder::new().component_instances(2).build(),
+    );
+    store.limiter(|s| s as &mut dyn ResourceLimiter);
+
+    linker.instantiate(&mut store, &component)?;
+    linker.instantiate(&mut store, &component)?;
+    let err = linker.instantiate(&mut store, &component).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("component instance count too high at 3"),
+        "{err:?}"
+    );
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_limits_resource_table_entries() -> Result<()> {
+    let engine = Engine::default();
+    let component = component::Component::new(
+        &engine,
+        r#"
+            (component
+                (type $t' (resource (rep i32)))
+                (export $t "t" (type $t'))
+
+                (core func $t_ctor (canon resource.new $t))
+                (func (export "ctor") (param "x" u32) (result (own $t))
+                    (canon lift (core func $t_ctor)))
+            )
+        "#,
+    )?;
+
+    let mut store = Store::new(
+        &engine,
+        StoreLimitsBuilder::new().resource_table_entries(2).build(),
+    );
+    store.limiter(|s| s as &mut dyn ResourceLimiter);
+    let instance = component::Linker::new(&engine).instantiate(&mut store, &component)?;
+    let ctor = instance.get_typed_func::<(u32,), (component::ResourceAny,)>(&mut store, "ctor")?;
+
+    // Resources owned by the host fill up the host's table.
+    let (a,) = ctor.call(&mut store, (1,))?;
+    let (_b,) = ctor.call(&mut store, (2,))?;
+    let err = ctor.call(&mut store, (3,)).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("resource table entry count too high at 3"),
+        "{err:?}"
+    );
+
+    // Dropping a resource frees up an entry in the table. Note that a new
+    // instance is used as the trap above poisoned the previous instance.
+    a.resource_drop(&mut store)?;
+    let instance = component::Linker::new(&engine).instantiate(&mut store, &component)?;
+    let ctor = instance.get_typed_func::<(u32,), (component::ResourceAny,)>(&mut store, "ctor")?;
+    ctor.call(&mut store, (4,))?;
+    Ok(())
+}
@@ -50,6 +50,7 @@ mod pooling_allocator;
 mod profiling;
 mod pulley;
 mod relocs;
+mod snapshot;
 mod stack_creator;
 mod stack_overflow;
 #[cfg(all(
@@ -650,3 +650,59 @@ fn deserialize_raw_fails_for_native() {
         );
     }
 }
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn execution_profile() -> Result<()> {
+    let engine = Engine::default();
+    let wat = r#"
+        (module
+            (func (export "abs") (param i32) (result i32)
+                (if (result i32) (i32.lt_s (local.get 0) (i32.const 0))
+                    (then (i32.sub (i32.const 0) (local.get 0)))
+                    (else (local.get 0))))
+        )
+    "#;
+
+    // Build a profile from the Wasm offsets in the address map, in which the
+    // first half of the function is hot and the rest never executes.
+    let module = Module::new(&engine, wat)?;
+    let mut offsets = module
+        .address_map()
+        .expect("address maps are enabled by default")
+        .filter_map(|(_, offset)| offset)
+        .collect::<Vec<_>>();
+    offsets.sort();
+    offsets.dedup();
+    let profile = offsets
+        .iter()
+        .enumerate()
+        .map(|(i, offset)| {
+            let count = if i < offsets.len() / 2 { 1000 } else { 0 };
+            format!("{offset:#x} {count}\n")
+        })
+        .collect::<String>();
+
+    let mut code = CodeBuilder::new(&engine);
+    code.wasm_binary_or_text(wat.as_bytes(), None)?;
+    code.execution_profile(&format!("# offset count\n{profile}"))?;
+    let err = code.execution_profile(&profile).unwrap_err();
+    assert!(format!("{err}").contains("twice"), "bad error: {err}");
+    let module = code.compile_module()?;
+
+    let mut store = Store::new(&engine, ());
+    let instance = Instance::new(&mut store, &module, &[])?;
+    let abs = instance.get_typed_func::<i32, i32>(&mut store, "abs")?;
+    assert_eq!(abs.call(&mut store, 5)?, 5);
+    assert_eq!(abs.call(&mut store, -5)?, 5);
+
+    let err = CodeBuilder::new(&engine)
+        .execution_profile("10")
+        .unwrap_err();
+    assert!(
+        format!("{err:?}").contains("expected `<offset> <count>`"),
+        "bad error: {err:?}"
+    );
+
+    Ok(())
+}
@@ -0,0 +1,569 @@
+use wasmtime::*;
+
+const COUNTER: &str = r#"
+    (module
+        (global $g (export "g") (mut i32) (i32.const 0))
+        (memory (export "memory") 1 10)
+        (table (export "table") 1 funcref)
+        (func $start
+            (global.set $g (i32.add (global.get $g) (i32.const 1))))
+        (start $start)
+        (elem declare func $start)
+        (func (export "init")
+            (i32.store (i32.const 100) (i32.const 42))
+            (global.set $g (i32.const 10))
+            (drop (memory.grow (i32.const 1)))
+            (i32.store (i32.const 65536) (i32.const 7))
+            (drop (table.grow (ref.func $start) (i32.const 2)))
+            (table.set (i32.const 0) (ref.func $start)))
+        (func (export "bump") (result i32)
+            (global.set $g (i32.add (global.get $g) (i32.const 1)))
+            (global.get $g))
+    )
+"#;
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn restores_memory_globals_and_tables() -> Result<()> {
+    let engine = Engine::default();
+    let module = Module::new(&engine, COUNTER)?;
+    let mut store = Store::new(&engine, ());
+    let instance = Instance::new(&mut store, &module, &[])?;
+    instance
+        .get_typed_func::<(), ()>(&mut store, "init")?
+        .call(&mut store, ())?;
+    let snapshot = instance.snapshot(&mut store)?;
+    assert!(Module::same(snapshot.module(), &module));
+
+    for _ in 0..2 {
+        let mut store = Store::new(&engine, ());
+        let instance = snapshot.instantiate(&mut store, &[])?;
+
+        // The start function isn't re-run, so the global starts at its
+        // snapshotted value.
+        let g = instance.get_global(&mut store, "g").unwrap();
+        assert_eq!(g.get(&mut store).i32(), Some(10));
+
+        let memory = instance.get_memory(&mut store, "memory").unwrap();
+        assert_eq!(memory.size(&store), 2);
+        let mut buf = [0; 4];
+        memory.read(&store, 100, &mut buf)?;
+        assert_eq!(i32::from_le_bytes(buf), 42);
+        memory.read(&store, 65536, &mut buf)?;
+        assert_eq!(i32::from_le_bytes(buf), 7);
+
+        let table = instance.get_table(&mut store, "table").unwrap();
+        assert_eq!(table.size(&store), 3);
+        for i in 0..3 {
+            assert!(table.get(&mut store, i).unwrap().unwrap_func().is_some());
+        }
+
+        // Instances created from a snapshot are independent of each other.
+        let bump = instance.get_typed_func::<(), i32>(&mut store, "bump")?;
+        assert_eq!(bump.call(&mut store, ())?, 11);
+        memory.write(&mut store, 100, &[0; 4])?;
+    }
+
+    // The original instance is unaffected by the above.
+    let g = instance.get_global(&mut store, "g").unwrap();
+    assert_eq!(g.get(&mut store).i32(), Some(10));
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn funcrefs_resolve_to_new_instance() -> Result<()> {
+    let engine = Engine::default();
+    let module = Module::new(
+        &engine,
+        r#"
+            (module
+                (import "" "f" (func $f (result i32)))
+                (global $g (export "g") (mut i32) (i32.const 0))
+                (global $r (mut funcref) (ref.null func))
+                (table $t 2 funcref)
+                (type $ty (func (result i32)))
+                (elem $e func $get)
+                (elem declare func $f)
+                (func $get (result i32) (global.get $g))
+                (func (export "init")
+                    (global.set $g (i32.const 5))
+                    (global.set $r (ref.func $get))
+                    (table.set $t (i32.const 0) (ref.func $f))
+                    (table.init $t $e (i32.const 1) (i32.const 0) (i32.const 1)))
+                (func (export "call-global") (result i32)
+                    (table.set $t (i32.const 0) (global.get $r))
+                    (call_indirect (type $ty) (i32.const 0)))
+                (func (export "call") (param i32) (result i32)
+                    (call_indirect (type $ty) (local.get 0)))
+                (func (export "reinit")
+                    (table.init $t $e (i32.const 1) (i32.const 0) (i32.const 1)))
+            )
+        "#,
+    )?;
+    let mut store = Store::new(&engine, ());
+    let f = Func::wrap(&mut store, || 1);
+    let instance = Instance::new(&mut store, &module, &[f.into()])?;
+    instance
+        .get_typed_func::<(), ()>(&mut store, "init")?
+        .call(&mut store, ())?;
+    let snapshot = instance.snapshot(&mut store)?;
+
+    let mut store = Store::new(&engine, ());
+    let f = Func::wrap(&mut store, || 2);
+    let instance = snapshot.instantiate(&mut store, &[f.into()])?;
+    instance
+        .get_global(&mut store, "g")
+        .unwrap()
+        .set(&mut store, Val::I32(6))?;
+
+    // Table entries refer to the new imports and new instance.
+    let call = instance.get_typed_func::<i32, i32>(&mut store, "call")?;
+    assert_eq!(call.call(&mut store, 0)?, 2);
+    assert_eq!(call.call(&mut store, 1)?, 6);
+
+    // Passive element segments are restored.
+    instance
+        .get_typed_func::<(), ()>(&mut store, "reinit")?
+        .call(&mut store, ())?;
+    assert_eq!(call.call(&mut store, 1)?, 6);
+
+    // Funcref globals also refer to the new instance.
+    let call_global = instance.get_typed_func::<(), i32>(&mut store, "call-global")?;
+    assert_eq!(call_global.call(&mut store, ())?, 6);
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn dropped_segments_stay_dropped() -> Result<()> {
+    let engine = Engine::default();
+    let module = Module::new(
+        &engine,
+        r#"
+            (module
+                (memory 1)
+                (data $d "abcd")
+                (func (export "drop") (data.drop $d))
+                (func (export "init")
+                    (memory.init $d (i32.const 0) (i32.const 0) (i32.const 4)))
+            )
+        "#,
+    )?;
+    let mut store = Store::new(&engine, ());
+    let instance = Instance::new(&mut store, &module, &[])?;
+    instance
+        .get_typed_func::<(), ()>(&mut store, "drop")?
+        .call(&mut store, ())?;
+    let snapshot = instance.snapshot(&mut store)?;
+
+    let mut store = Store::new(&engine, ());
+    let instance = snapshot.instantiate(&mut store, &[])?;
+    let init = instance.get_typed_func::<(), ()>(&mut store, "init")?;
+    assert!(init.call(&mut store, ()).is_err());
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn linker_instantiate_snapshot() -> Result<()> {
+    let engine = Engine::default();
+    let module = Module::new(
+        &engine,
+        r#"
+            (module
+                (import "host" "get" (func $get (result i32)))
+                (global $g (export "g") (mut i32) (i32.const 0))
+                (func $start (global.set $g (call $get)))
+                (start $start)
+            )
+        "#,
+    )?;
+    let mut linker = Linker::new(&engine);
+    linker.func_wrap("host", "get", |caller: Caller<'_, i32>| *caller.data())?;
+
+    let mut store = Store::new(&engine, 3);
+    let instance = linker.instantiate(&mut store, &module)?;
+    let snapshot = instance.snapshot(&mut store)?;
+
+    let mut store = Store::new(&engine, 4);
+    let instance = linker.instantiate_snapshot(&mut store, &snapshot)?;
+    let g = instance.get_global(&mut store, "g").unwrap();
+    assert_eq!(g.get(&mut store).i32(), Some(3));
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn snapshot_with_pooling_allocator() -> Result<()> {
+    let mut config = Config::new();
+    config.allocation_strategy(PoolingAllocationConfig::default());
+    let engine = Engine::new(&config)?;
+    let module = Module::new(&engine, COUNTER)?;
+    let mut store = Store::new(&engine, ());
+    let instance = Instance::new(&mut store, &module, &[])?;
+    instance
+        .get_typed_func::<(), ()>(&mut store, "init")?
+        .call(&mut store, ())?;
+    let snapshot = instance.snapshot(&mut store)?;
+    drop(store);
+
+    for _ in 0..3 {
+        let mut store = Store::new(&engine, ());
+        let instance = snapshot.instantiate(&mut store, &[])?;
+        let memory = instance.get_memory(&mut store, "memory").unwrap();
+        let mut buf = [0; 4];
+        memory.read(&store, 100, &mut buf)?;
+        assert_eq!(i32::from_le_bytes(buf), 42);
+        memory.write(&mut store, 100, &[1; 4])?;
+    }
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn rejects_shared_memory() -> Result<()> {
+    let mut config = Config::new();
+    config.wasm_threads(true).shared_memory(true);
+    let engine = Engine::new(&config)?;
+    let module = Module::new(&engine, r#"(module (memory 1 1 shared))"#)?;
+    let mut store = Store::new(&engine, ());
+    let instance = Instance::new(&mut store, &module, &[])?;
+    assert!(instance.snapshot(&mut store).is_err());
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn rejects_externref() -> Result<()> {
+    let engine = Engine::default();
+    let module = Module::new(
+        &engine,
+        r#"
+            (module
+                (table (export "t") 1 externref)
+            )
+        "#,
+    )?;
+    let mut store = Store::new(&engine, ());
+    let instance = Instance::new(&mut store, &module, &[])?;
+    assert!(instance.snapshot(&mut store).is_ok());
+
+    let r = ExternRef::new(&mut store, 1)?;
+    instance
+        .get_table(&mut store, "t")
+        .unwrap()
+        .set(&mut store, 0, r.into())?;
+    assert!(instance.snapshot(&mut store).is_err());
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn snapshot_without_memory_init_cow() -> Result<()> {
+    let mut config = Config::new();
+    config.memory_init_cow(false);
+    let engine = Engine::new(&config)?;
+    let module = Module::new(&engine, COUNTER)?;
+    let mut store = Store::new(&engine, ());
+    let instance = Instance::new(&mut store, &module, &[])?;
+    instance
+        .get_typed_func::<(), ()>(&mut store, "init")?
+        .call(&mut store, ())?;
+    let snapshot = instance.snapshot(&mut store)?;
+
+    let mut store = Store::new(&engine, ());
+    let instance = snapshot.instantiate(&mut store, &[])?;
+    let memory = instance.get_memory(&mut store, "memory").unwrap();
+    let mut buf = [0; 4];
+    memory.read(&store, 65536, &mut buf)?;
+    assert_eq!(i32::from_le_bytes(buf), 7);
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn serialize_and_deserialize() -> Result<()> {
+    let engine = Engine::default();
+    let module = Module::new(&engine, COUNTER)?;
+    let mut store = Store::new(&engine, ());
+    let instance = Instance::new(&mut store, &module, &[])?;
+    instance
+        .get_typed_func::<(), ()>(&mut store, "init")?
+        .call(&mut store, ())?;
+    let bytes = instance.snapshot(&mut store)?.serialize()?;
+
+    // Resume the instance on a differently-configured engine.
+    let mut config = Config::new();
+    config.memory_init_cow(false);
+    let engine = Engine::new(&config)?;
+    let module = Module::new(&engine, COUNTER)?;
+    let snapshot = InstanceSnapshot::deserialize(&module, &bytes)?;
+    assert_eq!(snapshot.serialize()?, bytes);
+
+    let mut store = Store::new(&engine, ());
+    let instance = snapshot.instantiate(&mut store, &[])?;
+    let bump = instance.get_typed_func::<(), i32>(&mut store, "bump")?;
+    assert_eq!(bump.call(&mut store, ())?, 11);
+    let memory = instance.get_memory(&mut store, "memory").unwrap();
+    assert_eq!(memory.size(&store), 2);
+    let mut buf = [0; 4];
+    memory.read(&store, 100, &mut buf)?;
+    assert_eq!(i32::from_le_bytes(buf), 42);
+    memory.read(&store, 65536, &mut buf)?;
+    assert_eq!(i32::from_le_bytes(buf), 7);
+    let table = instance.get_table(&mut store, "table").unwrap();
+    assert_eq!(table.size(&store), 3);
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn deserialize_rejects_invalid_input() -> Result<()> {
+    let engine = Engine::default();
+    let module = Module::new(&engine, COUNTER)?;
+    let mut store = Store::new(&engine, ());
+    let instance = Instance::new(&mut store, &module, &[])?;
+    let bytes = instance.snapshot(&mut store)?.serialize()?;
+
+    assert!(InstanceSnapshot::deserialize(&module, b"").is_err());
+    assert!(InstanceSnapshot::deserialize(&module, &bytes[..bytes.len() - 1]).is_err());
+
+    // A snapshot of one module can't be used with a module of a different
+    // shape.
+    let other = Module::new(&engine, r#"(module (memory 1))"#)?;
+    assert!(InstanceSnapshot::deserialize(&other, &bytes).is_err());
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn deserialize_rejects_wrong_module() -> Result<()> {
+    let engine = Engine::default();
+    let module = Module::new(&engine, COUNTER)?;
+    let mut store = Store::new(&engine, ());
+    let instance = Instance::new(&mut store, &module, &[])?;
+    let bytes = instance.snapshot(&mut store)?.serialize()?;
+
+    // A module with the same memories, tables, and globals but different
+    // code doesn't match the snapshot either.
+    let other = Module::new(
+        &engine,
+        &COUNTER.replace("(i32.const 42)", "(i32.const 43)"),
+    )?;
+    assert!(InstanceSnapshot::deserialize(&other, &bytes).is_err());
+    assert!(InstanceSnapshot::deserialize(&module, &bytes).is_ok());
+    Ok(())
+}
+
+const LIBRARY: &str = r#"
+    (module
+        (global $calls (mut i32) (i32.const 0))
+        (func (export "answer") (result i32)
+            (global.set $calls (i32.add (global.get $calls) (i32.const 1)))
+            (i32.add (i32.const 41) (global.get $calls)))
+    )
+"#;
+
+const CLIENT: &str = r#"
+    (module
+        (import "lib" "answer" (func $answer (result i32)))
+        (import "host" "double" (func $double (param i32) (result i32)))
+        (table $t 1 funcref)
+        (global $saved (mut funcref) (ref.null func))
+        (elem declare func $answer)
+        (func (export "init")
+            (table.set $t (i32.const 0) (ref.func $answer))
+            (global.set $saved (ref.func $answer))
+            (drop (call $answer)))
+        (func (export "run") (result i32)
+            (call $double (call_indirect $t (result i32) (i32.const 0))))
+        (func (export "saved") (result funcref)
+            (global.get $saved))
+    )
+"#;
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn store_snapshot_links_instances() -> Result<()> {
+    let engine = Engine::default();
+    let library = Module::new(&engine, LIBRARY)?;
+    let client = Module::new(&engine, CLIENT)?;
+    let mut linker = Linker::new(&engine);
+    linker.func_wrap("host", "double", |x: i32| x * 2)?;
+
+    let bytes = {
+        let mut store = Store::new(&engine, ());
+        let mut linker = linker.clone();
+        let lib = linker.instantiate(&mut store, &library)?;
+        linker.instance(&mut store, "lib", lib)?;
+        let instance = linker.instantiate(&mut store, &client)?;
+        instance
+            .get_typed_func::<(), ()>(&mut store, "init")?
+            .call(&mut store, ())?;
+        store.snapshot()?.serialize()?
+    };
+
+    let snapshot = StoreSnapshot::deserialize(&[client.clone(), library.clone()], &bytes)?;
+    assert_eq!(snapshot.serialize()?, bytes);
+    let mut store = Store::new(&engine, ());
+    let instances = linker.instantiate_store_snapshot(&mut store, &snapshot)?;
+    assert_eq!(instances.len(), 2);
+    let run = instances[1].get_typed_func::<(), i32>(&mut store, "run")?;
+    assert_eq!(run.call(&mut store, ())?, 86);
+    assert_eq!(run.call(&mut store, ())?, 88);
+
+    // The saved funcref refers to the restored library instance.
+    let saved = instances[1]
+        .get_typed_func::<(), Option<Func>>(&mut store, "saved")?
+        .call(&mut store, ())?
+        .unwrap();
+    let answer = instances[0].get_func(&mut store, "answer").unwrap();
+    assert_eq!(saved.typed::<(), i32>(&store)?.call(&mut store, ())?, 45);
+    assert_eq!(answer.typed::<(), i32>(&store)?.call(&mut store, ())?, 46);
+
+    // Without the host import the snapshot can't be restored.
+    let mut store = Store::new(&engine, ());
+    assert!(
+        Linker::new(&engine)
+            .instantiate_store_snapshot(&mut store, &snapshot)
+            .is_err()
+    );
+    assert!(StoreSnapshot::deserialize(&[client], &bytes).is_err());
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn store_snapshot_gc_objects() -> Result<()> {
+    let mut config = Config::new();
+    config.wasm_gc(true);
+    let engine = Engine::new(&config)?;
+    let module = Module::new(
+        &engine,
+        r#"
+            (module
+                (type $node (struct (field $value i32) (field $next (mut (ref null $node)))))
+                (type $list (array (mut (ref null $node))))
+                (type $pair (struct (field (ref $node)) (field (ref $list))))
+                (global $head (mut (ref null $node)) (ref.null $node))
+                (global $pair (mut (ref null $pair)) (ref.null $pair))
+                (global $i31 (mut anyref) (ref.null any))
+                (func (export "init")
+                    (local $a (ref $node))
+                    (local $b (ref $node))
+                    (local.set $a (struct.new $node (i32.const 1) (ref.null $node)))
+                    (local.set $b (struct.new $node (i32.const 2) (local.get $a)))
+                    (struct.set $node $next (local.get $a) (local.get $b))
+                    (global.set $head (local.get $a))
+                    (global.set $pair
+                        (struct.new $pair
+                            (local.get $b)
+                            (array.new_fixed $list 2 (local.get $a) (ref.null $node))))
+                    (global.set $i31 (ref.i31 (i32.const 99))))
+                (func (export "sum") (result i32)
+                    (i32.add
+                        (i32.add
+                            (struct.get $node $value (global.get $head))
+                            (struct.get $node $value
+                                (struct.get $node $next (global.get $head))))
+                        (i32.add
+                            (struct.get $node $value
+                                (struct.get $node $next
+                                    (struct.get $node $next (global.get $head))))
+                            (i31.get_s (ref.cast (ref i31) (global.get $i31))))))
+                (func (export "shared") (result i32)
+                    (ref.eq
+                        (array.get $list
+                            (struct.get $pair 1 (global.get $pair))
+                            (i32.const 0))
+                        (struct.get $node $next
+                            (struct.get $pair 0 (global.get $pair)))))
+            )
+        "#,
+    )?;
+
+    let bytes = {
+        let mut store = Store::new(&engine, ());
+        let instance = Instance::new(&mut store, &module, &[])?;
+        instance
+            .get_typed_func::<(), ()>(&mut store, "init")?
+            .call(&mut store, ())?;
+        store.snapshot()?.serialize()?
+    };
+
+    let snapshot = StoreSnapshot::deserialize(&[module], &bytes)?;
+    let mut store = Store::new(&engine, ());
+    let instances = Linker::new(&engine).instantiate_store_snapshot(&mut store, &snapshot)?;
+    let sum = instances[0].get_typed_func::<(), i32>(&mut store, "sum")?;
+    assert_eq!(sum.call(&mut store, ())?, 1 + 2 + 1 + 99);
+    let shared = instances[0].get_typed_func::<(), i32>(&mut store, "shared")?;
+    assert_eq!(shared.call(&mut store, ())?, 1);
+    Ok(())
+}
+
+const RESOURCES: &str = r#"
+    (component
+        (type $r (resource (rep i32)))
+        (core func $new (canon resource.new $r))
+        (core func $rep (canon resource.rep $r))
+        (core module $m
+            (import "" "new" (func $new (param i32) (result i32)))
+            (import "" "rep" (func $rep (param i32) (result i32)))
+            (global $h (mut i32) (i32.const 0))
+            (func (export "init")
+                (global.set $h (call $new (i32.const 7)))
+                (drop (call $new (i32.const 8))))
+            (func (export "get") (result i32)
+                (call $rep (global.get $h)))
+            (func (export "next") (result i32)
+                (call $new (i32.const 9)))
+        )
+        (core instance $i (instantiate $m
+            (with "" (instance
+                (export "new" (func $new))
+                (export "rep" (func $rep))))))
+        (func (export "init") (canon lift (core func $i "init")))
+        (func (export "get") (result u32) (canon lift (core func $i "get")))
+        (func (export "next") (result u32) (canon lift (core func $i "next")))
+    )
+"#;
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn store_snapshot_component_resources() -> Result<()> {
+    use wasmtime::component::{Component, Linker};
+
+    let engine = Engine::default();
+    let component = Component::new(&engine, RESOURCES)?;
+    let linker = Linker::new(&engine);
+
+    let bytes = {
+        let mut store = Store::new(&engine, ());
+        let instance = linker.instantiate(&mut store, &component)?;
+        instance
+            .get_typed_func::<(), ()>(&mut store, "init")?
+            .call(&mut store, ())?;
+        store.snapshot()?.serialize()?
+    };
+
+    let snapshot = StoreSnapshot::deserialize_with_components(&[], &[component.clone()], &bytes)?;
+    assert_eq!(snapshot.serialize()?, bytes);
+    let mut store = Store::new(&engine, ());
+    let instances = linker.instantiate_store_snapshot(&mut store, &snapshot)?;
+    assert_eq!(instances.len(), 1);
+    let get = instances[0].get_typed_func::<(), (u32,)>(&mut store, "get")?;
+    assert_eq!(get.call(&mut store, ())?, (7,));
+    let next = instances[0].get_typed_func::<(), (u32,)>(&mut store, "next")?;
+    assert_eq!(next.call(&mut store, ())?, (3,));
+
+    // Core instances of components are restored with the component linker.
+    let mut store = Store::new(&engine, ());
+    assert!(
+        wasmtime::Linker::new(&engine)
+            .instantiate_store_snapshot(&mut store, &snapshot)
+            .is_err()
+    );
+    assert!(StoreSnapshot::deserialize(&[], &bytes).is_err());
+    Ok(())
+}
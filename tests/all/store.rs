@@ -1,5 +1,6 @@
 use std::sync::atomic::{AtomicUsize, Ordering::SeqCst};
-use wasmtime::{Engine, Store};
+use std::time::Duration;
+use wasmtime::*;
 
 #[test]
 fn into_inner() {
@@ -20,3 +21,143 @@ fn into_inner() {
     Store::new(&engine, A).into_data();
     assert_eq!(HITS.load(SeqCst), 2);
 }
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn resource_usage() -> Result<()> {
+    let mut config = Config::new();
+    config.consume_fuel(true);
+    let engine = Engine::new(&config)?;
+    let mut store = Store::new(&engine, ());
+
+    let usage = store.resource_usage();
+    assert_eq!(usage.instances(), 0);
+    assert_eq!(usage.memory_bytes(), 0);
+    assert_eq!(usage.table_elements(), 0);
+    assert_eq!(usage.fuel_consumed(), Some(0));
+
+    let module = Module::new(
+        &engine,
+        r#"
+            (module
+                (memory (export "memory") 1)
+                (table 10 funcref)
+                (func (export "grow") (result i32)
+                    (memory.grow (i32.const 2)))
+            )
+        "#,
+    )?;
+    Instance::new(&mut store, &module, &[])?;
+    let instance = Instance::new(&mut store, &module, &[])?;
+    Memory::new(&mut store, MemoryType::new(1, None))?;
+    Table::new(
+        &mut store,
+        TableType::new(RefType::FUNCREF, 5, None),
+        Ref::Func(None),
+    )?;
+
+    let usage = store.resource_usage();
+    assert_eq!(usage.instances(), 2);
+    assert_eq!(usage.memory_bytes(), 3 * 65536);
+    assert_eq!(usage.table_elements(), 25);
+
+    // Fuel consumed is tracked across refueling the store.
+    store.set_fuel(10_000)?;
+    let grow = instance.get_typed_func::<(), i32>(&mut store, "grow")?;
+    grow.call(&mut store, ())?;
+    let consumed = store.resource_usage().fuel_consumed().unwrap();
+    assert!(consumed > 0);
+    assert_eq!(consumed, 10_000 - store.get_fuel()?);
+    store.set_fuel(10_000)?;
+    grow.call(&mut store, ())?;
+    assert_eq!(store.resource_usage().fuel_consumed(), Some(2 * consumed),);
+    assert_eq!(store.resource_usage().memory_bytes(), 7 * 65536);
+
+    // Call statistics aren't collected by default.
+    assert_eq!(store.resource_usage().wasm_calls(), 0);
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn instance_resource_usage() -> Result<()> {
+    let engine = Engine::default();
+    let mut store = Store::new(&engine, ());
+    let module = Module::new(
+        &engine,
+        r#"
+            (module
+                (import "" "memory" (memory 1))
+                (memory (export "memory") 2)
+                (table 10 funcref)
+                (func (export "grow") (result i32)
+                    (memory.grow 1 (i32.const 1)))
+            )
+        "#,
+    )?;
+    let imported = Memory::new(&mut store, MemoryType::new(1, None))?;
+    let a = Instance::new(&mut store, &module, &[imported.into()])?;
+    let b = Instance::new(&mut store, &module, &[imported.into()])?;
+
+    // Imported memories are only counted by the store, not the instance.
+    let usage = a.resource_usage(&store);
+    assert_eq!(usage.memory_bytes(), 2 * 65536);
+    assert_eq!(usage.table_elements(), 10);
+
+    let grow = a.get_typed_func::<(), i32>(&mut store, "grow")?;
+    grow.call(&mut store, ())?;
+    assert_eq!(a.resource_usage(&store).memory_bytes(), 3 * 65536);
+    assert_eq!(b.resource_usage(&store).memory_bytes(), 2 * 65536);
+    assert_eq!(store.resource_usage().memory_bytes(), 6 * 65536);
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn resource_usage_fuel_disabled() -> Result<()> {
+    let store = Store::<()>::default();
+    assert_eq!(store.resource_usage().fuel_consumed(), None);
+    assert_eq!(store.resource_usage().gc_heap_bytes(), 0);
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn resource_usage_calls() -> Result<()> {
+    let engine = Engine::default();
+    let mut store = Store::new(&engine, ());
+    store.track_call_usage(true);
+    let module = Module::new(
+        &engine,
+        r#"
+            (module
+                (import "" "sleep" (func $sleep))
+                (func (export "run")
+                    (call $sleep)
+                    (call $sleep))
+            )
+        "#,
+    )?;
+    let sleep = Func::wrap(&mut store, || std::thread::sleep(Duration::from_millis(10)));
+    let instance = Instance::new(&mut store, &module, &[sleep.into()])?;
+    let run = instance.get_typed_func::<(), ()>(&mut store, "run")?;
+    run.call(&mut store, ())?;
+
+    let usage = store.resource_usage();
+    assert_eq!(usage.wasm_calls(), 1);
+    assert_eq!(usage.host_calls(), 2);
+    assert!(usage.host_time() >= Duration::from_millis(20));
+    assert!(usage.wasm_time() < usage.host_time());
+    if let Some(host_cpu_time) = usage.host_cpu_time() {
+        // Sleeping doesn't consume CPU time.
+        assert!(host_cpu_time < usage.host_time());
+        assert!(usage.wasm_cpu_time().is_some());
+    }
+
+    // Disabling tracking retains what was collected so far.
+    store.track_call_usage(false);
+    run.call(&mut store, ())?;
+    assert_eq!(store.resource_usage().wasm_calls(), 1);
+    assert_eq!(store.resource_usage().host_calls(), 2);
+    Ok(())
+}
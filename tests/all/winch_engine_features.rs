@@ -64,3 +64,113 @@ fn ensure_compatibility_between_winch_and_debug_native(config: &mut Config) -> R
 
     Ok(())
 }
+
+#[wasmtime_test(strategies(only(Winch)))]
+#[cfg(feature = "winch")]
+fn tiered_compilation_requires_winch(config: &mut Config) -> Result<()> {
+    config.tiered_compilation(true);
+    Engine::new(&config)?;
+
+    config.strategy(Strategy::Cranelift);
+    let err = Engine::new(&config).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "tiered compilation requires the Winch compilation strategy"
+    );
+
+    Ok(())
+}
+
+#[wasmtime_test(strategies(only(Winch)))]
+#[cfg(feature = "winch")]
+fn tiered_compilation_recompiles_hot_functions(config: &mut Config) -> Result<()> {
+    use std::time::{Duration, Instant};
+
+    config.tiered_compilation(true);
+    config.tier_up_threshold(10);
+    let engine = Engine::new(&config)?;
+    let module = Module::new(
+        &engine,
+        r#"
+            (module
+                (type $t (func (param i32) (result i32)))
+                (table 1 funcref)
+                (elem (i32.const 0) $fib)
+
+                (func $fib (export "fib") (type $t)
+                    (if (i32.eq (local.get 0) (i32.const -1))
+                        (then unreachable))
+                    (if (result i32) (i32.lt_u (local.get 0) (i32.const 2))
+                        (then (local.get 0))
+                        (else
+                            (i32.add
+                                (call $fib (i32.sub (local.get 0) (i32.const 1)))
+                                (call_indirect (type $t)
+                                    (i32.sub (local.get 0) (i32.const 2))
+                                    (i32.const 0))))))
+
+                (func (export "late") (type $t)
+                    (if (i32.eq (local.get 0) (i32.const -1))
+                        (then unreachable))
+                    (i32.add (local.get 0) (i32.const 1)))
+
+                (func (export "cold") (result i32)
+                    (i32.const 42))
+            )
+        "#,
+    )?;
+
+    // Returns the module whose code `func` traps in when passed `-1`, which
+    // changes when the instance switches to a recompilation.
+    fn trapping_module(store: &mut Store<()>, func: &TypedFunc<u32, u32>) -> Module {
+        let err = func.call(&mut *store, u32::MAX).unwrap_err();
+        let trace = err.downcast_ref::<WasmBacktrace>().unwrap();
+        trace.frames()[0].module().clone()
+    }
+
+    let deadline = Instant::now() + Duration::from_secs(60);
+    let mut store = Store::new(&engine, ());
+    let instance = Instance::new(&mut store, &module, &[])?;
+    let fib = instance.get_typed_func::<u32, u32>(&mut store, "fib")?;
+    let late = instance.get_typed_func::<u32, u32>(&mut store, "late")?;
+    let cold = instance.get_typed_func::<(), u32>(&mut store, "cold")?;
+    assert!(Module::same(&trapping_module(&mut store, &fib), &module));
+
+    // Keep calling the hot function while it's recompiled in the background,
+    // and check that results don't change when switching to optimized code.
+    let tiered = loop {
+        assert_eq!(fib.call(&mut store, 15)?, 610);
+        assert_eq!(cold.call(&mut store, ())?, 42);
+        let current = trapping_module(&mut store, &fib);
+        if !Module::same(&current, &module) {
+            break current;
+        }
+        assert!(Instant::now() < deadline, "`fib` was never recompiled");
+        std::thread::sleep(Duration::from_millis(1));
+    };
+
+    // A function which becomes hot later is recompiled in turn.
+    assert!(Module::same(&trapping_module(&mut store, &late), &tiered));
+    loop {
+        for i in 0..100 {
+            assert_eq!(late.call(&mut store, i)?, i + 1);
+        }
+        if !Module::same(&trapping_module(&mut store, &late), &tiered) {
+            break;
+        }
+        assert!(Instant::now() < deadline, "`late` was never recompiled");
+        std::thread::sleep(Duration::from_millis(1));
+    }
+    assert_eq!(fib.call(&mut store, 15)?, 610);
+    assert_eq!(cold.call(&mut store, ())?, 42);
+
+    // New instances pick up the recompiled code as well.
+    let instance = Instance::new(&mut store, &module, &[])?;
+    let fib = instance.get_typed_func::<u32, u32>(&mut store, "fib")?;
+    for _ in 0..10 {
+        assert_eq!(fib.call(&mut store, 20)?, 6765);
+    }
+    assert!(!Module::same(&trapping_module(&mut store, &fib), &module));
+
+    Ok(())
+}
@@ -126,6 +126,7 @@ struct TestConfig {
     objdump: Option<TestConfigFlags>,
     filter: Option<String>,
     unsafe_intrinsics: Option<String>,
+    execution_profile: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -218,6 +219,9 @@ impl Test {
                 builder.expose_unsafe_intrinsics(name);
             }
         }
+        if let Some(profile) = self.config.execution_profile.as_deref() {
+            builder.execution_profile(profile)?;
+        }
 
         let elf = match builder.hint() {
             Some(CodeHint::Component) => builder
@@ -0,0 +1,61 @@
+;;! target = "x86_64"
+;;! test = "compile"
+;;! execution_profile = """
+;;! 0x2c 10000
+;;! 0x2e 9999
+;;! 0x33 1
+;;! 0x37 10000
+;;! """
+
+;; The execution profile covers only the second function and shows that its
+;; `then` arm is hot and its `else` arm is cold. The first function keeps the
+;; default layout, where the branch falls through to the `else` arm. In the
+;; second, the `then` arm is reached by falling through instead and the
+;; `else` arm is moved to the end of the function.
+
+(module
+  (func $unprofiled (param i32) (result i32)
+    local.get 0
+    if (result i32)
+      i32.const 1
+    else
+      local.get 0
+      i32.const 3
+      i32.mul
+    end
+  )
+
+  (func $profiled (param i32) (result i32)
+    local.get 0
+    if (result i32)
+      i32.const 1
+    else
+      local.get 0
+      i32.const 3
+      i32.mul
+    end
+  )
+)
+;; wasm[0]::function[0]::unprofiled:
+;;       pushq   %rbp
+;;       movq    %rsp, %rbp
+;;       testl   %edx, %edx
+;;       jne     0x14
+;;    c: imull   $3, %edx, %eax
+;;       jmp     0x19
+;;   14: movl    $1, %eax
+;;       movq    %rbp, %rsp
+;;       popq    %rbp
+;;       retq
+;;
+;; wasm[0]::function[1]::profiled:
+;;       pushq   %rbp
+;;       movq    %rsp, %rbp
+;;       testl   %edx, %edx
+;;       je      0x36
+;;   2c: movl    $1, %eax
+;;       movq    %rbp, %rsp
+;;       popq    %rbp
+;;       retq
+;;   36: imull   $3, %edx, %eax
+;;   39: jmp     0x31
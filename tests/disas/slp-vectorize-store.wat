@@ -0,0 +1,129 @@
+;;! target = "x86_64"
+;;! test = "optimize"
+;;! flags = [
+;;!   "-Ccranelift-has-sse41",
+;;!   "-Ccranelift-enable-slp-vectorization",
+;;! ]
+
+;; Element-wise addition of two arrays of four `i32`s in linear memory. The
+;; last element is stored first, so the first of the trapping heap stores traps
+;; exactly when a vector store over all four would, and they're packed.
+
+(module
+  (memory 1)
+
+  (func (export "add4") (param $dst i32) (param $a i32) (param $b i32)
+    (local $x0 i32) (local $x1 i32) (local $x2 i32) (local $x3 i32)
+    (local.set $x0
+      (i32.add (i32.load offset=0 (local.get $a)) (i32.load offset=0 (local.get $b))))
+    (local.set $x1
+      (i32.add (i32.load offset=4 (local.get $a)) (i32.load offset=4 (local.get $b))))
+    (local.set $x2
+      (i32.add (i32.load offset=8 (local.get $a)) (i32.load offset=8 (local.get $b))))
+    (local.set $x3
+      (i32.add (i32.load offset=12 (local.get $a)) (i32.load offset=12 (local.get $b))))
+    (i32.store offset=12 (local.get $dst) (local.get $x3))
+    (i32.store offset=8 (local.get $dst) (local.get $x2))
+    (i32.store offset=4 (local.get $dst) (local.get $x1))
+    (i32.store offset=0 (local.get $dst) (local.get $x0))
+  )
+
+  ;; Storing the first element first could write memory before trapping,
+  ;; which the vector store wouldn't, so this stays scalar.
+  (func (export "add4_in_order") (param $dst i32) (param $a i32) (param $b i32)
+    (local $x0 i32) (local $x1 i32) (local $x2 i32) (local $x3 i32)
+    (local.set $x0
+      (i32.add (i32.load offset=0 (local.get $a)) (i32.load offset=0 (local.get $b))))
+    (local.set $x1
+      (i32.add (i32.load offset=4 (local.get $a)) (i32.load offset=4 (local.get $b))))
+    (local.set $x2
+      (i32.add (i32.load offset=8 (local.get $a)) (i32.load offset=8 (local.get $b))))
+    (local.set $x3
+      (i32.add (i32.load offset=12 (local.get $a)) (i32.load offset=12 (local.get $b))))
+    (i32.store offset=0 (local.get $dst) (local.get $x0))
+    (i32.store offset=4 (local.get $dst) (local.get $x1))
+    (i32.store offset=8 (local.get $dst) (local.get $x2))
+    (i32.store offset=12 (local.get $dst) (local.get $x3))
+  )
+)
+;; function u0:0(i64 vmctx, i64, i32, i32, i32) tail {
+;;     region0 = 8 "VMContext+0x8"
+;;     region1 = 67108888 "VMStoreContext+0x18"
+;;     region2 = 603979776 "VMMemoryDefinition+0x0"
+;;     region3 = 603979784 "VMMemoryDefinition+0x8"
+;;     region4 = 201326592 "DefinedMemory(StaticModuleIndex(0), DefinedMemoryIndex(0))"
+;;     gv0 = vmctx
+;;     gv1 = load.i64 notrap aligned readonly can_move region0 gv0+8
+;;     gv2 = load.i64 notrap aligned region1 gv1+24
+;;     stack_limit = gv2
+;;
+;;                                 block0(v0: i64, v1: i64, v2: i32, v3: i32, v4: i32):
+;; @003f                               v7 = load.i64 notrap aligned readonly can_move region2 v0+56
+;; @003f                               v6 = uextend.i64 v3
+;; @003f                               v8 = iadd v7, v6
+;; @0044                               v10 = uextend.i64 v4
+;; @0044                               v12 = iadd v7, v10
+;; @0075                               v54 = uextend.i64 v2
+;; @0075                               v56 = iadd v7, v54
+;; @008a                               v72 = load.i32x4 little region4 v8
+;; @008a                               v73 = load.i32x4 little region4 v12
+;; @008a                               v74 = iadd v72, v73
+;; @008a                               store little region4 v74, v56
+;; @008d                               jump block1
+;;
+;;                                 block1:
+;; @008d                               return
+;; }
+;;
+;; function u0:1(i64 vmctx, i64, i32, i32, i32) tail {
+;;     region0 = 8 "VMContext+0x8"
+;;     region1 = 67108888 "VMStoreContext+0x18"
+;;     region2 = 603979776 "VMMemoryDefinition+0x0"
+;;     region3 = 603979784 "VMMemoryDefinition+0x8"
+;;     region4 = 201326592 "DefinedMemory(StaticModuleIndex(0), DefinedMemoryIndex(0))"
+;;     gv0 = vmctx
+;;     gv1 = load.i64 notrap aligned readonly can_move region0 gv0+8
+;;     gv2 = load.i64 notrap aligned region1 gv1+24
+;;     stack_limit = gv2
+;;
+;;                                 block0(v0: i64, v1: i64, v2: i32, v3: i32, v4: i32):
+;; @0094                               v7 = load.i64 notrap aligned readonly can_move region2 v0+56
+;; @0094                               v6 = uextend.i64 v3
+;; @0094                               v8 = iadd v7, v6
+;; @0094                               v9 = load.i32 little region4 v8
+;; @0099                               v10 = uextend.i64 v4
+;; @0099                               v12 = iadd v7, v10
+;; @0099                               v13 = load.i32 little region4 v12
+;; @00a1                               v18 = iconst.i64 4
+;; @00a1                               v19 = iadd v8, v18  ; v18 = 4
+;; @00a1                               v20 = load.i32 little region4 v19
+;; @00a6                               v25 = iadd v12, v18  ; v18 = 4
+;; @00a6                               v26 = load.i32 little region4 v25
+;; @00ae                               v31 = iconst.i64 8
+;; @00ae                               v32 = iadd v8, v31  ; v31 = 8
+;; @00ae                               v33 = load.i32 little region4 v32
+;; @00b3                               v38 = iadd v12, v31  ; v31 = 8
+;; @00b3                               v39 = load.i32 little region4 v38
+;; @00bb                               v44 = iconst.i64 12
+;; @00bb                               v45 = iadd v8, v44  ; v44 = 12
+;; @00bb                               v46 = load.i32 little region4 v45
+;; @00c0                               v51 = iadd v12, v44  ; v44 = 12
+;; @00c0                               v52 = load.i32 little region4 v51
+;; @009c                               v14 = iadd v9, v13
+;; @00ca                               v54 = uextend.i64 v2
+;; @00ca                               v56 = iadd v7, v54
+;; @00ca                               store little region4 v14, v56
+;; @00a9                               v27 = iadd v20, v26
+;; @00d1                               v61 = iadd v56, v18  ; v18 = 4
+;; @00d1                               store little region4 v27, v61
+;; @00b6                               v40 = iadd v33, v39
+;; @00d8                               v66 = iadd v56, v31  ; v31 = 8
+;; @00d8                               store little region4 v40, v66
+;; @00c3                               v53 = iadd v46, v52
+;; @00df                               v71 = iadd v56, v44  ; v44 = 12
+;; @00df                               store little region4 v53, v71
+;; @00e2                               jump block1
+;;
+;;                                 block1:
+;; @00e2                               return
+;; }
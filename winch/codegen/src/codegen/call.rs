@@ -70,7 +70,7 @@ use crate::{
     reg::{Reg, writable},
     stack::Val,
 };
-use wasmtime_environ::{DefinedFuncIndex, FuncIndex, PtrSize, VMOffsets};
+use wasmtime_environ::{DefinedFuncIndex, FuncIndex, FuncRefIndex, PtrSize, VMOffsets};
 
 /// All the information needed to emit a function call.
 #[derive(Copy, Clone)]
@@ -160,6 +160,11 @@ impl FnCall {
             Callee::FuncRef(_) => {
                 Self::lower_funcref(env.callee_sig::<M::ABI>(callee)?, ptr, context, masm)
             }
+            Callee::Local(i) if env.tiered_compilation() => {
+                let func_ref = env.translation.module.functions[*i].func_ref;
+                let sig = env.callee_sig::<M::ABI>(callee)?;
+                Self::lower_tiered_local(func_ref, sig, context, masm, vmoffsets)
+            }
             Callee::Local(i) => {
                 let f = env.translation.module.defined_func_index(*i).unwrap();
                 Ok(Self::lower_local(env, f))
@@ -200,6 +205,31 @@ impl FnCall {
         )
     }
 
+    /// Lowers a local function in a module compiled for tiered compilation by
+    /// loading its address from the function's `VMFuncRef`, which the runtime
+    /// updates once an optimized version of the function is available.
+    fn lower_tiered_local<M: MacroAssembler, P: PtrSize>(
+        func_ref: FuncRefIndex,
+        sig: &ABISig,
+        context: &mut CodeGenContext<Emission>,
+        masm: &mut M,
+        vmoffsets: &VMOffsets<P>,
+    ) -> Result<(CalleeKind, ContextArgs)> {
+        let callee =
+            context.without::<Result<Reg>, M, _>(&sig.regs, masm, |context, masm| {
+                context.any_gpr(masm)
+            })??;
+        let callee_body_offset =
+            vmoffsets.func_refs().at(func_ref) + u32::from(vmoffsets.ptr.vm_func_ref().wasm_call());
+        let callee_addr = masm.address_at_vmctx(callee_body_offset)?;
+        masm.load_ptr(callee_addr, writable!(callee))?;
+
+        Ok((
+            CalleeKind::indirect(callee),
+            ContextArgs::pinned_callee_and_caller_vmctx(),
+        ))
+    }
+
     /// Lowers a function import by loading its address to the next available
     /// register.
     fn lower_import<M: MacroAssembler, P: PtrSize>(
@@ -134,6 +134,8 @@ pub struct FuncEnv<'a, 'translation: 'a, 'data: 'translation, P: PtrSize> {
     table_access_spectre_mitigation: bool,
     /// Size of pages on the compilation target.
     pub page_size_log2: u8,
+    /// The index of the function being compiled.
+    pub func_index: DefinedFuncIndex,
     name_map: PrimaryMap<UserExternalNameRef, UserExternalName>,
     name_intern: HashMap<UserExternalName, UserExternalNameRef>,
 }
@@ -153,6 +155,7 @@ impl<'a, 'translation, 'data, P: PtrSize> FuncEnv<'a, 'translation, 'data, P> {
         builtins: &'translation mut BuiltinFunctions,
         isa: &dyn TargetIsa,
         ptr_type: WasmValType,
+        func_index: DefinedFuncIndex,
     ) -> Self {
         Self {
             vmoffsets,
@@ -167,12 +170,20 @@ impl<'a, 'translation, 'data, P: PtrSize> FuncEnv<'a, 'translation, 'data, P> {
             heap_access_spectre_mitigation: isa.flags().enable_heap_access_spectre_mitigation(),
             table_access_spectre_mitigation: isa.flags().enable_table_access_spectre_mitigation(),
             page_size_log2: isa.page_size_align_log2(),
+            func_index,
             builtins,
             name_map: Default::default(),
             name_intern: Default::default(),
         }
     }
 
+    /// Returns true if the module is compiled for tiered compilation, in which
+    /// case every defined function has an entry counter and calls between
+    /// defined functions go through their `VMFuncRef`s.
+    pub(crate) fn tiered_compilation(&self) -> bool {
+        self.translation.module.num_tier_up_counters > 0
+    }
+
     /// Derive the [`WasmType`] from the pointer size.
     pub(crate) fn ptr_type(&self) -> WasmValType {
         self.ptr_type
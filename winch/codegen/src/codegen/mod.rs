@@ -385,6 +385,8 @@ where
 
         self.maybe_emit_epoch_check()?;
 
+        self.maybe_emit_tier_up_check()?;
+
         // Once we have emitted the epilogue and reserved stack space for the locals, we push the
         // base control flow block.
         self.control_frames.push(ControlStackFrame::block(
@@ -2242,6 +2244,76 @@ where
         Ok(())
     }
 
+    /// Checks if tiered compilation is enabled and emits a series of
+    /// instructions that increment this function's entry counter and ask the
+    /// runtime to recompile the function once the counter reaches the
+    /// configured threshold.
+    pub fn maybe_emit_tier_up_check(&mut self) -> Result<()> {
+        if !self.tunables.tiered_compilation {
+            return Ok(());
+        }
+
+        let tier_up = self.env.builtins.tier_up::<M::ABI>()?;
+        let counter_reg = self.context.without::<Result<Reg>, M, _>(
+            &tier_up.sig().regs,
+            self.masm,
+            |cx, masm| cx.any_gpr(masm),
+        )??;
+
+        // Increment the counter in the `VMContext`.
+        let counter_offset = self
+            .env
+            .vmoffsets
+            .tier_up_counters()
+            .at(self.env.func_index);
+        self.masm.load(
+            self.masm.address_at_vmctx(counter_offset)?,
+            writable!(counter_reg),
+            OperandSize::S32,
+        )?;
+        self.masm.add(
+            writable!(counter_reg),
+            counter_reg,
+            RegImm::i32(1),
+            OperandSize::S32,
+        )?;
+        self.masm.store(
+            counter_reg.into(),
+            self.masm.address_at_vmctx(counter_offset)?,
+            OperandSize::S32,
+        )?;
+
+        // The continuation label if the function isn't hot yet.
+        let cont = self.masm.get_label()?;
+
+        // Spill locals and registers to avoid conflicts at the control flow
+        // merge below.
+        self.context.spill(self.masm)?;
+        self.masm.branch(
+            IntCmpKind::LtU,
+            counter_reg,
+            RegImm::i32(self.tunables.tier_up_threshold.cast_signed()),
+            cont,
+            OperandSize::S32,
+        )?;
+        // Threshold reached branch.
+        self.context
+            .stack
+            .extend([self.env.func_index.as_u32().try_into()?]);
+        FnCall::emit::<M>(
+            &mut self.env,
+            self.masm,
+            &mut self.context,
+            Callee::Builtin(tier_up.clone()),
+        )?;
+        self.context.pop_and_free(self.masm)?;
+
+        // Below threshold branch.
+        self.masm.bind(cont)?;
+        self.context.free_reg(counter_reg);
+        Ok(())
+    }
+
     fn emit_load_epoch_deadline_and_counter(
         &mut self,
         epoch_deadline_reg: Reg,
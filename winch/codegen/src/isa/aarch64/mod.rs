@@ -16,7 +16,9 @@ use masm::MacroAssembler as Aarch64Masm;
 use target_lexicon::Triple;
 use wasmparser::{FuncValidator, FunctionBody, ValidatorResources};
 use wasmtime_cranelift::CompiledFunction;
-use wasmtime_environ::{ModuleTranslation, ModuleTypesBuilder, Tunables, VMOffsets, WasmFuncType};
+use wasmtime_environ::{
+    DefinedFuncIndex, ModuleTranslation, ModuleTypesBuilder, Tunables, VMOffsets, WasmFuncType,
+};
 
 mod abi;
 mod address;
@@ -83,6 +85,7 @@ impl TargetIsa for Aarch64 {
         &self,
         sig: &WasmFuncType,
         body: &FunctionBody,
+        index: DefinedFuncIndex,
         translation: &ModuleTranslation,
         types: &ModuleTypesBuilder,
         builtins: &mut BuiltinFunctions,
@@ -107,6 +110,7 @@ impl TargetIsa for Aarch64 {
             builtins,
             self,
             abi::Aarch64ABI::ptr_type(),
+            index,
         );
         let type_converter = TypeConverter::new(env.translation, env.types);
         let defined_locals =
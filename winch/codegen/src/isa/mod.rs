@@ -11,7 +11,9 @@ use std::{
 use target_lexicon::{Architecture, Triple};
 use wasmparser::{FuncValidator, FunctionBody, ValidatorResources};
 use wasmtime_cranelift::CompiledFunction;
-use wasmtime_environ::{ModuleTranslation, ModuleTypesBuilder, Tunables, WasmFuncType};
+use wasmtime_environ::{
+    DefinedFuncIndex, ModuleTranslation, ModuleTypesBuilder, Tunables, WasmFuncType,
+};
 
 #[cfg(feature = "x64")]
 pub(crate) mod x64;
@@ -169,6 +171,7 @@ pub trait TargetIsa: Send + Sync {
         &self,
         sig: &WasmFuncType,
         body: &FunctionBody,
+        index: DefinedFuncIndex,
         translation: &ModuleTranslation,
         types: &ModuleTypesBuilder,
         builtins: &mut BuiltinFunctions,
@@ -15,7 +15,9 @@ use cranelift_codegen::{MachTextSectionBuilder, TextSectionBuilder};
 use target_lexicon::Triple;
 use wasmparser::{FuncValidator, FunctionBody, ValidatorResources};
 use wasmtime_cranelift::CompiledFunction;
-use wasmtime_environ::{ModuleTranslation, ModuleTypesBuilder, Tunables, VMOffsets, WasmFuncType};
+use wasmtime_environ::{
+    DefinedFuncIndex, ModuleTranslation, ModuleTypesBuilder, Tunables, VMOffsets, WasmFuncType,
+};
 
 use self::regs::{fpr_bit_set, gpr_bit_set};
 
@@ -86,6 +88,7 @@ impl TargetIsa for X64 {
         &self,
         sig: &WasmFuncType,
         body: &FunctionBody,
+        index: DefinedFuncIndex,
         translation: &ModuleTranslation,
         types: &ModuleTypesBuilder,
         builtins: &mut BuiltinFunctions,
@@ -112,6 +115,7 @@ impl TargetIsa for X64 {
             builtins,
             self,
             abi::X64ABI::ptr_type(),
+            index,
         );
         let type_converter = TypeConverter::new(env.translation, env.types);
         let defined_locals =